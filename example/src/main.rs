@@ -2,7 +2,7 @@ use mimerrust::{Connection, ToSql, CursorMode};
 
 fn main() {
     print!("Connecting to database\n");
-    let mut conn =
+    let conn =
         Connection::open("", "RUSTUSER", "RUSTPASSWORD").unwrap_or_else(|ec| panic!("{}", ec));
 
     conn.execute_statement("DROP TABLE test_table").ok();
@@ -0,0 +1,85 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+//! Loads rows in batches, locates a failing entry of a batch, and reads the rows back in batches.
+//!
+//! Run with `cargo run --example batch_loading`, with the database named in `MIMER_DATABASE`.
+
+use mimerrust::*;
+
+const BATCH_SIZE: usize = 500;
+
+pub fn main() {
+    let db = std::env::var("MIMER_DATABASE").unwrap_or_default();
+    let conn = Connection::open(&db, "RUSTUSER", "RUSTPASSWORD").expect("Could not connect");
+
+    conn.execute_statement("DROP TABLE example_measurement")
+        .ok();
+    conn.execute_statement(
+        "CREATE TABLE example_measurement (id INT PRIMARY KEY, reading DOUBLE PRECISION)",
+    )
+    .unwrap();
+
+    let mut insert = conn
+        .prepare(
+            "INSERT INTO example_measurement VALUES(:id, :reading)",
+            CursorMode::Forward,
+        )
+        .unwrap();
+    for chunk in (0..2000).collect::<Vec<i32>>().chunks(BATCH_SIZE) {
+        for id in chunk {
+            insert.add_batch(&[id, &(*id as f64 / 10.0)]).unwrap();
+        }
+        insert.execute_batch_locating().unwrap();
+    }
+
+    // A duplicate key makes the whole batch fail, and the failing entry is located
+    for id in [2000, 2001, 5, 2002] {
+        insert.add_batch(&[&id, &0.0]).unwrap();
+    }
+    let err = insert.execute_batch_locating().unwrap_err();
+    println!(
+        "Batch failed with error {} at entry {:?}",
+        err.get_error_code(),
+        err.get_index()
+    );
+    assert_eq!(err.get_index(), Some(2));
+
+    let select = conn
+        .prepare(
+            "SELECT id, reading FROM example_measurement ORDER BY id",
+            CursorMode::Forward,
+        )
+        .unwrap();
+    select.set_array_size(BATCH_SIZE as i32).unwrap();
+    let mut cursor = select.open_cursor().unwrap();
+    let mut rows = Vec::with_capacity(BATCH_SIZE);
+    let mut total = 0;
+    while cursor.next_n(BATCH_SIZE, &mut rows).unwrap() > 0 {
+        total += rows.len();
+        rows.clear();
+    }
+    println!("Read back {total} rows");
+    assert_eq!(total, 2000);
+}
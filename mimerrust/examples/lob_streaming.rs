@@ -0,0 +1,73 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+//! Stores and fetches large objects. Values larger than the chunk size of the C API are sent and fetched in chunks by the driver.
+//!
+//! Run with `cargo run --example lob_streaming`, with the database named in `MIMER_DATABASE`.
+
+use mimerrust::*;
+
+pub fn main() {
+    let db = std::env::var("MIMER_DATABASE").unwrap_or_default();
+    let conn = Connection::open(&db, "RUSTUSER", "RUSTPASSWORD").expect("Could not connect");
+
+    conn.execute_statement("DROP TABLE example_document").ok();
+    conn.execute_statement(
+        "CREATE TABLE example_document (id INT PRIMARY KEY, content BLOB(10M), notes NCLOB(10M))",
+    )
+    .unwrap();
+
+    let content: Vec<u8> = (0..3 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+    let notes = "Large objects with multi-byte characters: åäö € 😀\n".repeat(50_000);
+
+    let insert = conn
+        .prepare(
+            "INSERT INTO example_document VALUES(:id, :content, :notes)",
+            CursorMode::Forward,
+        )
+        .unwrap();
+    insert.execute_bind(&[&1, &content, &notes]).unwrap();
+
+    let select = conn
+        .prepare(
+            "SELECT content, notes FROM example_document WHERE id = :id",
+            CursorMode::Forward,
+        )
+        .unwrap();
+    select.bind(&1, 1).unwrap();
+    let mut cursor = select.open_cursor().unwrap();
+    let row = cursor
+        .next_row()
+        .unwrap()
+        .expect("The document was not found");
+    let (fetched_content, fetched_notes) = row.get_tuple::<(Vec<u8>, String)>().unwrap();
+
+    println!(
+        "Fetched {} bytes of content and {} characters of notes",
+        fetched_content.len(),
+        fetched_notes.chars().count()
+    );
+    assert_eq!(fetched_content, content);
+    assert_eq!(fetched_notes, notes);
+}
@@ -0,0 +1,81 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+//! Reads a result set page by page within one read-only transaction, so that all pages see the same data
+//! even while other sessions write to the table.
+//!
+//! Run with `cargo run --example pagination`, with the database named in `MIMER_DATABASE`.
+
+use mimerrust::*;
+use std::time::Duration;
+
+const PAGE_SIZE: usize = 25;
+
+pub fn main() {
+    let db = std::env::var("MIMER_DATABASE").unwrap_or_default();
+    let conn = Connection::open(&db, "RUSTUSER", "RUSTPASSWORD").expect("Could not connect");
+    let writer = Connection::open(&db, "RUSTUSER", "RUSTPASSWORD").expect("Could not connect");
+
+    conn.execute_statement("DROP TABLE example_article").ok();
+    conn.execute_statement("CREATE TABLE example_article (id INT PRIMARY KEY, title VARCHAR(50))")
+        .unwrap();
+    for id in 1..=100 {
+        conn.insert_values(
+            "example_article",
+            &[("id", &id), ("title", &format!("Article {id}"))],
+        )
+        .unwrap();
+    }
+
+    let mut reader = ConsistentReader::new(&conn, Duration::from_secs(60)).unwrap();
+    let mut page = 0;
+    let mut read = 0;
+    loop {
+        let titles = reader
+            .read_page(
+                "SELECT title FROM example_article ORDER BY id",
+                &[],
+                page,
+                PAGE_SIZE,
+                |row| Ok(row.get::<String>(1)?.unwrap_or_default()),
+            )
+            .unwrap();
+        if titles.is_empty() {
+            break;
+        }
+        println!("page {page}: {} .. {}", titles[0], titles[titles.len() - 1]);
+        read += titles.len();
+
+        // Rows added by another session after the first page don't show up in later pages
+        writer
+            .insert_values(
+                "example_article",
+                &[("id", &(1000 + page as i32)), ("title", &"Late article")],
+            )
+            .unwrap();
+        page += 1;
+    }
+    reader.finish().unwrap();
+    assert_eq!(read, 100);
+}
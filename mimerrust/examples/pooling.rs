@@ -0,0 +1,69 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+//! Shares connections between threads with an [r2d2](https://docs.rs/r2d2) pool.
+//!
+//! Run with `cargo run --example pooling --features r2d2`, with the database named in `MIMER_DATABASE`.
+
+use mimerrust::r2d2::MimerConnectionManager;
+use std::thread;
+
+pub fn main() {
+    let db = std::env::var("MIMER_DATABASE").unwrap_or_default();
+    let manager = MimerConnectionManager::new(&db, "RUSTUSER", "RUSTPASSWORD");
+    let pool = ::r2d2::Pool::builder().max_size(4).build(manager).unwrap();
+
+    let conn = pool.get().unwrap();
+    conn.execute_statement("DROP TABLE example_visit").ok();
+    conn.execute_statement("CREATE TABLE example_visit (worker INT, visit INT)")
+        .unwrap();
+    drop(conn);
+
+    let workers: Vec<_> = (0..8)
+        .map(|worker| {
+            let pool = pool.clone();
+            thread::spawn(move || {
+                for visit in 0..10 {
+                    let conn = pool.get().unwrap();
+                    conn.insert_values("example_visit", &[("worker", &worker), ("visit", &visit)])
+                        .unwrap();
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+
+    let conn = pool.get().unwrap();
+    let visits = conn
+        .query_scalar::<i32>("SELECT CAST(COUNT(*) AS INTEGER) FROM example_visit", &[])
+        .unwrap();
+    println!(
+        "{} visits with {} pooled connections",
+        visits.unwrap_or(0),
+        pool.state().connections
+    );
+    assert_eq!(visits, Some(80));
+}
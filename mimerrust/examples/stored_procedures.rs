@@ -0,0 +1,85 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+//! Calls a stored procedure with IN, OUT and INOUT parameters.
+//!
+//! The values of output parameters can't be read from a statement, so the call is made within a compound statement
+//! that stores them in a table, from where they are selected.
+//!
+//! Run with `cargo run --example stored_procedures`, with the database named in `MIMER_DATABASE`.
+
+use mimerrust::*;
+
+pub fn main() {
+    let db = std::env::var("MIMER_DATABASE").unwrap_or_default();
+    let conn = Connection::open(&db, "RUSTUSER", "RUSTPASSWORD").expect("Could not connect");
+
+    conn.execute_statement("DROP PROCEDURE example_split").ok();
+    conn.execute_statement("DROP TABLE example_split_result")
+        .ok();
+    conn.execute_statement(
+        "CREATE TABLE example_split_result (quotient INT, remainder INT, total INT)",
+    )
+    .unwrap();
+    conn.execute_statement(
+        "CREATE PROCEDURE example_split(IN dividend INTEGER, IN divisor INTEGER, OUT quotient INTEGER, INOUT total INTEGER)
+        BEGIN
+            SET quotient = dividend / divisor;
+            SET total = total + dividend;
+        END",
+    )
+    .unwrap();
+
+    let call = conn
+        .prepare(
+            "BEGIN
+                CALL example_split(:dividend, :divisor, :quotient, :total);
+                INSERT INTO example_split_result VALUES(:quotient, MOD(:dividend, :divisor), :total);
+            END",
+            CursorMode::Forward,
+        )
+        .unwrap();
+    for idx in 1..=call.num_params().unwrap() as i16 {
+        println!(
+            "parameter {} ({}) is {:?}",
+            idx,
+            call.get_parameter_name(idx).unwrap(),
+            call.get_parameter_mode(idx).unwrap()
+        );
+    }
+
+    // Output parameters are not bound
+    call.bind(&17, 1).unwrap();
+    call.bind(&5, 2).unwrap();
+    call.bind(&100, 4).unwrap();
+    call.execute().unwrap();
+
+    let (quotient, remainder, total) = conn
+        .query_row("SELECT * FROM example_split_result", &[], |row| {
+            row.get_tuple::<(i32, i32, i32)>()
+        })
+        .unwrap();
+    println!("17 = 5 * {quotient} + {remainder}, running total {total}");
+    assert_eq!((quotient, remainder, total), (3, 2, 117));
+}
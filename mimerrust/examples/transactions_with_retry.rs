@@ -0,0 +1,77 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+//! Moves money between accounts in transactions, retrying a transfer when it conflicts with another transaction.
+//!
+//! Run with `cargo run --example transactions_with_retry`, with the database named in `MIMER_DATABASE`.
+
+use mimerrust::*;
+use std::time::Duration;
+
+pub fn main() {
+    let db = std::env::var("MIMER_DATABASE").unwrap_or_default();
+    let conn = Connection::open(&db, "RUSTUSER", "RUSTPASSWORD").expect("Could not connect");
+
+    conn.execute_statement("DROP TABLE example_account").ok();
+    conn.execute_statement(
+        "CREATE TABLE example_account (name VARCHAR(30) PRIMARY KEY, balance INT)",
+    )
+    .unwrap();
+    conn.execute_statement("INSERT INTO example_account VALUES('alice', 100), ('bob', 50)")
+        .unwrap();
+
+    let policy = StatementRetry::new(5)
+        .backoff(Duration::from_millis(10), Duration::from_millis(500))
+        .on_retry(|event| println!("transfer conflicted, retry {}", event.attempt));
+
+    // Each attempt runs in a transaction of its own, which is rolled back if the closure fails
+    for amount in [30, 20] {
+        conn.run_with_retry(&policy, |conn| {
+            let trans = conn.begin_transaction(TransactionMode::ReadWrite)?;
+            trans.execute_statement(&format!(
+                "UPDATE example_account SET balance = balance - {amount} WHERE name = 'alice'"
+            ))?;
+            trans.execute_statement(&format!(
+                "UPDATE example_account SET balance = balance + {amount} WHERE name = 'bob'"
+            ))?;
+            trans.commit()
+        })
+        .unwrap_or_else(|ec| panic!("Transfer failed: {}", conn.get_error(ec)));
+    }
+
+    let balances = conn
+        .query_map(
+            "SELECT name, balance FROM example_account ORDER BY name",
+            &[],
+            |row| row.get_tuple::<(String, i32)>(),
+        )
+        .unwrap();
+    for (name, balance) in &balances {
+        println!("{name}: {balance}");
+    }
+    assert_eq!(
+        balances,
+        vec![(String::from("alice"), 50), (String::from("bob"), 100)]
+    );
+}
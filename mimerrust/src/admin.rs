@@ -0,0 +1,370 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{connection::Connection, sql::quote_ident};
+use std::fmt;
+
+/// The kind of ident created by [create_ident](crate::Connection::create_ident()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentType {
+    /// An ident that connects to the database, with a password or as an OS user.
+    User,
+    /// An ident that a session enters with *ENTER*, using its password, to run with the privileges of the program.
+    Program,
+    /// An ident that other idents are made [members](Privilege::Member) of, to share its privileges. A group has no password.
+    Group,
+}
+
+/// A privilege given by [grant](crate::Connection::grant()) and taken back by [revoke](crate::Connection::revoke()).
+///
+/// Names of objects are quoted with [quote_ident](crate::sql::quote_ident()), each part of a qualified name on its own,
+/// so they must be given as stored, e.g. `SALES.ORDERS` for a table created as `sales.orders` without quotes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Privilege {
+    /// Taking backups of databanks.
+    Backup,
+    /// Creating databanks.
+    Databank,
+    /// Creating idents.
+    Ident,
+    /// Creating schemas.
+    Schema,
+    /// Creating shadows of databanks.
+    Shadow,
+    /// Updating the statistics used by the query optimizer.
+    Statistics,
+    /// Creating tables in the named databank.
+    Table(String),
+    /// Reading the named table or view.
+    Select(String),
+    /// Inserting into the named table or view.
+    Insert(String),
+    /// Updating the named table or view.
+    Update(String),
+    /// Deleting from the named table or view.
+    Delete(String),
+    /// Referring to the named table from foreign keys.
+    References(String),
+    /// Every privilege on the named table or view.
+    All(String),
+    /// Calling the named procedure.
+    ExecuteProcedure(String),
+    /// Calling the named function.
+    ExecuteFunction(String),
+    /// Using the named sequence.
+    UsageOnSequence(String),
+    /// Membership of the named group ident.
+    Member(String),
+}
+
+impl fmt::Display for Privilege {
+    /// Writes the privilege as it appears in a *GRANT* or *REVOKE* statement, e.g. `SELECT ON orders`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Privilege::Backup => f.write_str("BACKUP"),
+            Privilege::Databank => f.write_str("DATABANK"),
+            Privilege::Ident => f.write_str("IDENT"),
+            Privilege::Schema => f.write_str("SCHEMA"),
+            Privilege::Shadow => f.write_str("SHADOW"),
+            Privilege::Statistics => f.write_str("STATISTICS"),
+            Privilege::Table(databank) => write!(f, "TABLE ON {}", quote_name(databank)),
+            Privilege::Select(table) => write!(f, "SELECT ON {}", quote_name(table)),
+            Privilege::Insert(table) => write!(f, "INSERT ON {}", quote_name(table)),
+            Privilege::Update(table) => write!(f, "UPDATE ON {}", quote_name(table)),
+            Privilege::Delete(table) => write!(f, "DELETE ON {}", quote_name(table)),
+            Privilege::References(table) => write!(f, "REFERENCES ON {}", quote_name(table)),
+            Privilege::All(table) => write!(f, "ALL ON {}", quote_name(table)),
+            Privilege::ExecuteProcedure(procedure) => {
+                write!(f, "EXECUTE ON PROCEDURE {}", quote_name(procedure))
+            }
+            Privilege::ExecuteFunction(function) => {
+                write!(f, "EXECUTE ON FUNCTION {}", quote_name(function))
+            }
+            Privilege::UsageOnSequence(sequence) => {
+                write!(f, "USAGE ON SEQUENCE {}", quote_name(sequence))
+            }
+            Privilege::Member(group) => write!(f, "MEMBER ON {}", quote_ident(group)),
+        }
+    }
+}
+
+/// Quotes a possibly qualified name, e.g. `SALES.ORDERS`, one part at a time.
+fn quote_name(name: &str) -> String {
+    name.split('.')
+        .map(quote_ident)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Quotes the grantee of [grant](crate::Connection::grant()) and [revoke](crate::Connection::revoke()), except `PUBLIC`.
+fn quote_grantee(grantee: &str) -> String {
+    match grantee.eq_ignore_ascii_case("PUBLIC") {
+        true => String::from("PUBLIC"),
+        false => quote_ident(grantee),
+    }
+}
+
+/// Text standing in for a password in the statements seen by the history, the observer, tracing and metrics.
+const REDACTED_PASSWORD: &str = "'***'";
+
+/// Builds the start of the *CREATE IDENT* statement of [create_ident](crate::Connection::create_ident()), up to the password.
+fn create_ident_sql(name: &str, ident_type: IdentType) -> String {
+    let ident_type = match ident_type {
+        IdentType::User => "USER",
+        IdentType::Program => "PROGRAM",
+        IdentType::Group => "GROUP",
+    };
+    format!("CREATE IDENT {} AS {ident_type}", quote_ident(name))
+}
+
+/// Appends `password` to `prefix` as a character string literal, like [quote_literal](crate::sql::quote_literal()),
+/// in a single allocation, so that wiping the returned statement leaves no copy of the password behind.
+fn with_password_literal(prefix: &str, password: &str) -> String {
+    let mut sql = String::with_capacity(prefix.len() + 2 * password.len() + 2);
+    sql.push_str(prefix);
+    sql.push('\'');
+    for c in password.chars() {
+        if c == '\'' {
+            sql.push('\'');
+        }
+        sql.push(c);
+    }
+    sql.push('\'');
+    sql
+}
+
+impl Connection {
+    /// Changes the password of the ident the connection logged in as from `old_password` to `new_password`,
+    /// also after entering a program ident.
+    ///
+    /// Since the server doesn't ask for the old password when an ident changes its own password,
+    /// it is checked by opening a second session to the database with it, on which the password is changed.
+    /// The new password is left out of the statement seen by the [statement history](crate::Transaction::record_history()),
+    /// the [statement observer](crate::Connection::set_statement_observer()), tracing and metrics,
+    /// and with the `zeroize` feature the copies of the statement are wiped after execution.
+    ///
+    /// # Errors
+    /// Returns [Err] when the old password is wrong, or the password couldn't be changed, e.g. because the new one is too short.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// let conn = Connection::open(db, "REPORTER", "s3cret").unwrap();
+    /// conn.change_password("s3cret", "n3w s3cret").unwrap();
+    /// ```
+    pub fn change_password(&self, old_password: &str, new_password: &str) -> Result<i32, i32> {
+        let login = Connection::open(self.database(), self.login_ident(), old_password)
+            .map_err(|err| err.get_error_code())?;
+        // The new session hasn't entered a program ident, so this is the login ident as stored by the server
+        let ident = login
+            .query_scalar::<String>("SELECT CURRENT_USER FROM SYSTEM.ONEROW", &[])?
+            .unwrap_or_default();
+        let prefix = format!("ALTER IDENT {} SET PASSWORD ", quote_ident(&ident));
+        login.execute_secret_statement(
+            with_password_literal(&prefix, new_password),
+            &format!("{prefix}{REDACTED_PASSWORD}"),
+        )
+    }
+
+    /// Creates an ident, with a password unless it is a [Group](IdentType::Group) or a user that logs in as an OS user.
+    /// The name is quoted with [quote_ident](crate::sql::quote_ident()), so it is stored exactly as given.
+    /// The password is kept out of what is recorded, as for [change_password](crate::Connection::change_password()).
+    /// Requires the [Ident](Privilege::Ident) privilege.
+    ///
+    /// # Errors
+    /// Returns [Err] when the ident couldn't be created, e.g. because it exists or the current ident lacks the privilege.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// let conn = Connection::open(db, "SYSADM", "SYSADM").unwrap();
+    /// conn.create_ident("REPORTER", IdentType::User, Some("s3cret")).unwrap();
+    /// conn.grant(&Privilege::Select("SALES.ORDERS".to_string()), "REPORTER", false).unwrap();
+    /// ```
+    pub fn create_ident(
+        &self,
+        name: &str,
+        ident_type: IdentType,
+        password: Option<&str>,
+    ) -> Result<i32, i32> {
+        let sql = create_ident_sql(name, ident_type);
+        match password {
+            Some(password) => {
+                let prefix = format!("{sql} USING ");
+                self.execute_secret_statement(
+                    with_password_literal(&prefix, password),
+                    &format!("{prefix}{REDACTED_PASSWORD}"),
+                )
+            }
+            None => self.execute_statement(&sql),
+        }
+    }
+
+    /// Drops an ident. With `cascade` the objects it owns, such as its schemas, tables and databanks, are dropped with it,
+    /// otherwise dropping an ident that owns objects fails.
+    ///
+    /// The name is quoted, as for [create_ident](crate::Connection::create_ident()).
+    ///
+    /// # Errors
+    /// Returns [Err] when the ident couldn't be dropped.
+    pub fn drop_ident(&self, name: &str, cascade: bool) -> Result<i32, i32> {
+        let behavior = if cascade { "CASCADE" } else { "RESTRICT" };
+        self.execute_statement(&format!("DROP IDENT {} {behavior}", quote_ident(name)))
+    }
+
+    /// Grants a privilege to an ident, or to all idents with the grantee `PUBLIC`.
+    /// The grantee is quoted, as for [create_ident](crate::Connection::create_ident()), and so are the names in the [Privilege].
+    /// With `with_grant_option` the grantee may grant the privilege to others in turn.
+    ///
+    /// # Errors
+    /// Returns [Err] when the privilege couldn't be granted, e.g. because the current ident may not grant it.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// conn.grant(&Privilege::Select("TEST_TABLE".to_string()), "PUBLIC", false).unwrap();
+    /// conn.revoke(&Privilege::Select("TEST_TABLE".to_string()), "PUBLIC").unwrap();
+    /// ```
+    pub fn grant(
+        &self,
+        privilege: &Privilege,
+        grantee: &str,
+        with_grant_option: bool,
+    ) -> Result<i32, i32> {
+        let option = if with_grant_option {
+            " WITH GRANT OPTION"
+        } else {
+            ""
+        };
+        self.execute_statement(&format!(
+            "GRANT {privilege} TO {}{option}",
+            quote_grantee(grantee)
+        ))
+    }
+
+    /// Revokes a privilege [granted](crate::Connection::grant()) to an ident or to `PUBLIC`.
+    ///
+    /// # Errors
+    /// Returns [Err] when the privilege couldn't be revoked.
+    pub fn revoke(&self, privilege: &Privilege, grantee: &str) -> Result<i32, i32> {
+        self.execute_statement(&format!(
+            "REVOKE {privilege} FROM {}",
+            quote_grantee(grantee)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod admin_tests {
+    use super::*;
+    use crate::testing::*;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    #[test]
+    fn generated_sql() {
+        assert_eq!(
+            create_ident_sql("readers", IdentType::Group),
+            "CREATE IDENT \"readers\" AS GROUP"
+        );
+        assert_eq!(
+            create_ident_sql("x AS USER; DROP IDENT sysadm --", IdentType::User),
+            "CREATE IDENT \"x AS USER; DROP IDENT sysadm --\" AS USER"
+        );
+        let sql = with_password_literal("CREATE IDENT reporter AS USER USING ", "it's");
+        assert_eq!(sql, "CREATE IDENT reporter AS USER USING 'it''s'");
+        assert_eq!(
+            sql,
+            format!(
+                "CREATE IDENT reporter AS USER USING {}",
+                crate::sql::quote_literal("it's")
+            )
+        );
+        assert_eq!(Privilege::Databank.to_string(), "DATABANK");
+        assert_eq!(
+            Privilege::ExecuteProcedure("mathmagic".to_string()).to_string(),
+            "EXECUTE ON PROCEDURE \"mathmagic\""
+        );
+        assert_eq!(
+            Privilege::Select("SALES.Orders".to_string()).to_string(),
+            "SELECT ON \"SALES\".\"Orders\""
+        );
+        assert_eq!(quote_grantee("public"), "PUBLIC");
+        assert_eq!(quote_grantee("Reporter\""), "\"Reporter\"\"\"");
+    }
+
+    #[test]
+    fn grant_and_revoke() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+        let privilege = Privilege::Select(EXAMPLE_TABLE.to_uppercase());
+        conn.grant(&privilege, "PUBLIC", false).unwrap();
+        conn.revoke(&privilege, "PUBLIC").unwrap();
+    }
+
+    #[test]
+    fn change_password_checks_old_password() {
+        let conn = establish_connection();
+        assert!(conn
+            .change_password("not the password", "NEWPASSWORD")
+            .is_err());
+        // Changed on the login ident, here to the same password
+        conn.change_password(PASSWORD, PASSWORD).unwrap();
+    }
+
+    #[test]
+    fn password_not_observed() {
+        let conn = establish_connection();
+        let observed = Arc::new(Mutex::new(Vec::new()));
+        let sink = observed.clone();
+        conn.set_statement_observer(Some(Box::new(move |event| {
+            sink.lock().push(event.sql.to_string())
+        })));
+
+        let ident = crate::test_support::unique_name("ADMIN_IDENT");
+        // Recorded whether or not RUSTUSER may create idents
+        if conn
+            .create_ident(&ident, IdentType::User, Some("VERY_SECRET_PASSWORD"))
+            .is_ok()
+        {
+            conn.drop_ident(&ident, true).unwrap();
+        }
+        conn.set_statement_observer(None);
+
+        let observed = observed.lock();
+        assert!(observed.iter().any(|sql| sql.ends_with(REDACTED_PASSWORD)));
+        assert!(observed
+            .iter()
+            .all(|sql| !sql.contains("VERY_SECRET_PASSWORD")));
+    }
+}
@@ -0,0 +1,159 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::columnar::*;
+use crate::inner_statement::InnerStatement;
+use crate::row::Row;
+
+#[doc(hidden)]
+use arrow::{
+    array::{
+        ArrayRef, BinaryBuilder, BooleanBuilder, Float32Builder, Float64Builder, Int32Builder,
+        Int64Builder, StringBuilder,
+    },
+    datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
+    record_batch::RecordBatch,
+};
+#[doc(hidden)]
+use std::sync::Arc;
+
+/// Returns the Arrow data type values of a kind are converted to.
+fn data_type(kind: ValueKind) -> DataType {
+    match kind {
+        ValueKind::Int32 => DataType::Int32,
+        ValueKind::Int64 => DataType::Int64,
+        ValueKind::Float32 => DataType::Float32,
+        ValueKind::Float64 => DataType::Float64,
+        ValueKind::Boolean => DataType::Boolean,
+        ValueKind::Utf8 | ValueKind::Clob => DataType::Utf8,
+        ValueKind::Binary => DataType::Binary,
+        ValueKind::Date => DataType::Date32,
+        ValueKind::Time => DataType::Time64(TimeUnit::Microsecond),
+        ValueKind::Timestamp => DataType::Timestamp(TimeUnit::Microsecond, None),
+    }
+}
+
+/// Builds one column of a [RecordBatch].
+enum ColumnBuilder {
+    Int32(Int32Builder),
+    Int64(Int64Builder),
+    Float32(Float32Builder),
+    Float64(Float64Builder),
+    Boolean(BooleanBuilder),
+    Utf8(StringBuilder),
+    Binary(BinaryBuilder),
+    /// Dates and times are fetched as strings and cast to the Arrow type when the batch is finished.
+    Temporal(StringBuilder, DataType),
+}
+
+/// Collects rows from a cursor into a [RecordBatch].
+pub(crate) struct RecordBatchBuilder {
+    schema: SchemaRef,
+    columns: Vec<(ValueKind, ColumnBuilder)>,
+}
+
+impl RecordBatchBuilder {
+    /// Creates a builder for the result set of a statement, with room for `capacity` rows.
+    pub(crate) fn new(
+        inner_statement: &InnerStatement,
+        capacity: usize,
+    ) -> Result<RecordBatchBuilder, i32> {
+        let descriptions = describe_columns(inner_statement)?;
+        let mut fields = Vec::with_capacity(descriptions.len());
+        let mut columns = Vec::with_capacity(descriptions.len());
+        for column in descriptions {
+            let data_type = data_type(column.kind);
+            let builder = match column.kind {
+                ValueKind::Int32 => ColumnBuilder::Int32(Int32Builder::with_capacity(capacity)),
+                ValueKind::Int64 => ColumnBuilder::Int64(Int64Builder::with_capacity(capacity)),
+                ValueKind::Float32 => {
+                    ColumnBuilder::Float32(Float32Builder::with_capacity(capacity))
+                }
+                ValueKind::Float64 => {
+                    ColumnBuilder::Float64(Float64Builder::with_capacity(capacity))
+                }
+                ValueKind::Boolean => {
+                    ColumnBuilder::Boolean(BooleanBuilder::with_capacity(capacity))
+                }
+                ValueKind::Utf8 | ValueKind::Clob => ColumnBuilder::Utf8(StringBuilder::new()),
+                ValueKind::Binary => ColumnBuilder::Binary(BinaryBuilder::new()),
+                ValueKind::Date | ValueKind::Time | ValueKind::Timestamp => {
+                    ColumnBuilder::Temporal(StringBuilder::new(), data_type.clone())
+                }
+            };
+            fields.push(Field::new(column.name, data_type, column.nullable));
+            columns.push((column.kind, builder));
+        }
+
+        Ok(RecordBatchBuilder {
+            schema: Arc::new(Schema::new(fields)),
+            columns,
+        })
+    }
+
+    /// Returns the schema of the batches built for the result set.
+    #[cfg_attr(not(feature = "parquet"), allow(dead_code))]
+    pub(crate) fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    /// Appends the current row of a cursor.
+    pub(crate) fn append_row(&mut self, row: &Row) -> Result<(), i32> {
+        for (i, (kind, builder)) in self.columns.iter_mut().enumerate() {
+            let idx = i as i16 + 1;
+            match builder {
+                ColumnBuilder::Int32(b) => b.append_option(read_i32(row, idx)?),
+                ColumnBuilder::Int64(b) => b.append_option(read_i64(row, idx)?),
+                ColumnBuilder::Float32(b) => b.append_option(read_f32(row, idx)?),
+                ColumnBuilder::Float64(b) => b.append_option(read_f64(row, idx)?),
+                ColumnBuilder::Boolean(b) => b.append_option(read_bool(row, idx)?),
+                ColumnBuilder::Binary(b) => b.append_option(read_binary(row, idx)?),
+                ColumnBuilder::Utf8(b) | ColumnBuilder::Temporal(b, _) => {
+                    b.append_option(read_str(row, idx, *kind)?)
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Finishes the batch.
+    pub(crate) fn finish(self) -> Result<RecordBatch, i32> {
+        let mut arrays: Vec<ArrayRef> = Vec::with_capacity(self.columns.len());
+        for (_, builder) in self.columns {
+            arrays.push(match builder {
+                ColumnBuilder::Int32(mut b) => Arc::new(b.finish()),
+                ColumnBuilder::Int64(mut b) => Arc::new(b.finish()),
+                ColumnBuilder::Float32(mut b) => Arc::new(b.finish()),
+                ColumnBuilder::Float64(mut b) => Arc::new(b.finish()),
+                ColumnBuilder::Boolean(mut b) => Arc::new(b.finish()),
+                ColumnBuilder::Utf8(mut b) => Arc::new(b.finish()),
+                ColumnBuilder::Binary(mut b) => Arc::new(b.finish()),
+                ColumnBuilder::Temporal(mut b, data_type) => {
+                    arrow::compute::cast(&b.finish(), &data_type).map_err(|_| -26200)?
+                }
+            });
+        }
+        RecordBatch::try_new(self.schema, arrays).map_err(|_| -26200)
+    }
+}
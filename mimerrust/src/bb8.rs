@@ -0,0 +1,176 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{
+    connection::Connection,
+    connection_options::ConnectionOptions,
+    mimer_error::MimerError,
+    pool_support::{connect_blocking, validate},
+};
+
+/// A [bb8](https://docs.rs/bb8) connection manager for Mimer SQL connections.
+///
+/// Connections are opened on the tokio blocking thread pool. Whenever bb8 tests a connection on checkout,
+/// it is checked with [ping](Connection::ping()), as by the r2d2 adapter,
+/// or with a statement set with [validation_query](MimerConnectionManager::validation_query()).
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// use mimerrust::bb8::MimerConnectionManager;
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+/// runtime.block_on(async {
+///     let manager = MimerConnectionManager::new(db, ident, pass);
+///     let pool = bb8::Pool::builder().max_size(4).build(manager).await.unwrap();
+///
+///     let conn = pool.get().await.unwrap();
+///     let one = conn.query_scalar::<i32>("SELECT 1 FROM SYSTEM.ONEROW", &[]).unwrap();
+///     assert_eq!(one, Some(1));
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct MimerConnectionManager {
+    options: ConnectionOptions,
+    validation_query: Option<String>, // None to ping
+}
+
+impl MimerConnectionManager {
+    /// Creates a connection manager opening connections to the given database as `ident`.
+    pub fn new(database: &str, ident: &str, password: &str) -> MimerConnectionManager {
+        MimerConnectionManager::from_options(ConnectionOptions::new(database, ident, password))
+    }
+
+    /// Creates a connection manager opening connections with [Connection::open_with].
+    pub fn from_options(options: ConnectionOptions) -> MimerConnectionManager {
+        MimerConnectionManager {
+            options,
+            validation_query: None,
+        }
+    }
+
+    /// Sets a statement executed to check that a connection still works, instead of [ping](Connection::ping()).
+    pub fn validation_query(mut self, sqlstatement: &str) -> MimerConnectionManager {
+        self.validation_query = Some(sqlstatement.to_string());
+        self
+    }
+}
+
+impl ::bb8::ManageConnection for MimerConnectionManager {
+    type Connection = Connection;
+    type Error = MimerError;
+
+    async fn connect(&self) -> Result<Connection, MimerError> {
+        let result = connect_blocking(self.options.clone()).await;
+        #[cfg(feature = "metrics")]
+        crate::metrics::pool_connection_opened("bb8", &result);
+        result
+    }
+
+    async fn is_valid(&self, conn: &mut Connection) -> Result<(), MimerError> {
+        let result = validate(conn, self.validation_query.as_deref()).await;
+        #[cfg(feature = "metrics")]
+        crate::metrics::pool_checkout("bb8", &result);
+        result
+    }
+
+    fn has_broken(&self, _conn: &mut Connection) -> bool {
+        // There is no cheap way of telling whether the session is still alive, is_valid is used when checking out instead.
+        false
+    }
+}
+
+#[cfg(test)]
+mod bb8_tests {
+    use super::*;
+    use crate::testing::*;
+
+    #[test]
+    fn debug_hides_password() {
+        let debug = format!("{:?}", MimerConnectionManager::new("db", "ident", "s3cret"));
+        assert!(!debug.contains("s3cret"));
+    }
+
+    #[test]
+    fn pool_checkout() {
+        let db = std::env::var("MIMER_DATABASE").unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let manager = MimerConnectionManager::new(&db, IDENT, PASSWORD)
+                .validation_query("SELECT 2 FROM SYSTEM.ONEROW");
+            let pool = ::bb8::Pool::builder()
+                .max_size(2)
+                .test_on_check_out(true)
+                .build(manager)
+                .await
+                .unwrap();
+
+            let conn = pool.get().await.unwrap();
+            assert_eq!(
+                conn.query_scalar::<i32>("SELECT 1 FROM SYSTEM.ONEROW", &[])
+                    .unwrap(),
+                Some(1)
+            );
+        });
+    }
+
+    #[test]
+    fn pool_checkout_pings() {
+        let db = std::env::var("MIMER_DATABASE").unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let manager = MimerConnectionManager::new(&db, IDENT, PASSWORD);
+            let mut conn = ::bb8::ManageConnection::connect(&manager).await.unwrap();
+            ::bb8::ManageConnection::is_valid(&manager, &mut conn)
+                .await
+                .unwrap();
+        });
+    }
+
+    #[test]
+    fn invalid_validation_query() {
+        let db = std::env::var("MIMER_DATABASE").unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let manager = MimerConnectionManager::new(&db, IDENT, PASSWORD)
+                .validation_query("SELECT * FROM non_existing_table");
+            let mut conn = ::bb8::ManageConnection::connect(&manager).await.unwrap();
+            assert!(::bb8::ManageConnection::is_valid(&manager, &mut conn)
+                .await
+                .is_err());
+        });
+    }
+}
@@ -0,0 +1,299 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{connection::Connection, row::Row};
+
+/// Schemas holding the data dictionary, which are left out of [tables](crate::Connection::tables()).
+const SYSTEM_SCHEMAS: &str = "('INFORMATION_SCHEMA', 'SYSTEM', 'MIMER', 'BUILTIN')";
+
+/// A table or view, as returned by [tables](crate::Connection::tables()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableInfo {
+    /// Schema the table belongs to.
+    pub schema: String,
+    /// Name of the table.
+    pub name: String,
+    /// Type of the table, e.g. `BASE TABLE` or `VIEW`.
+    pub table_type: String,
+}
+
+/// A column of a table, as returned by [columns](crate::Connection::columns()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableColumn {
+    /// Name of the column.
+    pub name: String,
+    /// Position of the column in the table, starting at 1.
+    pub ordinal_position: i32,
+    /// Name of the data type of the column, e.g. `INTEGER` or `CHARACTER VARYING`.
+    pub data_type: String,
+    /// Whether the column may hold null values.
+    pub nullable: bool,
+    /// Maximum length of a character or binary column.
+    pub max_length: Option<i32>,
+    /// Precision of a numeric column.
+    pub precision: Option<i32>,
+    /// Scale of an exact numeric column.
+    pub scale: Option<i32>,
+    /// Default value of the column, as SQL text.
+    pub default: Option<String>,
+}
+
+/// An index on a table, as returned by [indexes](crate::Connection::indexes()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexInfo {
+    /// Schema the index belongs to.
+    pub schema: String,
+    /// Name of the index.
+    pub name: String,
+    /// Whether the index only allows unique values.
+    pub unique: bool,
+    /// Columns of the index, in index order.
+    pub columns: Vec<String>,
+}
+
+/// A databank, as returned by [databanks](crate::Connection::databanks()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatabankInfo {
+    /// Name of the databank.
+    pub name: String,
+    /// Ident that created the databank.
+    pub creator: String,
+    /// Name of the file holding the databank.
+    pub file_name: String,
+}
+
+/// Gets a text column which is never null in the data dictionary.
+fn text(row: &Row, idx: i16) -> Result<String, i32> {
+    Ok(row.get::<String>(idx)?.unwrap_or_default())
+}
+
+impl Connection {
+    /// Lists the tables and views visible to the current ident, leaving out those of the data dictionary.
+    ///
+    /// # Errors
+    /// Returns [Err] when the data dictionary couldn't be queried.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    ///
+    /// let tables = conn.tables().unwrap();
+    /// assert!(tables.iter().any(|table| table.name == "TEST_TABLE"));
+    /// ```
+    pub fn tables(&self) -> Result<Vec<TableInfo>, i32> {
+        self.query_map(
+            &format!(
+                "SELECT table_schema, table_name, table_type FROM INFORMATION_SCHEMA.TABLES \
+                 WHERE table_schema NOT IN {SYSTEM_SCHEMAS} ORDER BY table_schema, table_name"
+            ),
+            &[],
+            |row| {
+                Ok(TableInfo {
+                    schema: text(row, 1)?,
+                    name: text(row, 2)?,
+                    table_type: text(row, 3)?,
+                })
+            },
+        )
+    }
+
+    /// Lists the columns of `table` in the schema of the current ident, ordered by their position in the table.
+    /// The table name is matched as an unquoted identifier, i.e. case insensitively.
+    ///
+    /// # Errors
+    /// Returns [Err] when the data dictionary couldn't be queried.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT NOT NULL)").unwrap();
+    ///
+    /// let columns = conn.columns("test_table").unwrap();
+    /// assert_eq!(columns[0].name, "COLUMN_1");
+    /// assert_eq!(columns[0].max_length, Some(30));
+    /// assert!(!columns[1].nullable);
+    /// ```
+    pub fn columns(&self, table: &str) -> Result<Vec<TableColumn>, i32> {
+        self.query_map(
+            "SELECT column_name, CAST(ordinal_position AS INTEGER), data_type, is_nullable, \
+             CAST(character_maximum_length AS INTEGER), CAST(numeric_precision AS INTEGER), \
+             CAST(numeric_scale AS INTEGER), column_default \
+             FROM INFORMATION_SCHEMA.COLUMNS \
+             WHERE table_schema = CURRENT_USER AND table_name = UPPER(CAST(? AS VARCHAR(128))) \
+             ORDER BY ordinal_position",
+            &[&table],
+            |row| {
+                Ok(TableColumn {
+                    name: text(row, 1)?,
+                    ordinal_position: row.get(2)?.unwrap_or_default(),
+                    data_type: text(row, 3)?,
+                    nullable: text(row, 4)? == "YES",
+                    max_length: row.get(5)?,
+                    precision: row.get(6)?,
+                    scale: row.get(7)?,
+                    default: row.get(8)?,
+                })
+            },
+        )
+    }
+
+    /// Lists the indexes on `table` in the schema of the current ident, with their columns in index order.
+    /// The table name is matched as an unquoted identifier, i.e. case insensitively.
+    ///
+    /// # Errors
+    /// Returns [Err] when the data dictionary couldn't be queried.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// conn.execute_statement("create unique index test_index on test_table (column_2, column_1)").unwrap();
+    ///
+    /// let indexes = conn.indexes("test_table").unwrap();
+    /// assert_eq!(indexes[0].name, "TEST_INDEX");
+    /// assert_eq!(indexes[0].columns, vec!["COLUMN_2", "COLUMN_1"]);
+    /// ```
+    pub fn indexes(&self, table: &str) -> Result<Vec<IndexInfo>, i32> {
+        let rows = self.query_map(
+            "SELECT i.index_schema, i.index_name, i.is_unique, c.column_name \
+             FROM INFORMATION_SCHEMA.EXT_INDEXES i \
+             JOIN INFORMATION_SCHEMA.EXT_INDEX_COLUMN_USAGE c \
+             ON c.index_schema = i.index_schema AND c.index_name = i.index_name \
+             WHERE i.table_schema = CURRENT_USER AND i.table_name = UPPER(CAST(? AS VARCHAR(128))) \
+             ORDER BY i.index_schema, i.index_name, c.ordinal_position",
+            &[&table],
+            |row| Ok((text(row, 1)?, text(row, 2)?, text(row, 3)?, text(row, 4)?)),
+        )?;
+        let mut indexes: Vec<IndexInfo> = Vec::new();
+        for (schema, name, unique, column) in rows {
+            match indexes.last_mut() {
+                Some(index) if index.schema == schema && index.name == name => {
+                    index.columns.push(column)
+                }
+                _ => indexes.push(IndexInfo {
+                    schema,
+                    name,
+                    unique: unique == "YES",
+                    columns: vec![column],
+                }),
+            }
+        }
+        Ok(indexes)
+    }
+
+    /// Lists the databanks visible to the current ident.
+    ///
+    /// # Errors
+    /// Returns [Err] when the data dictionary couldn't be queried.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    ///
+    /// for databank in conn.databanks().unwrap() {
+    ///     println!("{} is stored in {}", databank.name, databank.file_name);
+    /// }
+    /// ```
+    pub fn databanks(&self) -> Result<Vec<DatabankInfo>, i32> {
+        self.query_map(
+            "SELECT databank_name, databank_creator, file_name \
+             FROM INFORMATION_SCHEMA.EXT_DATABANKS ORDER BY databank_name",
+            &[],
+            |row| {
+                Ok(DatabankInfo {
+                    name: text(row, 1)?,
+                    creator: text(row, 2)?,
+                    file_name: text(row, 3)?,
+                })
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod catalog_tests {
+    use crate::testing::*;
+
+    #[test]
+    fn describe_table() {
+        let conn = establish_connection();
+        drop_create_table(
+            &conn,
+            "catalog_table",
+            "(id INT PRIMARY KEY, name VARCHAR(40) NOT NULL, price DECIMAL(8,2) DEFAULT 0)",
+        );
+        conn.execute_statement("CREATE INDEX catalog_index ON catalog_table (name, price)")
+            .unwrap();
+
+        let table = conn
+            .tables()
+            .unwrap()
+            .into_iter()
+            .find(|table| table.name == "CATALOG_TABLE")
+            .unwrap();
+        assert_eq!(table.schema, IDENT.to_uppercase());
+        assert_eq!(table.table_type, "BASE TABLE");
+
+        let columns = conn.columns("catalog_table").unwrap();
+        let names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["ID", "NAME", "PRICE"]);
+        assert!(!columns[0].nullable);
+        assert_eq!(columns[1].max_length, Some(40));
+        assert_eq!((columns[2].precision, columns[2].scale), (Some(8), Some(2)));
+        assert!(columns[2].nullable);
+        assert!(columns[2].default.is_some());
+
+        let index = conn
+            .indexes("catalog_table")
+            .unwrap()
+            .into_iter()
+            .find(|index| index.name == "CATALOG_INDEX")
+            .unwrap();
+        assert!(!index.unique);
+        assert_eq!(index.columns, vec!["NAME", "PRICE"]);
+
+        assert!(!conn.databanks().unwrap().is_empty());
+        assert!(conn.columns("no_such_catalog_table").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,205 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::common::{mimer_options::ColumnType, traits::GetHandle};
+use crate::ffi_buffer::read_string;
+use crate::inner_statement::InnerStatement;
+use crate::row::Row;
+use crate::types::MimerDatatype;
+use mimerrust_sys as ffi;
+
+/// How the values of a column are read when a result set is converted into columnar form.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub(crate) enum ValueKind {
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+    Boolean,
+    /// Read as a string with `MimerGetString8`.
+    Utf8,
+    /// Character large objects, read through [Row::get_type].
+    Clob,
+    /// Binary, large binary and spatial values, read through [Row::get_type].
+    Binary,
+    /// Dates and times, read as strings.
+    Date,
+    Time,
+    Timestamp,
+}
+
+impl ValueKind {
+    /// Returns how values of a column type are read.
+    ///
+    /// Exact numerics are read as strings, since the C API doesn't report their precision and scale.
+    pub(crate) fn from_column_type(column_type: ColumnType) -> ValueKind {
+        match column_type {
+            ColumnType::SmallInt | ColumnType::Integer => ValueKind::Int32,
+            ColumnType::BigInt => ValueKind::Int64,
+            ColumnType::Real => ValueKind::Float32,
+            ColumnType::Double | ColumnType::Float => ValueKind::Float64,
+            ColumnType::Boolean => ValueKind::Boolean,
+            ColumnType::Date => ValueKind::Date,
+            ColumnType::Time => ValueKind::Time,
+            ColumnType::Timestamp => ValueKind::Timestamp,
+            ColumnType::Clob | ColumnType::Nclob => ValueKind::Clob,
+            ColumnType::Binary
+            | ColumnType::BinaryVarying
+            | ColumnType::Blob
+            | ColumnType::GisLocation
+            | ColumnType::GisLatitude
+            | ColumnType::GisLongitude
+            | ColumnType::GisCoordinate => ValueKind::Binary,
+            _ => ValueKind::Utf8,
+        }
+    }
+}
+
+/// Describes a column of a result set for columnar conversion.
+pub(crate) struct ColumnDescription {
+    pub(crate) name: String,
+    pub(crate) column_type: ColumnType,
+    pub(crate) kind: ValueKind,
+    /// False only for native numeric columns declared NOT NULL.
+    #[cfg_attr(not(feature = "arrow"), allow(dead_code))]
+    // Polars series don't carry nullability
+    pub(crate) nullable: bool,
+}
+
+/// Describes the columns of the result set of a statement.
+pub(crate) fn describe_columns(
+    inner_statement: &InnerStatement,
+) -> Result<Vec<ColumnDescription>, i32> {
+    let handle = inner_statement.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
+    let column_count = unsafe { ffi::MimerColumnCount(*handle) };
+    if column_count < 0 {
+        return Err(column_count);
+    }
+
+    let mut columns = Vec::with_capacity(column_count as usize);
+    for idx in 1..=column_count as i16 {
+        let code = unsafe { ffi::MimerColumnType(*handle, idx) };
+        if code < 0 {
+            return Err(code);
+        }
+        let name =
+            read_string(|buf, size| unsafe { ffi::MimerColumnName8(*handle, idx, buf, size) })?
+                .unwrap_or_default();
+        let column_type = ColumnType::from_code(code);
+        columns.push(ColumnDescription {
+            name,
+            column_type,
+            kind: ValueKind::from_column_type(column_type),
+            nullable: ColumnType::nullable_from_code(code).unwrap_or(true),
+        });
+    }
+    Ok(columns)
+}
+
+/// Calls `f` with the statement handle of a row.
+fn with_handle<T, F>(row: &Row, f: F) -> Result<T, i32>
+where
+    F: FnOnce(ffi::MimerStatement) -> Result<T, i32>,
+{
+    let strong_inner_statement = row.inner_statement.upgrade().ok_or(-26004)?;
+    let handle = strong_inner_statement.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
+    f(*handle)
+}
+
+/// Reads a fixed size value with one of the numeric getters of the C API. Returns [`Ok<None>`] for null values.
+fn read_fixed<T: Default, F>(row: &Row, get: F) -> Result<Option<T>, i32>
+where
+    F: FnOnce(ffi::MimerStatement, &mut T) -> i32,
+{
+    with_handle(row, |handle| {
+        let mut val = T::default();
+        match get(handle, &mut val) {
+            ffi::MIMER_SQL_NULL_VALUE => Ok(None),
+            rc if rc < 0 => Err(rc),
+            _ => Ok(Some(val)),
+        }
+    })
+}
+
+pub(crate) fn read_i32(row: &Row, idx: i16) -> Result<Option<i32>, i32> {
+    read_fixed(row, |h, v| unsafe { ffi::MimerGetInt32(h, idx, v) })
+}
+
+pub(crate) fn read_i64(row: &Row, idx: i16) -> Result<Option<i64>, i32> {
+    read_fixed(row, |h, v| unsafe { ffi::MimerGetInt64(h, idx, v) })
+}
+
+pub(crate) fn read_f32(row: &Row, idx: i16) -> Result<Option<f32>, i32> {
+    read_fixed(row, |h, v| unsafe { ffi::MimerGetFloat(h, idx, v) })
+}
+
+pub(crate) fn read_f64(row: &Row, idx: i16) -> Result<Option<f64>, i32> {
+    read_fixed(row, |h, v| unsafe { ffi::MimerGetDouble(h, idx, v) })
+}
+
+pub(crate) fn read_bool(row: &Row, idx: i16) -> Result<Option<bool>, i32> {
+    match with_handle(row, |h| unsafe { Ok(ffi::MimerGetBoolean(h, idx)) })? {
+        1 => Ok(Some(true)),
+        0 => Ok(Some(false)),
+        ffi::MIMER_SQL_NULL_VALUE => Ok(None),
+        rc => Err(rc),
+    }
+}
+
+/// Reads a value of any kind except [ValueKind::Binary] as a string.
+pub(crate) fn read_str(row: &Row, idx: i16, kind: ValueKind) -> Result<Option<String>, i32> {
+    match kind {
+        ValueKind::Clob => match row.get_type(idx)? {
+            MimerDatatype::String(val) => Ok(Some(val)),
+            MimerDatatype::Null => Ok(None),
+            _ => Err(-26200),
+        },
+        _ => with_handle(row, |h| {
+            read_string(|buf, size| unsafe { ffi::MimerGetString8(h, idx, buf, size) })
+        }),
+    }
+}
+
+pub(crate) fn read_binary(row: &Row, idx: i16) -> Result<Option<Vec<u8>>, i32> {
+    match row.get_type(idx)? {
+        MimerDatatype::BinaryArray(val) => Ok(Some(val)),
+        MimerDatatype::Null => Ok(None),
+        _ => Err(-26200),
+    }
+}
+
+/// Reads a value of any kind as a string, in its SQL string form except for binary values which are read in hexadecimal.
+pub(crate) fn read_text(row: &Row, idx: i16, kind: ValueKind) -> Result<Option<String>, i32> {
+    Ok(match kind {
+        ValueKind::Int32 => read_i32(row, idx)?.map(|v| v.to_string()),
+        ValueKind::Int64 => read_i64(row, idx)?.map(|v| v.to_string()),
+        ValueKind::Float32 => read_f32(row, idx)?.map(|v| v.to_string()),
+        ValueKind::Float64 => read_f64(row, idx)?.map(|v| v.to_string()),
+        ValueKind::Boolean => read_bool(row, idx)?.map(|v| v.to_string()),
+        ValueKind::Binary => {
+            read_binary(row, idx)?.map(|v| v.iter().map(|b| format!("{b:02X}")).collect())
+        }
+        kind => read_str(row, idx, kind)?,
+    })
+}
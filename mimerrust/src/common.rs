@@ -25,6 +25,7 @@
 /// Defines enums of options for methods that need them.
 pub mod mimer_options {
     use mimerrust_sys as ffi;
+    use std::fmt;
 
     /// Cursor mode options.
     #[derive(PartialEq, Clone, Copy)]
@@ -64,7 +65,7 @@ pub mod mimer_options {
     }
 
     /// Transaction mode options.
-    #[derive(PartialEq, Clone, Copy)]
+    #[derive(PartialEq, Clone, Copy, Debug)]
     pub enum TransactionMode {
         ReadOnly = ffi::MIMER_TRANS_READONLY as isize,
         ReadWrite = ffi::MIMER_TRANS_READWRITE as isize,
@@ -77,6 +78,52 @@ pub mod mimer_options {
         Commit = ffi::MIMER_COMMIT as isize,
     }
 
+    /// How [get_by_name](crate::Row::get_by_name()) matches a name against the column names reported by the server,
+    /// set per connection with [set_column_name_resolution](crate::Connection::set_column_name_resolution()).
+    ///
+    /// Mimer SQL stores unquoted identifiers in uppercase, so a column created as `id` is reported as `ID`.
+    #[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+    pub enum ColumnNameResolution {
+        /// The name must equal the column name exactly.
+        Exact,
+        /// The name matches a column name exactly, or after being converted to uppercase like an unquoted SQL identifier. This is the default.
+        #[default]
+        UppercaseFold,
+        /// The name matches a column name regardless of case.
+        CaseInsensitive,
+    }
+
+    impl ColumnNameResolution {
+        /// Returns whether `name` refers to the column `column_name` under this policy.
+        pub(crate) fn matches(self, column_name: &str, name: &str) -> bool {
+            match self {
+                ColumnNameResolution::Exact => column_name == name,
+                ColumnNameResolution::UppercaseFold => {
+                    column_name == name || column_name == name.to_uppercase()
+                }
+                ColumnNameResolution::CaseInsensitive => {
+                    column_name.to_uppercase() == name.to_uppercase()
+                }
+            }
+        }
+
+        pub(crate) fn from_u8(value: u8) -> ColumnNameResolution {
+            match value {
+                0 => ColumnNameResolution::Exact,
+                2 => ColumnNameResolution::CaseInsensitive,
+                _ => ColumnNameResolution::UppercaseFold,
+            }
+        }
+
+        pub(crate) fn as_u8(self) -> u8 {
+            match self {
+                ColumnNameResolution::Exact => 0,
+                ColumnNameResolution::UppercaseFold => 1,
+                ColumnNameResolution::CaseInsensitive => 2,
+            }
+        }
+    }
+
     /// Parametermodes used in routines
     #[derive(PartialEq, Clone, Copy, Debug)]
     pub enum ParameterMode {
@@ -85,28 +132,273 @@ pub mod mimer_options {
         INOUT = 3,
     }
 
-    /// Option for [get_statistics](crate::Connection::get_statistics()).
-    pub const BSI_4K: i32 = ffi::BSI_4K_PAGES as i32;
-    /// Option for [get_statistics](crate::Connection::get_statistics()).
-    pub const BSI_32K: i32 = ffi::BSI_32K_PAGES as i32;
-    /// Option for [get_statistics](crate::Connection::get_statistics()).
-    pub const BSI_128K: i32 = ffi::BSI_128K_PAGES as i32;
-    /// Option for [get_statistics](crate::Connection::get_statistics()).
-    pub const BSI_4K_USED: i32 = ffi::BSI_4K_PAGES_USED as i32;
-    /// Option for [get_statistics](crate::Connection::get_statistics()).
-    pub const BSI_32K_USED: i32 = ffi::BSI_32K_PAGES_USED as i32;
-    /// Option for [get_statistics](crate::Connection::get_statistics()).
-    pub const BSI_128K_USED: i32 = ffi::BSI_128K_PAGES_USED as i32;
-    /// Option for [get_statistics](crate::Connection::get_statistics()).
-    pub const BSI_PAGES_USED: i32 = ffi::BSI_PAGES_USED as i32;
+    /// Data type of a column or parameter, as returned by [column_type](crate::Statement::column_type()) and [parameter_type](crate::Statement::parameter_type()).
+    /// Type codes without a variant of their own are returned as [Other](ColumnType::Other).
+    #[derive(PartialEq, Eq, Clone, Copy, Debug)]
+    #[non_exhaustive]
+    pub enum ColumnType {
+        Character,
+        CharacterVarying,
+        NationalCharacter,
+        NationalCharacterVarying,
+        Decimal,
+        Numeric,
+        SmallInt,
+        Integer,
+        BigInt,
+        Real,
+        Double,
+        Float,
+        Boolean,
+        Date,
+        Time,
+        Timestamp,
+        IntervalYear,
+        IntervalMonth,
+        IntervalDay,
+        IntervalHour,
+        IntervalMinute,
+        IntervalSecond,
+        IntervalYearToMonth,
+        IntervalDayToHour,
+        IntervalDayToMinute,
+        IntervalDayToSecond,
+        IntervalHourToMinute,
+        IntervalHourToSecond,
+        IntervalMinuteToSecond,
+        Binary,
+        BinaryVarying,
+        Blob,
+        Clob,
+        Nclob,
+        Uuid,
+        GisLocation,
+        GisLatitude,
+        GisLongitude,
+        GisCoordinate,
+        /// A type code from the Mimer SQL C API without a variant of its own.
+        Other(i32),
+    }
+
+    impl ColumnType {
+        /// Converts a type code from the Mimer SQL C API.
+        pub(crate) fn from_code(code: i32) -> ColumnType {
+            match code as u32 {
+                ffi::MIMER_CHARACTER => ColumnType::Character,
+                ffi::MIMER_CHARACTER_VARYING | ffi::MIMER_UTF8 => ColumnType::CharacterVarying,
+                ffi::MIMER_NCHAR => ColumnType::NationalCharacter,
+                ffi::MIMER_NCHAR_VARYING => ColumnType::NationalCharacterVarying,
+                ffi::MIMER_DECIMAL | ffi::MIMER_GOLDEN_DECIMAL => ColumnType::Decimal,
+                ffi::MIMER_NUMERIC => ColumnType::Numeric,
+                ffi::MIMER_T_SMALLINT
+                | ffi::MIMER_NATIVE_SMALLINT
+                | ffi::MIMER_NATIVE_SMALLINT_NULLABLE => ColumnType::SmallInt,
+                ffi::MIMER_INTEGER
+                | ffi::MIMER_GOLDEN_INTEGER
+                | ffi::MIMER_T_INTEGER
+                | ffi::MIMER_NATIVE_INTEGER
+                | ffi::MIMER_NATIVE_INTEGER_NULLABLE => ColumnType::Integer,
+                ffi::MIMER_T_BIGINT
+                | ffi::MIMER_NATIVE_BIGINT
+                | ffi::MIMER_NATIVE_BIGINT_NULLABLE => ColumnType::BigInt,
+                ffi::MIMER_T_REAL | ffi::MIMER_NATIVE_REAL | ffi::MIMER_NATIVE_REAL_NULLABLE => {
+                    ColumnType::Real
+                }
+                ffi::MIMER_T_DOUBLE
+                | ffi::MIMER_NATIVE_DOUBLE
+                | ffi::MIMER_NATIVE_DOUBLE_NULLABLE => ColumnType::Double,
+                ffi::MIMER_FLOAT | ffi::MIMER_T_FLOAT => ColumnType::Float,
+                ffi::MIMER_BOOLEAN => ColumnType::Boolean,
+                ffi::MIMER_DATE => ColumnType::Date,
+                ffi::MIMER_TIME => ColumnType::Time,
+                ffi::MIMER_TIMESTAMP => ColumnType::Timestamp,
+                ffi::MIMER_INTERVAL_YEAR => ColumnType::IntervalYear,
+                ffi::MIMER_INTERVAL_MONTH => ColumnType::IntervalMonth,
+                ffi::MIMER_INTERVAL_DAY => ColumnType::IntervalDay,
+                ffi::MIMER_INTERVAL_HOUR => ColumnType::IntervalHour,
+                ffi::MIMER_INTERVAL_MINUTE => ColumnType::IntervalMinute,
+                ffi::MIMER_INTERVAL_SECOND => ColumnType::IntervalSecond,
+                ffi::MIMER_INTERVAL_YEAR_TO_MONTH => ColumnType::IntervalYearToMonth,
+                ffi::MIMER_INTERVAL_DAY_TO_HOUR => ColumnType::IntervalDayToHour,
+                ffi::MIMER_INTERVAL_DAY_TO_MINUTE => ColumnType::IntervalDayToMinute,
+                ffi::MIMER_INTERVAL_DAY_TO_SECOND => ColumnType::IntervalDayToSecond,
+                ffi::MIMER_INTERVAL_HOUR_TO_MINUTE => ColumnType::IntervalHourToMinute,
+                ffi::MIMER_INTERVAL_HOUR_TO_SECOND => ColumnType::IntervalHourToSecond,
+                ffi::MIMER_INTERVAL_MINUTE_TO_SECOND => ColumnType::IntervalMinuteToSecond,
+                ffi::MIMER_BINARY => ColumnType::Binary,
+                ffi::MIMER_BINARY_VARYING => ColumnType::BinaryVarying,
+                ffi::MIMER_BLOB | ffi::MIMER_NATIVE_BLOB => ColumnType::Blob,
+                ffi::MIMER_CLOB | ffi::MIMER_NATIVE_CLOB => ColumnType::Clob,
+                ffi::MIMER_NCLOB | ffi::MIMER_NATIVE_NCLOB => ColumnType::Nclob,
+                ffi::MIMER_UUID => ColumnType::Uuid,
+                ffi::MIMER_GIS_LOCATION => ColumnType::GisLocation,
+                ffi::MIMER_GIS_LATITUDE => ColumnType::GisLatitude,
+                ffi::MIMER_GIS_LONGITUDE => ColumnType::GisLongitude,
+                ffi::MIMER_GIS_COORDINATE => ColumnType::GisCoordinate,
+                _ => ColumnType::Other(code),
+            }
+        }
+
+        /// Returns the number of bytes used by a value of the type, if the type has a fixed size.
+        /// Character, binary, numeric, temporal and large object types have a declared length and return [None].
+        pub fn fixed_size(&self) -> Option<usize> {
+            match self {
+                ColumnType::Boolean => Some(1),
+                ColumnType::SmallInt => Some(2),
+                ColumnType::Integer
+                | ColumnType::Real
+                | ColumnType::GisLatitude
+                | ColumnType::GisLongitude => Some(4),
+                ColumnType::BigInt
+                | ColumnType::Double
+                | ColumnType::GisLocation
+                | ColumnType::GisCoordinate => Some(8),
+                ColumnType::Uuid => Some(16),
+                _ => None,
+            }
+        }
+
+        /// Returns the Rust type that values of the type are best fetched as with [get](crate::Row::get()), e.g. `"i32"` for *INTEGER*.
+        /// Types without a dedicated conversion are recommended to be fetched as a [String] or a [MimerDatatype](crate::MimerDatatype).
+        ///
+        /// # Examples
+        /// ```
+        /// # use mimerrust::*;
+        /// assert_eq!(ColumnType::Date.recommended_rust_type(), "chrono::NaiveDate");
+        /// assert_eq!(ColumnType::IntervalDayToSecond.recommended_rust_type(), "IntervalDayTime");
+        /// ```
+        pub fn recommended_rust_type(&self) -> &'static str {
+            match self {
+                ColumnType::Character
+                | ColumnType::CharacterVarying
+                | ColumnType::NationalCharacter
+                | ColumnType::NationalCharacterVarying
+                | ColumnType::Clob
+                | ColumnType::Nclob
+                | ColumnType::Decimal
+                | ColumnType::Numeric => "String",
+                ColumnType::SmallInt => "i16",
+                ColumnType::Integer => "i32",
+                ColumnType::BigInt => "i64",
+                ColumnType::Real | ColumnType::GisLatitude | ColumnType::GisLongitude => "f32",
+                ColumnType::Double | ColumnType::Float => "f64",
+                ColumnType::Boolean => "bool",
+                ColumnType::Date => "chrono::NaiveDate",
+                ColumnType::Time => "chrono::NaiveTime",
+                ColumnType::Timestamp => "chrono::NaiveDateTime",
+                ColumnType::IntervalYear
+                | ColumnType::IntervalMonth
+                | ColumnType::IntervalYearToMonth => "IntervalYearMonth",
+                ColumnType::IntervalDay
+                | ColumnType::IntervalHour
+                | ColumnType::IntervalMinute
+                | ColumnType::IntervalSecond
+                | ColumnType::IntervalDayToHour
+                | ColumnType::IntervalDayToMinute
+                | ColumnType::IntervalDayToSecond
+                | ColumnType::IntervalHourToMinute
+                | ColumnType::IntervalHourToSecond
+                | ColumnType::IntervalMinuteToSecond => "IntervalDayTime",
+                ColumnType::Binary | ColumnType::BinaryVarying | ColumnType::Blob => "Vec<u8>",
+                ColumnType::Uuid => "uuid::Uuid",
+                ColumnType::GisLocation => "(f32, f32)",
+                ColumnType::GisCoordinate => "geo::Point<i32>",
+                ColumnType::Other(_) => "MimerDatatype",
+            }
+        }
+
+        /// Returns a hint for a failed conversion from a column of the type, naming the recommended Rust type.
+        ///
+        /// # Examples
+        /// ```
+        /// # use mimerrust::*;
+        /// assert_eq!(
+        ///     ColumnType::IntervalDayToSecond.conversion_hint(),
+        ///     "column is INTERVAL DAY TO SECOND, consider fetching as IntervalDayTime"
+        /// );
+        /// ```
+        pub fn conversion_hint(&self) -> String {
+            format!(
+                "column is {self}, consider fetching as {}",
+                self.recommended_rust_type()
+            )
+        }
+
+        /// Returns whether a type code from the Mimer SQL C API tells if the value is nullable.
+        /// Only the native numeric types come in a nullable and a not nullable variant.
+        pub(crate) fn nullable_from_code(code: i32) -> Option<bool> {
+            match code as u32 {
+                ffi::MIMER_NATIVE_SMALLINT_NULLABLE
+                | ffi::MIMER_NATIVE_INTEGER_NULLABLE
+                | ffi::MIMER_NATIVE_BIGINT_NULLABLE
+                | ffi::MIMER_NATIVE_REAL_NULLABLE
+                | ffi::MIMER_NATIVE_DOUBLE_NULLABLE => Some(true),
+                ffi::MIMER_NATIVE_SMALLINT
+                | ffi::MIMER_NATIVE_INTEGER
+                | ffi::MIMER_NATIVE_BIGINT
+                | ffi::MIMER_NATIVE_REAL
+                | ffi::MIMER_NATIVE_DOUBLE => Some(false),
+                _ => None,
+            }
+        }
+    }
+
+    impl fmt::Display for ColumnType {
+        /// Writes the name of the type as it is written in SQL, e.g. `INTERVAL DAY TO SECOND`.
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let name = match self {
+                ColumnType::Character => "CHARACTER",
+                ColumnType::CharacterVarying => "CHARACTER VARYING",
+                ColumnType::NationalCharacter => "NATIONAL CHARACTER",
+                ColumnType::NationalCharacterVarying => "NATIONAL CHARACTER VARYING",
+                ColumnType::Decimal => "DECIMAL",
+                ColumnType::Numeric => "NUMERIC",
+                ColumnType::SmallInt => "SMALLINT",
+                ColumnType::Integer => "INTEGER",
+                ColumnType::BigInt => "BIGINT",
+                ColumnType::Real => "REAL",
+                ColumnType::Double => "DOUBLE PRECISION",
+                ColumnType::Float => "FLOAT",
+                ColumnType::Boolean => "BOOLEAN",
+                ColumnType::Date => "DATE",
+                ColumnType::Time => "TIME",
+                ColumnType::Timestamp => "TIMESTAMP",
+                ColumnType::IntervalYear => "INTERVAL YEAR",
+                ColumnType::IntervalMonth => "INTERVAL MONTH",
+                ColumnType::IntervalDay => "INTERVAL DAY",
+                ColumnType::IntervalHour => "INTERVAL HOUR",
+                ColumnType::IntervalMinute => "INTERVAL MINUTE",
+                ColumnType::IntervalSecond => "INTERVAL SECOND",
+                ColumnType::IntervalYearToMonth => "INTERVAL YEAR TO MONTH",
+                ColumnType::IntervalDayToHour => "INTERVAL DAY TO HOUR",
+                ColumnType::IntervalDayToMinute => "INTERVAL DAY TO MINUTE",
+                ColumnType::IntervalDayToSecond => "INTERVAL DAY TO SECOND",
+                ColumnType::IntervalHourToMinute => "INTERVAL HOUR TO MINUTE",
+                ColumnType::IntervalHourToSecond => "INTERVAL HOUR TO SECOND",
+                ColumnType::IntervalMinuteToSecond => "INTERVAL MINUTE TO SECOND",
+                ColumnType::Binary => "BINARY",
+                ColumnType::BinaryVarying => "BINARY VARYING",
+                ColumnType::Blob => "BINARY LARGE OBJECT",
+                ColumnType::Clob => "CHARACTER LARGE OBJECT",
+                ColumnType::Nclob => "NATIONAL CHARACTER LARGE OBJECT",
+                ColumnType::Uuid => "BUILTIN.UUID",
+                ColumnType::GisLocation => "BUILTIN.GIS_LOCATION",
+                ColumnType::GisLatitude => "BUILTIN.GIS_LATITUDE",
+                ColumnType::GisLongitude => "BUILTIN.GIS_LONGITUDE",
+                ColumnType::GisCoordinate => "BUILTIN.GIS_COORDINATE",
+                ColumnType::Other(code) => return write!(f, "type code {code}"),
+            };
+            f.write_str(name)
+        }
+    }
 }
 
 pub mod traits {
+    use crate::session_guard::SessionGuard;
     use mimerrust_sys as ffi;
-    use parking_lot::MappedMutexGuard;
     pub(crate) enum MimerHandle<'a> {
-        Session(MappedMutexGuard<'a, ffi::MimerSession>),
-        Statement(MappedMutexGuard<'a, ffi::MimerStatement>),
+        Session(SessionGuard<'a, ffi::MimerSession>),
+        Statement(SessionGuard<'a, ffi::MimerStatement>),
     }
 
     pub(crate) trait GetHandle {
@@ -114,13 +406,11 @@ pub mod traits {
             Err(-26100)
         }
 
-        fn get_session_handle(&self) -> Result<Option<MappedMutexGuard<ffi::MimerSession>>, i32> {
+        fn get_session_handle(&self) -> Result<Option<SessionGuard<ffi::MimerSession>>, i32> {
             Ok(None)
         }
 
-        fn get_statement_handle(
-            &self,
-        ) -> Result<Option<MappedMutexGuard<ffi::MimerStatement>>, i32> {
+        fn get_statement_handle(&self) -> Result<Option<SessionGuard<ffi::MimerStatement>>, i32> {
             Ok(None)
         }
     }
@@ -132,7 +422,7 @@ pub mod traits {
 
         #[test]
         fn get_handle() {
-            let mut conn = establish_connection();
+            let conn = establish_connection();
 
             drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
             let stmnt = conn
@@ -0,0 +1,179 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use std::{env, fs, path::PathBuf};
+
+/// Where a database registered in `SQLHOSTS` is located.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DatabaseKind {
+    /// A database on the local machine, registered in the `LOCAL` section.
+    Local,
+    /// A database reached over the network, registered in the `REMOTE` section.
+    Remote,
+}
+
+/// A database registered in `SQLHOSTS`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatabaseEntry {
+    /// Name of the database, as passed to [Connection::open](crate::Connection::open).
+    pub name: String,
+    /// Whether the database is local or remote.
+    pub kind: DatabaseKind,
+    /// Whether the database is named in the `DEFAULT` section, i.e. used when connecting with an empty database name.
+    pub is_default: bool,
+}
+
+/// Returns the path of the `SQLHOSTS` file, which is `MIMER_SQLHOSTS` if set and `/etc/sqlhosts` otherwise.
+pub fn sqlhosts_path() -> PathBuf {
+    env::var_os("MIMER_SQLHOSTS")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("/etc/sqlhosts"))
+}
+
+/// Lists the databases registered in the local `SQLHOSTS` file.
+/// On Windows the registrations are stored in the registry by the Mimer Administrator, in which case `MIMER_SQLHOSTS` must point to an exported file.
+///
+/// # Errors
+/// Returns [Err] with -26014 when the `SQLHOSTS` file couldn't be read.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::config;
+/// for database in config::list_databases().unwrap_or_default() {
+///     println!("{} ({:?})", database.name, database.kind);
+/// }
+/// ```
+pub fn list_databases() -> Result<Vec<DatabaseEntry>, i32> {
+    let content = fs::read_to_string(sqlhosts_path()).map_err(|_| -26014)?; // Could not read SQLHOSTS
+    Ok(parse_sqlhosts(&content))
+}
+
+/// Parses the content of a `SQLHOSTS` file.
+/// Lines starting with `--` are comments, and the sections are introduced by `LOCAL:`, `REMOTE:` and `DEFAULT:`.
+/// The first word of each entry is the database name.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::config::*;
+/// let sqlhosts = "
+/// LOCAL:
+///     mydb        /var/lib/mimer/mydb
+/// REMOTE:
+///     otherdb     server.example.com  ''  tcp  1360
+/// DEFAULT:
+///     mydb
+/// ";
+/// let databases = parse_sqlhosts(sqlhosts);
+/// assert_eq!(databases.len(), 2);
+/// assert_eq!(databases[1].kind, DatabaseKind::Remote);
+/// assert!(databases[0].is_default);
+/// ```
+pub fn parse_sqlhosts(content: &str) -> Vec<DatabaseEntry> {
+    enum Section {
+        None,
+        Local,
+        Remote,
+        Default,
+    }
+
+    let mut section = Section::None;
+    let mut entries: Vec<DatabaseEntry> = Vec::new();
+    let mut defaults: Vec<String> = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("--") {
+            continue;
+        }
+        match line.to_ascii_uppercase().as_str() {
+            "LOCAL:" => section = Section::Local,
+            "REMOTE:" => section = Section::Remote,
+            "DEFAULT:" => section = Section::Default,
+            _ => {
+                let name = line.split_whitespace().next().unwrap().to_string(); // Ok unwrap since the line isn't empty
+                match section {
+                    Section::Local => entries.push(DatabaseEntry {
+                        name,
+                        kind: DatabaseKind::Local,
+                        is_default: false,
+                    }),
+                    Section::Remote => entries.push(DatabaseEntry {
+                        name,
+                        kind: DatabaseKind::Remote,
+                        is_default: false,
+                    }),
+                    Section::Default => defaults.push(name),
+                    Section::None => (),
+                }
+            }
+        }
+    }
+
+    for entry in entries.iter_mut() {
+        entry.is_default = defaults.iter().any(|d| d.eq_ignore_ascii_case(&entry.name));
+    }
+    entries
+}
+
+#[cfg(test)]
+mod config_tests {
+    use super::*;
+
+    #[test]
+    fn parse_sqlhosts_sections() {
+        let sqlhosts = "
+-- Mimer SQL hosts file
+LOCAL:
+-- Database     Path
+   localdb      /var/lib/mimer/localdb
+REMOTE:
+-- Database     Node        Interface   Protocol    Service
+   remotedb     dbhost      ''          tcp         1360
+DEFAULT:
+   remotedb
+";
+        assert_eq!(
+            parse_sqlhosts(sqlhosts),
+            vec![
+                DatabaseEntry {
+                    name: String::from("localdb"),
+                    kind: DatabaseKind::Local,
+                    is_default: false,
+                },
+                DatabaseEntry {
+                    name: String::from("remotedb"),
+                    kind: DatabaseKind::Remote,
+                    is_default: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn list_databases_contains_test_database() {
+        let db = std::env::var("MIMER_DATABASE").unwrap();
+        let databases = list_databases().unwrap();
+        assert!(databases.iter().any(|d| d.name.eq_ignore_ascii_case(&db)));
+    }
+}
@@ -24,22 +24,61 @@
 
 use crate::{
     common::{mimer_options::*, return_codes::*, traits::*},
-    inner_connection::InnerConnection,
-    MimerError, Statement, Transaction,
+    connection_options::{ConnectionBuilder, ConnectionOptions},
+    error_sink::unexpected_return_code,
+    ffi_buffer::fill_slice,
+    inner_connection::{secret_cstring, wipe, InnerConnection, RollbackFailureHook},
+    observer::StatementObserver,
+    server_info::Capabilities,
+    session_guard::SessionGuard,
+    statistics::{ServerStatistics, StatisticsCounter},
+    tracing_support::traced,
+    transaction::is_write_statement,
+    types::{FromSql, ToSql},
+    MimerError, Row, Statement, StatementRecord, StatementRetry, Transaction,
 };
 use mimerrust_sys as ffi;
 
 #[doc(hidden)]
-use parking_lot::MappedMutexGuard;
+use parking_lot::MutexGuard;
 #[doc(hidden)]
 use std::{
     cmp::Ordering,
     ffi::CString,
     result::Result::{Err, Ok},
-    sync::Arc,
+    sync::{atomic::Ordering as AtomicOrdering, mpsc, Arc},
+    thread,
 };
 
 /// Represents a connection to a MimerSQL database.
+///
+/// # Thread safety
+/// A connection is [Send] and [Sync], and may be used from any thread. The C API doesn't allow calls on a session and its statements to overlap,
+/// so the connection and its [statements](crate::Statement) share a session lock, which is held for each call into the C API,
+/// e.g. for the whole of [execute_statement](crate::Connection::execute_statement()), preparing a statement, or fetching a row.
+/// Threads sharing a connection are thereby serialized call by call, while different connections don't contend.
+///
+/// The only lock shared by all connections is held while a session is begun or ended, i.e. in [open](crate::Connection::open()) and when the last handle to a connection is dropped.
+///
+/// # Cloning
+/// Cloning a connection is cheap and returns another handle to the same session, so that several parts of an application can hold it
+/// without wrapping it in an `Arc<Mutex<Connection>>`. The clones share the session lock, the statements, the settings, and the transaction state of the session,
+/// so a [Transaction] begun through one clone also covers statements executed through the others.
+/// The session is ended when the last clone is dropped, after which statements prepared through any of them can no longer be used.
+///
+/// ```
+/// # use mimerrust::*;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// let conn = Connection::open(db, ident, pass).unwrap();
+/// let other = conn.clone();
+/// std::thread::spawn(move || other.execute_statement("SELECT 1 FROM SYSTEM.ONEROW").unwrap())
+///     .join()
+///     .unwrap();
+/// conn.execute_statement("SELECT 1 FROM SYSTEM.ONEROW").unwrap();
+/// ```
+#[derive(Clone)]
 pub struct Connection {
     inner_connection: Arc<InnerConnection>,
 }
@@ -48,7 +87,7 @@ impl GetHandle for Connection {
     fn get_handle(&self) -> Result<MimerHandle, i32> {
         self.inner_connection.get_handle()
     }
-    fn get_session_handle(&self) -> Result<Option<MappedMutexGuard<ffi::MimerSession>>, i32> {
+    fn get_session_handle(&self) -> Result<Option<SessionGuard<ffi::MimerSession>>, i32> {
         self.inner_connection.get_session_handle()
     }
 }
@@ -68,12 +107,77 @@ impl Connection {
     /// let conn = Connection::open(db, ident, pass).unwrap();
     /// ```
     pub fn open(database: &str, ident: &str, password: &str) -> Result<Connection, MimerError> {
-        let inner = InnerConnection::open(database, ident, password)?;
+        let inner = traced!(
+            "mimer.connect",
+            { db.name = database, db.user = ident },
+            InnerConnection::open(database, ident, password)
+        )?;
         Ok(Connection {
             inner_connection: Arc::new(inner),
         })
     }
 
+    /// Opens a connection to a MimerSQL database using [ConnectionOptions].
+    ///
+    /// # Errors
+    /// Returns [Err] holding a [MimerError] when a connection failed to open.
+    /// If TLS options are set the connection is not opened and -26012 is returned, since the Mimer SQL C API
+    /// only applies transport security configured in `SQLHOSTS`.
+    /// If a [connect timeout](crate::ConnectionOptions::connect_timeout) is set and the connection isn't opened in time, -26028 is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open_with(&ConnectionOptions::new(db, ident, pass)).unwrap();
+    /// ```
+    pub fn open_with(options: &ConnectionOptions) -> Result<Connection, MimerError> {
+        if options.tls.is_some() {
+            return Err(MimerError::mimer_error_from_code(-26012)); // TLS options can't be applied
+        }
+        let conn = match options.connect_timeout {
+            Some(timeout) => {
+                let (sender, receiver) = mpsc::channel();
+                let (database, ident, password) = (
+                    options.database.clone(),
+                    options.ident.clone(),
+                    options.password.clone(), // Wiped when dropped with the zeroize feature
+                );
+                // A connection that arrives after the timeout is dropped, and thereby closed, when sending fails
+                thread::spawn(move || {
+                    let result = Connection::open(&database, &ident, &password);
+                    drop(password);
+                    let _ = sender.send(result);
+                });
+                receiver
+                    .recv_timeout(timeout)
+                    .map_err(|_| MimerError::mimer_error_from_code(-26028))?? // Timed out opening the connection
+            }
+            None => Connection::open(&options.database, &options.ident, &options.password)?,
+        };
+        if options.read_only {
+            conn.execute_statement("SET SESSION READ ONLY")
+                .map_err(|ec| conn.get_error(ec))?;
+        }
+        Ok(conn)
+    }
+
+    /// Returns a [ConnectionBuilder] for setting the options of a connection one by one before opening it.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::builder().database(db).ident(ident).password(pass).open().unwrap();
+    /// ```
+    pub fn builder() -> ConnectionBuilder {
+        ConnectionBuilder::new()
+    }
+
     /// Returns a MimerError given a [Connection] and a return code.
     /// This can be errors from the Mimer database itself, or errors from the Mimer Rust API.
     ///
@@ -104,6 +208,8 @@ impl Connection {
     ///
     /// # Errors
     /// Returns [`Err`] when a statement can't be executed, e.g. if the query contained a syntax error or if the database server is stopped.
+    /// Returns -26022 for a statement that modifies data while a [ReadOnly](TransactionMode::ReadOnly) [Transaction] is open,
+    /// see [Transaction::execute_statement](crate::Transaction::execute_statement()).
     ///
     /// # Examples
     /// ```
@@ -118,6 +224,14 @@ impl Connection {
     /// conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
     /// ```
     pub fn execute_statement(&self, sqlstatement: &str) -> Result<i32, i32> {
+        self.check_writable(sqlstatement)?;
+        self.inner_connection.record_statement(sqlstatement, 0, || {
+            self.execute_statement_auxillary(sqlstatement)
+        })
+    }
+
+    /// Executes an SQL statement on the session.
+    fn execute_statement_auxillary(&self, sqlstatement: &str) -> Result<i32, i32> {
         let stmnt_char_ptr = CString::new(sqlstatement)
             .or_else(|_| Err(-26999))?
             .into_raw();
@@ -131,18 +245,45 @@ impl Connection {
             match rc.cmp(MIMER_SUCCESS) {
                 Ordering::Less => Err(rc),
                 Ordering::Equal => Ok(rc),
-                Ordering::Greater => {
-                    // i suppose this is a reasonable panic?
-                    panic!("Return code is positive from C API function which doesn't return a positive value");
-                }
+                Ordering::Greater => Err(unexpected_return_code("executing a statement", rc)),
             }
         }
     }
 
+    /// Executes a statement holding a password, such as *ALTER IDENT*, which mustn't reach the statement history, the observer, tracing or metrics.
+    /// They see `recorded` instead, the statement with the password left out.
+    /// The statement and its copy for the C API are wiped after execution with the `zeroize` feature.
+    pub(crate) fn execute_secret_statement(
+        &self,
+        sqlstatement: String,
+        recorded: &str,
+    ) -> Result<i32, i32> {
+        if let Err(ec) = self.check_writable(recorded) {
+            wipe(sqlstatement.into_bytes());
+            return Err(ec);
+        }
+        let result = self.inner_connection.record_statement(recorded, 0, || {
+            let handle = self.get_session_handle()?.unwrap(); //Ok unwrap since we know the session is a session
+            let stmnt = secret_cstring(&sqlstatement).map_err(|err| err.get_error_code())?;
+            let rc = unsafe { ffi::MimerExecuteStatement8(*handle, stmnt.as_ptr()) };
+            drop(handle);
+            wipe(stmnt.into_bytes_with_nul());
+            match rc.cmp(MIMER_SUCCESS) {
+                Ordering::Less => Err(rc),
+                Ordering::Equal => Ok(rc),
+                Ordering::Greater => Err(unexpected_return_code("executing a statement", rc)),
+            }
+        });
+        wipe(sqlstatement.into_bytes());
+        result
+    }
+
     /// Prepares a SQL statement and creates a [Statement].
+    /// Only a shared reference is needed, so statements can be prepared through a connection shared between threads or components.
     ///
     /// # Errors
     /// Returns [Err] when a statement can't be prepared, e.g. if the query contained invalid syntax.
+    /// Returns -26022 for a statement that modifies data while a [ReadOnly](TransactionMode::ReadOnly) [Transaction] is open.
     ///
     /// # Examples
     /// ```
@@ -150,19 +291,149 @@ impl Connection {
     /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
     /// # let ident = "RUSTUSER";
     /// # let pass = "RUSTPASSWORD";
-    /// let mut conn = Connection::open(db, ident, pass).unwrap();
+    /// let conn = Connection::open(db, ident, pass).unwrap();
     ///
     /// # conn.execute_statement("drop table test_table").ok();
     /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
     /// let stmnt = conn.prepare("INSERT INTO test_table VALUES(:column_1,:column_2)", CursorMode::Forward).unwrap();
     /// ```
-    pub fn prepare(&mut self, sqlstatement: &str, option: CursorMode) -> Result<Statement, i32> {
-        let (inner, stmt) =
-            Statement::new(Arc::downgrade(&self.inner_connection), sqlstatement, option)?;
+    pub fn prepare(&self, sqlstatement: &str, option: CursorMode) -> Result<Statement, i32> {
+        self.check_writable(sqlstatement)?;
+        let (inner, stmt) = traced!(
+            "mimer.prepare",
+            {
+                db.statement = crate::tracing_support::statement_text(
+                    Some(&self.inner_connection),
+                    sqlstatement
+                ),
+                params = tracing::field::Empty
+            },
+            {
+                let result =
+                    Statement::new(Arc::downgrade(&self.inner_connection), sqlstatement, option);
+                #[cfg(feature = "tracing")]
+                if let Ok(params) = result.as_ref().map_err(|_| ()).and_then(|(_, stmt)| {
+                    stmt.num_params().map_err(|_| ())
+                }) {
+                    tracing::Span::current().record("params", params);
+                }
+                result
+            }
+        )?;
         self.inner_connection.push_statement(inner);
         Ok(stmt)
     }
 
+    /// Executes a query and maps the first row of the result set using the closure `f`.
+    /// The statement is prepared, bound with `params`, executed and closed within the call.
+    /// Any rows after the first one are ignored.
+    ///
+    /// # Errors
+    /// Returns [Err] when the query couldn't be prepared or executed, when it returned no rows (-26009), or when `f` returns an error.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
+    /// let (text, number) = conn
+    ///     .query_row("SELECT * FROM test_table WHERE column_2 = ?", &[&1], |row| {
+    ///         Ok((row.get::<String>(1)?, row.get::<i32>(2)?))
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn query_row<T, F>(&self, sqlstatement: &str, params: &[&dyn ToSql], f: F) -> Result<T, i32>
+    where
+        F: FnOnce(&Row) -> Result<T, i32>,
+    {
+        let stmnt = self.prepare(sqlstatement, CursorMode::Forward)?;
+        if !params.is_empty() {
+            stmnt.bind_params(params)?;
+        }
+        let mut cursor = stmnt.open_cursor()?;
+        match cursor.next_row()? {
+            Some(row) => f(row),
+            None => Err(-26009), // Query returned no rows
+        }
+    }
+
+    /// Executes a query and maps every row of the result set using the closure `f`.
+    /// The statement is prepared, bound with `params`, executed and closed within the call, and the mapped rows are returned in a [Vec].
+    ///
+    /// # Errors
+    /// Returns [Err] when the query couldn't be prepared or executed, when a row couldn't be fetched, or when `f` returns an error.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
+    /// let texts: Vec<String> = conn
+    ///     .query_map("SELECT column_1 FROM test_table", &[], |row| {
+    ///         Ok(row.get::<String>(1)?.unwrap_or_default())
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn query_map<T, F>(
+        &self,
+        sqlstatement: &str,
+        params: &[&dyn ToSql],
+        mut f: F,
+    ) -> Result<Vec<T>, i32>
+    where
+        F: FnMut(&Row) -> Result<T, i32>,
+    {
+        let stmnt = self.prepare(sqlstatement, CursorMode::Forward)?;
+        if !params.is_empty() {
+            stmnt.bind_params(params)?;
+        }
+        let mut cursor = stmnt.open_cursor()?;
+        let mut mapped = Vec::new();
+        while let Some(row) = cursor.next_row()? {
+            mapped.push(f(row)?);
+        }
+        Ok(mapped)
+    }
+
+    /// Executes a query and returns the value of the first column of the first row.
+    /// Returns [`Ok<None>`] if the value is null.
+    ///
+    /// # Errors
+    /// Returns [Err] when the query couldn't be prepared or executed, when it returned no rows (-26009), or when the value couldn't be converted to `T`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
+    /// let text = conn
+    ///     .query_scalar::<String>("SELECT column_1 FROM test_table WHERE column_2 = ?", &[&1])
+    ///     .unwrap();
+    /// assert_eq!(text.as_deref(), Some("the number one"));
+    /// ```
+    pub fn query_scalar<T: FromSql>(
+        &self,
+        sqlstatement: &str,
+        params: &[&dyn ToSql],
+    ) -> Result<Option<T>, i32> {
+        self.query_row(sqlstatement, params, |row| row.get::<T>(1))
+    }
+
     /// Initiates a database transaction.
     /// This method only needs to be called if two or more database operations should participate in the transaction.
     ///
@@ -175,7 +446,7 @@ impl Connection {
     /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
     /// # let ident = "RUSTUSER";
     /// # let pass = "RUSTPASSWORD";
-    /// let mut conn = Connection::open(db, ident, pass).unwrap();
+    /// let conn = Connection::open(db, ident, pass).unwrap();
     /// let trans_option = TransactionMode::ReadWrite;
     /// let trans = conn.begin_transaction(trans_option).unwrap();
     ///
@@ -183,71 +454,326 @@ impl Connection {
     ///
     /// trans.commit().unwrap();
     /// ```
-    pub fn begin_transaction(&mut self, trans_option: TransactionMode) -> Result<Transaction, i32> {
+    pub fn begin_transaction(&self, trans_option: TransactionMode) -> Result<Transaction, i32> {
         Transaction::new(self, trans_option)
     }
-    /// Obtains server statistics information.
-    /// Statistics is returned in the form of counters.
-    /// Counters may either be an absolute value representing the current status or a monotonically increasing value representing the number of occurred events since the server started.
-    /// An example of the former is current number of users and an example of the latter is number of server page requests.
-    /// The available counter values are:
-    ///
+
+    /// Runs `f` with the connection, running it again when it fails with an error that the policy retries, by default [TRANSACTION_CONFLICT](crate::TRANSACTION_CONFLICT).
+    /// This retries a unit of work as a whole, such as a [Transaction] that is started and committed by `f`, unlike
+    /// [set_retry](crate::Statement::set_retry()) which retries single statements. A transaction that `f` leaves uncommitted on error is rolled back when it is dropped,
+    /// so each attempt starts afresh.
     ///
-    /// - BSI_4K : The number of 4K pages available in the system.
-    /// - BSI_32K : The number of 32K pages available in the system.
-    /// - BSI_128K : The number of 128K pages available in the system.
-    /// - BSI_PAGES_USED : The total number of pages in use.
-    /// - BSI_4K_USED : The number of 4K pages in use.
-    /// - BSI_32K_USED : The number of 32K pages in use.
-    /// - BSI_128K_USED : The number of 128K pages in use.
+    /// A lost connection can't be recovered by retrying on the same connection, so the error codes for it should not be added to the policy.
     ///
     /// # Errors
-    /// Returns [Err] on invalid counter parameter in "counters" argument, or if failed to connect to server.
+    /// Returns the error from the last attempt when `f` fails with an error that isn't retried, or when the retries are used up.
     ///
     /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # use std::time::Duration;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// let policy = StatementRetry::new(5).backoff(Duration::from_millis(10), Duration::from_millis(500));
+    /// conn.run_with_retry(&policy, |conn| {
+    ///     let trans = conn.begin_transaction(TransactionMode::ReadWrite)?;
+    ///     trans.execute_statement("UPDATE test_table SET column_2 = column_2 - 1 WHERE column_1 = 'from'")?;
+    ///     trans.execute_statement("UPDATE test_table SET column_2 = column_2 + 1 WHERE column_1 = 'to'")?;
+    ///     trans.commit()
+    /// })
+    /// .unwrap();
+    /// ```
+    pub fn run_with_retry<T, F>(&self, policy: &StatementRetry, mut f: F) -> Result<T, i32>
+    where
+        F: FnMut(&Connection) -> Result<T, i32>,
+    {
+        policy.run(|| f(self))
+    }
+    /// Sets the maximum number of bytes a fetch buffer may use, or removes the limit with [None].
+    /// The budget applies to all statements of the connection, and is checked by [set_array_size](crate::Statement::set_array_size())
+    /// against the [row size](crate::Statement::row_size()) times the requested array size. By default there is no limit.
     ///
+    /// # Examples
     /// ```
     /// # use mimerrust::*;
     /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
     /// # let ident = "RUSTUSER";
     /// # let pass = "RUSTPASSWORD";
     /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// conn.set_fetch_memory_budget(Some(1024 * 1024));
     ///
-    /// // Specify counters
-    /// let mut counters = vec![BSI_4K,BSI_4K_USED,BSI_32K,BSI_32K_USED,BSI_128K,BSI_128K_USED,BSI_PAGES_USED];
+    /// let stmnt = conn.prepare("SELECT * FROM test_table", CursorMode::Forward).unwrap();
+    /// stmnt.set_array_size(100).unwrap();
+    /// assert_eq!(stmnt.set_array_size(1_000_000), Err(-26015));
+    /// ```
+    pub fn set_fetch_memory_budget(&self, bytes: Option<usize>) {
+        self.inner_connection
+            .fetch_memory_budget
+            .store(bytes.unwrap_or(0), AtomicOrdering::Relaxed);
+    }
+
+    /// Returns the fetch memory budget set with [set_fetch_memory_budget](crate::Connection::set_fetch_memory_budget()).
+    pub fn fetch_memory_budget(&self) -> Option<usize> {
+        match self
+            .inner_connection
+            .fetch_memory_budget
+            .load(AtomicOrdering::Relaxed)
+        {
+            0 => None,
+            bytes => Some(bytes),
+        }
+    }
+
+    /// Sets how [get_by_name](crate::Row::get_by_name()) resolves column names for all statements of the connection.
+    /// The default is [UppercaseFold](ColumnNameResolution::UppercaseFold).
     ///
-    /// // Get statistics
-    /// conn.get_statistics(&mut counters).unwrap();
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), \"Column_2\" INT)").unwrap();
+    /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
+    /// conn.set_column_name_resolution(ColumnNameResolution::CaseInsensitive);
     ///
-    /// // counters now holds statistics from the database
-    /// counters.iter().for_each(|c| assert!(c >= MIMER_SUCCESS));
+    /// let stmnt = conn.prepare("SELECT * FROM test_table", CursorMode::Forward).unwrap();
+    /// let mut cursor = stmnt.open_cursor().unwrap();
+    /// let row = cursor.next_row().unwrap().unwrap();
+    /// let value: i32 = row.get_by_name("column_2").unwrap().unwrap();
     /// ```
+    pub fn set_column_name_resolution(&self, resolution: ColumnNameResolution) {
+        self.inner_connection
+            .column_name_resolution
+            .store(resolution.as_u8(), AtomicOrdering::Relaxed);
+    }
 
-    pub fn get_statistics(&self, counters: &mut Vec<i32>) -> Result<i32, i32> {
-        let num_counters = counters.len() as i16;
-        let counters_arr = counters.as_mut_ptr();
-        let rc: i32;
-        unsafe {
-            rc = ffi::MimerGetStatistics(
-                *self.get_session_handle()?.unwrap(),
-                counters_arr,
-                num_counters,
-            );
+    /// Returns the column name resolution set with [set_column_name_resolution](crate::Connection::set_column_name_resolution()).
+    pub fn column_name_resolution(&self) -> ColumnNameResolution {
+        ColumnNameResolution::from_u8(
+            self.inner_connection
+                .column_name_resolution
+                .load(AtomicOrdering::Relaxed),
+        )
+    }
+
+    /// Sets a function that is called when a [Transaction] that is dropped without being committed or rolled back fails to roll back,
+    /// or removes it with [None]. Without a hook such failures are ignored.
+    ///
+    /// Dropping a transaction that has already ended, e.g. one aborted by the server, is not a failure.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// conn.set_rollback_failure_hook(Some(Box::new(|err| eprintln!("rollback failed: {err}"))));
+    ///
+    /// let trans = conn.begin_transaction(TransactionMode::ReadWrite).unwrap();
+    /// drop(trans); // rolled back
+    /// ```
+    pub fn set_rollback_failure_hook(&self, hook: Option<RollbackFailureHook>) {
+        *self.inner_connection.rollback_failure_hook.lock() = hook;
+    }
+
+    /// Sets a function that is called for every statement executed, cursor opened and cursor closed on the connection, or removes it with [None].
+    /// The function receives the SQL, the duration, the number of rows and the outcome, see [StatementEvent].
+    ///
+    /// The observer is called after the statement has run, on the thread that ran it, and may itself use the connection.
+    /// Rows are only counted and timed for cursors opened while an observer is set.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// conn.set_statement_observer(Some(Box::new(|event| {
+    ///     eprintln!("{:?} {} took {:?}", event.kind, event.sql, event.duration)
+    /// })));
+    ///
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// ```
+    pub fn set_statement_observer(&self, observer: Option<StatementObserver>) {
+        *self.inner_connection.statement_observer.lock() = observer.map(Arc::from);
+    }
+
+    /// Sets whether the SQL in the spans emitted with the `tracing` feature is redacted, replacing string and numeric literals with `?`
+    /// so that values written into the SQL text don't end up in traces. The SQL is not redacted by default.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// conn.set_trace_redaction(true);
+    /// ```
+    #[cfg(feature = "tracing")]
+    pub fn set_trace_redaction(&self, redact: bool) {
+        self.inner_connection
+            .redact_traced_sql
+            .store(redact, AtomicOrdering::Relaxed);
+    }
+
+    /// Returns the hook set with [set_rollback_failure_hook](crate::Connection::set_rollback_failure_hook()).
+    pub(crate) fn rollback_failure_hook(&self) -> MutexGuard<'_, Option<RollbackFailureHook>> {
+        self.inner_connection.rollback_failure_hook.lock()
+    }
+
+    /// Returns the capabilities probed for the connection, [None] until they have been probed.
+    pub(crate) fn cached_capabilities(&self) -> Option<Capabilities> {
+        self.inner_connection.capabilities.get().copied()
+    }
+
+    /// Caches the capabilities probed for the connection.
+    pub(crate) fn cache_capabilities(&self, capabilities: Capabilities) {
+        let _ = self.inner_connection.capabilities.set(capabilities); // Another thread may have probed them first
+    }
+
+    /// Returns the database name given when opening the connection, empty for the default database.
+    pub(crate) fn database(&self) -> &str {
+        &self.inner_connection.database
+    }
+
+    /// Returns the ident given when opening the connection, which it logged in as.
+    pub(crate) fn login_ident(&self) -> &str {
+        &self.inner_connection.ident
+    }
+
+    /// Wraps the inner connection of a statement in a [Connection] sharing its session.
+    pub(crate) fn from_inner(inner_connection: Arc<InnerConnection>) -> Connection {
+        Connection { inner_connection }
+    }
+
+    /// Returns the statement history recorded by a [Transaction], [None] when no transaction is recording.
+    pub(crate) fn statement_history(&self) -> MutexGuard<'_, Option<Vec<StatementRecord>>> {
+        self.inner_connection.statement_history.lock()
+    }
+
+    /// Records whether a read-only transaction is open on the session, for [check_writable](Connection::check_writable()).
+    pub(crate) fn set_read_only_transaction(&self, read_only: bool) {
+        self.inner_connection
+            .read_only_transaction
+            .store(read_only, AtomicOrdering::Relaxed);
+    }
+
+    /// Rejects, with -26022, a statement that modifies data or the schema while a read-only transaction is open.
+    /// See [execute_statement](crate::Transaction::execute_statement()) for what the check covers.
+    fn check_writable(&self, sqlstatement: &str) -> Result<(), i32> {
+        match self
+            .inner_connection
+            .read_only_transaction
+            .load(AtomicOrdering::Relaxed)
+            && is_write_statement(sqlstatement)
+        {
+            true => Err(-26022), // Statement modifies data in a read-only transaction
+            false => Ok(()),
         }
+    }
+
+    /// Obtains server statistics information, reading all [counters](crate::StatisticsCounter).
+    /// Counters may either be an absolute value representing the current status or a monotonically increasing value representing the number of occurred events since the server started.
+    ///
+    /// # Errors
+    /// Returns [Err] if failed to connect to server.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    ///
+    /// let statistics = conn.get_statistics().unwrap();
+    /// if let (Some(used), Some(available)) = (statistics.pages_4k_used, statistics.pages_4k) {
+    ///     println!("{used} of {available} 4K pages in use");
+    /// }
+    /// ```
+    pub fn get_statistics(&self) -> Result<ServerStatistics, i32> {
+        self.get_statistics_for(&StatisticsCounter::ALL)
+    }
+
+    /// Obtains the given server statistics counters, like [get_statistics](crate::Connection::get_statistics()).
+    /// Counters that aren't requested are [None] in the returned statistics.
+    ///
+    /// # Errors
+    /// Returns [Err] on an invalid counter, or if failed to connect to server.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    ///
+    /// let statistics = conn.get_statistics_for(&[StatisticsCounter::PagesUsed]).unwrap();
+    /// assert!(statistics.get(StatisticsCounter::PagesUsed).is_some());
+    /// assert_eq!(statistics.pages_4k, None);
+    /// ```
+    pub fn get_statistics_for(
+        &self,
+        counters: &[StatisticsCounter],
+    ) -> Result<ServerStatistics, i32> {
+        let mut values: Vec<i32> = counters.iter().map(|counter| *counter as i32).collect();
+        let session = self.get_session_handle()?.unwrap(); //Ok unwrap since we know the connection is a connection
+        fill_slice(
+            &mut values,
+            i16::MAX as usize,
+            |counters_arr, num_counters| unsafe {
+                ffi::MimerGetStatistics(*session, counters_arr, num_counters as i16)
+            },
+        )?;
+        Ok(ServerStatistics::from_values(counters, &values))
+    }
+
+    /// Checks that the session is still usable by making a round trip to the server, without executing a statement.
+    /// Useful for detecting dead sessions, e.g. in connection pools or long-lived services, before running real work.
+    ///
+    /// # Errors
+    /// Returns [Err] when the server can't be reached through the session, e.g. after a network failure or a server restart.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// if let Err(ec) = conn.ping() {
+    ///     eprintln!("session is dead: {}", conn.get_error(ec));
+    /// }
+    /// ```
+    pub fn ping(&self) -> Result<i32, i32> {
+        let session = self.get_session_handle()?.unwrap(); //Ok unwrap since we know the connection is a connection
+        let rc = unsafe { ffi::MimerPing(*session) };
         match rc.cmp(MIMER_SUCCESS) {
-            Ordering::Equal => {
-                // replace each element in counters with the corresponding element in counters_arr
-                unsafe {
-                    let _ = counters
-                        .iter_mut()
-                        .enumerate()
-                        .map(|(idx, c)| *c = *counters_arr.offset(idx as isize));
-                }
-                Ok(*MIMER_SUCCESS)
-            }
-            _ => Err(rc),
+            Ordering::Less => Err(rc),
+            _ => Ok(rc),
         }
     }
+
+    /// Returns whether the session is still usable, see [ping](Connection::ping()).
+    pub fn is_valid(&self) -> bool {
+        self.ping().is_ok()
+    }
 }
 
 #[cfg(test)]
@@ -255,6 +781,16 @@ mod connection_tests {
     use super::*;
     use crate::testing::*;
 
+    #[test]
+    fn connection_ping() {
+        let conn = establish_connection();
+        conn.ping().unwrap();
+        assert!(conn.is_valid());
+        conn.execute_statement("SELECT 1 FROM SYSTEM.ONEROW")
+            .unwrap();
+        assert!(conn.is_valid());
+    }
+
     #[test]
     fn connection_new_fail() {
         let db: &str = "invalid_database_name";
@@ -278,7 +814,7 @@ mod connection_tests {
 
     #[test]
     fn create_transaction() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         let trans_option = TransactionMode::ReadWrite;
         let _trans = match conn.begin_transaction(trans_option) {
             Ok(t) => t,
@@ -288,7 +824,7 @@ mod connection_tests {
 
     #[test]
     fn statement_list_decreasing() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         let stmt = conn
             .prepare("SELECT * FROM test_table", CursorMode::Forward)
             .unwrap();
@@ -298,9 +834,175 @@ mod connection_tests {
     }
 }
 
+#[cfg(test)]
+mod query_tests {
+    use super::*;
+    use crate::testing::*;
+
+    fn insert_example_rows(conn: &Connection) {
+        drop_create_table(conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+        let stmnt = conn
+            .prepare(
+                &format!("INSERT INTO {EXAMPLE_TABLE} VALUES(:str,:int)"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        for (s, i) in [("one", 1), ("two", 2), ("three", 3)] {
+            stmnt.execute_bind(&[&s, &i]).unwrap();
+        }
+    }
+
+    #[test]
+    fn query_row() {
+        let conn = establish_connection();
+        insert_example_rows(&conn);
+
+        let text = conn
+            .query_row(
+                &format!("SELECT column_1 FROM {EXAMPLE_TABLE} WHERE column_2 = ?"),
+                &[&2],
+                |row| row.get::<String>(1),
+            )
+            .unwrap();
+        assert_eq!(text.unwrap(), "two");
+
+        match conn.query_row(
+            &format!("SELECT column_1 FROM {EXAMPLE_TABLE} WHERE column_2 = ?"),
+            &[&4],
+            |row| row.get::<String>(1),
+        ) {
+            Ok(_) => panic!("Query returned a row when it shouldn't have!"),
+            Err(ec) => assert_eq!(ec, -26009), // Query returned no rows
+        }
+    }
+
+    #[test]
+    fn query_map() {
+        let conn = establish_connection();
+        insert_example_rows(&conn);
+
+        let numbers = conn
+            .query_map(
+                &format!("SELECT column_2 FROM {EXAMPLE_TABLE} ORDER BY column_2"),
+                &[],
+                |row| Ok(row.get::<i32>(1)?.unwrap()),
+            )
+            .unwrap();
+        assert_eq!(numbers, vec![1, 2, 3]);
+
+        let empty = conn
+            .query_map(
+                &format!("SELECT column_2 FROM {EXAMPLE_TABLE} WHERE column_2 > ?"),
+                &[&3],
+                |row| Ok(row.get::<i32>(1)?.unwrap()),
+            )
+            .unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn query_scalar() {
+        let conn = establish_connection();
+        insert_example_rows(&conn);
+
+        let count = conn
+            .query_scalar::<i32>(
+                &format!("SELECT CAST(COUNT(*) AS INTEGER) FROM {EXAMPLE_TABLE}"),
+                &[],
+            )
+            .unwrap();
+        assert_eq!(count, Some(3));
+        assert_eq!(0, conn.inner_connection.statements.lock().len());
+    }
+
+    #[test]
+    fn concurrent_statements() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Connection>();
+        assert_send_sync::<Statement>();
+
+        let conn = establish_connection();
+        insert_example_rows(&conn);
+        let sql = format!("SELECT * FROM {EXAMPLE_TABLE}");
+        let stmts: Vec<Statement> = (0..4)
+            .map(|_| conn.prepare(&sql, CursorMode::Forward).unwrap())
+            .collect();
+
+        // Requests on statements and on the session of one connection are interleaved from several threads
+        let conn = &conn;
+        thread::scope(|s| {
+            for stmt in &stmts {
+                s.spawn(move || {
+                    for _ in 0..25 {
+                        let mut cursor = stmt.open_cursor().unwrap();
+                        let mut rows = 0;
+                        while cursor.next_row().unwrap().is_some() {
+                            rows += 1;
+                        }
+                        assert_eq!(rows, 3);
+                    }
+                });
+            }
+            s.spawn(move || {
+                for _ in 0..25 {
+                    let count = conn
+                        .query_scalar::<i32>(
+                            &format!("SELECT CAST(COUNT(*) AS INTEGER) FROM {EXAMPLE_TABLE}"),
+                            &[],
+                        )
+                        .unwrap();
+                    assert_eq!(count, Some(3));
+                }
+            });
+        });
+        assert_eq!(4, conn.inner_connection.statements.lock().len());
+    }
+
+    #[test]
+    fn prepare_shared_reference() {
+        let conn = establish_connection();
+        let conn = &conn;
+        let stmts: Vec<Statement> = thread::scope(|s| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| {
+                    s.spawn(move || {
+                        conn.prepare("SELECT 1 FROM SYSTEM.ONEROW", CursorMode::Forward)
+                            .unwrap()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert_eq!(stmts.len(), conn.inner_connection.statements.lock().len());
+
+        let trans = conn.begin_transaction(TransactionMode::ReadOnly).unwrap();
+        trans
+            .prepare("SELECT 1 FROM SYSTEM.ONEROW", CursorMode::Forward)
+            .unwrap();
+        trans.rollback().unwrap();
+    }
+
+    #[test]
+    fn clone_connection() {
+        let conn = establish_connection();
+        let other = conn.clone();
+        let stmt = other
+            .prepare("SELECT 1 FROM SYSTEM.ONEROW", CursorMode::Forward)
+            .unwrap();
+        assert_eq!(1, conn.inner_connection.statements.lock().len());
+
+        // The session stays open as long as a clone remains
+        drop(conn);
+        stmt.open_cursor().unwrap();
+        assert!(other.is_valid());
+
+        drop(other);
+        assert!(stmt.open_cursor().is_err());
+    }
+}
+
 #[cfg(test)]
 mod execute_tests {
-    use std::vec;
 
     use super::*;
     use crate::testing::*;
@@ -333,7 +1035,7 @@ mod execute_tests {
 
     #[test]
     fn prepare_new_fail() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         let stmnt: Result<Statement, i32>;
         let option = CursorMode::Forward;
@@ -347,7 +1049,7 @@ mod execute_tests {
 
     #[test]
     fn prepare_new_succeed() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
 
         let _stmnt: Statement;
@@ -360,7 +1062,7 @@ mod execute_tests {
 
     #[test]
     fn prepare_execute_with_params_set() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
 
         let stmnt: Statement;
@@ -383,7 +1085,7 @@ mod execute_tests {
 
     #[test]
     fn execute_with_params_unset() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
 
         let stmnt: Statement;
@@ -411,18 +1113,15 @@ mod execute_tests {
     #[test]
     fn test_get_statistics() {
         let conn = establish_connection();
-        let mut counters = vec![
-            BSI_4K,
-            BSI_4K_USED,
-            BSI_32K,
-            BSI_32K_USED,
-            BSI_128K,
-            BSI_128K_USED,
-            BSI_PAGES_USED,
-        ];
-        dbg!(&counters);
-        conn.get_statistics(&mut counters).unwrap();
-        counters.iter().for_each(|c| assert!(c >= MIMER_SUCCESS));
-        dbg!(counters);
+        let statistics = conn.get_statistics().unwrap();
+        dbg!(&statistics);
+        for counter in StatisticsCounter::ALL {
+            assert!(statistics.get(counter).is_some());
+        }
+        let used = conn
+            .get_statistics_for(&[StatisticsCounter::PagesUsed])
+            .unwrap();
+        assert!(used.pages_used.is_some());
+        assert_eq!(used.pages_4k, None);
     }
 }
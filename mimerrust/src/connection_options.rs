@@ -0,0 +1,403 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{Connection, MimerError};
+
+#[doc(hidden)]
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// A password held by [ConnectionOptions], overwritten with zeros when dropped with the `zeroize` feature.
+#[cfg(feature = "zeroize")]
+pub(crate) type Password = zeroize::Zeroizing<String>;
+
+/// A password held by [ConnectionOptions], overwritten with zeros when dropped with the `zeroize` feature.
+#[cfg(not(feature = "zeroize"))]
+pub(crate) type Password = String;
+
+/// Options used when opening a [Connection](crate::Connection) with [Connection::open_with](crate::Connection::open_with).
+/// The password is shown as `***` when formatted with [Debug](fmt::Debug), also within the builder and the pool managers holding the options.
+#[derive(Clone)]
+pub struct ConnectionOptions {
+    pub(crate) database: String,
+    pub(crate) ident: String,
+    pub(crate) password: Password,
+    pub(crate) tls: Option<TlsOptions>,
+    pub(crate) connect_timeout: Option<Duration>,
+    pub(crate) read_only: bool,
+}
+
+impl fmt::Debug for ConnectionOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConnectionOptions")
+            .field("database", &self.database)
+            .field("ident", &self.ident)
+            .field("password", &"***")
+            .field("tls", &self.tls)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("read_only", &self.read_only)
+            .finish()
+    }
+}
+
+impl ConnectionOptions {
+    /// Creates connection options for the given database, ident and password.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let options = ConnectionOptions::new(db, ident, pass);
+    /// let conn = Connection::open_with(&options).unwrap();
+    /// ```
+    pub fn new(database: &str, ident: &str, password: &str) -> ConnectionOptions {
+        ConnectionOptions {
+            database: database.to_string(),
+            ident: ident.to_string(),
+            password: Password::from(password.to_string()),
+            tls: None,
+            connect_timeout: None,
+            read_only: false,
+        }
+    }
+
+    /// Sets how long [Connection::open_with](crate::Connection::open_with) waits for the server to accept the connection.
+    /// By default it waits as long as the Mimer SQL C API does.
+    ///
+    /// The C API can't abort a login, so after a timeout the login goes on in the background, and a session that is opened late is closed again right away.
+    pub fn connect_timeout(mut self, timeout: Duration) -> ConnectionOptions {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Makes transactions of the connection read-only by default, with `SET SESSION READ ONLY`.
+    pub fn read_only(mut self, read_only: bool) -> ConnectionOptions {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Sets the transport security requirements of the connection.
+    /// The [TlsOptions] are validated before they are accepted.
+    ///
+    /// The Mimer SQL C API reads the transport configuration of a database from `SQLHOSTS` and has no way of receiving
+    /// certificates at runtime. [Connection::open_with](crate::Connection::open_with) therefore refuses to open a connection
+    /// with TLS options (-26012) instead of silently connecting with a transport the application did not ask for.
+    ///
+    /// # Errors
+    /// Returns [Err] when the options are inconsistent or refer to files that are missing or aren't PEM encoded:
+    /// * -26010: A certificate or key file was not found.
+    /// * -26011: A certificate or key file is not PEM encoded.
+    /// * -26013: A client key was given without a client certificate, or vice versa.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// let options = ConnectionOptions::new("db", "ident", "password")
+    ///     .tls(TlsOptions::new().ca_certificate("/path/does/not/exist.pem"));
+    /// assert_eq!(options.unwrap_err(), -26010);
+    /// ```
+    pub fn tls(mut self, tls: TlsOptions) -> Result<ConnectionOptions, i32> {
+        tls.validate()?;
+        self.tls = Some(tls);
+        Ok(self)
+    }
+}
+
+/// Builds the options of a connection step by step and opens it, created with [Connection::builder](crate::Connection::builder).
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # use std::time::Duration;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// let conn = Connection::builder()
+///     .database(db)
+///     .ident(ident)
+///     .password(pass)
+///     .connect_timeout(Duration::from_secs(10))
+///     .read_only(true)
+///     .open()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConnectionBuilder {
+    options: ConnectionOptions,
+    tls: Option<TlsOptions>,
+}
+
+impl ConnectionBuilder {
+    /// Creates a builder for the default database, with an empty ident and password.
+    pub(crate) fn new() -> ConnectionBuilder {
+        ConnectionBuilder {
+            options: ConnectionOptions::new("", "", ""),
+            tls: None,
+        }
+    }
+
+    /// Sets the name of the database. An empty name connects to the default database in `SQLHOSTS`.
+    pub fn database(mut self, database: &str) -> ConnectionBuilder {
+        self.options.database = database.to_string();
+        self
+    }
+
+    /// Sets the ident to log in as.
+    pub fn ident(mut self, ident: &str) -> ConnectionBuilder {
+        self.options.ident = ident.to_string();
+        self
+    }
+
+    /// Sets the password of the ident.
+    pub fn password(mut self, password: &str) -> ConnectionBuilder {
+        self.options.password = Password::from(password.to_string());
+        self
+    }
+
+    /// Sets the transport security requirements, see [ConnectionOptions::tls]. The options are validated when the connection is opened.
+    pub fn tls(mut self, tls: TlsOptions) -> ConnectionBuilder {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Sets the timeout for opening the connection, see [ConnectionOptions::connect_timeout].
+    pub fn connect_timeout(mut self, timeout: Duration) -> ConnectionBuilder {
+        self.options = self.options.connect_timeout(timeout);
+        self
+    }
+
+    /// Makes transactions read-only by default, see [ConnectionOptions::read_only].
+    pub fn read_only(mut self, read_only: bool) -> ConnectionBuilder {
+        self.options = self.options.read_only(read_only);
+        self
+    }
+
+    /// Returns the [ConnectionOptions] that have been built, e.g. to hand them to a connection pool.
+    ///
+    /// # Errors
+    /// Returns [Err] when the TLS options are invalid, see [ConnectionOptions::tls].
+    pub fn options(self) -> Result<ConnectionOptions, i32> {
+        match self.tls {
+            Some(tls) => self.options.tls(tls),
+            None => Ok(self.options),
+        }
+    }
+
+    /// Opens the connection, see [Connection::open_with](crate::Connection::open_with).
+    ///
+    /// # Errors
+    /// Returns [Err] holding a [MimerError] when the options are invalid or the connection failed to open.
+    pub fn open(self) -> Result<Connection, MimerError> {
+        let options = self.options().map_err(MimerError::mimer_error_from_code)?;
+        Connection::open_with(&options)
+    }
+}
+
+/// Transport security configuration for a connection.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TlsOptions {
+    ca_certificate: Option<PathBuf>,
+    client_certificate: Option<PathBuf>,
+    client_key: Option<PathBuf>,
+}
+
+impl TlsOptions {
+    /// Creates empty TLS options.
+    pub fn new() -> TlsOptions {
+        TlsOptions::default()
+    }
+
+    /// Sets the PEM encoded certificate authority used to verify the server.
+    pub fn ca_certificate<P: AsRef<Path>>(mut self, path: P) -> TlsOptions {
+        self.ca_certificate = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the PEM encoded certificate used to authenticate the client.
+    pub fn client_certificate<P: AsRef<Path>>(mut self, path: P) -> TlsOptions {
+        self.client_certificate = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the PEM encoded private key belonging to the client certificate.
+    pub fn client_key<P: AsRef<Path>>(mut self, path: P) -> TlsOptions {
+        self.client_key = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Checks that the options are consistent and that all referenced files exist and are PEM encoded.
+    ///
+    /// # Errors
+    /// Returns [Err] with the same error codes as [ConnectionOptions::tls].
+    pub fn validate(&self) -> Result<(), i32> {
+        if self.client_certificate.is_some() != self.client_key.is_some() {
+            return Err(-26013); // Client certificate and key must be given together
+        }
+        [
+            &self.ca_certificate,
+            &self.client_certificate,
+            &self.client_key,
+        ]
+        .into_iter()
+        .flatten()
+        .try_for_each(|path| check_pem_file(path))
+    }
+}
+
+/// Checks that a file exists and contains PEM encoded data.
+fn check_pem_file(path: &Path) -> Result<(), i32> {
+    let content = fs::read(path).map_err(|_| -26010)?; // File not found
+    match String::from_utf8_lossy(&content).contains("-----BEGIN ") {
+        true => Ok(()),
+        false => Err(-26011), // File is not PEM encoded
+    }
+}
+
+#[cfg(test)]
+mod connection_options_tests {
+    use super::*;
+
+    fn write_temp_file(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn debug_hides_password() {
+        let options = ConnectionOptions::new("db", "ident", "s3cret");
+        let builder = Connection::builder().password("s3cret");
+        for debug in [format!("{options:?}"), format!("{builder:?}")] {
+            assert!(!debug.contains("s3cret"), "{debug}");
+            assert!(debug.contains("***"), "{debug}");
+        }
+    }
+
+    #[test]
+    fn tls_validation() {
+        let pem = write_temp_file(
+            "mimerrust_tls_ca.pem",
+            "-----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----\n",
+        );
+        let not_pem = write_temp_file("mimerrust_tls_ca.der", "not a certificate");
+
+        let options = ConnectionOptions::new("db", "ident", "password");
+        assert!(options
+            .clone()
+            .tls(TlsOptions::new().ca_certificate(&pem))
+            .is_ok());
+        assert_eq!(
+            options
+                .clone()
+                .tls(TlsOptions::new().ca_certificate(&not_pem))
+                .unwrap_err(),
+            -26011
+        );
+        assert_eq!(
+            options
+                .clone()
+                .tls(TlsOptions::new().ca_certificate(pem.with_extension("missing")))
+                .unwrap_err(),
+            -26010
+        );
+        assert_eq!(
+            options
+                .tls(TlsOptions::new().client_certificate(&pem))
+                .unwrap_err(),
+            -26013
+        );
+    }
+
+    #[test]
+    fn builder_options() {
+        let options = Connection::builder()
+            .database("db")
+            .ident("ident")
+            .password("password")
+            .connect_timeout(Duration::from_secs(3))
+            .read_only(true)
+            .options()
+            .unwrap();
+        assert_eq!(options.database, "db");
+        assert_eq!(options.ident, "ident");
+        assert_eq!(options.password.as_str(), "password");
+        assert_eq!(options.connect_timeout, Some(Duration::from_secs(3)));
+        assert!(options.read_only);
+        assert!(options.tls.is_none());
+
+        let err = Connection::builder()
+            .tls(TlsOptions::new().client_key("key.pem"))
+            .open()
+            .err()
+            .unwrap();
+        assert_eq!(err.get_error_code(), -26013);
+    }
+
+    #[test]
+    fn builder_read_only() {
+        let conn = crate::testing::establish_connection();
+        crate::testing::drop_create_table(
+            &conn,
+            crate::testing::EXAMPLE_TABLE,
+            crate::testing::EXAMPLE_TABLE_COLUMNS,
+        );
+        drop(conn);
+
+        let conn = Connection::builder()
+            .database(&std::env::var("MIMER_DATABASE").unwrap())
+            .ident("RUSTUSER")
+            .password("RUSTPASSWORD")
+            .connect_timeout(Duration::from_secs(30))
+            .read_only(true)
+            .open()
+            .unwrap();
+        assert!(conn
+            .execute_statement(&format!(
+                "INSERT INTO {} {}",
+                crate::testing::EXAMPLE_TABLE,
+                crate::testing::EXAMPLE_TABLE_EXAMPLE_VALUES
+            ))
+            .is_err());
+    }
+
+    #[test]
+    fn open_with_tls_is_refused() {
+        let pem = write_temp_file(
+            "mimerrust_tls_refused.pem",
+            "-----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----\n",
+        );
+        let options = ConnectionOptions::new("db", "ident", "password")
+            .tls(TlsOptions::new().ca_certificate(pem))
+            .unwrap();
+        match crate::Connection::open_with(&options) {
+            Ok(_) => panic!("Connection opened with TLS options that can't be applied"),
+            Err(e) => assert_eq!(e.get_error_code(), -26012),
+        }
+    }
+}
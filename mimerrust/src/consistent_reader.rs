@@ -0,0 +1,273 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{
+    common::mimer_options::{CursorMode, EndTransactionMode, ScrollOption, TransactionMode},
+    connection::Connection,
+    row::Row,
+    transaction::{begin_session_transaction, end_session_transaction, report_rollback_on_drop},
+    types::ToSql,
+};
+
+#[doc(hidden)]
+use std::{
+    ops::Deref,
+    time::{Duration, Instant},
+};
+
+/// Reads a result set page by page from a single [ReadOnly](TransactionMode::ReadOnly) transaction,
+/// so that all pages come from the same consistent view of the database even while other sessions write to it.
+///
+/// The reader pins the connection it is given, typically one checked out from a connection pool, for as long as it lives.
+/// To keep a forgotten reader from holding a pooled connection and an open transaction indefinitely,
+/// it has a lease. The lease is not enforced in the background: an expired lease takes effect on the next call to
+/// [read_page](ConsistentReader::read_page()), [connection](ConsistentReader::connection()) or [finish](ConsistentReader::finish()),
+/// which rolls the transaction back and fails with the error code -26026, as does every later call.
+/// Until then, an idle reader keeps the transaction open and the connection checked out, so drop readers that are no longer used.
+///
+/// The transaction is ended with [finish](ConsistentReader::finish()), or rolled back when the reader is dropped.
+/// Errors from the rollback on drop are passed to the [rollback failure hook](crate::Connection::set_rollback_failure_hook()) of the connection, if any.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// use std::time::Duration;
+///
+/// let conn = Connection::open(db, ident, pass).unwrap();
+/// # conn.execute_statement("drop table test_table").ok();
+/// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+/// # conn.execute_statement("INSERT INTO test_table VALUES('one',1), ('two',2), ('three',3)").unwrap();
+/// let mut reader = ConsistentReader::new(&conn, Duration::from_secs(60)).unwrap();
+///
+/// let mut page = 0;
+/// loop {
+///     let numbers = reader
+///         .read_page("SELECT column_2 FROM test_table ORDER BY column_2", &[], page, 2, |row| {
+///             row.get::<i32>(1)
+///         })
+///         .unwrap();
+///     if numbers.is_empty() {
+///         break;
+///     }
+///     // Export the page
+///     page += 1;
+/// }
+/// reader.finish().unwrap();
+/// ```
+pub struct ConsistentReader<C: Deref<Target = Connection>> {
+    connection: C,
+    lease: Duration,
+    started: Instant,
+    ended: bool,
+}
+
+impl<C: Deref<Target = Connection>> ConsistentReader<C> {
+    /// Starts a [ReadOnly](TransactionMode::ReadOnly) transaction on `connection` with the given `lease`.
+    /// The lease is checked on each later call, so the transaction outlives it until the reader is used again or dropped.
+    ///
+    /// # Errors
+    /// Returns [Err] when the transaction can't be started, e.g. if a transaction is already active on the connection.
+    pub fn new(connection: C, lease: Duration) -> Result<ConsistentReader<C>, i32> {
+        begin_session_transaction(&connection, TransactionMode::ReadOnly)?;
+        Ok(ConsistentReader {
+            connection,
+            lease,
+            started: Instant::now(),
+            ended: false,
+        })
+    }
+
+    /// Returns how much of the lease is left, or [Duration::ZERO] if it has expired.
+    /// An expired lease doesn't end the transaction until the next [read_page](ConsistentReader::read_page()), [connection](ConsistentReader::connection()) or [finish](ConsistentReader::finish()).
+    pub fn remaining_lease(&self) -> Duration {
+        self.lease.saturating_sub(self.started.elapsed())
+    }
+
+    /// Returns the pinned connection, e.g. to run other queries against the same consistent view.
+    /// Statements that modify data fail, since the transaction is read-only.
+    ///
+    /// # Errors
+    /// Returns [Err] when the lease has expired (-26026).
+    pub fn connection(&mut self) -> Result<&Connection, i32> {
+        self.check_lease()?;
+        Ok(&self.connection)
+    }
+
+    /// Executes a query and maps the rows of one page of its result set using the closure `f`.
+    /// Pages are numbered from 0, and a page past the end of the result set is returned as an empty [Vec].
+    ///
+    /// The result set should have a stable order, e.g. through an `ORDER BY` on a unique key, for the pages not to overlap.
+    ///
+    /// # Errors
+    /// Returns [Err] when the lease has expired (-26026), when the query couldn't be prepared or executed, or when `f` returns an error.
+    pub fn read_page<T, F>(
+        &mut self,
+        sqlstatement: &str,
+        params: &[&dyn ToSql],
+        page: usize,
+        page_size: usize,
+        mut f: F,
+    ) -> Result<Vec<T>, i32>
+    where
+        F: FnMut(&Row) -> Result<T, i32>,
+    {
+        self.check_lease()?;
+        let mut rows = Vec::with_capacity(page_size);
+        if page_size == 0 {
+            return Ok(rows);
+        }
+        let first_row: i32 = page
+            .checked_mul(page_size)
+            .and_then(|offset| offset.checked_add(1))
+            .and_then(|first| first.try_into().ok())
+            .ok_or(-26999)?;
+
+        let stmnt = self
+            .connection
+            .prepare(sqlstatement, CursorMode::Scrollable)?;
+        if !params.is_empty() {
+            stmnt.bind_params(params)?;
+        }
+        let mut cursor = stmnt.open_cursor()?;
+        cursor.set_scroll_option(ScrollOption::ABSOLUTE);
+        let mut row = cursor.scroll(first_row)?;
+        while let Some(current) = row {
+            rows.push(f(current)?);
+            if rows.len() == page_size {
+                break;
+            }
+            row = cursor.next_row()?;
+        }
+        Ok(rows)
+    }
+
+    /// Ends the read-only transaction and releases the connection, e.g. back to its pool.
+    ///
+    /// # Errors
+    /// Returns [Err] when the lease has expired (-26026), or when the transaction couldn't be ended.
+    pub fn finish(mut self) -> Result<i32, i32> {
+        self.check_lease()?;
+        let rc = end_session_transaction(&self.connection, EndTransactionMode::Commit)?;
+        self.ended = true;
+        Ok(rc)
+    }
+
+    /// Rolls the transaction back if the lease has expired. This is the only place the lease is enforced.
+    fn check_lease(&mut self) -> Result<(), i32> {
+        if self.ended {
+            return Err(-26026); // Lease of the consistent reader has expired
+        }
+        if self.started.elapsed() > self.lease {
+            self.ended = true;
+            let result = end_session_transaction(&self.connection, EndTransactionMode::Rollback);
+            report_rollback_on_drop(&self.connection, result);
+            return Err(-26026); // Lease of the consistent reader has expired
+        }
+        Ok(())
+    }
+}
+
+impl<C: Deref<Target = Connection>> Drop for ConsistentReader<C> {
+    fn drop(&mut self) {
+        if self.ended {
+            return;
+        }
+        let result = end_session_transaction(&self.connection, EndTransactionMode::Rollback);
+        report_rollback_on_drop(&self.connection, result);
+    }
+}
+
+#[cfg(test)]
+mod consistent_reader_tests {
+    use super::*;
+    use crate::testing::*;
+
+    #[test]
+    fn pages_ignore_concurrent_writes() {
+        let conn = establish_connection();
+        let writer = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+        for i in 1..=5 {
+            conn.execute_statement(&format!("INSERT INTO {EXAMPLE_TABLE} VALUES('row', {i})"))
+                .unwrap();
+        }
+
+        let query = format!("SELECT column_2 FROM {EXAMPLE_TABLE} ORDER BY column_2");
+        let mut reader = ConsistentReader::new(&conn, Duration::from_secs(60)).unwrap();
+        let mut numbers = reader
+            .read_page(&query, &[], 0, 2, |row| row.get::<i32>(1))
+            .unwrap();
+
+        writer
+            .execute_statement(&format!("INSERT INTO {EXAMPLE_TABLE} VALUES('row', 0)"))
+            .unwrap();
+
+        for page in 1.. {
+            let rows = reader
+                .read_page(&query, &[], page, 2, |row| row.get::<i32>(1))
+                .unwrap();
+            if rows.is_empty() {
+                break;
+            }
+            numbers.extend(rows);
+        }
+        let expected: Vec<Option<i32>> = (1..=5).map(Some).collect();
+        assert_eq!(numbers, expected);
+        reader.finish().unwrap();
+
+        // Outside of the reader the new row is visible
+        let count = conn
+            .query_scalar::<i32>(
+                &format!("SELECT CAST(COUNT(*) AS INTEGER) FROM {EXAMPLE_TABLE}"),
+                &[],
+            )
+            .unwrap();
+        assert_eq!(count, Some(6));
+    }
+
+    #[test]
+    fn expired_lease() {
+        let conn = establish_connection();
+        let mut reader = ConsistentReader::new(&conn, Duration::ZERO).unwrap();
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(reader.remaining_lease(), Duration::ZERO);
+        // The lease is only enforced on the next call, so the transaction is still open
+        assert!(begin_session_transaction(&conn, TransactionMode::ReadOnly).is_err());
+        assert_eq!(
+            reader
+                .read_page("SELECT 1 FROM SYSTEM.ONEROW", &[], 0, 1, |row| row
+                    .get::<i32>(1))
+                .unwrap_err(),
+            -26026
+        );
+        assert_eq!(reader.finish(), Err(-26026));
+
+        // The transaction was rolled back, so a new one can be started
+        let reader = ConsistentReader::new(&conn, Duration::from_secs(60)).unwrap();
+        reader.finish().unwrap();
+    }
+}
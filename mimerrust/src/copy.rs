@@ -0,0 +1,221 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{
+    common::mimer_options::CursorMode,
+    connection::Connection,
+    sql::quote_ident,
+    types::{MimerDatatype, ToSql},
+};
+
+/// Options for [copy_table].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopyOptions {
+    array_size: i32,
+    batch_size: usize,
+    match_by_name: bool,
+}
+
+impl Default for CopyOptions {
+    /// Rows fetched 1000 at a time and inserted in batches of 1000 rows, into the columns named as in the query.
+    fn default() -> Self {
+        CopyOptions {
+            array_size: 1000,
+            batch_size: 1000,
+            match_by_name: true,
+        }
+    }
+}
+
+impl CopyOptions {
+    /// Creates the [default](CopyOptions::default()) options.
+    pub fn new() -> CopyOptions {
+        CopyOptions::default()
+    }
+
+    /// Sets the number of rows fetched from the source in each server request, see [set_array_size](crate::Statement::set_array_size()).
+    /// An array size below 1 is treated as 1.
+    pub fn array_size(mut self, array_size: i32) -> CopyOptions {
+        self.array_size = array_size.max(1);
+        self
+    }
+
+    /// Sets the number of rows inserted per batch into the destination. A batch size of 0 is treated as 1.
+    pub fn batch_size(mut self, batch_size: usize) -> CopyOptions {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Sets whether the values are inserted into the columns of the target table named as the columns of the query,
+    /// or into every column of the target table in order.
+    pub fn match_by_name(mut self, match_by_name: bool) -> CopyOptions {
+        self.match_by_name = match_by_name;
+        self
+    }
+}
+
+/// Copies the rows of a query on one connection into a table on another, and returns the number of rows copied.
+///
+/// The rows are fetched with the [array size](CopyOptions::array_size()) of the options and inserted with a prepared statement,
+/// in batches of [batch_size](CopyOptions::batch_size()) rows. Values are bound as they are fetched, so dates, times and
+/// exact numerics are passed as strings and converted by the destination server.
+///
+/// By default, the values go into the columns of `table` with the same names as the columns of the query,
+/// so expressions in the query need an alias. With [match_by_name](CopyOptions::match_by_name()) set to false,
+/// the query has to return a value for every column of `table`, in order.
+///
+/// Batches are inserted as they are fetched, so if a batch fails the earlier batches stay inserted unless the copy is done
+/// within a [Transaction](crate::Transaction) on the destination. The source and destination may be handles to the same connection.
+///
+/// # Errors
+/// Returns [Err] if the query or the insert couldn't be prepared or executed, or a row couldn't be fetched.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// let source = Connection::open(db, ident, pass).unwrap();
+/// let destination = Connection::open(db, ident, pass).unwrap();
+/// # source.execute_statement("drop table test_table").ok();
+/// # source.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+/// # source.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
+/// # destination.execute_statement("drop table test_archive").ok();
+/// # destination.execute_statement("create table test_archive (column_1 VARCHAR(30), column_2 INT)").unwrap();
+///
+/// let rows = copy_table(
+///     &source,
+///     "SELECT column_1, column_2 FROM test_table WHERE column_2 < 10",
+///     &destination,
+///     "test_archive",
+///     &CopyOptions::new(),
+/// )
+/// .unwrap();
+/// assert_eq!(rows, 1);
+/// ```
+pub fn copy_table(
+    source: &Connection,
+    query: &str,
+    destination: &Connection,
+    table: &str,
+    options: &CopyOptions,
+) -> Result<usize, i32> {
+    let select = source.prepare(query, CursorMode::Forward)?;
+    select.set_array_size(options.array_size)?;
+    let column_count = select.column_count()? as i16;
+
+    let column_list = match options.match_by_name {
+        true => {
+            let names = (1..=column_count)
+                .map(|idx| select.get_column_name(idx).map(|name| quote_ident(&name)))
+                .collect::<Result<Vec<_>, _>>()?;
+            format!("({})", names.join(", "))
+        }
+        false => String::new(),
+    };
+    let placeholders = vec!["?"; column_count as usize].join(", ");
+    let mut insert = destination.prepare(
+        &format!("INSERT INTO {table} {column_list} VALUES({placeholders})"),
+        CursorMode::Forward,
+    )?;
+
+    let mut cursor = select.open_cursor()?;
+    let mut rows = 0;
+    let mut batched = 0;
+    while let Some(row) = cursor.next_row()? {
+        let values = (1..=column_count)
+            .map(|idx| row.get_type(idx))
+            .collect::<Result<Vec<MimerDatatype>, _>>()?;
+        let params: Vec<&dyn ToSql> = values.iter().map(|v| v as &dyn ToSql).collect();
+        insert.add_batch(&params)?;
+        batched += 1;
+        if batched == options.batch_size {
+            insert
+                .execute_batch_locating()
+                .map_err(|err| err.get_error_code())?;
+            rows += batched;
+            batched = 0;
+        }
+    }
+    if batched > 0 {
+        insert
+            .execute_batch_locating()
+            .map_err(|err| err.get_error_code())?;
+        rows += batched;
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod copy_tests {
+    use super::*;
+    use crate::testing::*;
+
+    const COPY_TABLE: &str = "test_table_copy";
+
+    #[test]
+    fn copy_between_connections() {
+        let source = establish_connection();
+        let destination = establish_connection();
+        drop_create_table(&source, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+        drop_create_table(&destination, COPY_TABLE, EXAMPLE_TABLE_COLUMNS);
+        let stmnt = source
+            .prepare(
+                &format!("INSERT INTO {EXAMPLE_TABLE} {EXAMPLE_TABLE_COLUMN_NAMES} VALUES(?,?)"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        for i in 0..25 {
+            let value = match i % 5 {
+                0 => None,
+                _ => Some(format!("value {i}")),
+            };
+            stmnt.execute_bind(&[&value, &i]).unwrap();
+        }
+
+        // Columns are matched by name, whatever their order in the query
+        let options = CopyOptions::new().array_size(7).batch_size(10);
+        let query = format!("SELECT column_2, column_1 FROM {EXAMPLE_TABLE}");
+        let rows = copy_table(&source, &query, &destination, COPY_TABLE, &options).unwrap();
+        assert_eq!(rows, 25);
+
+        // ... or by position
+        let options = options.match_by_name(false);
+        let query = format!("SELECT column_1, column_2 + 100 FROM {EXAMPLE_TABLE}");
+        let rows = copy_table(&source, &query, &source, COPY_TABLE, &options).unwrap();
+        assert_eq!(rows, 25);
+
+        let query = format!(
+            "SELECT CAST(count(*) AS INTEGER), CAST(count(column_1) AS INTEGER), \
+             CAST(sum(column_2) AS INTEGER) FROM {COPY_TABLE}"
+        );
+        let stmnt = destination.prepare(&query, CursorMode::Forward).unwrap();
+        let mut cursor = stmnt.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+        assert_eq!(row.get::<i32>(1).unwrap(), Some(50));
+        assert_eq!(row.get::<i32>(2).unwrap(), Some(40));
+        assert_eq!(row.get::<i32>(3).unwrap(), Some(2 * 300 + 2500));
+    }
+}
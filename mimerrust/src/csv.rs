@@ -0,0 +1,295 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{
+    columnar::*, common::mimer_options::CursorMode, connection::Connection, cursor::Cursor,
+    types::MimerDatatype, ToSql,
+};
+
+#[doc(hidden)]
+use std::io::{Read, Write};
+
+/// Options for [write_csv](crate::Cursor::write_csv()) and [copy_csv_into](crate::Connection::copy_csv_into()).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvOptions {
+    delimiter: u8,
+    has_headers: bool,
+    null_value: String,
+    batch_size: usize,
+}
+
+impl Default for CsvOptions {
+    /// Comma separated values with a header row, empty fields for null values, and batches of 1000 rows.
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: b',',
+            has_headers: true,
+            null_value: String::new(),
+            batch_size: 1000,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Creates the [default](CsvOptions::default()) options.
+    pub fn new() -> CsvOptions {
+        CsvOptions::default()
+    }
+
+    /// Sets the field delimiter.
+    pub fn delimiter(mut self, delimiter: u8) -> CsvOptions {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Sets whether the first row holds the column names.
+    ///
+    /// When importing, the header names the columns the values are inserted into. Without a header,
+    /// each record has to hold a value for every column of the table, in order.
+    pub fn has_headers(mut self, has_headers: bool) -> CsvOptions {
+        self.has_headers = has_headers;
+        self
+    }
+
+    /// Sets the field representing a null value.
+    pub fn null_value(mut self, null_value: &str) -> CsvOptions {
+        self.null_value = null_value.to_string();
+        self
+    }
+
+    /// Sets the number of rows inserted per batch when importing. A batch size of 0 is treated as 1.
+    pub fn batch_size(mut self, batch_size: usize) -> CsvOptions {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+}
+
+impl Cursor {
+    /// Writes the remaining rows of the result set as CSV, and returns the number of rows written.
+    ///
+    /// Values are written in their SQL string form, except for binary values which are written in hexadecimal.
+    ///
+    /// Only available with the `csv` feature.
+    ///
+    /// # Errors
+    /// Returns [Err] when a row couldn't be fetched, or with the error code -26021 if writing failed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// use mimerrust::csv::CsvOptions;
+    ///
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
+    /// let stmnt = conn.prepare("SELECT * FROM test_table", CursorMode::Forward).unwrap();
+    /// let mut cursor = stmnt.open_cursor().unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// cursor.write_csv(&mut out, &CsvOptions::new()).unwrap();
+    /// assert_eq!(String::from_utf8(out).unwrap(), "COLUMN_1,COLUMN_2\nthe number one,1\n");
+    /// ```
+    pub fn write_csv<W: Write>(&mut self, writer: W, options: &CsvOptions) -> Result<usize, i32> {
+        let strong_inner_statement = self.inner_statement.upgrade().ok_or(-26004)?;
+        strong_inner_statement.check_connection()?;
+        let columns = describe_columns(&strong_inner_statement)?;
+
+        let mut writer = ::csv::WriterBuilder::new()
+            .delimiter(options.delimiter)
+            .from_writer(writer);
+        if options.has_headers {
+            writer
+                .write_record(columns.iter().map(|column| column.name.as_str()))
+                .map_err(|_| -26021)?;
+        }
+
+        let mut record: Vec<Option<String>> = Vec::with_capacity(columns.len());
+        let mut rows = 0;
+        while let Some(row) = self.next_row()? {
+            record.clear();
+            for (i, column) in columns.iter().enumerate() {
+                let idx = i as i16 + 1;
+                record.push(read_text(row, idx, column.kind)?);
+            }
+            writer
+                .write_record(
+                    record
+                        .iter()
+                        .map(|v| v.as_deref().unwrap_or(&options.null_value)),
+                )
+                .map_err(|_| -26021)?;
+            rows += 1;
+        }
+        writer.flush().map_err(|_| -26021)?;
+        Ok(rows)
+    }
+}
+
+impl Connection {
+    /// Inserts CSV records into a table, and returns the number of rows inserted.
+    ///
+    /// The records are inserted with a prepared statement, in batches of [batch_size](CsvOptions::batch_size()) rows.
+    /// Values are bound as strings and converted to the column types by the server. Fields equal to the
+    /// [null value](CsvOptions::null_value()) are inserted as null.
+    ///
+    /// Batches are executed as they are read, so if a batch fails the earlier batches stay inserted unless the copy is done within a [Transaction](crate::Transaction).
+    ///
+    /// Only available with the `csv` feature.
+    ///
+    /// # Errors
+    /// Returns [Err] if the insert couldn't be prepared or executed, or with the error code -26021 if the input couldn't be read as CSV.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// use mimerrust::csv::CsvOptions;
+    ///
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// let data = "column_2,column_1\n1,one\n2,two\n";
+    /// let rows = conn.copy_csv_into("test_table", data.as_bytes(), &CsvOptions::new()).unwrap();
+    /// assert_eq!(rows, 2);
+    /// ```
+    pub fn copy_csv_into<R: Read>(
+        &mut self,
+        table: &str,
+        reader: R,
+        options: &CsvOptions,
+    ) -> Result<usize, i32> {
+        let mut reader = ::csv::ReaderBuilder::new()
+            .delimiter(options.delimiter)
+            .has_headers(options.has_headers)
+            .from_reader(reader);
+
+        let (column_list, column_count) = match options.has_headers {
+            true => {
+                let headers = reader.headers().map_err(|_| -26021)?;
+                (
+                    format!("({})", headers.iter().collect::<Vec<_>>().join(", ")),
+                    headers.len(),
+                )
+            }
+            false => {
+                let stmnt = self.prepare(&format!("SELECT * FROM {table}"), CursorMode::Forward)?;
+                (String::new(), stmnt.column_count()? as usize)
+            }
+        };
+        let placeholders = vec!["?"; column_count].join(", ");
+        let mut stmnt = self.prepare(
+            &format!("INSERT INTO {table} {column_list} VALUES({placeholders})"),
+            CursorMode::Forward,
+        )?;
+
+        let mut rows = 0;
+        let mut batched = 0;
+        for record in reader.records() {
+            let record = record.map_err(|_| -26021)?;
+            let values: Vec<MimerDatatype> = record
+                .iter()
+                .map(|field| match field == options.null_value {
+                    true => MimerDatatype::Null,
+                    false => MimerDatatype::StringRef(field),
+                })
+                .collect();
+            let params: Vec<&dyn ToSql> = values.iter().map(|v| v as &dyn ToSql).collect();
+            stmnt.add_batch(&params)?;
+            batched += 1;
+            if batched == options.batch_size {
+                stmnt
+                    .execute_batch_locating()
+                    .map_err(|err| err.get_error_code())?;
+                rows += batched;
+                batched = 0;
+            }
+        }
+        if batched > 0 {
+            stmnt
+                .execute_batch_locating()
+                .map_err(|err| err.get_error_code())?;
+            rows += batched;
+        }
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod csv_tests {
+    use super::*;
+    use crate::testing::*;
+
+    #[test]
+    fn csv_round_trip() {
+        let mut conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+
+        let data = "column_1;column_2\none;1\n;2\nthree;NULL\n";
+        let options = CsvOptions::new()
+            .delimiter(b';')
+            .null_value("NULL")
+            .batch_size(2);
+        let rows = conn
+            .copy_csv_into(EXAMPLE_TABLE, data.as_bytes(), &options)
+            .unwrap();
+        assert_eq!(rows, 3);
+
+        let stmnt = conn
+            .prepare(
+                &format!("SELECT * FROM {EXAMPLE_TABLE} ORDER BY column_1"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = stmnt.open_cursor().unwrap();
+        let mut out = Vec::new();
+        assert_eq!(cursor.write_csv(&mut out, &options).unwrap(), 3);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "COLUMN_1;COLUMN_2\n;2\none;1\nthree;NULL\n"
+        );
+    }
+
+    #[test]
+    fn csv_without_headers() {
+        let mut conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+
+        let options = CsvOptions::new().has_headers(false);
+        let rows = conn
+            .copy_csv_into(EXAMPLE_TABLE, "one,1\ntwo,2\n".as_bytes(), &options)
+            .unwrap();
+        assert_eq!(rows, 2);
+
+        // A record with too few fields fails
+        assert!(conn
+            .copy_csv_into(EXAMPLE_TABLE, "three\n".as_bytes(), &options)
+            .is_err());
+    }
+}
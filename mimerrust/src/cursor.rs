@@ -25,8 +25,10 @@
 use crate::common::mimer_options::*;
 use crate::common::return_codes::MIMER_SUCCESS;
 use crate::common::traits::GetHandle;
+use crate::error_sink::{report, unexpected_return_code};
 use crate::inner_statement::*;
-use crate::row::Row;
+use crate::observer::FetchObservation;
+use crate::row::{OwnedRow, Row};
 use mimerrust_sys as ffi;
 
 #[doc(hidden)]
@@ -35,6 +37,7 @@ use fallible_streaming_iterator::FallibleStreamingIterator;
 use std::{
     cmp::Ordering,
     sync::{Arc, Weak},
+    time::Instant,
 };
 
 /// An iterator for result sets from MimerSQL databases.
@@ -42,7 +45,12 @@ pub struct Cursor {
     mode: CursorMode,
     pub(crate) inner_statement: Weak<InnerStatement>,
     pub(crate) scroll_option: ScrollOption,
-    row: Option<Row>, // To store the current row
+    row: Option<Row>,                                 // To store the current row
+    pub(crate) observation: Option<FetchObservation>, // Some when a statement observer was set as the cursor was opened, or with the metrics feature
+    #[cfg(feature = "tracing")]
+    pub(crate) span: tracing::Span, // The "mimer.fetch" span, which lasts as long as the cursor
+    #[cfg(feature = "tracing")]
+    fetched_rows: u64,
 }
 
 impl Cursor {
@@ -50,26 +58,62 @@ impl Cursor {
         inner_statement: Arc<InnerStatement>,
         mode: CursorMode,
     ) -> Result<Cursor, i32> {
-        let handle = inner_statement.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
-        inner_statement.check_connection()?;
-        let code: i32;
-        unsafe {
-            code = ffi::MimerOpenCursor(*handle);
-        }
+        open_cursor_handle(&inner_statement)?;
+        Ok(Cursor {
+            inner_statement: Arc::downgrade(&inner_statement),
+            observation: None,
+            #[cfg(feature = "tracing")]
+            span: tracing::Span::none(),
+            #[cfg(feature = "tracing")]
+            fetched_rows: 0,
+            mode,
+            scroll_option: ScrollOption::NEXT,
+            row: None,
+        })
+    }
 
-        match code.cmp(MIMER_SUCCESS) {
-            Ordering::Less => Err(code),
-            Ordering::Equal => Ok(Cursor {
-                inner_statement: Arc::downgrade(&inner_statement),
-                mode,
-                scroll_option: ScrollOption::NEXT,
-                row: None,
-            }),
-            Ordering::Greater => {
-                // i suppose this is a reasonable panic?
-                panic!("Return code is positive from C API function which doesn't return a positive value")
-            }
-        }
+    /// Moves the cursor back to before the first row, so that the result set can be iterated again.
+    ///
+    /// A [scrollable](CursorMode::Scrollable) cursor is repositioned without executing the query again.
+    /// A forward cursor is closed and opened again, which executes the query again and may therefore see changes made since it was opened.
+    ///
+    /// # Errors
+    /// Returns [Err] when the cursor couldn't be repositioned or opened again.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// # conn.execute_statement("INSERT INTO test_table VALUES('one',1), ('two',2)").unwrap();
+    /// let stmnt = conn.prepare("SELECT column_2 FROM test_table", CursorMode::Scrollable).unwrap();
+    /// let mut cursor = stmnt.open_cursor().unwrap();
+    ///
+    /// while let Some(_row) = cursor.next_row().unwrap() {
+    ///     // first pass
+    /// }
+    /// cursor.rewind().unwrap();
+    /// assert!(cursor.next_row().unwrap().is_some());
+    /// ```
+    pub fn rewind(&mut self) -> Result<(), i32> {
+        match self.mode {
+            CursorMode::Scrollable => self.fetch_scroll(ScrollOption::ABSOLUTE, 0).map(|_| ()),
+            CursorMode::Forward => self.reopen(),
+        }?;
+        self.row = None;
+        Ok(())
+    }
+
+    /// Closes the cursor and opens it again on the same statement, executing the query again.
+    pub(crate) fn reopen(&mut self) -> Result<(), i32> {
+        self.close_cursor()?;
+        self.row = None;
+        let strong_inner_statement = self.inner_statement.upgrade().ok_or(-26004)?;
+        open_cursor_handle(&strong_inner_statement)
     }
 
     /// Closes the cursor.
@@ -83,10 +127,7 @@ impl Cursor {
             code = ffi::MimerCloseCursor(*handle);
         }
         match code.cmp(MIMER_SUCCESS) {
-            Ordering::Greater => {
-                // i suppose this is a reasonable panic?
-                panic!("Return code is positive from C API function which doesn't return a positive value")
-            }
+            Ordering::Greater => Err(unexpected_return_code("closing a cursor", code)),
             Ordering::Equal => Ok(code),
             Ordering::Less => Err(code),
         }
@@ -121,7 +162,7 @@ impl Cursor {
     /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
     /// # let ident = "RUSTUSER";
     /// # let pass = "RUSTPASSWORD";
-    /// let mut conn = Connection::open(db, ident, pass).unwrap();
+    /// let conn = Connection::open(db, ident, pass).unwrap();
     /// # conn.execute_statement("drop table test_table").ok();
     /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
     /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
@@ -133,13 +174,105 @@ impl Cursor {
     /// let row = cursor.scroll(2).unwrap().expect("Nothing was found on the specified index");
     /// ```
     pub fn scroll(&mut self, idx: i32) -> Result<Option<&Row>, i32> {
+        self.fetch_scroll(self.scroll_option, idx)
+    }
+
+    /// Moves a scrollable cursor to the first row of the result set and returns its contents,
+    /// or [None] if the result set is empty.
+    ///
+    /// Unlike [scroll](crate::cursor::Cursor::scroll), the positioning methods `first`, [last](crate::cursor::Cursor::last),
+    /// [absolute](crate::cursor::Cursor::absolute), [relative](crate::cursor::Cursor::relative) and [previous](crate::cursor::Cursor::previous)
+    /// don't depend on, nor change, the scroll option of the cursor.
+    ///
+    /// # Errors
+    /// Returns [Err] when the cursor couldn't be moved, e.g. if the statement wasn't prepared as [scrollable](CursorMode::Scrollable).
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// # conn.execute_statement("INSERT INTO test_table VALUES('one',1), ('two',2), ('three',3)").unwrap();
+    /// let stmnt = conn.prepare("SELECT column_2 FROM test_table ORDER BY column_2", CursorMode::Scrollable).unwrap();
+    /// let mut cursor = stmnt.open_cursor().unwrap();
+    ///
+    /// assert_eq!(cursor.last().unwrap().unwrap().get::<i32>(1).unwrap(), Some(3));
+    /// assert_eq!(cursor.previous().unwrap().unwrap().get::<i32>(1).unwrap(), Some(2));
+    /// assert_eq!(cursor.first().unwrap().unwrap().get::<i32>(1).unwrap(), Some(1));
+    /// assert_eq!(cursor.relative(2).unwrap().unwrap().get::<i32>(1).unwrap(), Some(3));
+    /// assert_eq!(cursor.absolute(2).unwrap().unwrap().get::<i32>(1).unwrap(), Some(2));
+    /// assert!(cursor.absolute(4).unwrap().is_none());
+    /// ```
+    pub fn first(&mut self) -> Result<Option<&Row>, i32> {
+        self.fetch_scroll(ScrollOption::FIRST, 0)
+    }
+
+    /// Moves a scrollable cursor to the last row of the result set and returns its contents,
+    /// or [None] if the result set is empty. See [first](crate::cursor::Cursor::first).
+    ///
+    /// # Errors
+    /// Returns [Err] when the cursor couldn't be moved, e.g. if the statement wasn't prepared as [scrollable](CursorMode::Scrollable).
+    pub fn last(&mut self) -> Result<Option<&Row>, i32> {
+        self.fetch_scroll(ScrollOption::LAST, 0)
+    }
+
+    /// Moves a scrollable cursor to row `idx`, where the first row is 1, and returns its contents,
+    /// or [None] if there is no such row. See [first](crate::cursor::Cursor::first).
+    ///
+    /// # Errors
+    /// Returns [Err] when the cursor couldn't be moved, e.g. if the statement wasn't prepared as [scrollable](CursorMode::Scrollable).
+    pub fn absolute(&mut self, idx: i32) -> Result<Option<&Row>, i32> {
+        self.fetch_scroll(ScrollOption::ABSOLUTE, idx)
+    }
+
+    /// Moves a scrollable cursor `offset` rows forward, or backward if negative, from the current row and returns its contents,
+    /// or [None] if there is no such row. See [first](crate::cursor::Cursor::first).
+    ///
+    /// # Errors
+    /// Returns [Err] when the cursor couldn't be moved, e.g. if the statement wasn't prepared as [scrollable](CursorMode::Scrollable).
+    pub fn relative(&mut self, offset: i32) -> Result<Option<&Row>, i32> {
+        self.fetch_scroll(ScrollOption::RELATIVE, offset)
+    }
+
+    /// Moves a scrollable cursor to the previous row and returns its contents,
+    /// or [None] if the cursor was on the first row. See [first](crate::cursor::Cursor::first).
+    ///
+    /// # Errors
+    /// Returns [Err] when the cursor couldn't be moved, e.g. if the statement wasn't prepared as [scrollable](CursorMode::Scrollable).
+    pub fn previous(&mut self) -> Result<Option<&Row>, i32> {
+        self.fetch_scroll(ScrollOption::PREVIOUS, 0)
+    }
+
+    /// Counts a fetched row in the "mimer.fetch" span, or records the error code of a failed fetch.
+    #[cfg(feature = "tracing")]
+    fn trace_fetch(&mut self, code: i32) {
+        match code.try_into() {
+            Ok(ffi::MIMER_SUCCESS) => self.fetched_rows += 1,
+            Ok(ffi::MIMER_NO_DATA) => (),
+            _ => {
+                self.span.record("error_code", code);
+            }
+        }
+    }
+
+    /// Fetches a row with `MimerFetchScroll`, using the given scroll option and row index.
+    fn fetch_scroll(&mut self, option: ScrollOption, idx: i32) -> Result<Option<&Row>, i32> {
         let strong_inner_statement = self.inner_statement.upgrade().ok_or(-26004)?;
         let handle = strong_inner_statement.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
         strong_inner_statement.check_connection()?;
-        let code: i32;
-        unsafe {
-            code = ffi::MimerFetchScroll(*handle, self.scroll_option.to_c_int(), idx);
+        let started = self.observation.is_some().then(Instant::now);
+        let code = strong_inner_statement.with_query_timeout(|| unsafe {
+            ffi::MimerFetchScroll(*handle, option.to_c_int(), idx)
+        });
+        if let (Some(observation), Some(started)) = (&mut self.observation, started) {
+            observation.record(started, code);
         }
+        #[cfg(feature = "tracing")]
+        self.trace_fetch(code);
         match code.try_into() {
             Ok(ffi::MIMER_SUCCESS) => {
                 self.row = Some(Row {
@@ -167,7 +300,7 @@ impl Cursor {
     /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
     /// # let ident = "RUSTUSER";
     /// # let pass = "RUSTPASSWORD";
-    /// let mut conn = Connection::open(db, ident, pass).unwrap();
+    /// let conn = Connection::open(db, ident, pass).unwrap();
     /// # conn.execute_statement("drop table test_table").ok();
     /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
     /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
@@ -204,6 +337,40 @@ impl Cursor {
             }
         }
     }
+    /// Returns the number of rows in the result set of a scrollable cursor.
+    /// The row count is found by moving to the last row, after which the cursor is moved back to the row it was on.
+    ///
+    /// # Errors
+    /// Returns [Err] when the cursor isn't [scrollable](CursorMode::Scrollable) (-26029), or when it couldn't be moved.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// # conn.execute_statement("INSERT INTO test_table VALUES('one',1), ('two',2)").unwrap();
+    /// let stmnt = conn.prepare("SELECT * FROM test_table", CursorMode::Scrollable).unwrap();
+    /// let mut cursor = stmnt.open_cursor().unwrap();
+    ///
+    /// assert_eq!(cursor.row_count().unwrap(), 2);
+    /// ```
+    pub fn row_count(&mut self) -> Result<usize, i32> {
+        if self.mode != CursorMode::Scrollable {
+            return Err(-26029); // Cursor is not scrollable
+        }
+        let position = self.current_row()?;
+        let count = match self.fetch_scroll(ScrollOption::LAST, 0)? {
+            Some(_) => self.current_row()? as usize,
+            None => 0,
+        };
+        self.fetch_scroll(ScrollOption::ABSOLUTE, position)?;
+        Ok(count)
+    }
+
     /// Returns the maximum number of bytes required to hold one row of data.
     /// This method might be used to calculate the maximum number of rows allowed in an array fetching scenario under certain memory restrictions.
     pub fn get_row_size(&self) -> Result<i32, i32> {
@@ -217,7 +384,235 @@ impl Cursor {
             }
         }
     }
+
+    /// Fetches up to `n` rows from the current position, decodes them and appends them to `rows`.
+    /// Returns the number of rows that were appended, which is less than `n` only when the end of the result set is reached.
+    ///
+    /// The vector is not cleared, and room for `n` more rows is reserved up front, so a vector that is cleared and reused between calls
+    /// does not allocate again once it has grown to the batch size.
+    /// Combine with [set_array_size](crate::Statement::set_array_size()) to also fetch `n` rows per server request.
+    ///
+    /// # Errors
+    /// Returns [Err] if a row could not be fetched or decoded. Rows fetched before the error remain in `rows`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// let stmnt = conn.prepare("SELECT * FROM test_table", CursorMode::Forward).unwrap();
+    /// stmnt.set_array_size(100).unwrap();
+    /// let mut cursor = stmnt.open_cursor().unwrap();
+    ///
+    /// let mut batch = Vec::with_capacity(100);
+    /// while cursor.next_n(100, &mut batch).unwrap() > 0 {
+    ///     // process the batch
+    ///     batch.clear();
+    /// }
+    /// ```
+    pub fn next_n(&mut self, n: usize, rows: &mut Vec<OwnedRow>) -> Result<usize, i32> {
+        rows.reserve(n);
+        let mut fetched = 0;
+        while fetched < n {
+            match self.next_row()? {
+                Some(row) => rows.push(row.to_owned_row()?),
+                None => break,
+            }
+            fetched += 1;
+        }
+        Ok(fetched)
+    }
+
+    /// Processes the result set in parallel on the [rayon] thread pool.
+    ///
+    /// Rows are fetched in chunks of `chunk_size` rows on the calling thread, so the statement handle is never used from more than one thread.
+    /// Each chunk is decoded into [OwnedRow]s and handed to `f` on the rayon thread pool, while the calling thread continues fetching the next chunk.
+    /// The results of `f` are returned in the same order as the chunks were fetched.
+    ///
+    /// Note that fetching is not throttled by the processing, so a slow `f` on a large result set will keep the fetched chunks in memory until they have been processed.
+    /// A `chunk_size` of 0 is treated as 1.
+    ///
+    /// Only available with the `rayon` feature.
+    ///
+    /// # Errors
+    /// Returns [Err] when a row couldn't be fetched. Chunks that were already handed to the thread pool are processed before the error is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
+    /// let stmnt = conn.prepare("SELECT * FROM test_table", CursorMode::Forward).unwrap();
+    /// let mut cursor = stmnt.open_cursor().unwrap();
+    ///
+    /// let sums = cursor
+    ///     .par_process(1000, |rows| {
+    ///         rows.iter()
+    ///             .map(|row| row.get::<i32>(2).unwrap().unwrap_or(0) as i64)
+    ///             .sum::<i64>()
+    ///     })
+    ///     .unwrap();
+    /// let total: i64 = sums.iter().sum();
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_process<F, R>(&mut self, chunk_size: usize, f: F) -> Result<Vec<R>, i32>
+    where
+        F: Fn(Vec<OwnedRow>) -> R + Sync,
+        R: Send,
+    {
+        let chunk_size = chunk_size.max(1);
+        let results = parking_lot::Mutex::new(Vec::new());
+        let (f, results_ref) = (&f, &results);
+
+        // in_place_scope keeps the fetching on the calling thread, only the processing is moved to the pool.
+        rayon::in_place_scope(|scope| -> Result<(), i32> {
+            let mut chunk_idx: usize = 0;
+            loop {
+                let mut chunk = Vec::with_capacity(chunk_size);
+                if self.next_n(chunk_size, &mut chunk)? == 0 {
+                    return Ok(());
+                }
+                scope.spawn(move |_| {
+                    let result = f(chunk);
+                    results_ref.lock().push((chunk_idx, result));
+                });
+                chunk_idx += 1;
+            }
+        })?;
+
+        let mut results = results.into_inner();
+        results.sort_unstable_by_key(|(chunk_idx, _)| *chunk_idx);
+        Ok(results.into_iter().map(|(_, result)| result).collect())
+    }
+
+    /// Fetches up to `batch_size` rows into an Arrow [RecordBatch](arrow::record_batch::RecordBatch).
+    /// Returns [`Ok<None>`] when there are no more rows. A `batch_size` of 0 is treated as 1.
+    ///
+    /// Columns are mapped to Arrow types as follows. All fields are nullable, except native numeric columns declared NOT NULL.
+    ///
+    /// | Mimer SQL type | Arrow type |
+    /// |---------|---------|
+    /// | *SMALLINT*, *INTEGER* | Int32 |
+    /// | *BIGINT* | Int64 |
+    /// | *REAL* | Float32 |
+    /// | *DOUBLE PRECISION*, *FLOAT* | Float64 |
+    /// | *BOOLEAN* | Boolean |
+    /// | *DATE* | Date32 |
+    /// | *TIME* | Time64 (microseconds) |
+    /// | *TIMESTAMP* | Timestamp (microseconds, no time zone) |
+    /// | *BINARY*, *VARBINARY*, *BLOB* and the spatial types | Binary |
+    /// | Character types, *CLOB*, *NCLOB*, *DECIMAL*, intervals and other types | Utf8 |
+    ///
+    /// *DECIMAL* values are kept as strings, since the C API doesn't report their precision and scale.
+    ///
+    /// Only available with the `arrow` feature.
+    ///
+    /// # Errors
+    /// Returns [Err] when a row couldn't be fetched or a value couldn't be converted.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
+    /// let stmnt = conn.prepare("SELECT * FROM test_table", CursorMode::Forward).unwrap();
+    /// stmnt.set_array_size(1000).unwrap();
+    /// let mut cursor = stmnt.open_cursor().unwrap();
+    ///
+    /// while let Some(batch) = cursor.fetch_arrow(1000).unwrap() {
+    ///     println!("{} rows", batch.num_rows());
+    /// }
+    /// ```
+    #[cfg(feature = "arrow")]
+    pub fn fetch_arrow(
+        &mut self,
+        batch_size: usize,
+    ) -> Result<Option<arrow::record_batch::RecordBatch>, i32> {
+        let batch_size = batch_size.max(1);
+        let strong_inner_statement = self.inner_statement.upgrade().ok_or(-26004)?;
+        strong_inner_statement.check_connection()?;
+        let mut builder =
+            crate::arrow_support::RecordBatchBuilder::new(&strong_inner_statement, batch_size)?;
+
+        let mut fetched = 0;
+        while fetched < batch_size {
+            match self.next_row()? {
+                Some(row) => builder.append_row(row)?,
+                None => break,
+            }
+            fetched += 1;
+        }
+        match fetched {
+            0 => Ok(None),
+            _ => builder.finish().map(Some),
+        }
+    }
+
+    /// Fetches the remaining rows of the result set into a Polars [DataFrame](polars::frame::DataFrame).
+    ///
+    /// Columns are mapped as in [fetch_arrow](crate::Cursor::fetch_arrow()), except that dates and times are kept as strings.
+    /// They can be parsed with the temporal functions of Polars where needed.
+    ///
+    /// Only available with the `polars` feature.
+    ///
+    /// # Errors
+    /// Returns [Err] when a row couldn't be fetched or a value couldn't be converted.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
+    /// let stmnt = conn.prepare("SELECT * FROM test_table", CursorMode::Forward).unwrap();
+    /// let mut cursor = stmnt.open_cursor().unwrap();
+    ///
+    /// let df = cursor.fetch_dataframe().unwrap();
+    /// println!("{df}");
+    /// ```
+    #[cfg(feature = "polars")]
+    pub fn fetch_dataframe(&mut self) -> Result<polars::frame::DataFrame, i32> {
+        let strong_inner_statement = self.inner_statement.upgrade().ok_or(-26004)?;
+        strong_inner_statement.check_connection()?;
+        let mut builder = crate::polars_support::DataFrameBuilder::new(&strong_inner_statement)?;
+
+        while let Some(row) = self.next_row()? {
+            builder.append_row(row)?;
+        }
+        builder.finish()
+    }
 }
+/// Opens a cursor on the statement handle with `MimerOpenCursor`.
+fn open_cursor_handle(inner_statement: &InnerStatement) -> Result<(), i32> {
+    let handle = inner_statement.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
+    inner_statement.check_connection()?;
+    let code = inner_statement.with_query_timeout(|| unsafe { ffi::MimerOpenCursor(*handle) });
+
+    match code.cmp(MIMER_SUCCESS) {
+        Ordering::Less => Err(code),
+        Ordering::Equal => Ok(()),
+        Ordering::Greater => Err(unexpected_return_code("opening a cursor", code)),
+    }
+}
+
 impl FallibleStreamingIterator for Cursor {
     type Error = i32;
     type Item = Row;
@@ -226,16 +621,18 @@ impl FallibleStreamingIterator for Cursor {
         let strong_inner_statement = self.inner_statement.upgrade().ok_or(-26004)?;
         let handle = strong_inner_statement.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
         strong_inner_statement.check_connection()?;
-        let code: i32;
-        if self.mode == CursorMode::Scrollable {
-            unsafe {
-                code = ffi::MimerFetchScroll(*handle, ffi::MIMER_NEXT as i32, 0);
-            }
-        } else {
-            unsafe {
-                code = ffi::MimerFetch(*handle);
+        let started = self.observation.is_some().then(Instant::now);
+        let code = strong_inner_statement.with_query_timeout(|| unsafe {
+            match self.mode {
+                CursorMode::Scrollable => ffi::MimerFetchScroll(*handle, ffi::MIMER_NEXT as i32, 0),
+                CursorMode::Forward => ffi::MimerFetch(*handle),
             }
+        });
+        if let (Some(observation), Some(started)) = (&mut self.observation, started) {
+            observation.record(started, code);
         }
+        #[cfg(feature = "tracing")]
+        self.trace_fetch(code);
         match code.try_into() {
             Ok(ffi::MIMER_SUCCESS) => {
                 self.row = Some(Row {
@@ -256,14 +653,116 @@ impl FallibleStreamingIterator for Cursor {
     }
 }
 
+impl IntoIterator for Cursor {
+    type Item = Result<OwnedRow, i32>;
+    type IntoIter = OwnedRows;
+
+    /// Turns the cursor into a standard [Iterator] over [OwnedRow]s, for use with `for` loops, `collect` and iterator adapters.
+    /// Each row is fetched and decoded as the iterator advances. After an error the iterator ends.
+    ///
+    /// For a scrollable cursor the number of remaining rows is looked up with [row_count](crate::Cursor::row_count()),
+    /// so that [size_hint](Iterator::size_hint()) is exact and e.g. `collect` allocates once.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
+    /// let stmnt = conn.prepare("SELECT column_2 FROM test_table", CursorMode::Forward).unwrap();
+    /// let cursor = stmnt.open_cursor().unwrap();
+    ///
+    /// let numbers = cursor
+    ///     .into_iter()
+    ///     .map(|row| row?.get::<i32>(1))
+    ///     .collect::<Result<Vec<_>, i32>>()
+    ///     .unwrap();
+    /// assert_eq!(numbers, vec![Some(1)]);
+    /// ```
+    fn into_iter(mut self) -> OwnedRows {
+        let remaining = match self.mode {
+            CursorMode::Scrollable => self.row_count().ok().and_then(|count| {
+                let position = self.current_row().ok()? as usize;
+                Some(count.saturating_sub(position))
+            }),
+            CursorMode::Forward => None,
+        };
+        OwnedRows {
+            cursor: self,
+            done: false,
+            remaining,
+        }
+    }
+}
+
+/// A standard [Iterator] over the rows of a [Cursor], yielding each row as an [OwnedRow].
+///
+/// Created by [into_iter](crate::Cursor::into_iter()).
+pub struct OwnedRows {
+    cursor: Cursor,
+    done: bool,
+    remaining: Option<usize>, // Known for scrollable cursors
+}
+
+impl Iterator for OwnedRows {
+    type Item = Result<OwnedRow, i32>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let row = match self.cursor.next_row() {
+            Ok(Some(row)) => row.to_owned_row(),
+            Ok(None) => {
+                self.done = true;
+                return None;
+            }
+            Err(ec) => Err(ec),
+        };
+        self.done = row.is_err();
+        if let Some(remaining) = &mut self.remaining {
+            *remaining = remaining.saturating_sub(1);
+        }
+        Some(row)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match (self.done, self.remaining) {
+            (true, _) => (0, Some(0)),
+            (false, Some(remaining)) => (remaining, Some(remaining)),
+            (false, None) => (0, None),
+        }
+    }
+}
+
+impl std::iter::FusedIterator for OwnedRows {}
+
 impl Drop for Cursor {
     fn drop(&mut self) {
+        #[cfg(feature = "tracing")]
+        self.span.record("rows", self.fetched_rows);
+        if let Some(observation) = self.observation.take() {
+            #[cfg(feature = "metrics")]
+            crate::metrics::record_event(&observation.event());
+            let observer = self
+                .inner_statement
+                .upgrade()
+                .and_then(|statement| statement.inner_connection.upgrade())
+                .and_then(|conn| conn.statement_observer());
+            if let Some(observer) = observer {
+                observer(&observation.event());
+            }
+        }
         match self.close_cursor() {
             Ok(_) => (),
             Err(-26003) => (), // Mimer Rust API error : Connection is dropped
             Err(-26004) => (), // Mimer Rust API error : Statement is dropped
-            // is this is a reasonable panic?
-            Err(ec) => panic!("Failed to close cursor: {ec}"),
+            Err(-26037) => (), // Already reported
+            Err(ec) => report("closing a dropped cursor", ec),
         }
     }
 }
@@ -276,7 +775,7 @@ mod cursor_tests {
 
     #[test]
     fn cursor_open_close() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
 
         let stmt = conn
@@ -291,7 +790,7 @@ mod cursor_tests {
 
     #[test]
     fn cursor_fetch_empty() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
 
         let stmt = conn
@@ -310,7 +809,7 @@ mod cursor_tests {
 
     #[test]
     fn cursor_fetch() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
         conn.execute_statement(&format!(
             "INSERT INTO {EXAMPLE_TABLE} {EXAMPLE_TABLE_EXAMPLE_VALUES}"
@@ -332,7 +831,7 @@ mod cursor_tests {
 
     #[test]
     fn cursor_iter_get_once() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
         conn.execute_statement(&format!(
             "INSERT INTO {EXAMPLE_TABLE} {EXAMPLE_TABLE_EXAMPLE_VALUES}"
@@ -358,7 +857,7 @@ mod cursor_tests {
 
     #[test]
     fn cursor_iter_map() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
         conn.execute_statement(&format!(
             "INSERT INTO {EXAMPLE_TABLE} {EXAMPLE_TABLE_EXAMPLE_VALUES}"
@@ -383,7 +882,7 @@ mod cursor_tests {
     }
     #[test]
     fn cursor_iter_map_many() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
         for _ in 0..10 {
             conn.execute_statement(&format!(
@@ -424,7 +923,7 @@ mod cursor_tests {
 
     #[test]
     fn cursor_scroll() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
         for _ in 0..10 {
             conn.execute_statement(&format!(
@@ -469,7 +968,7 @@ mod cursor_tests {
         let mut cursor;
         let stmt;
         {
-            let mut conn = establish_connection();
+            let conn = establish_connection();
 
             drop_create_table(&conn, &EXAMPLE_TABLE, &EXAMPLE_TABLE_COLUMNS);
             stmt = conn
@@ -489,7 +988,7 @@ mod cursor_tests {
     fn check_statement_next_row() {
         let mut cursor;
         {
-            let mut conn = establish_connection();
+            let conn = establish_connection();
 
             drop_create_table(&conn, &EXAMPLE_TABLE, &EXAMPLE_TABLE_COLUMNS);
             let stmt = conn
@@ -506,7 +1005,7 @@ mod cursor_tests {
     }
     #[test]
     fn test_current_row() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, RESULT_TABLE, RESULT_TABLE_COLUMNS);
 
@@ -540,7 +1039,7 @@ mod cursor_tests {
 
     #[test]
     fn test_row_size() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, BIGINT_TABLE, BIGINT_TABLE_COLUMNS);
 
         //let bytes: Vec<u8> = vec![b't', b'e', b's', b't'];
@@ -568,7 +1067,7 @@ mod cursor_tests {
 
     #[test]
     fn test_scrolloption() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
 
         let values_to_insert = [
@@ -648,9 +1147,308 @@ mod cursor_tests {
         assert_eq!(row.get::<i32>(2).unwrap().unwrap(), 5);
     }
 
+    #[test]
+    fn cursor_positioned_fetch() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+        conn.execute_statement(&format!(
+            "INSERT INTO {EXAMPLE_TABLE} VALUES('one', 1), ('two', 2), ('three', 3), ('four', 4), ('five', 5)"
+        ))
+        .unwrap();
+        let stmt = conn
+            .prepare(
+                &format!("SELECT column_2 FROM {EXAMPLE_TABLE} ORDER BY column_2"),
+                CursorMode::Scrollable,
+            )
+            .unwrap();
+        let mut cursor = stmt.open_cursor().unwrap();
+        let value = |row: Option<&Row>| row.unwrap().get::<i32>(1).unwrap().unwrap();
+
+        assert_eq!(value(cursor.last().unwrap()), 5);
+        assert_eq!(value(cursor.previous().unwrap()), 4);
+        assert_eq!(value(cursor.first().unwrap()), 1);
+        assert!(cursor.previous().unwrap().is_none());
+        assert_eq!(value(cursor.absolute(3).unwrap()), 3);
+        assert_eq!(value(cursor.relative(2).unwrap()), 5);
+        assert_eq!(value(cursor.relative(-4).unwrap()), 1);
+        assert!(cursor.absolute(6).unwrap().is_none());
+
+        // The positioning methods leave the scroll option alone
+        assert!(cursor.scroll_option == ScrollOption::NEXT);
+        assert_eq!(value(cursor.first().unwrap()), 1);
+        assert_eq!(value(cursor.scroll(0).unwrap()), 2);
+    }
+
+    #[test]
+    fn cursor_rewind_and_reopen() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+        conn.execute_statement(&format!(
+            "INSERT INTO {EXAMPLE_TABLE} VALUES('one', 1), ('two', 2), ('three', 3)"
+        ))
+        .unwrap();
+        let sum = |cursor: &mut Cursor| {
+            let mut sum = 0;
+            while let Some(row) = cursor.next_row().unwrap() {
+                sum += row.get::<i32>(2).unwrap().unwrap();
+            }
+            sum
+        };
+
+        for mode in [CursorMode::Scrollable, CursorMode::Forward] {
+            let stmt = conn
+                .prepare(&format!("SELECT * FROM {EXAMPLE_TABLE}"), mode)
+                .unwrap();
+            let mut cursor = stmt.open_cursor().unwrap();
+            assert_eq!(sum(&mut cursor), 6);
+            cursor.rewind().unwrap();
+            assert_eq!(sum(&mut cursor), 6);
+
+            let mut cursor = stmt.reopen_cursor(cursor).unwrap();
+            cursor.next_row().unwrap();
+            assert_eq!(sum(&mut cursor), 5);
+        }
+    }
+
+    #[test]
+    fn cursor_row_count() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+        conn.execute_statement(&format!(
+            "INSERT INTO {EXAMPLE_TABLE} VALUES('one', 1), ('two', 2), ('three', 3)"
+        ))
+        .unwrap();
+        let query = format!("SELECT column_2 FROM {EXAMPLE_TABLE} ORDER BY column_2");
+
+        let stmt = conn.prepare(&query, CursorMode::Scrollable).unwrap();
+        let mut cursor = stmt.open_cursor().unwrap();
+        assert_eq!(cursor.row_count().unwrap(), 3);
+        // The position is kept
+        cursor.next_row().unwrap();
+        assert_eq!(cursor.row_count().unwrap(), 3);
+        assert_eq!(
+            cursor.next_row().unwrap().unwrap().get::<i32>(1).unwrap(),
+            Some(2)
+        );
+
+        let mut rows = cursor.into_iter();
+        assert_eq!(rows.size_hint(), (1, Some(1)));
+        rows.next().unwrap().unwrap();
+        assert_eq!(rows.size_hint(), (0, Some(0)));
+        assert!(rows.next().is_none());
+
+        let stmt = conn.prepare(&query, CursorMode::Forward).unwrap();
+        let mut cursor = stmt.open_cursor().unwrap();
+        assert_eq!(cursor.row_count(), Err(-26029));
+        assert_eq!(cursor.into_iter().size_hint(), (0, None));
+    }
+
+    #[test]
+    fn cursor_next_n() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+        for _ in 0..5 {
+            conn.execute_statement(&format!(
+                "INSERT INTO {EXAMPLE_TABLE} {EXAMPLE_TABLE_EXAMPLE_VALUES}"
+            ))
+            .unwrap();
+        }
+
+        let stmt = conn
+            .prepare(
+                &format!("SELECT * FROM {EXAMPLE_TABLE}"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = stmt.open_cursor().unwrap();
+        let mut rows = Vec::new();
+        assert_eq!(cursor.next_n(3, &mut rows).unwrap(), 3);
+        assert_eq!(cursor.next_n(3, &mut rows).unwrap(), 2);
+        assert_eq!(cursor.next_n(3, &mut rows).unwrap(), 0);
+        assert_eq!(rows.len(), 5);
+        for row in &rows {
+            assert_eq!(row.get::<String>(1).unwrap().unwrap(), EXAMPLE_VALUE_1);
+            assert_eq!(row.get::<i32>(2).unwrap().unwrap(), EXAMPLE_VALUE_2);
+        }
+
+        // A cleared vector is reused without growing
+        drop(cursor);
+        let mut cursor = stmt.open_cursor().unwrap();
+        rows.clear();
+        let capacity = rows.capacity();
+        assert_eq!(cursor.next_n(2, &mut rows).unwrap(), 2);
+        rows.clear();
+        assert_eq!(cursor.next_n(2, &mut rows).unwrap(), 2);
+        assert_eq!(rows.capacity(), capacity);
+    }
+
+    #[test]
+    fn cursor_into_iter() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+        for _ in 0..4 {
+            conn.execute_statement(&format!(
+                "INSERT INTO {EXAMPLE_TABLE} {EXAMPLE_TABLE_EXAMPLE_VALUES}"
+            ))
+            .unwrap();
+        }
+
+        let stmt = conn
+            .prepare(
+                &format!("SELECT * FROM {EXAMPLE_TABLE}"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut count = 0;
+        for row in stmt.open_cursor().unwrap() {
+            let row = row.unwrap();
+            assert_eq!(row.get::<String>(1).unwrap().unwrap(), EXAMPLE_VALUE_1);
+            count += 1;
+        }
+        assert_eq!(count, 4);
+
+        let sum: i32 = stmt
+            .open_cursor()
+            .unwrap()
+            .into_iter()
+            .map(|row| row.unwrap().get::<i32>(2).unwrap().unwrap())
+            .sum();
+        assert_eq!(sum, 4 * EXAMPLE_VALUE_2);
+
+        let mut rows = stmt.open_cursor().unwrap().into_iter();
+        assert_eq!(rows.by_ref().count(), 4);
+        assert!(rows.next().is_none());
+    }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn cursor_fetch_arrow() {
+        use arrow::array::{Array, AsArray};
+        use arrow::datatypes::{DataType, Int32Type, TimeUnit};
+
+        let conn = establish_connection();
+        drop_create_table(
+            &conn,
+            EXAMPLE_TABLE,
+            "(c_int INTEGER, c_bigint BIGINT, c_double DOUBLE PRECISION, c_varchar VARCHAR(30), c_ts TIMESTAMP, c_bin VARBINARY(10))",
+        );
+        for i in 0..3 {
+            conn.execute_statement(&format!(
+                "INSERT INTO {EXAMPLE_TABLE} VALUES({i}, 10000000000, 1.5, 'row {i}', TIMESTAMP '2024-01-02 03:04:05', X'0102')"
+            ))
+            .unwrap();
+        }
+        conn.execute_statement(&format!(
+            "INSERT INTO {EXAMPLE_TABLE} VALUES(NULL, NULL, NULL, NULL, NULL, NULL)"
+        ))
+        .unwrap();
+
+        let stmt = conn
+            .prepare(
+                &format!("SELECT * FROM {EXAMPLE_TABLE} ORDER BY c_int"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = stmt.open_cursor().unwrap();
+
+        let batch = cursor.fetch_arrow(3).unwrap().unwrap();
+        assert_eq!(batch.num_rows(), 3);
+        let schema = batch.schema();
+        assert_eq!(schema.field(0).name().to_lowercase(), "c_int");
+        assert_eq!(schema.field(0).data_type(), &DataType::Int32);
+        assert_eq!(schema.field(1).data_type(), &DataType::Int64);
+        assert_eq!(schema.field(2).data_type(), &DataType::Float64);
+        assert_eq!(schema.field(3).data_type(), &DataType::Utf8);
+        assert_eq!(
+            schema.field(4).data_type(),
+            &DataType::Timestamp(TimeUnit::Microsecond, None)
+        );
+        assert_eq!(schema.field(5).data_type(), &DataType::Binary);
+
+        let ints = batch.column(0).as_primitive::<Int32Type>();
+        assert_eq!(ints.values().to_vec(), vec![0, 1, 2]);
+        assert_eq!(batch.column(3).as_string::<i32>().value(2), "row 2");
+        assert_eq!(batch.column(5).as_binary::<i32>().value(0), &[1u8, 2]);
+
+        let batch = cursor.fetch_arrow(3).unwrap().unwrap();
+        assert_eq!(batch.num_rows(), 1);
+        for column in batch.columns() {
+            assert!(column.is_null(0));
+        }
+        assert!(cursor.fetch_arrow(3).unwrap().is_none());
+    }
+
+    #[cfg(feature = "polars")]
+    #[test]
+    fn cursor_fetch_dataframe() {
+        use polars::prelude::DataType;
+
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+        for _ in 0..3 {
+            conn.execute_statement(&format!(
+                "INSERT INTO {EXAMPLE_TABLE} {EXAMPLE_TABLE_EXAMPLE_VALUES}"
+            ))
+            .unwrap();
+        }
+
+        let stmt = conn
+            .prepare(
+                &format!("SELECT * FROM {EXAMPLE_TABLE}"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = stmt.open_cursor().unwrap();
+        let df = cursor.fetch_dataframe().unwrap();
+
+        assert_eq!(df.shape(), (3, 2));
+        let columns = df.get_columns();
+        assert_eq!(columns[0].dtype(), &DataType::String);
+        assert_eq!(columns[1].dtype(), &DataType::Int32);
+        assert_eq!(columns[1].i32().unwrap().get(0), Some(EXAMPLE_VALUE_2));
+
+        // The cursor is exhausted
+        assert_eq!(cursor.fetch_dataframe().unwrap().height(), 0);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn cursor_par_process() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+
+        let stmnt = conn
+            .prepare(
+                &format!("INSERT INTO {EXAMPLE_TABLE} VALUES(:str,:int)"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        for i in 1..=100 {
+            stmnt.execute_bind(&[&"value", &i]).unwrap();
+        }
+
+        let stmt = conn
+            .prepare(
+                &format!("SELECT column_2 FROM {EXAMPLE_TABLE} ORDER BY column_2"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = stmt.open_cursor().unwrap();
+        let chunks = cursor
+            .par_process(7, |rows| {
+                rows.iter()
+                    .map(|row| row.get::<i32>(1).unwrap().unwrap())
+                    .collect::<Vec<i32>>()
+            })
+            .unwrap();
+
+        assert_eq!(chunks.len(), 15);
+        assert_eq!(chunks.concat(), (1..=100).collect::<Vec<i32>>());
+    }
+
     #[test]
     fn test_scroll_option_fail() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
 
         let values_to_insert = [
@@ -0,0 +1,242 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{
+    connection::Connection,
+    connection_options::ConnectionOptions,
+    mimer_error::MimerError,
+    pool_support::{connect_blocking, run_blocking, validate},
+};
+use ::deadpool::managed::{self, Metrics, RecycleError, RecycleResult};
+
+/// A [deadpool](https://docs.rs/deadpool) pool of Mimer SQL connections.
+pub type Pool = managed::Pool<Manager>;
+
+/// A connection checked out from a [Pool]. It dereferences to a [Connection].
+pub type Object = managed::Object<Manager>;
+
+/// How a connection is checked before it is handed out again by the pool.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum RecyclingMethod {
+    /// The connection is reused without any check.
+    Fast,
+    /// The connection is checked with [ping](Connection::ping()), as by the r2d2 adapter.
+    #[default]
+    Verified,
+    /// The connection is checked with the given statement.
+    Custom(String),
+}
+
+/// A [deadpool](https://docs.rs/deadpool) manager for Mimer SQL connections.
+///
+/// Connections are opened on the tokio blocking thread pool, and checked according to the [RecyclingMethod] when they are returned to the pool.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// use mimerrust::deadpool::{Manager, Pool, RecyclingMethod};
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+/// runtime.block_on(async {
+///     let manager = Manager::new(db, ident, pass).recycling_method(RecyclingMethod::Fast);
+///     let pool = Pool::builder(manager).max_size(4).build().unwrap();
+///
+///     let conn = pool.get().await.unwrap();
+///     let one = conn.query_scalar::<i32>("SELECT 1 FROM SYSTEM.ONEROW", &[]).unwrap();
+///     assert_eq!(one, Some(1));
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct Manager {
+    options: ConnectionOptions,
+    recycling_method: RecyclingMethod,
+}
+
+impl Manager {
+    /// Creates a manager opening connections to the given database as `ident`.
+    pub fn new(database: &str, ident: &str, password: &str) -> Manager {
+        Manager::from_options(ConnectionOptions::new(database, ident, password))
+    }
+
+    /// Creates a manager opening connections with [Connection::open_with].
+    pub fn from_options(options: ConnectionOptions) -> Manager {
+        Manager {
+            options,
+            recycling_method: RecyclingMethod::default(),
+        }
+    }
+
+    /// Sets how connections are checked when they are recycled.
+    pub fn recycling_method(mut self, recycling_method: RecyclingMethod) -> Manager {
+        self.recycling_method = recycling_method;
+        self
+    }
+}
+
+impl managed::Manager for Manager {
+    type Type = Connection;
+    type Error = MimerError;
+
+    async fn create(&self) -> Result<Connection, MimerError> {
+        let result = connect_blocking(self.options.clone()).await;
+        #[cfg(feature = "metrics")]
+        crate::metrics::pool_connection_opened("deadpool", &result);
+        result
+    }
+
+    async fn recycle(&self, conn: &mut Connection, _: &Metrics) -> RecycleResult<MimerError> {
+        let result = match &self.recycling_method {
+            RecyclingMethod::Fast => Ok(()),
+            RecyclingMethod::Verified => validate(conn, None).await,
+            RecyclingMethod::Custom(sqlstatement) => validate(conn, Some(sqlstatement)).await,
+        };
+        #[cfg(feature = "metrics")]
+        crate::metrics::pool_checkout("deadpool", &result);
+        result.map_err(RecycleError::Backend)
+    }
+}
+
+/// Runs blocking work with a pooled connection on the tokio blocking thread pool, like `interact` in other deadpool adapters.
+/// Calls on [Connection] wait for the server to answer, so running them directly in async code stalls the runtime.
+///
+/// The closure gets its own handle to the session of `conn`, and error codes it returns are turned into a [MimerError].
+///
+/// # Errors
+/// Returns [Err] when `f` returns an error, or when the blocking task failed (-26017).
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// use mimerrust::deadpool::{interact, Manager, Pool};
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+/// runtime.block_on(async {
+///     let pool = Pool::builder(Manager::new(db, ident, pass)).build().unwrap();
+///     let conn = pool.get().await.unwrap();
+///
+///     let one = interact(&conn, |conn| conn.query_scalar::<i32>("SELECT 1 FROM SYSTEM.ONEROW", &[]))
+///         .await
+///         .unwrap();
+///     assert_eq!(one, Some(1));
+/// });
+/// ```
+pub async fn interact<F, R>(conn: &Connection, f: F) -> Result<R, MimerError>
+where
+    F: FnOnce(&Connection) -> Result<R, i32> + Send + 'static,
+    R: Send + 'static,
+{
+    run_blocking(conn, f).await
+}
+
+#[cfg(test)]
+mod deadpool_tests {
+    use super::*;
+    use crate::testing::*;
+
+    #[test]
+    fn debug_hides_password() {
+        let debug = format!("{:?}", Manager::new("db", "ident", "s3cret"));
+        assert!(!debug.contains("s3cret"));
+    }
+
+    #[test]
+    fn pool_recycle() {
+        let db = std::env::var("MIMER_DATABASE").unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let manager = Manager::new(&db, IDENT, PASSWORD);
+            let pool = Pool::builder(manager).max_size(1).build().unwrap();
+
+            let conn = pool.get().await.unwrap();
+            drop(conn);
+            // The connection is verified and reused
+            let conn = pool.get().await.unwrap();
+            assert_eq!(
+                conn.query_scalar::<i32>("SELECT 1 FROM SYSTEM.ONEROW", &[])
+                    .unwrap(),
+                Some(1)
+            );
+            assert_eq!(pool.status().size, 1);
+        });
+    }
+
+    #[test]
+    fn pool_interact() {
+        let db = std::env::var("MIMER_DATABASE").unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let pool = Pool::builder(Manager::new(&db, IDENT, PASSWORD))
+                .max_size(1)
+                .build()
+                .unwrap();
+            let conn: Object = pool.get().await.unwrap();
+
+            let one = interact(&conn, |conn| {
+                conn.query_scalar::<i32>("SELECT 1 FROM SYSTEM.ONEROW", &[])
+            })
+            .await
+            .unwrap();
+            assert_eq!(one, Some(1));
+
+            let err = interact(&conn, |conn| {
+                conn.execute_statement("SELECT * FROM non_existing_table")
+            })
+            .await
+            .unwrap_err();
+            assert!(err.get_error_code() < 0);
+        });
+    }
+
+    #[test]
+    fn failing_recycle() {
+        let db = std::env::var("MIMER_DATABASE").unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let manager = Manager::new(&db, IDENT, PASSWORD).recycling_method(
+                RecyclingMethod::Custom(String::from("SELECT * FROM non_existing_table")),
+            );
+            let mut conn = managed::Manager::create(&manager).await.unwrap();
+            assert!(
+                managed::Manager::recycle(&manager, &mut conn, &Metrics::default())
+                    .await
+                    .is_err()
+            );
+        });
+    }
+}
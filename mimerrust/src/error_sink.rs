@@ -0,0 +1,117 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use lazy_static::lazy_static;
+use parking_lot::RwLock;
+
+#[doc(hidden)]
+use std::fmt;
+
+/// Function called with every [DriverFailure], see [set_error_handler](crate::set_error_handler()).
+pub(crate) type ErrorHandler = Box<dyn Fn(&DriverFailure) + Send + Sync>;
+
+lazy_static! {
+    static ref error_handler: RwLock<Option<ErrorHandler>> = RwLock::new(None);
+}
+
+/// A failure that the driver can't return to the caller, e.g. from closing a cursor that is being dropped,
+/// or a return code from the C API that it isn't documented to return.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DriverFailure {
+    /// What the driver was doing, e.g. `"closing a dropped cursor"`.
+    pub context: &'static str,
+    /// The error or return code.
+    pub code: i32,
+}
+
+impl fmt::Display for DriverFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} failed with code {}", self.context, self.code)
+    }
+}
+
+/// Sets the function that is called with failures that the driver can't return to the caller, instead of panicking on them.
+/// Without a handler, the failures are written to standard error, and with the `tracing` feature also emitted as error events.
+/// [None] restores the default.
+///
+/// The handler may be called from `Drop` implementations, possibly while a thread is unwinding, so it must not panic.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// set_error_handler(Some(Box::new(|failure| {
+///     eprintln!("database driver: {failure}");
+/// })));
+/// ```
+pub fn set_error_handler(handler: Option<ErrorHandler>) {
+    *error_handler.write() = handler;
+}
+
+/// Reports a failure to the [error handler](crate::set_error_handler()).
+pub(crate) fn report(context: &'static str, code: i32) {
+    let failure = DriverFailure { context, code };
+    match error_handler.read().as_ref() {
+        Some(handler) => handler(&failure),
+        None => {
+            #[cfg(feature = "tracing")]
+            tracing::error!(code, "{}", failure);
+            eprintln!("mimerrust: {failure}");
+        }
+    }
+}
+
+/// Reports a positive return code from a C API function that doesn't return positive values,
+/// and returns the error code to return to the caller instead.
+pub(crate) fn unexpected_return_code(context: &'static str, rc: i32) -> i32 {
+    report(context, rc);
+    -26037 // Unexpected return code from the C API
+}
+
+#[cfg(test)]
+mod error_sink_tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn report_to_handler() {
+        let failures = Arc::new(Mutex::new(Vec::new()));
+        let failures_clone = Arc::clone(&failures);
+        set_error_handler(Some(Box::new(move |failure| {
+            failures_clone.lock().unwrap().push(failure.clone())
+        })));
+        assert_eq!(unexpected_return_code("executing a statement", 3), -26037);
+        set_error_handler(None);
+        assert_eq!(
+            *failures.lock().unwrap(),
+            vec![DriverFailure {
+                context: "executing a statement",
+                code: 3
+            }]
+        );
+        assert_eq!(
+            failures.lock().unwrap()[0].to_string(),
+            "executing a statement failed with code 3"
+        );
+    }
+}
@@ -0,0 +1,243 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use mimerrust_sys as ffi;
+
+use std::os::raw::c_char;
+
+/// Reads a null-terminated UTF-8 string from a C API function following the Mimer SQL C API convention for strings:
+/// called with size 0 the function returns the length of the string, excluding the null terminator,
+/// and called with a buffer it fills the buffer with as much of the string as fits, including the null terminator.
+///
+/// `call` is given the buffer pointer and its size in bytes. Returns [`Ok<None>`] if the function reports `MIMER_SQL_NULL_VALUE`.
+///
+/// # Errors
+/// Returns [Err] with the return code when `call` fails, or -26001 if the string isn't valid UTF-8.
+pub(crate) fn read_string<F>(mut call: F) -> Result<Option<String>, i32>
+where
+    F: FnMut(*mut c_char, usize) -> i32,
+{
+    // Some functions don't accept a null pointer even when the size is 0
+    let mut probe = [0u8; 1];
+    let mut length = match call(probe.as_mut_ptr() as *mut c_char, 0) {
+        rc if rc == ffi::MIMER_SQL_NULL_VALUE => return Ok(None),
+        rc if rc < 0 => return Err(rc),
+        rc => rc as usize,
+    };
+
+    loop {
+        let mut buffer = vec![0u8; length + 1];
+        let rc = call(buffer.as_mut_ptr() as *mut c_char, buffer.len());
+        if rc == ffi::MIMER_SQL_NULL_VALUE {
+            return Ok(None);
+        }
+        if rc < 0 {
+            return Err(rc);
+        }
+        // The value may have grown between the calls, in which case the buffer was too small and the string is truncated.
+        if rc as usize > length {
+            length = rc as usize;
+            continue;
+        }
+        let end = buffer.iter().position(|b| *b == 0).unwrap_or(length);
+        buffer.truncate(end);
+        return String::from_utf8(buffer).map(Some).map_err(|_| -26001);
+    }
+}
+
+/// Initial size of the buffer used by [read_string_buffered].
+const MIN_STRING_BUFFER: usize = 256;
+
+/// Reads a string like [read_string], but into `buffer`, which is reused between calls and only ever grows.
+///
+/// Unlike [read_string], the length isn't asked for first. A string which fits in the buffer is therefore read
+/// with a single call, and the only allocation is that of the returned [String].
+///
+/// # Errors
+/// Returns [Err] with the return code when `call` fails, or -26001 if the string isn't valid UTF-8.
+pub(crate) fn read_string_buffered<F>(
+    buffer: &mut Vec<u8>,
+    mut call: F,
+) -> Result<Option<String>, i32>
+where
+    F: FnMut(*mut c_char, usize) -> i32,
+{
+    if buffer.len() < MIN_STRING_BUFFER {
+        buffer.resize(MIN_STRING_BUFFER, 0);
+    }
+    loop {
+        let rc = call(buffer.as_mut_ptr() as *mut c_char, buffer.len());
+        if rc == ffi::MIMER_SQL_NULL_VALUE {
+            return Ok(None);
+        }
+        if rc < 0 {
+            return Err(rc);
+        }
+        let length = rc as usize;
+        // The string and its null terminator didn't fit, so it was truncated
+        if length >= buffer.len() {
+            buffer.resize(length + 1, 0);
+            continue;
+        }
+        let end = buffer[..length]
+            .iter()
+            .position(|b| *b == 0)
+            .unwrap_or(length);
+        return std::str::from_utf8(&buffer[..end])
+            .map(|s| Some(s.to_owned()))
+            .map_err(|_| -26001);
+    }
+}
+
+/// Lets a C API function fill a slice of values in place.
+/// `call` is given a pointer to the first element and the number of elements.
+///
+/// # Errors
+/// Returns [Err] with the return code when `call` fails, or -26016 if the slice is longer than `max_len` elements.
+pub(crate) fn fill_slice<T, F>(values: &mut [T], max_len: usize, call: F) -> Result<i32, i32>
+where
+    F: FnOnce(*mut T, usize) -> i32,
+{
+    if values.len() > max_len {
+        return Err(-26016); // Buffer too large for the C API
+    }
+    match call(values.as_mut_ptr(), values.len()) {
+        rc if rc < 0 => Err(rc),
+        rc => Ok(rc),
+    }
+}
+
+#[cfg(test)]
+mod ffi_buffer_tests {
+    use super::*;
+
+    /// Mimics a C API function writing `value` into the buffer.
+    fn write_value(value: &[u8], buf: *mut c_char, size: usize) -> i32 {
+        if size > 0 {
+            let n = value.len().min(size - 1);
+            unsafe {
+                std::ptr::copy_nonoverlapping(value.as_ptr(), buf as *mut u8, n);
+                *buf.add(n) = 0;
+            }
+        }
+        value.len() as i32
+    }
+
+    #[test]
+    fn read_string_values() {
+        assert_eq!(
+            read_string(|buf, size| write_value("ÅÄÖ name".as_bytes(), buf, size)),
+            Ok(Some(String::from("ÅÄÖ name")))
+        );
+        assert_eq!(
+            read_string(|buf, size| write_value(b"", buf, size)),
+            Ok(Some(String::new()))
+        );
+        assert_eq!(read_string(|_, _| ffi::MIMER_SQL_NULL_VALUE), Ok(None));
+        assert_eq!(read_string(|_, _| -24102), Err(-24102));
+        assert_eq!(
+            read_string(|buf, size| write_value(&[0xff, 0xfe], buf, size)),
+            Err(-26001)
+        );
+    }
+
+    #[test]
+    fn read_string_growing_value() {
+        let mut calls = 0;
+        let result = read_string(|buf, size| {
+            calls += 1;
+            match calls {
+                1 => 3,
+                _ => write_value(b"longer than announced", buf, size),
+            }
+        });
+        assert_eq!(result, Ok(Some(String::from("longer than announced"))));
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn read_string_buffered_values() {
+        let mut buffer = Vec::new();
+        let mut calls = 0;
+        let result = read_string_buffered(&mut buffer, |buf, size| {
+            calls += 1;
+            write_value("ÅÄÖ name".as_bytes(), buf, size)
+        });
+        assert_eq!(result, Ok(Some(String::from("ÅÄÖ name"))));
+        assert_eq!(calls, 1);
+        assert_eq!(buffer.len(), MIN_STRING_BUFFER);
+
+        let long = "x".repeat(MIN_STRING_BUFFER * 2);
+        calls = 0;
+        let result = read_string_buffered(&mut buffer, |buf, size| {
+            calls += 1;
+            write_value(long.as_bytes(), buf, size)
+        });
+        assert_eq!(result, Ok(Some(long.clone())));
+        assert_eq!(calls, 2);
+        assert_eq!(buffer.len(), long.len() + 1);
+
+        // The buffer doesn't shrink, so shorter strings are read with a single call
+        calls = 0;
+        let result = read_string_buffered(&mut buffer, |buf, size| {
+            calls += 1;
+            write_value(b"short", buf, size)
+        });
+        assert_eq!(result, Ok(Some(String::from("short"))));
+        assert_eq!(calls, 1);
+        assert_eq!(buffer.len(), long.len() + 1);
+
+        assert_eq!(
+            read_string_buffered(&mut buffer, |_, _| ffi::MIMER_SQL_NULL_VALUE),
+            Ok(None)
+        );
+        assert_eq!(
+            read_string_buffered(&mut buffer, |_, _| -24102),
+            Err(-24102)
+        );
+        assert_eq!(
+            read_string_buffered(&mut buffer, |buf, size| write_value(
+                &[0xff, 0xfe],
+                buf,
+                size
+            )),
+            Err(-26001)
+        );
+    }
+
+    #[test]
+    fn fill_slice_values() {
+        let mut values = vec![1, 2, 3];
+        let rc = fill_slice(&mut values, 10, |ptr, len| {
+            unsafe { std::slice::from_raw_parts_mut(ptr, len) }
+                .iter_mut()
+                .for_each(|v| *v *= 10);
+            0
+        });
+        assert_eq!(rc, Ok(0));
+        assert_eq!(values, vec![10, 20, 30]);
+        assert_eq!(fill_slice(&mut values, 2, |_, _| 0), Err(-26016));
+        assert_eq!(fill_slice(&mut values, 10, |_, _| -24101), Err(-24101));
+    }
+}
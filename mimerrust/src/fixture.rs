@@ -0,0 +1,263 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{
+    connection::Connection, script::ScriptError, test_support::TestDb, CursorMode, MimerDatatype,
+    ToSql,
+};
+use std::fmt;
+
+impl TestDb {
+    /// Loads test data into the databank through [connection](TestDb::connection()), from a file or from the text of a fixture.
+    ///
+    /// `fixture` is read as a file if it names one. A `.sql` file, or text that isn't a mapping of names to lists, is run as an SQL script with
+    /// [execute_script](Connection::execute_script()). Otherwise the fixture is read as YAML or JSON mapping table names to lists of rows,
+    /// each row mapping column names to values. The rows of a table are inserted in batches of rows with the same columns,
+    /// and tables are loaded in the order they appear. The tables must already exist, e.g. created by an SQL fixture loaded first.
+    ///
+    /// # Errors
+    /// Returns [Err] holding a [FixtureError] when the file couldn't be read, the fixture isn't in the format above,
+    /// or a statement or insert failed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::test_support::*;
+    /// let db = TestDb::create(&TestConfig::from_env()).unwrap();
+    /// db.load_fixture(&format!("CREATE TABLE orders (id INT, item VARCHAR(30)) IN {};", db.databank()))
+    ///     .unwrap();
+    /// db.load_fixture(
+    ///     "orders:
+    ///        - { id: 1, item: apples }
+    ///        - { id: 2, item: pears }",
+    /// )
+    /// .unwrap();
+    /// ```
+    pub fn load_fixture(&self, fixture: &str) -> Result<(), FixtureError> {
+        let path = std::path::Path::new(fixture);
+        let text = match path.is_file() {
+            true => std::fs::read_to_string(path).map_err(FixtureError::Io)?,
+            false => fixture.to_string(),
+        };
+        let is_sql = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("sql"));
+        match serde_yaml::from_str::<serde_yaml::Value>(&text) {
+            Ok(serde_yaml::Value::Mapping(tables))
+                if !is_sql && tables.values().all(serde_yaml::Value::is_sequence) =>
+            {
+                load_tables(self.connection(), &tables)
+            }
+            _ => self
+                .connection()
+                .execute_script(&text)
+                .map(|_| ())
+                .map_err(FixtureError::Script),
+        }
+    }
+}
+
+/// Error returned by [TestDb::load_fixture].
+#[derive(Debug)]
+pub enum FixtureError {
+    /// The fixture file couldn't be read.
+    Io(std::io::Error),
+    /// The data of the fixture isn't a mapping of table names to lists of rows of column values, described by the message.
+    Format(String),
+    /// A statement of an SQL fixture failed.
+    Script(ScriptError),
+    /// Inserting the rows of a table failed. `row` is the index, starting at 0, of the failing row within the table when it could be located.
+    Insert {
+        table: String,
+        row: Option<usize>,
+        error_code: i32,
+    },
+}
+
+impl fmt::Display for FixtureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FixtureError::Io(err) => write!(f, "The fixture couldn't be read: {err}"),
+            FixtureError::Format(message) => write!(f, "The fixture is malformed: {message}"),
+            FixtureError::Script(err) => err.fmt(f),
+            FixtureError::Insert {
+                table,
+                row: Some(row),
+                error_code,
+            } => write!(
+                f,
+                "Row {row} of table {table} failed with error {error_code}"
+            ),
+            FixtureError::Insert {
+                table,
+                row: None,
+                error_code,
+            } => write!(
+                f,
+                "Inserting into table {table} failed with error {error_code}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FixtureError {}
+
+/// Inserts the rows of every table of a data fixture, batching consecutive rows with the same columns.
+fn load_tables(conn: &Connection, tables: &serde_yaml::Mapping) -> Result<(), FixtureError> {
+    for (table, rows) in tables {
+        let table = table
+            .as_str()
+            .ok_or_else(|| FixtureError::Format(format!("{table:?} isn't a table name")))?;
+        let rows = rows
+            .as_sequence()
+            .ok_or_else(|| FixtureError::Format(format!("the rows of {table} aren't a list")))?;
+        let rows = rows
+            .iter()
+            .map(|row| fixture_row(table, row))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut first = 0;
+        while first < rows.len() {
+            let columns = &rows[first].0;
+            let count = rows[first..]
+                .iter()
+                .take_while(|(other, _)| other == columns)
+                .count();
+            insert_batch(conn, table, columns, &rows[first..first + count]).map_err(
+                |(row, error_code)| FixtureError::Insert {
+                    table: table.to_string(),
+                    row: row.map(|row| first + row),
+                    error_code,
+                },
+            )?;
+            first += count;
+        }
+    }
+    Ok(())
+}
+
+/// Splits a row of a data fixture into its column names and values.
+fn fixture_row(
+    table: &str,
+    row: &serde_yaml::Value,
+) -> Result<(Vec<String>, Vec<MimerDatatype<'static>>), FixtureError> {
+    let row = row
+        .as_mapping()
+        .ok_or_else(|| FixtureError::Format(format!("a row of {table} isn't a mapping")))?;
+    let mut columns = Vec::with_capacity(row.len());
+    let mut values = Vec::with_capacity(row.len());
+    for (column, value) in row {
+        let column = column.as_str().ok_or_else(|| {
+            FixtureError::Format(format!("{column:?} in {table} isn't a column name"))
+        })?;
+        let value = match value {
+            serde_yaml::Value::Null => MimerDatatype::Null,
+            serde_yaml::Value::Bool(b) => MimerDatatype::Bool(*b),
+            serde_yaml::Value::String(s) => MimerDatatype::String(s.clone()),
+            serde_yaml::Value::Number(n) => match (n.as_i64(), n.as_f64()) {
+                (Some(i), _) => match i32::try_from(i) {
+                    Ok(i) => MimerDatatype::Int(i),
+                    Err(_) => MimerDatatype::BigInt(i),
+                },
+                (None, Some(f)) => MimerDatatype::Double(f),
+                (None, None) => {
+                    return Err(FixtureError::Format(format!(
+                        "{n} in {table}.{column} is out of range"
+                    )))
+                }
+            },
+            _ => {
+                return Err(FixtureError::Format(format!(
+                    "the value of {table}.{column} isn't a scalar"
+                )))
+            }
+        };
+        columns.push(column.to_string());
+        values.push(value);
+    }
+    Ok((columns, values))
+}
+
+/// Inserts rows with the same columns as one batch. On failure returns the index of the failing row, if found, with the error code.
+fn insert_batch(
+    conn: &Connection,
+    table: &str,
+    columns: &[String],
+    rows: &[(Vec<String>, Vec<MimerDatatype<'static>>)],
+) -> Result<(), (Option<usize>, i32)> {
+    let markers = vec!["?"; columns.len()].join(", ");
+    let mut stmnt = conn
+        .prepare(
+            &format!(
+                "INSERT INTO {table} ({}) VALUES({markers})",
+                columns.join(", ")
+            ),
+            CursorMode::Forward,
+        )
+        .map_err(|ec| (None, ec))?;
+    for (index, (_, values)) in rows.iter().enumerate() {
+        let params: Vec<&dyn ToSql> = values.iter().map(|v| v as &dyn ToSql).collect();
+        stmnt.add_batch(&params).map_err(|ec| (Some(index), ec))?;
+    }
+    stmnt
+        .execute_batch_locating()
+        .map(|_| ())
+        .map_err(|err| (err.get_index(), err.get_error_code()))
+}
+
+#[cfg(test)]
+mod fixture_tests {
+    use super::*;
+    use crate::test_support::TestConfig;
+
+    #[test]
+    fn load_fixtures() {
+        let db = TestDb::create(&TestConfig::from_env().databank("fixture_db")).unwrap();
+        db.load_fixture(&format!(
+            "CREATE TABLE fixture_table (id INT, name VARCHAR(30), amount BIGINT) IN {};",
+            db.databank()
+        ))
+        .unwrap();
+        db.load_fixture(
+            "fixture_table:
+               - { id: 1, name: one, amount: 10000000000 }
+               - { id: 2, name: null, amount: 2 }
+               - { id: 3, name: three }",
+        )
+        .unwrap();
+        db.load_fixture(r#"{"fixture_table": [{"id": 4, "name": "four"}]}"#)
+            .unwrap();
+
+        let conn = db.connection();
+        let count =
+            conn.query_scalar::<i32>("SELECT CAST(COUNT(*) AS INTEGER) FROM fixture_table", &[]);
+        assert_eq!(count, Ok(Some(4)));
+        let amount = conn.query_scalar::<i64>("SELECT amount FROM fixture_table WHERE id = 1", &[]);
+        assert_eq!(amount, Ok(Some(10000000000)));
+
+        match db.load_fixture("fixture_table: [{ id: [1] }]") {
+            Err(FixtureError::Format(_)) => {}
+            other => panic!("Expected a format error, got {other:?}"),
+        }
+    }
+}
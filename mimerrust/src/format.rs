@@ -0,0 +1,311 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{columnar::*, common::mimer_options::ColumnType, cursor::Cursor};
+
+#[doc(hidden)]
+use std::io::Write;
+
+/// Layout of a table written by [format_table](crate::Cursor::format_table()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TableStyle {
+    /// Columns separated by `|` and framed by `+---+` lines.
+    #[default]
+    Ascii,
+    /// A GitHub flavored Markdown table, with `|` in values escaped.
+    Markdown,
+}
+
+/// Options for [format_table](crate::Cursor::format_table()).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatOptions {
+    style: TableStyle,
+    null_value: String,
+    max_column_width: Option<usize>,
+    max_rows: Option<usize>,
+}
+
+impl Default for FormatOptions {
+    /// An [ASCII](TableStyle::Ascii) table of all remaining rows, with `NULL` for null values and no limit on the column width.
+    fn default() -> Self {
+        FormatOptions {
+            style: TableStyle::Ascii,
+            null_value: String::from("NULL"),
+            max_column_width: None,
+            max_rows: None,
+        }
+    }
+}
+
+impl FormatOptions {
+    /// Creates the [default](FormatOptions::default()) options.
+    pub fn new() -> FormatOptions {
+        FormatOptions::default()
+    }
+
+    /// Sets the layout of the table.
+    pub fn style(mut self, style: TableStyle) -> FormatOptions {
+        self.style = style;
+        self
+    }
+
+    /// Sets the text shown for null values.
+    pub fn null_value(mut self, null_value: &str) -> FormatOptions {
+        self.null_value = null_value.to_string();
+        self
+    }
+
+    /// Sets the maximum number of characters shown of a value or column name.
+    /// Longer values are cut and end with `...`. A width below 3 is treated as 3.
+    pub fn max_column_width(mut self, max_column_width: usize) -> FormatOptions {
+        self.max_column_width = Some(max_column_width.max(3));
+        self
+    }
+
+    /// Sets the maximum number of rows fetched and written. The remaining rows are left in the cursor.
+    pub fn max_rows(mut self, max_rows: usize) -> FormatOptions {
+        self.max_rows = Some(max_rows);
+        self
+    }
+}
+
+impl Cursor {
+    /// Writes the remaining rows of the result set as a table with aligned columns, and returns the number of rows written.
+    ///
+    /// The header holds the column names. Values are written in their SQL string form, except for binary values which
+    /// are written in hexadecimal, and numeric columns are aligned to the right.
+    /// All rows are fetched before anything is written, since the column widths depend on every value,
+    /// so limit large result sets with [max_rows](FormatOptions::max_rows()).
+    ///
+    /// # Errors
+    /// Returns [Err] when a row couldn't be fetched, or with the error code -26039 if writing failed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// use mimerrust::format::FormatOptions;
+    ///
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
+    /// let stmnt = conn.prepare("SELECT * FROM test_table", CursorMode::Forward).unwrap();
+    /// let mut cursor = stmnt.open_cursor().unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// cursor.format_table(&mut out, FormatOptions::new()).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(out).unwrap(),
+    ///     "+----------------+----------+\n\
+    ///      | COLUMN_1       | COLUMN_2 |\n\
+    ///      +----------------+----------+\n\
+    ///      | the number one |        1 |\n\
+    ///      +----------------+----------+\n"
+    /// );
+    /// ```
+    pub fn format_table<W: Write>(
+        &mut self,
+        writer: &mut W,
+        options: FormatOptions,
+    ) -> Result<usize, i32> {
+        let strong_inner_statement = self.inner_statement.upgrade().ok_or(-26004)?;
+        strong_inner_statement.check_connection()?;
+        let columns = describe_columns(&strong_inner_statement)?;
+
+        let cell = |text: &str| fit(text, &options);
+        let header: Vec<String> = columns.iter().map(|column| cell(&column.name)).collect();
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        while options.max_rows != Some(rows.len()) {
+            let row = match self.next_row()? {
+                Some(row) => row,
+                None => break,
+            };
+            let mut values = Vec::with_capacity(columns.len());
+            for (i, column) in columns.iter().enumerate() {
+                let value = read_text(row, i as i16 + 1, column.kind)?;
+                values.push(cell(value.as_deref().unwrap_or(&options.null_value)));
+            }
+            rows.push(values);
+        }
+
+        let layout: Vec<(usize, bool)> = columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let width = rows
+                    .iter()
+                    .map(|values| values[i].chars().count())
+                    .chain(std::iter::once(header[i].chars().count()))
+                    .max()
+                    .unwrap_or(0);
+                (width, is_numeric(column.column_type))
+            })
+            .collect();
+        write_table(writer, options.style, &header, &rows, &layout).map_err(|_| -26039)?;
+        Ok(rows.len())
+    }
+}
+
+/// Returns whether values of a column type are aligned to the right.
+fn is_numeric(column_type: ColumnType) -> bool {
+    matches!(
+        column_type,
+        ColumnType::SmallInt
+            | ColumnType::Integer
+            | ColumnType::BigInt
+            | ColumnType::Decimal
+            | ColumnType::Numeric
+            | ColumnType::Real
+            | ColumnType::Double
+            | ColumnType::Float
+    )
+}
+
+/// Prepares a value for a cell, replacing line breaks and tabs, escaping `|` in Markdown and cutting it to the maximum width.
+fn fit(text: &str, options: &FormatOptions) -> String {
+    let mut cell = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\n' | '\r' | '\t' => cell.push(' '),
+            '|' if options.style == TableStyle::Markdown => cell.push_str("\\|"),
+            c => cell.push(c),
+        }
+    }
+    match options.max_column_width {
+        Some(max_width) if cell.chars().count() > max_width => {
+            let mut cut: String = cell.chars().take(max_width - 3).collect();
+            if cut.ends_with('\\') && options.style == TableStyle::Markdown {
+                cut.pop(); // Don't leave half of an escaped `|`
+            }
+            cut.push_str("...");
+            cut
+        }
+        _ => cell,
+    }
+}
+
+/// Writes the header and rows, where `layout` holds the width of each column and whether it is aligned to the right.
+fn write_table<W: Write>(
+    writer: &mut W,
+    style: TableStyle,
+    header: &[String],
+    rows: &[Vec<String>],
+    layout: &[(usize, bool)],
+) -> std::io::Result<()> {
+    let write_row = |writer: &mut W, values: &[String], align: bool| -> std::io::Result<()> {
+        write!(writer, "|")?;
+        for (value, &(width, right)) in values.iter().zip(layout) {
+            let padding = width - value.chars().count();
+            if align && right {
+                write!(writer, " {}{} |", " ".repeat(padding), value)?;
+            } else {
+                write!(writer, " {}{} |", value, " ".repeat(padding))?;
+            }
+        }
+        writeln!(writer)
+    };
+    let write_rule = |writer: &mut W| -> std::io::Result<()> {
+        write!(writer, "+")?;
+        for &(width, _) in layout {
+            write!(writer, "{}+", "-".repeat(width + 2))?;
+        }
+        writeln!(writer)
+    };
+
+    match style {
+        TableStyle::Ascii => {
+            write_rule(writer)?;
+            write_row(writer, header, false)?;
+            write_rule(writer)?;
+            for values in rows {
+                write_row(writer, values, true)?;
+            }
+            write_rule(writer)?;
+        }
+        TableStyle::Markdown => {
+            write_row(writer, header, false)?;
+            write!(writer, "|")?;
+            for &(width, right) in layout {
+                // Markdown needs at least three dashes in a delimiter cell
+                let dashes = "-".repeat(width.max(3));
+                if right {
+                    write!(writer, " {}: |", &dashes[1..])?;
+                } else {
+                    write!(writer, " {} |", dashes)?;
+                }
+            }
+            writeln!(writer)?;
+            for values in rows {
+                write_row(writer, values, true)?;
+            }
+        }
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod format_tests {
+    use super::*;
+    use crate::testing::*;
+    use crate::CursorMode;
+
+    #[test]
+    fn format_markdown_table() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+        conn.execute_statement(&format!(
+            "INSERT INTO {EXAMPLE_TABLE} VALUES('a|b',1),('a long value',NULL),('last',3)"
+        ))
+        .unwrap();
+
+        let stmnt = conn
+            .prepare(
+                &format!("SELECT * FROM {EXAMPLE_TABLE} ORDER BY column_2"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = stmnt.open_cursor().unwrap();
+        let options = FormatOptions::new()
+            .style(TableStyle::Markdown)
+            .null_value("-")
+            .max_column_width(6)
+            .max_rows(2);
+        let mut out = Vec::new();
+        assert_eq!(cursor.format_table(&mut out, options).unwrap(), 2);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "| COL... | COL... |\n\
+             | ------ | -----: |\n\
+             | a\\|b   |      1 |\n\
+             | last   |      3 |\n"
+        );
+
+        // The rows beyond max_rows are left in the cursor
+        let row = cursor.next_row().unwrap().unwrap();
+        assert_eq!(row.get::<String>(1).unwrap().unwrap(), "a long value");
+    }
+}
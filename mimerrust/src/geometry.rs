@@ -0,0 +1,838 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{
+    match_mimer_CLOB, match_mimer_strings,
+    types::{FromSql, MimerDatatype, ToSql},
+};
+use geo::{
+    Coord, Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon,
+    Point, Polygon,
+};
+use mimerrust_sys as ffi;
+
+#[doc(hidden)]
+use std::fmt::Write;
+
+/// Geometry type codes of the well-known binary format.
+const WKB_POINT: u32 = 1;
+const WKB_LINE_STRING: u32 = 2;
+const WKB_POLYGON: u32 = 3;
+const WKB_MULTI_POINT: u32 = 4;
+const WKB_MULTI_LINE_STRING: u32 = 5;
+const WKB_MULTI_POLYGON: u32 = 6;
+const WKB_GEOMETRY_COLLECTION: u32 = 7;
+
+/// Checks that a value bound as `bytes` to a *BUILTIN.GIS_LATITUDE*, *BUILTIN.GIS_LONGITUDE* or *BUILTIN.GIS_LOCATION* parameter
+/// is within the range of the type, so that it is rejected with an error code of its own instead of by the server.
+/// Values for other parameter types are not checked.
+pub(crate) fn check_spatial(parameter_type: u32, bytes: &[u8]) -> Result<(), i32> {
+    let degrees = |pos: usize| f32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap());
+    match (parameter_type, bytes.len()) {
+        (ffi::MIMER_GIS_LATITUDE, 4) => check_latitude(degrees(0)),
+        (ffi::MIMER_GIS_LONGITUDE, 4) => check_longitude(degrees(0)),
+        (ffi::MIMER_GIS_LOCATION, 8) => {
+            check_latitude(degrees(0))?;
+            check_longitude(degrees(4))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_latitude(latitude: f32) -> Result<(), i32> {
+    match (-90.0..=90.0).contains(&latitude) {
+        true => Ok(()),
+        false => Err(-26031), // Latitude is out of range
+    }
+}
+
+fn check_longitude(longitude: f32) -> Result<(), i32> {
+    match (-180.0..=180.0).contains(&longitude) {
+        true => Ok(()),
+        false => Err(-26032), // Longitude is out of range
+    }
+}
+
+/// Encodes a geometry for a parameter of type `parameter_type`.
+/// A point bound to *BUILTIN.GIS_LOCATION* becomes a location, with its y coordinate as latitude and its x coordinate as longitude.
+/// Character columns get the geometry as WKT, other columns as WKB.
+pub(crate) fn encode(
+    geometry: &Geometry<f64>,
+    parameter_type: u32,
+) -> Result<MimerDatatype<'static>, i32> {
+    match (parameter_type, geometry) {
+        (ffi::MIMER_GIS_LOCATION, Geometry::Point(point)) => Ok(MimerDatatype::BinaryArray(
+            location_bytes(point.y() as f32, point.x() as f32),
+        )),
+        (ffi::MIMER_GIS_LOCATION, _) => Err(-26203), // Invalid parameter type for MimerDatatype-variant
+        (match_mimer_strings!() | match_mimer_CLOB!(), _) => {
+            Ok(MimerDatatype::String(to_wkt(geometry)))
+        }
+        _ => Ok(MimerDatatype::BinaryArray(to_wkb(geometry))),
+    }
+}
+
+/// Encodes a latitude and a longitude the way *BUILTIN.GIS_LOCATION* stores them.
+fn location_bytes(latitude: f32, longitude: f32) -> Vec<u8> {
+    [latitude.to_le_bytes(), longitude.to_le_bytes()].concat()
+}
+
+/// Decodes a fetched value into a geometry.
+/// A binary value of 8 bytes is read as a *BUILTIN.GIS_LOCATION*, other binary values as WKB and strings as WKT.
+fn decode(value: MimerDatatype) -> Result<Geometry<f64>, i32> {
+    match value {
+        MimerDatatype::Geometry(geometry) => Ok(geometry),
+        MimerDatatype::BinaryArray(bytes) => decode_binary(&bytes),
+        MimerDatatype::BinaryArrayRef(bytes) => decode_binary(bytes),
+        MimerDatatype::String(text) => from_wkt(&text),
+        MimerDatatype::StringRef(text) => from_wkt(text),
+        _ => Err(-26200),
+    }
+}
+
+fn decode_binary(bytes: &[u8]) -> Result<Geometry<f64>, i32> {
+    match bytes.len() {
+        8 => {
+            let latitude = f32::from_le_bytes(bytes[0..4].try_into().unwrap());
+            let longitude = f32::from_le_bytes(bytes[4..8].try_into().unwrap());
+            Ok(Point::new(longitude as f64, latitude as f64).into())
+        }
+        _ => from_wkb(bytes),
+    }
+}
+
+/// The rings of a polygon, exterior first, leaving out empty rings.
+fn rings(polygon: &Polygon<f64>) -> impl Iterator<Item = &LineString<f64>> {
+    std::iter::once(polygon.exterior())
+        .chain(polygon.interiors())
+        .filter(|ring| !ring.0.is_empty())
+}
+
+/// Builds a polygon from its rings, exterior first.
+fn polygon(mut rings: Vec<LineString<f64>>) -> Polygon<f64> {
+    match rings.is_empty() {
+        true => Polygon::new(LineString::new(Vec::new()), Vec::new()),
+        false => {
+            let exterior = rings.remove(0);
+            Polygon::new(exterior, rings)
+        }
+    }
+}
+
+/// Formats a geometry as well-known text, e.g. `LINESTRING(1 2,3 4)`.
+/// Lines, rectangles and triangles are written as the line strings and polygons they correspond to.
+pub(crate) fn to_wkt(geometry: &Geometry<f64>) -> String {
+    let mut text = String::new();
+    write_wkt(&mut text, geometry);
+    text
+}
+
+fn write_wkt(out: &mut String, geometry: &Geometry<f64>) {
+    match geometry {
+        Geometry::Point(point) => {
+            out.push_str("POINT");
+            write_list(out, std::slice::from_ref(&point.0), write_coord);
+        }
+        Geometry::LineString(line) => {
+            out.push_str("LINESTRING");
+            write_list(out, &line.0, write_coord);
+        }
+        Geometry::Polygon(polygon) => {
+            out.push_str("POLYGON");
+            write_list(out, rings(polygon), write_ring);
+        }
+        Geometry::MultiPoint(points) => {
+            out.push_str("MULTIPOINT");
+            write_list(out, &points.0, |out, point| {
+                write_list(out, std::slice::from_ref(&point.0), write_coord)
+            });
+        }
+        Geometry::MultiLineString(lines) => {
+            out.push_str("MULTILINESTRING");
+            write_list(out, &lines.0, write_ring);
+        }
+        Geometry::MultiPolygon(polygons) => {
+            out.push_str("MULTIPOLYGON");
+            write_list(out, &polygons.0, |out, polygon| {
+                write_list(out, rings(polygon), write_ring)
+            });
+        }
+        Geometry::GeometryCollection(geometries) => {
+            out.push_str("GEOMETRYCOLLECTION");
+            write_list(out, &geometries.0, write_wkt);
+        }
+        Geometry::Line(line) => write_wkt(out, &LineString::from(*line).into()),
+        Geometry::Rect(rect) => write_wkt(out, &rect.to_polygon().into()),
+        Geometry::Triangle(triangle) => write_wkt(out, &triangle.to_polygon().into()),
+    }
+}
+
+/// Writes a parenthesized, comma separated list, or ` EMPTY` if there are no items.
+fn write_list<T>(
+    out: &mut String,
+    items: impl IntoIterator<Item = T>,
+    mut write_item: impl FnMut(&mut String, T),
+) {
+    let mut items = items.into_iter().peekable();
+    if items.peek().is_none() {
+        out.push_str(" EMPTY");
+        return;
+    }
+    out.push('(');
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_item(out, item);
+    }
+    out.push(')');
+}
+
+fn write_ring(out: &mut String, ring: &LineString<f64>) {
+    write_list(out, &ring.0, write_coord)
+}
+
+fn write_coord(out: &mut String, coord: &Coord<f64>) {
+    write!(out, "{} {}", coord.x, coord.y).unwrap(); // Writing to a String can't fail
+}
+
+/// Parses two dimensional geometries in well-known text, e.g. `POLYGON((0 0,1 0,1 1,0 0))`.
+///
+/// # Errors
+/// Returns -26033 if the text isn't a supported geometry.
+pub(crate) fn from_wkt(text: &str) -> Result<Geometry<f64>, i32> {
+    let mut parser = WktParser { text, pos: 0 };
+    let geometry = parser.geometry()?;
+    parser.skip_whitespace();
+    match parser.pos == text.len() {
+        true => Ok(geometry),
+        false => Err(-26033), // Invalid WKT or WKB geometry
+    }
+}
+
+struct WktParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl WktParser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    /// Consumes `byte` if it is the next token.
+    fn eat(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        let found = self.peek() == Some(byte);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    /// Consumes the next run of bytes for which `accept` holds.
+    fn take_while(&mut self, accept: impl Fn(u8) -> bool) -> &str {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self.peek().is_some_and(&accept) {
+            self.pos += 1;
+        }
+        &self.text[start..self.pos]
+    }
+
+    fn number(&mut self) -> Result<f64, i32> {
+        self.take_while(|b| b.is_ascii_digit() || b"+-.eE".contains(&b))
+            .parse()
+            .or(Err(-26033)) // Invalid WKT or WKB geometry
+    }
+
+    fn coord(&mut self) -> Result<Coord<f64>, i32> {
+        Ok(Coord {
+            x: self.number()?,
+            y: self.number()?,
+        })
+    }
+
+    fn ring(&mut self) -> Result<LineString<f64>, i32> {
+        Ok(LineString::new(self.list(Self::coord)?))
+    }
+
+    /// A point of a multipoint, which may or may not be parenthesized.
+    fn point(&mut self) -> Result<Point<f64>, i32> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'(') => match self.list(Self::coord)?[..] {
+                [coord] => Ok(Point(coord)),
+                _ => Err(-26033), // Invalid WKT or WKB geometry
+            },
+            _ => Ok(Point(self.coord()?)),
+        }
+    }
+
+    /// Parses a parenthesized, comma separated list, or `EMPTY`.
+    fn list<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, i32>,
+    ) -> Result<Vec<T>, i32> {
+        let start = self.pos;
+        if self
+            .take_while(|b| b.is_ascii_alphabetic())
+            .eq_ignore_ascii_case("EMPTY")
+        {
+            return Ok(Vec::new());
+        }
+        self.pos = start;
+        if !self.eat(b'(') {
+            return Err(-26033); // Invalid WKT or WKB geometry
+        }
+        let mut items = vec![item(self)?];
+        while self.eat(b',') {
+            items.push(item(self)?);
+        }
+        match self.eat(b')') {
+            true => Ok(items),
+            false => Err(-26033), // Invalid WKT or WKB geometry
+        }
+    }
+
+    fn geometry(&mut self) -> Result<Geometry<f64>, i32> {
+        let tag = self
+            .take_while(|b| b.is_ascii_alphabetic())
+            .to_ascii_uppercase();
+        Ok(match tag.as_str() {
+            "POINT" => match self.list(Self::coord)?[..] {
+                [coord] => Point(coord).into(),
+                _ => return Err(-26033), // Invalid WKT or WKB geometry
+            },
+            "LINESTRING" => self.ring()?.into(),
+            "POLYGON" => polygon(self.list(Self::ring)?).into(),
+            "MULTIPOINT" => MultiPoint(self.list(Self::point)?).into(),
+            "MULTILINESTRING" => MultiLineString(self.list(Self::ring)?).into(),
+            "MULTIPOLYGON" => {
+                MultiPolygon(self.list(|parser| Ok(polygon(parser.list(Self::ring)?)))?).into()
+            }
+            "GEOMETRYCOLLECTION" => {
+                Geometry::GeometryCollection(GeometryCollection(self.list(Self::geometry)?))
+            }
+            _ => return Err(-26033), // Invalid WKT or WKB geometry
+        })
+    }
+}
+
+/// Encodes a geometry as little endian well-known binary.
+/// Lines, rectangles and triangles are written as the line strings and polygons they correspond to.
+pub(crate) fn to_wkb(geometry: &Geometry<f64>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    write_wkb(&mut bytes, geometry);
+    bytes
+}
+
+fn write_wkb(out: &mut Vec<u8>, geometry: &Geometry<f64>) {
+    match geometry {
+        Geometry::Point(point) => write_wkb_point(out, point),
+        Geometry::LineString(line) => write_wkb_line_string(out, line),
+        Geometry::Polygon(polygon) => write_wkb_polygon(out, polygon),
+        Geometry::MultiPoint(points) => {
+            write_wkb_header(out, WKB_MULTI_POINT, points.0.len());
+            points.iter().for_each(|point| write_wkb_point(out, point));
+        }
+        Geometry::MultiLineString(lines) => {
+            write_wkb_header(out, WKB_MULTI_LINE_STRING, lines.0.len());
+            lines
+                .iter()
+                .for_each(|line| write_wkb_line_string(out, line));
+        }
+        Geometry::MultiPolygon(polygons) => {
+            write_wkb_header(out, WKB_MULTI_POLYGON, polygons.0.len());
+            polygons
+                .iter()
+                .for_each(|polygon| write_wkb_polygon(out, polygon));
+        }
+        Geometry::GeometryCollection(geometries) => {
+            write_wkb_header(out, WKB_GEOMETRY_COLLECTION, geometries.0.len());
+            geometries
+                .iter()
+                .for_each(|geometry| write_wkb(out, geometry));
+        }
+        Geometry::Line(line) => write_wkb_line_string(out, &LineString::from(*line)),
+        Geometry::Rect(rect) => write_wkb_polygon(out, &rect.to_polygon()),
+        Geometry::Triangle(triangle) => write_wkb_polygon(out, &triangle.to_polygon()),
+    }
+}
+
+/// Writes the byte order and the geometry type, followed by the number of items for anything but a point.
+fn write_wkb_header(out: &mut Vec<u8>, geometry_type: u32, count: usize) {
+    out.push(1); // Little endian
+    out.extend_from_slice(&geometry_type.to_le_bytes());
+    if geometry_type != WKB_POINT {
+        out.extend_from_slice(&(count as u32).to_le_bytes());
+    }
+}
+
+fn write_wkb_coords(out: &mut Vec<u8>, coords: &[Coord<f64>]) {
+    for coord in coords {
+        out.extend_from_slice(&coord.x.to_le_bytes());
+        out.extend_from_slice(&coord.y.to_le_bytes());
+    }
+}
+
+fn write_wkb_point(out: &mut Vec<u8>, point: &Point<f64>) {
+    write_wkb_header(out, WKB_POINT, 1);
+    write_wkb_coords(out, &[point.0]);
+}
+
+fn write_wkb_line_string(out: &mut Vec<u8>, line: &LineString<f64>) {
+    write_wkb_header(out, WKB_LINE_STRING, line.0.len());
+    write_wkb_coords(out, &line.0);
+}
+
+fn write_wkb_polygon(out: &mut Vec<u8>, polygon: &Polygon<f64>) {
+    write_wkb_header(out, WKB_POLYGON, rings(polygon).count());
+    for ring in rings(polygon) {
+        out.extend_from_slice(&(ring.0.len() as u32).to_le_bytes());
+        write_wkb_coords(out, &ring.0);
+    }
+}
+
+/// Decodes two dimensional geometries in well-known binary of either byte order.
+///
+/// # Errors
+/// Returns -26033 if the bytes aren't a supported geometry.
+pub(crate) fn from_wkb(bytes: &[u8]) -> Result<Geometry<f64>, i32> {
+    let mut reader = WkbReader {
+        bytes,
+        pos: 0,
+        little_endian: true,
+    };
+    let geometry = reader.geometry()?;
+    match reader.pos == bytes.len() {
+        true => Ok(geometry),
+        false => Err(-26033), // Invalid WKT or WKB geometry
+    }
+}
+
+struct WkbReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    little_endian: bool,
+}
+
+impl WkbReader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], i32> {
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + N)
+            .ok_or(-26033)? // Invalid WKT or WKB geometry
+            .try_into()
+            .unwrap();
+        self.pos += N;
+        Ok(bytes)
+    }
+
+    fn count(&mut self) -> Result<u32, i32> {
+        let bytes = self.take()?;
+        Ok(match self.little_endian {
+            true => u32::from_le_bytes(bytes),
+            false => u32::from_be_bytes(bytes),
+        })
+    }
+
+    fn number(&mut self) -> Result<f64, i32> {
+        let bytes = self.take()?;
+        Ok(match self.little_endian {
+            true => f64::from_le_bytes(bytes),
+            false => f64::from_be_bytes(bytes),
+        })
+    }
+
+    fn coord(&mut self) -> Result<Coord<f64>, i32> {
+        Ok(Coord {
+            x: self.number()?,
+            y: self.number()?,
+        })
+    }
+
+    /// Reads a count followed by that many items.
+    fn items<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, i32>,
+    ) -> Result<Vec<T>, i32> {
+        let count = self.count()?;
+        let mut items = Vec::new();
+        for _ in 0..count {
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    fn ring(&mut self) -> Result<LineString<f64>, i32> {
+        Ok(LineString::new(self.items(Self::coord)?))
+    }
+
+    /// Reads a nested geometry, which must be of the type `T`.
+    fn nested<T: TryFrom<Geometry<f64>>>(&mut self) -> Result<T, i32> {
+        self.geometry()?.try_into().or(Err(-26033)) // Invalid WKT or WKB geometry
+    }
+
+    fn geometry(&mut self) -> Result<Geometry<f64>, i32> {
+        self.little_endian = match self.take::<1>()? {
+            [0] => false,
+            [1] => true,
+            _ => return Err(-26033), // Invalid WKT or WKB geometry
+        };
+        Ok(match self.count()? {
+            WKB_POINT => Point(self.coord()?).into(),
+            WKB_LINE_STRING => self.ring()?.into(),
+            WKB_POLYGON => polygon(self.items(Self::ring)?).into(),
+            WKB_MULTI_POINT => MultiPoint(self.items(Self::nested)?).into(),
+            WKB_MULTI_LINE_STRING => MultiLineString(self.items(Self::nested)?).into(),
+            WKB_MULTI_POLYGON => MultiPolygon(self.items(Self::nested)?).into(),
+            WKB_GEOMETRY_COLLECTION => {
+                Geometry::GeometryCollection(GeometryCollection(self.items(Self::geometry)?))
+            }
+            _ => return Err(-26033), // Invalid WKT or WKB geometry
+        })
+    }
+}
+
+/// A latitude in degrees, as stored in *BUILTIN.GIS_LATITUDE* columns. It is always within [-90, 90].
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// assert_eq!(Latitude::new(59.3293).unwrap().degrees(), 59.3293);
+/// assert_eq!(Latitude::new(91.0), Err(-26031));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Latitude(f32);
+
+impl Latitude {
+    /// Creates a latitude of `degrees`.
+    ///
+    /// # Errors
+    /// Returns -26031 if `degrees` isn't within [-90, 90].
+    pub fn new(degrees: f32) -> Result<Self, i32> {
+        check_latitude(degrees)?;
+        Ok(Latitude(degrees))
+    }
+
+    /// Gets the latitude in degrees.
+    pub fn degrees(self) -> f32 {
+        self.0
+    }
+}
+
+/// A longitude in degrees, as stored in *BUILTIN.GIS_LONGITUDE* columns. It is always within [-180, 180].
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Longitude(f32);
+
+impl Longitude {
+    /// Creates a longitude of `degrees`.
+    ///
+    /// # Errors
+    /// Returns -26032 if `degrees` isn't within [-180, 180].
+    pub fn new(degrees: f32) -> Result<Self, i32> {
+        check_longitude(degrees)?;
+        Ok(Longitude(degrees))
+    }
+
+    /// Gets the longitude in degrees.
+    pub fn degrees(self) -> f32 {
+        self.0
+    }
+}
+
+/// A position on the earth, as stored in *BUILTIN.GIS_LOCATION* columns.
+///
+/// Unlike a ([f32],[f32]) tuple, the latitude and longitude are labelled and validated, so they can't be passed in the wrong order.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// let conn = Connection::open(db, ident, pass).unwrap();
+/// # _ = conn.execute_statement("DROP TABLE city_table");
+/// conn.execute_statement("CREATE TABLE city_table (name VARCHAR(30), position BUILTIN.GIS_LOCATION)").unwrap();
+///
+/// let stockholm = Location::new(59.3293, 18.0686).unwrap();
+/// let stmnt = conn.prepare("INSERT INTO city_table VALUES(:name, :position)", CursorMode::Forward).unwrap();
+/// stmnt.execute_bind(&[&"Stockholm", &stockholm]).unwrap();
+///
+/// let stmnt = conn.prepare("SELECT position FROM city_table", CursorMode::Forward).unwrap();
+/// let mut cursor = stmnt.open_cursor().unwrap();
+/// let row = cursor.next_row().unwrap().unwrap();
+/// assert_eq!(row.get::<Location>(1).unwrap(), Some(stockholm));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Location {
+    pub lat: Latitude,
+    pub lon: Longitude,
+}
+
+impl Location {
+    /// Creates a location of a latitude and a longitude in degrees.
+    ///
+    /// # Errors
+    /// Returns -26031 if `lat` isn't within [-90, 90] and -26032 if `lon` isn't within [-180, 180].
+    pub fn new(lat: f32, lon: f32) -> Result<Self, i32> {
+        Ok(Location {
+            lat: Latitude::new(lat)?,
+            lon: Longitude::new(lon)?,
+        })
+    }
+}
+
+impl From<Location> for Point<f64> {
+    /// Converts a location into a point with the longitude as x coordinate and the latitude as y coordinate.
+    fn from(location: Location) -> Self {
+        Point::new(location.lon.0 as f64, location.lat.0 as f64)
+    }
+}
+
+impl ToSql for Latitude {
+    fn to_sql(&self) -> MimerDatatype<'_> {
+        MimerDatatype::Real(self.0)
+    }
+}
+impl FromSql for Latitude {
+    fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
+        Latitude::new(f32::from_sql(value)?)
+    }
+}
+
+impl ToSql for Longitude {
+    fn to_sql(&self) -> MimerDatatype<'_> {
+        MimerDatatype::Real(self.0)
+    }
+}
+impl FromSql for Longitude {
+    fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
+        Longitude::new(f32::from_sql(value)?)
+    }
+}
+
+impl ToSql for Location {
+    fn to_sql(&self) -> MimerDatatype<'_> {
+        MimerDatatype::BinaryArray(location_bytes(self.lat.0, self.lon.0))
+    }
+}
+impl FromSql for Location {
+    fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
+        let (lat, lon) = <(f32, f32)>::from_sql(value)?;
+        Location::new(lat, lon)
+    }
+}
+
+impl ToSql for Geometry<f64> {
+    fn to_sql(&self) -> MimerDatatype<'_> {
+        MimerDatatype::Geometry(self.clone())
+    }
+}
+impl FromSql for Geometry<f64> {
+    fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
+        decode(value)
+    }
+}
+
+/// Implements [ToSql] and [FromSql] for the geometry types that a [Geometry] can be converted to and from.
+macro_rules! impl_geometry_conversions {
+    ($($geometry:ident),* $(,)?) => {$(
+        impl ToSql for $geometry<f64> {
+            fn to_sql(&self) -> MimerDatatype<'_> {
+                MimerDatatype::Geometry(self.clone().into())
+            }
+        }
+        impl FromSql for $geometry<f64> {
+            fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
+                decode(value)?.try_into().or(Err(-26200))
+            }
+        }
+    )*};
+}
+
+impl_geometry_conversions!(
+    Point,
+    LineString,
+    Polygon,
+    MultiPoint,
+    MultiLineString,
+    MultiPolygon,
+);
+
+#[cfg(test)]
+mod geometry_tests {
+    use super::*;
+    use crate::common::mimer_options::CursorMode;
+    use crate::testing::*;
+
+    #[test]
+    fn wkt_and_wkb() {
+        for text in [
+            "POINT(1 2)",
+            "LINESTRING(1 2,3.5 -4)",
+            "POLYGON((0 0,1 0,1 1,0 0),(0.1 0.1,0.2 0.1,0.2 0.2,0.1 0.1))",
+            "MULTIPOINT((1 2),(3 4))",
+            "MULTILINESTRING((1 2,3 4),(5 6,7 8))",
+            "MULTIPOLYGON(((0 0,1 0,1 1,0 0)),((2 2,3 2,3 3,2 2)))",
+            "GEOMETRYCOLLECTION(POINT(1 2),LINESTRING(1 2,3 4))",
+            "LINESTRING EMPTY",
+        ] {
+            let geometry = from_wkt(text).unwrap();
+            assert_eq!(to_wkt(&geometry), text);
+            assert_eq!(from_wkb(&to_wkb(&geometry)).unwrap(), geometry);
+        }
+        assert_eq!(
+            from_wkt(" multipoint ( 1 2 , 3 4 ) "),
+            from_wkt("MULTIPOINT((1 2),(3 4))")
+        );
+        for text in [
+            "POINT(1)",
+            "POINT Z(1 2 3)",
+            "LINESTRING(1 2",
+            "POINT(1 2) x",
+        ] {
+            assert_eq!(from_wkt(text), Err(-26033));
+        }
+
+        // Big endian point
+        let mut bytes = vec![0];
+        bytes.extend_from_slice(&WKB_POINT.to_be_bytes());
+        bytes.extend_from_slice(&1.5f64.to_be_bytes());
+        bytes.extend_from_slice(&2.5f64.to_be_bytes());
+        assert_eq!(from_wkb(&bytes), Ok(Point::new(1.5, 2.5).into()));
+        assert_eq!(from_wkb(&bytes[..10]), Err(-26033));
+
+        assert_eq!(
+            check_spatial(ffi::MIMER_GIS_LATITUDE, &90f32.to_le_bytes()),
+            Ok(())
+        );
+        assert_eq!(
+            check_spatial(ffi::MIMER_GIS_LATITUDE, &90.5f32.to_le_bytes()),
+            Err(-26031)
+        );
+        assert_eq!(
+            check_spatial(ffi::MIMER_GIS_LONGITUDE, &f32::NAN.to_le_bytes()),
+            Err(-26032)
+        );
+    }
+
+    #[test]
+    fn location_types() {
+        assert!(Latitude::new(-90.0).is_ok());
+        assert_eq!(Latitude::new(-90.5), Err(-26031));
+        assert_eq!(Longitude::new(f32::NAN), Err(-26032));
+        assert_eq!(Location::new(59.0, 181.0), Err(-26032));
+
+        let conn = establish_connection();
+        drop_create_table(&conn, SPATIAL_TABLE, SPATIAL_TABLE_COLUMNS);
+        let stmnt = conn
+            .prepare(
+                &format!(
+                    "INSERT INTO {SPATIAL_TABLE} {SPATIAL_TABLE_COLUMN_NAMES} VALUES(?,?,?,?)"
+                ),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let location = Location::new(59.3293, 18.0686).unwrap();
+        stmnt
+            .execute_bind(&[
+                &geo::Point::new(1, 2),
+                &location.lat,
+                &location.lon,
+                &location,
+            ])
+            .unwrap();
+
+        let stmnt = conn
+            .prepare(
+                &format!("SELECT * FROM {SPATIAL_TABLE}"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = stmnt.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+        assert_eq!(row.get::<Latitude>(2).unwrap(), Some(location.lat));
+        assert_eq!(row.get::<Longitude>(3).unwrap(), Some(location.lon));
+        assert_eq!(row.get::<Location>(4).unwrap(), Some(location));
+        assert_eq!(
+            row.get::<Point<f64>>(4).unwrap(),
+            Some(Point::from(location))
+        );
+    }
+
+    #[test]
+    fn bind_geometry() {
+        let conn = establish_connection();
+        drop_create_table(
+            &conn,
+            "geometry_table",
+            "(location BUILTIN.GIS_LOCATION, wkt VARCHAR(200), wkb VARBINARY(200))",
+        );
+        let stmnt = conn
+            .prepare(
+                "INSERT INTO geometry_table VALUES(?,?,?)",
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let point = Point::new(18.0686, 59.3293);
+        let line = LineString::from(vec![(0.0, 0.0), (1.0, 1.0), (2.0, 0.5)]);
+        stmnt.execute_bind(&[&point, &line, &line]).unwrap();
+
+        assert_eq!(
+            stmnt.execute_bind(&[&Point::new(18.0, 91.0), &line, &line]),
+            Err(-26031)
+        );
+        assert_eq!(
+            stmnt.execute_bind(&[&Point::new(-181.0, 59.0), &line, &line]),
+            Err(-26032)
+        );
+        assert_eq!(
+            stmnt.execute_bind(&[&(95.0f32, 0.0f32), &line, &line]),
+            Err(-26031)
+        );
+
+        let stmnt = conn
+            .prepare("SELECT * FROM geometry_table", CursorMode::Forward)
+            .unwrap();
+        let mut cursor = stmnt.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+        let fetched = row.get::<Point<f64>>(1).unwrap().unwrap();
+        assert!((fetched.x() - point.x()).abs() < 1e-4 && (fetched.y() - point.y()).abs() < 1e-4);
+        assert_eq!(
+            row.get::<String>(2).unwrap().unwrap(),
+            "LINESTRING(0 0,1 1,2 0.5)"
+        );
+        assert_eq!(row.get::<LineString<f64>>(2).unwrap(), Some(line.clone()));
+        assert_eq!(row.get::<Geometry<f64>>(3).unwrap(), Some(line.into()));
+        assert_eq!(row.get::<Polygon<f64>>(3), Err(-26200));
+    }
+}
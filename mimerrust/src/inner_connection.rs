@@ -22,19 +22,30 @@
 * See license for more details.
 * *********************************************************************/
 
+use crate::common::mimer_options::ColumnNameResolution;
 use crate::common::return_codes::MIMER_SUCCESS;
 use crate::common::traits::*;
+use crate::error_sink::{report, unexpected_return_code};
 use crate::inner_statement::*;
 use crate::mimer_error::*;
+use crate::observer::{SharedStatementObserver, StatementEvent, StatementEventKind};
+use crate::server_info::Capabilities;
+use crate::session_guard::{SessionGuard, SessionLock};
+use crate::tracing_support::traced;
+use crate::transaction::StatementRecord;
 use mimerrust_sys as ffi;
 
 #[doc(hidden)]
 use lazy_static::lazy_static;
 
 #[doc(hidden)]
-use parking_lot::{MappedMutexGuard, Mutex, MutexGuard};
+use parking_lot::{Mutex, ReentrantMutex};
+
+/// Function called with errors from rolling back a dropped [Transaction](crate::Transaction).
+pub(crate) type RollbackFailureHook = Box<dyn Fn(&MimerError) + Send + Sync>;
 
 lazy_static! {
+    // Serializes beginning and ending sessions in the C API. It is never taken for requests on an open session.
     static ref connect_disconnect_mtx: Mutex<i32> = Mutex::new(0);
 }
 
@@ -44,17 +55,59 @@ use std::{
     collections::HashMap,
     ffi::CString,
     result::Result::{Err, Ok},
-    sync::Weak,
+    sync::{
+        atomic::{AtomicBool, AtomicU8, AtomicUsize},
+        Arc, OnceLock, Weak,
+    },
+    time::Instant,
 };
 
 /// Represents the internal parts of a Connection and handles the C API session struct.
 pub struct InnerConnection {
     pub(crate) session: Mutex<ffi::MimerSession>,
+    pub(crate) session_lock: SessionLock, // Shared with the statements of the connection
+    pub(crate) database: String,          // As given when opening, empty for the default database
+    pub(crate) ident: String,             // As given when opening
+    pub(crate) capabilities: OnceLock<Capabilities>, // Probed on first use
     pub(crate) statements: Mutex<HashMap<u64, Weak<InnerStatement>>>,
+    pub(crate) fetch_memory_budget: AtomicUsize, // 0 means no budget
+    pub(crate) column_name_resolution: AtomicU8, // see ColumnNameResolution::as_u8
+    pub(crate) rollback_failure_hook: Mutex<Option<RollbackFailureHook>>,
+    pub(crate) statement_history: Mutex<Option<Vec<StatementRecord>>>, // None when not recording
+    pub(crate) read_only_transaction: AtomicBool, // Set while a read-only transaction is open on the session
+    pub(crate) statement_observer: Mutex<Option<SharedStatementObserver>>,
+    #[cfg(feature = "tracing")]
+    pub(crate) redact_traced_sql: std::sync::atomic::AtomicBool,
+}
+
+// SAFETY: The session handle is a pointer owned by the C API, which isn't tied to the thread that created it.
+// It is only reached through a SessionGuard, which holds the session lock, except by cancel, which the C API allows
+// to be called while another thread is in a call on the session. The remaining fields are Send and Sync themselves.
+unsafe impl Send for InnerConnection {}
+unsafe impl Sync for InnerConnection {}
+
+/// Copies a password, or a statement holding one, into a [CString] for the C API. The terminating nul is allocated up front,
+/// so that no reallocation leaves a copy of the password behind that [wipe] doesn't reach.
+pub(crate) fn secret_cstring(secret: &str) -> Result<CString, MimerError> {
+    let mut bytes = Vec::with_capacity(secret.len() + 1);
+    bytes.extend_from_slice(secret.as_bytes());
+    CString::new(bytes).map_err(|err| {
+        wipe(err.into_vec());
+        MimerError::mimer_error_from_code(-26999)
+    })
 }
 
-unsafe impl Send for InnerConnection {} //TODO: Is this safe to be left empty?
-unsafe impl Sync for InnerConnection {} //TODO: Is this safe to be left empty?
+/// Overwrites a buffer that held a password with zeros before freeing it. Requires the `zeroize` feature, without it the buffer is just freed.
+#[cfg(feature = "zeroize")]
+pub(crate) fn wipe(mut buffer: Vec<u8>) {
+    zeroize::Zeroize::zeroize(&mut buffer);
+}
+
+/// Overwrites a buffer that held a password with zeros before freeing it. Requires the `zeroize` feature, without it the buffer is just freed.
+#[cfg(not(feature = "zeroize"))]
+pub(crate) fn wipe(buffer: Vec<u8>) {
+    drop(buffer);
+}
 
 impl InnerConnection {
     /// Opens a connection to a MimerSQL database.
@@ -72,9 +125,7 @@ impl InnerConnection {
         let ident_char_ptr = CString::new(ident)
             .or_else(|_| Err(MimerError::mimer_error_from_code(-26999)))?
             .into_raw();
-        let pw_char_ptr = CString::new(password)
-            .or_else(|_| Err(MimerError::mimer_error_from_code(-26999)))?
-            .into_raw();
+        let pw_char_ptr = secret_cstring(password)?.into_raw();
 
         unsafe {
             let _lck = connect_disconnect_mtx.lock();
@@ -83,12 +134,12 @@ impl InnerConnection {
             // retake pointers to free memory
             let _ = CString::from_raw(db_char_ptr);
             let _ = CString::from_raw(ident_char_ptr);
-            let _ = CString::from_raw(pw_char_ptr);
+            wipe(CString::from_raw(pw_char_ptr).into_bytes_with_nul());
 
             match rc.cmp(MIMER_SUCCESS) {
                 Ordering::Greater => {
-                    // i suppose this is a reasonable panic?
-                    panic!("Return code is positive from C API function which doesn't return a positive value")
+                    let ec = unexpected_return_code("opening a session", rc);
+                    return Err(MimerError::mimer_error_from_code(ec));
                 }
                 Ordering::Equal => (),
                 Ordering::Less => return Err(MimerError::mimer_error_from_code(rc)),
@@ -97,7 +148,19 @@ impl InnerConnection {
             match sess.as_mut() {
                 Some(session) => Ok(InnerConnection {
                     session: Mutex::new(session),
+                    session_lock: Arc::new(ReentrantMutex::new(())),
+                    database: database.to_string(),
+                    ident: ident.to_string(),
+                    capabilities: OnceLock::new(),
                     statements: Mutex::new(HashMap::new()),
+                    fetch_memory_budget: AtomicUsize::new(0),
+                    column_name_resolution: AtomicU8::new(ColumnNameResolution::default().as_u8()),
+                    rollback_failure_hook: Mutex::new(None),
+                    statement_history: Mutex::new(None),
+                    read_only_transaction: AtomicBool::new(false),
+                    statement_observer: Mutex::new(None),
+                    #[cfg(feature = "tracing")]
+                    redact_traced_sql: std::sync::atomic::AtomicBool::new(false),
                 }),
 
                 None => Err(MimerError::mimer_error_from_code(-26002)), // Session pointer returned from C API was NULL
@@ -105,11 +168,91 @@ impl InnerConnection {
         }
     }
 
+    /// Runs `f`, which executes `sql`, adds it to the statement history if a [Transaction](crate::Transaction) is recording one,
+    /// and reports it to the statement observer if one is set and to the metrics recorder with the `metrics` feature.
+    /// `params` is the number of parameters of the statement, put in the tracing span with the `tracing` feature.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn record_statement<F>(&self, sql: &str, params: usize, f: F) -> Result<i32, i32>
+    where
+        F: FnOnce() -> Result<i32, i32>,
+    {
+        traced!(
+            "mimer.execute",
+            {
+                db.statement = crate::tracing_support::statement_text(Some(self), sql),
+                params,
+                rows = tracing::field::Empty
+            },
+            {
+                let result = self.record_statement_auxillary(sql, f);
+                #[cfg(feature = "tracing")]
+                if let Ok(rows) = result {
+                    tracing::Span::current().record("rows", rows);
+                }
+                result
+            }
+        )
+    }
+
+    fn record_statement_auxillary<F>(&self, sql: &str, f: F) -> Result<i32, i32>
+    where
+        F: FnOnce() -> Result<i32, i32>,
+    {
+        let observer = self.statement_observer();
+        if self.statement_history.lock().is_none()
+            && observer.is_none()
+            && !cfg!(feature = "metrics")
+        {
+            return f();
+        }
+        let started = Instant::now();
+        let result = f();
+        let duration = started.elapsed();
+        if let Some(history) = self.statement_history.lock().as_mut() {
+            history.push(StatementRecord {
+                sql: sql.to_string(),
+                result,
+                duration,
+            });
+        }
+        let event = StatementEvent {
+            kind: StatementEventKind::Execute,
+            sql,
+            duration,
+            rows: result.ok().map(|rc| rc as u64),
+            result,
+        };
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_event(&event);
+        if let Some(observer) = observer {
+            observer(&event);
+        }
+        result
+    }
+
+    /// Returns the statement observer, if one is set.
+    /// The lock is released before the observer is called, so that the observer can use the connection.
+    pub(crate) fn statement_observer(&self) -> Option<SharedStatementObserver> {
+        self.statement_observer.lock().clone()
+    }
+
+    /// Cancels the request that is running on the session, if any, with `MimerCancel`.
+    /// The session lock isn't taken, since it is held by the thread waiting for the request.
+    pub(crate) fn cancel(&self) -> i32 {
+        // SAFETY: The session pointer isn't changed after the session is begun, and is only ended when the connection is dropped.
+        unsafe { ffi::MimerCancel(*self.session.data_ptr()) }
+    }
+
     /// Pushes a statement pointer to the [HashMap] of statements.
     pub(crate) fn push_statement(&self, stmt: Weak<InnerStatement>) {
-        let strong_stmt = stmt.upgrade().unwrap(); //Ok unwrap since we know the statement is still alive
-        let id = strong_stmt.get_statement_handle().unwrap().unwrap(); //Ok unwraps since we know the statement is still alive and is a statement
-        self.statements.lock().insert(*id as u64, stmt);
+        let id = match stmt.upgrade() {
+            Some(strong_stmt) => match strong_stmt.get_statement_handle() {
+                Ok(Some(handle)) => *handle as u64,
+                _ => return,
+            },
+            None => return, // Already dropped, so there is nothing to end when the connection is dropped
+        };
+        self.statements.lock().insert(id, stmt);
     }
 
     /// Removes a statement pointer from the [HashMap] of statements.
@@ -122,28 +265,47 @@ impl Drop for InnerConnection {
     fn drop(&mut self) {
         for stmt in self.statements.lock().values_mut() {
             if let Some(stmt) = stmt.upgrade() {
-                stmt.end_statement().unwrap(); //Ok unwrap since if error occurs in drop it is unrecoverable
+                if let Err(ec) = stmt.end_statement() {
+                    report("ending a statement of a dropped connection", ec);
+                }
             }
         }
-        unsafe {
+        if let Ok(Some(mut session)) = self.get_session_handle() {
             let _lck = connect_disconnect_mtx.lock();
-            ffi::MimerEndSessionHard(&mut *self.get_session_handle().unwrap().unwrap());
-            //Ok unwraps since if error occurs in drop it is unrecoverable
+            let rc = unsafe { ffi::MimerEndSessionHard(&mut *session) };
+            if rc < 0 {
+                report("ending the session of a dropped connection", rc);
+            }
         }
     }
 }
 
 impl GetHandle for InnerConnection {
     fn get_handle(&self) -> Result<MimerHandle, i32> {
-        Ok(MimerHandle::Session(MutexGuard::map(
-            self.session.lock(),
-            |inner| &mut *inner,
+        Ok(MimerHandle::Session(SessionGuard::lock(
+            &self.session_lock,
+            &self.session,
         )))
     }
 
-    fn get_session_handle(&self) -> Result<Option<MappedMutexGuard<ffi::MimerSession>>, i32> {
-        Ok(Some(MutexGuard::map(self.session.lock(), |inner| {
-            &mut *inner
-        })))
+    fn get_session_handle(&self) -> Result<Option<SessionGuard<ffi::MimerSession>>, i32> {
+        Ok(Some(SessionGuard::lock(&self.session_lock, &self.session)))
+    }
+}
+
+#[cfg(test)]
+mod inner_connection_tests {
+    use super::*;
+
+    #[test]
+    fn secret_cstring_fits_nul() {
+        let password = secret_cstring("RUSTPASSWORD").unwrap();
+        let bytes = password.into_bytes_with_nul();
+        assert_eq!(bytes, b"RUSTPASSWORD\0");
+        assert_eq!(bytes.capacity(), bytes.len());
+        wipe(bytes);
+
+        let err = secret_cstring("RUST\0PASSWORD").unwrap_err();
+        assert_eq!(err.get_error_code(), -26999);
     }
 }
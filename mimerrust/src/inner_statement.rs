@@ -26,44 +26,56 @@ use crate::common::mimer_options::*;
 use crate::common::return_codes::MIMER_SUCCESS;
 use crate::common::traits::GetHandle;
 use crate::common::traits::MimerHandle;
+use crate::error_sink::{report, unexpected_return_code};
 use crate::inner_connection::*;
+use crate::session_guard::{SessionGuard, SessionLock};
+use crate::watchdog::run_with_timeout;
 use mimerrust_sys as ffi;
 
 #[doc(hidden)]
-use parking_lot::{MappedMutexGuard, Mutex, MutexGuard};
+use parking_lot::Mutex;
 #[doc(hidden)]
 use std::{
     cmp::Ordering,
     ffi::CString,
     result::Result::{Err, Ok},
-    sync::Weak,
+    sync::{
+        atomic::{AtomicU64, Ordering as AtomicOrdering},
+        Weak,
+    },
+    time::Duration,
 };
 /// Represents the internal parts of a Statement and handles the C API statement struct.
 pub struct InnerStatement {
     statement: Mutex<ffi::MimerStatement>,
+    session_lock: SessionLock, // Shared with the connection
     pub(crate) inner_connection: Weak<InnerConnection>,
     statement_list_in_connection_id: u64,
+    pub(crate) string_buffer: Mutex<Vec<u8>>, // Reused when fetching string values, see read_string_buffered
+    query_timeout: AtomicU64, // In nanoseconds, 0 for no timeout. Atomic since it is read on every request
+    bound_parameters: Mutex<Vec<bool>>, // Whether each parameter has been bound since the statement was last executed
 }
 
-unsafe impl Send for InnerStatement {} //TODO: Is this safe to be left empty?
-unsafe impl Sync for InnerStatement {} //TODO: Is this safe to be left empty?
+// SAFETY: The statement handle is a pointer owned by the C API, which isn't tied to the thread that created it.
+// It is only reached through a SessionGuard, which holds the lock of the statement and the session lock of its connection,
+// so calls into the C API on the statement never overlap with other calls on the same session.
+unsafe impl Send for InnerStatement {}
+unsafe impl Sync for InnerStatement {}
 
 impl Drop for InnerStatement {
     fn drop(&mut self) {
-        let mut handle = self.get_statement_handle().unwrap().unwrap(); //Ok unwraps since if an error occurs in drop it is unrecoverable
-        match self.check_connection() {
-            Ok(_) => {
-                self.inner_connection
-                    .upgrade()
-                    .unwrap()
-                    .remove_statement(self.statement_list_in_connection_id); //Ok unwrap since if an error occurs in drop it is unrecoverable
-                unsafe {
-                    ffi::MimerEndStatement(&mut *handle);
-                }
+        // When the connection is dropped, it has already ended the statement
+        if let Some(inner_connection) = self.inner_connection.upgrade() {
+            // Removed before taking the handle, as the connection takes its statements before their handles when it is dropped
+            inner_connection.remove_statement(self.statement_list_in_connection_id);
+            let mut handle = match self.get_statement_handle() {
+                Ok(Some(handle)) => handle,
+                _ => return,
+            };
+            let rc = unsafe { ffi::MimerEndStatement(&mut *handle) };
+            if rc < 0 {
+                report("ending a dropped statement", rc);
             }
-            Err(-26003) => (),
-            // is this is a reasonable panic?
-            Err(ec) => panic!("Failed to check connection while dropping statement: {ec}"),
         }
     }
 }
@@ -77,12 +89,61 @@ impl InnerStatement {
         Ok(())
     }
 
+    /// Runs `f`, which calls a C API function making a request to the server with this statement,
+    /// cancelling the request if it exceeds the [query timeout](crate::Statement::set_query_timeout()).
+    pub(crate) fn with_query_timeout<F>(&self, f: F) -> i32
+    where
+        F: FnOnce() -> i32,
+    {
+        run_with_timeout(&self.inner_connection, self.query_timeout(), f)
+    }
+
+    /// Gets the [query timeout](crate::Statement::set_query_timeout()), [None] when there is none.
+    pub(crate) fn query_timeout(&self) -> Option<Duration> {
+        match self.query_timeout.load(AtomicOrdering::Relaxed) {
+            0 => None,
+            nanos => Some(Duration::from_nanos(nanos)),
+        }
+    }
+
+    /// Sets the [query timeout](crate::Statement::set_query_timeout()), where [None] or zero removes it.
+    pub(crate) fn set_query_timeout(&self, timeout: Option<Duration>) {
+        let nanos = timeout.map_or(0, |timeout| timeout.as_nanos().min(u64::MAX as u128) as u64);
+        self.query_timeout.store(nanos, AtomicOrdering::Relaxed);
+    }
+
+    /// Marks a parameter as bound, identified by its index starting at 1.
+    pub(crate) fn mark_bound(&self, idx: i16) {
+        let mut bound_parameters = self.bound_parameters.lock();
+        if let Some(bound) = (idx as usize)
+            .checked_sub(1)
+            .and_then(|i| bound_parameters.get_mut(i))
+        {
+            *bound = true;
+        }
+    }
+
+    /// Returns whether a parameter has been bound since the statement was last executed.
+    pub(crate) fn is_bound(&self, idx: i16) -> bool {
+        let bound_parameters = self.bound_parameters.lock();
+        (idx as usize)
+            .checked_sub(1)
+            .and_then(|i| bound_parameters.get(i))
+            == Some(&true)
+    }
+
+    /// Forgets the bound parameters, after the statement has been executed.
+    pub(crate) fn clear_bound(&self) {
+        self.bound_parameters.lock().fill(false);
+    }
+
     /// Creates a new InnerStatement.
     pub(crate) fn new(
         inner_connection: Weak<InnerConnection>,
         sqlstatement: &str,
         cursor_mode: CursorMode,
     ) -> Result<(InnerStatement, usize), i32> {
+        let connection = inner_connection.upgrade().ok_or(-26003)?;
         let stmnt_char_ptr = CString::new(sqlstatement)
             .or_else(|_| Err(-26999))?
             .into_raw();
@@ -91,11 +152,7 @@ impl InnerStatement {
 
         unsafe {
             rc = ffi::MimerBeginStatement8(
-                *inner_connection
-                    .upgrade()
-                    .ok_or(-26003)?
-                    .get_session_handle()?
-                    .unwrap(), //Ok unwrap since we know that the connection is a connection
+                *connection.get_session_handle()?.unwrap(), //Ok unwrap since we know that the connection is a connection
                 stmnt_char_ptr,
                 cursor_mode as i32,
                 &mut statement,
@@ -114,8 +171,12 @@ impl InnerStatement {
                             Ok((
                                 InnerStatement {
                                     statement: Mutex::new(statement),
+                                    session_lock: connection.session_lock.clone(),
                                     inner_connection,
                                     statement_list_in_connection_id: statement as u64,
+                                    string_buffer: Mutex::new(Vec::new()),
+                                    query_timeout: AtomicU64::new(0),
+                                    bound_parameters: Mutex::new(vec![false; num_param]),
                                 },
                                 num_param,
                             ))
@@ -123,10 +184,7 @@ impl InnerStatement {
                         Ordering::Less => Err(rc),
                     }
                 }
-                Ordering::Greater => {
-                    // i suppose this is a reasonable panic?
-                    panic!("Return code is positive from C API function which doesn't return a positive value");
-                }
+                Ordering::Greater => Err(unexpected_return_code("preparing a statement", rc)),
             }
         }
     }
@@ -146,15 +204,16 @@ impl InnerStatement {
 
 impl GetHandle for InnerStatement {
     fn get_handle(&self) -> Result<MimerHandle, i32> {
-        Ok(MimerHandle::Statement(MutexGuard::map(
-            self.statement.lock(),
-            |inner| &mut *inner,
+        Ok(MimerHandle::Statement(SessionGuard::lock(
+            &self.session_lock,
+            &self.statement,
         )))
     }
 
-    fn get_statement_handle(&self) -> Result<Option<MappedMutexGuard<ffi::MimerStatement>>, i32> {
-        Ok(Some(MutexGuard::map(self.statement.lock(), |inner| {
-            &mut *inner
-        })))
+    fn get_statement_handle(&self) -> Result<Option<SessionGuard<ffi::MimerStatement>>, i32> {
+        Ok(Some(SessionGuard::lock(
+            &self.session_lock,
+            &self.statement,
+        )))
     }
 }
@@ -0,0 +1,567 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{
+    common::mimer_options::ColumnType,
+    types::{FromSql, MimerDatatype, ToSql},
+};
+
+#[doc(hidden)]
+use std::fmt;
+
+/// A field of an interval qualifier, e.g. *DAY* in *INTERVAL DAY TO SECOND*.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Field {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl Field {
+    /// Size of the field in the base unit of its family, months or nanoseconds.
+    fn unit(self) -> i128 {
+        match self {
+            Field::Year => 12,
+            Field::Month => 1,
+            Field::Day => 86_400 * NANOS_PER_SECOND,
+            Field::Hour => 3_600 * NANOS_PER_SECOND,
+            Field::Minute => 60 * NANOS_PER_SECOND,
+            Field::Second => NANOS_PER_SECOND,
+        }
+    }
+
+    fn is_year_month(self) -> bool {
+        matches!(self, Field::Year | Field::Month)
+    }
+}
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+const YEAR_TO_MONTH: &[Field] = &[Field::Year, Field::Month];
+const DAY_TO_SECOND: &[Field] = &[Field::Day, Field::Hour, Field::Minute, Field::Second];
+
+/// Returns the fields of an interval type, from the leading to the trailing field, or [None] if the type isn't an interval.
+fn fields(column_type: ColumnType) -> Option<&'static [Field]> {
+    use Field::*;
+    match column_type {
+        ColumnType::IntervalYear => Some(&[Year]),
+        ColumnType::IntervalMonth => Some(&[Month]),
+        ColumnType::IntervalYearToMonth => Some(YEAR_TO_MONTH),
+        ColumnType::IntervalDay => Some(&[Day]),
+        ColumnType::IntervalHour => Some(&[Hour]),
+        ColumnType::IntervalMinute => Some(&[Minute]),
+        ColumnType::IntervalSecond => Some(&[Second]),
+        ColumnType::IntervalDayToHour => Some(&[Day, Hour]),
+        ColumnType::IntervalDayToMinute => Some(&[Day, Hour, Minute]),
+        ColumnType::IntervalDayToSecond => Some(DAY_TO_SECOND),
+        ColumnType::IntervalHourToMinute => Some(&[Hour, Minute]),
+        ColumnType::IntervalHourToSecond => Some(&[Hour, Minute, Second]),
+        ColumnType::IntervalMinuteToSecond => Some(&[Minute, Second]),
+        _ => None,
+    }
+}
+
+/// Returns whether a column type is an interval type.
+pub(crate) fn is_interval(column_type: ColumnType) -> bool {
+    fields(column_type).is_some()
+}
+
+/// Parses interval text in the format Mimer SQL uses for the given fields, e.g. `-02 03:04:05.5` for *DAY TO SECOND*.
+/// Returns the value in the base unit of the fields, months or nanoseconds.
+fn parse_interval(text: &str, fields: &[Field]) -> Result<i128, i32> {
+    let text = text.trim();
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let parts: Vec<&str> = text.split([' ', ':', '-']).collect();
+    if parts.len() != fields.len() {
+        return Err(-26200);
+    }
+
+    let mut total: i128 = 0;
+    for (part, field) in parts.iter().zip(fields) {
+        let (whole, fraction) = match (field, part.split_once('.')) {
+            (Field::Second, Some((whole, fraction))) => (whole, fraction),
+            _ => (*part, ""),
+        };
+        if whole.is_empty() || !whole.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(-26200);
+        }
+        if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(-26200);
+        }
+        let whole: i128 = whole.parse().or(Err(-26200))?;
+        // Digits beyond nanoseconds are truncated
+        let nanos: i128 = format!("{:0<9.9}", fraction).parse().or(Err(-26200))?;
+        total = whole
+            .checked_mul(field.unit())
+            .and_then(|v| v.checked_add(nanos))
+            .and_then(|v| total.checked_add(v))
+            .ok_or(-26200)?;
+    }
+    Ok(if negative { -total } else { total })
+}
+
+/// Formats a value in the base unit of the fields as interval text in the format Mimer SQL uses for them.
+/// Returns -26024 if the value has a part that is finer than the trailing field.
+fn format_interval(value: i128, fields: &[Field]) -> Result<String, i32> {
+    let mut formatted = String::new();
+    if value < 0 {
+        formatted.push('-');
+    }
+    let mut remainder = value.unsigned_abs() as i128;
+    let mut previous = None;
+    for field in fields {
+        let part = remainder / field.unit();
+        remainder %= field.unit();
+        match (previous, field) {
+            (None, _) => formatted.push_str(&part.to_string()),
+            (Some(Field::Year), _) => formatted.push_str(&format!("-{part:02}")),
+            (Some(Field::Day), _) => formatted.push_str(&format!(" {part:02}")),
+            _ => formatted.push_str(&format!(":{part:02}")),
+        }
+        previous = Some(*field);
+    }
+    match (previous, remainder) {
+        (_, 0) => {}
+        (Some(Field::Second), nanos) => {
+            let fraction = format!("{nanos:09}");
+            formatted.push('.');
+            formatted.push_str(fraction.trim_end_matches('0'));
+        }
+        _ => return Err(-26024), // Interval value doesn't fit the fields of the parameter
+    }
+    Ok(formatted)
+}
+
+/// Converts interval text from the format of one interval type to another, as needed when binding a parameter.
+/// Text is returned unchanged if the types are the same or `to` isn't an interval type.
+///
+/// Returns -26203 if the types aren't both year-month or both day-time intervals, and -26024 if the value doesn't fit the fields of `to`.
+pub(crate) fn convert(text: &str, from: ColumnType, to: ColumnType) -> Result<String, i32> {
+    let (from_fields, to_fields) = match (fields(from), fields(to)) {
+        (Some(from_fields), Some(to_fields)) if from != to => (from_fields, to_fields),
+        _ => return Ok(text.to_string()),
+    };
+    if from_fields[0].is_year_month() != to_fields[0].is_year_month() {
+        return Err(-26203); // Invalid parameter type for MimerDatatype-variant
+    }
+    format_interval(parse_interval(text, from_fields)?, to_fields)
+}
+
+/// Parses a fetched value for one of the interval families.
+fn from_datatype(value: MimerDatatype, canonical: ColumnType) -> Result<i128, i32> {
+    let canonical_fields = fields(canonical).ok_or(-26200)?;
+    match value {
+        MimerDatatype::Interval(text, column_type) => match fields(column_type) {
+            Some(f) if f[0].is_year_month() == canonical_fields[0].is_year_month() => {
+                parse_interval(&text, f)
+            }
+            _ => Err(-26200),
+        },
+        MimerDatatype::String(text) => parse_interval(&text, canonical_fields),
+        MimerDatatype::StringRef(text) => parse_interval(text, canonical_fields),
+        _ => Err(-26200),
+    }
+}
+
+/// A year-month interval, as stored in *INTERVAL YEAR*, *INTERVAL MONTH* and *INTERVAL YEAR TO MONTH* columns.
+///
+/// The value is held as a number of months, which may be negative.
+/// It is displayed in the *YEAR TO MONTH* format, e.g. `1-02` for one year and two months.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// let conn = Connection::open(db, ident, pass).unwrap();
+/// # _ = conn.execute_statement("DROP TABLE interval_table");
+/// conn.execute_statement("CREATE TABLE interval_table (column1 INTERVAL YEAR(3) TO MONTH, column2 INTERVAL MONTH(4))").unwrap();
+///
+/// let interval = IntervalYearMonth::new(1, 2);
+/// let stmnt = conn.prepare("INSERT INTO interval_table VALUES(:c1, :c2)", CursorMode::Forward).unwrap();
+/// stmnt.execute_bind(&[&interval, &interval]).unwrap();
+///
+/// let stmnt = conn.prepare("SELECT * FROM interval_table", CursorMode::Forward).unwrap();
+/// let mut cursor = stmnt.open_cursor().unwrap();
+/// let row = cursor.next_row().unwrap().unwrap();
+/// assert_eq!(row.get::<IntervalYearMonth>(1).unwrap(), Some(interval));
+/// assert_eq!(row.get::<IntervalYearMonth>(2).unwrap().unwrap().total_months(), 14);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct IntervalYearMonth {
+    months: i32,
+}
+
+impl IntervalYearMonth {
+    /// Creates an interval of `years` and `months`. The months may exceed 11 and have either sign.
+    pub fn new(years: i32, months: i32) -> IntervalYearMonth {
+        IntervalYearMonth {
+            months: years * 12 + months,
+        }
+    }
+
+    /// Creates an interval of a total number of months.
+    pub fn from_months(months: i32) -> IntervalYearMonth {
+        IntervalYearMonth { months }
+    }
+
+    /// Returns the total number of months.
+    pub fn total_months(&self) -> i32 {
+        self.months
+    }
+
+    /// Returns the number of whole years, with the sign of the interval.
+    pub fn years(&self) -> i32 {
+        self.months / 12
+    }
+
+    /// Returns the months part that doesn't make up a whole year, with the sign of the interval.
+    pub fn months(&self) -> i32 {
+        self.months % 12
+    }
+}
+
+impl fmt::Display for IntervalYearMonth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format_interval(self.months.into(), YEAR_TO_MONTH).map_err(|_| fmt::Error)?)
+    }
+}
+
+impl ToSql for IntervalYearMonth {
+    fn to_sql(&self) -> MimerDatatype<'_> {
+        MimerDatatype::Interval(self.to_string(), ColumnType::IntervalYearToMonth)
+    }
+}
+impl FromSql for IntervalYearMonth {
+    fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
+        let months = from_datatype(value, ColumnType::IntervalYearToMonth)?;
+        Ok(IntervalYearMonth {
+            months: months.try_into().or(Err(-26200))?,
+        })
+    }
+}
+
+impl TryFrom<IntervalYearMonth> for chrono::Months {
+    type Error = i32;
+
+    /// Converts a non-negative interval. Returns -26200 for negative intervals.
+    fn try_from(interval: IntervalYearMonth) -> Result<Self, Self::Error> {
+        let months: u32 = interval.months.try_into().or(Err(-26200))?;
+        Ok(chrono::Months::new(months))
+    }
+}
+
+impl TryFrom<chrono::Months> for IntervalYearMonth {
+    type Error = i32;
+
+    /// Returns -26200 if the number of months doesn't fit an [i32].
+    fn try_from(months: chrono::Months) -> Result<Self, Self::Error> {
+        Ok(IntervalYearMonth {
+            months: months.as_u32().try_into().or(Err(-26200))?,
+        })
+    }
+}
+
+/// A day-time interval, as stored in *INTERVAL DAY*, *INTERVAL HOUR*, *INTERVAL MINUTE*, *INTERVAL SECOND*
+/// and the *INTERVAL DAY TO SECOND* family of columns.
+///
+/// The value is held as a number of nanoseconds, which may be negative.
+/// It is displayed in the *DAY TO SECOND* format, e.g. `2 03:04:05.5`, and converts losslessly to and from [chrono::Duration] within its range.
+///
+/// When bound to a parameter with fewer fields, e.g. *INTERVAL HOUR*, a value with a finer part (minutes in this case) is rejected with the error code -26024 instead of being truncated.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// let conn = Connection::open(db, ident, pass).unwrap();
+/// # _ = conn.execute_statement("DROP TABLE interval_table");
+/// conn.execute_statement("CREATE TABLE interval_table (column1 INTERVAL DAY(3) TO SECOND(3))").unwrap();
+///
+/// let interval = IntervalDayTime::new(2, 3, 4, 5, 500_000_000);
+/// let stmnt = conn.prepare("INSERT INTO interval_table VALUES(:c1)", CursorMode::Forward).unwrap();
+/// stmnt.execute_bind(&[&interval]).unwrap();
+///
+/// let stmnt = conn.prepare("SELECT * FROM interval_table", CursorMode::Forward).unwrap();
+/// let mut cursor = stmnt.open_cursor().unwrap();
+/// let row = cursor.next_row().unwrap().unwrap();
+/// let fetched = row.get::<IntervalDayTime>(1).unwrap().unwrap();
+/// assert_eq!(fetched, interval);
+/// assert_eq!(fetched.to_string(), "2 03:04:05.5");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct IntervalDayTime {
+    nanoseconds: i128,
+}
+
+impl IntervalDayTime {
+    /// Creates an interval from its parts. The parts may exceed their usual range and have either sign.
+    pub fn new(
+        days: i64,
+        hours: i64,
+        minutes: i64,
+        seconds: i64,
+        nanoseconds: i64,
+    ) -> IntervalDayTime {
+        IntervalDayTime {
+            nanoseconds: days as i128 * Field::Day.unit()
+                + hours as i128 * Field::Hour.unit()
+                + minutes as i128 * Field::Minute.unit()
+                + seconds as i128 * Field::Second.unit()
+                + nanoseconds as i128,
+        }
+    }
+
+    /// Creates an interval of a total number of seconds.
+    pub fn from_seconds(seconds: i64) -> IntervalDayTime {
+        IntervalDayTime::new(0, 0, 0, seconds, 0)
+    }
+
+    /// Creates an interval of a total number of nanoseconds.
+    pub fn from_nanoseconds(nanoseconds: i128) -> IntervalDayTime {
+        IntervalDayTime { nanoseconds }
+    }
+
+    /// Returns the total number of nanoseconds.
+    pub fn total_nanoseconds(&self) -> i128 {
+        self.nanoseconds
+    }
+
+    /// Returns the number of whole days, with the sign of the interval.
+    pub fn days(&self) -> i64 {
+        (self.nanoseconds / Field::Day.unit()) as i64
+    }
+
+    /// Returns the hours part (-23 to 23) that doesn't make up a whole day.
+    pub fn hours(&self) -> i64 {
+        (self.nanoseconds % Field::Day.unit() / Field::Hour.unit()) as i64
+    }
+
+    /// Returns the minutes part (-59 to 59) that doesn't make up a whole hour.
+    pub fn minutes(&self) -> i64 {
+        (self.nanoseconds % Field::Hour.unit() / Field::Minute.unit()) as i64
+    }
+
+    /// Returns the seconds part (-59 to 59) that doesn't make up a whole minute.
+    pub fn seconds(&self) -> i64 {
+        (self.nanoseconds % Field::Minute.unit() / Field::Second.unit()) as i64
+    }
+
+    /// Returns the fractional second in nanoseconds, with the sign of the interval.
+    pub fn nanoseconds(&self) -> i64 {
+        (self.nanoseconds % Field::Second.unit()) as i64
+    }
+}
+
+impl fmt::Display for IntervalDayTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format_interval(self.nanoseconds, DAY_TO_SECOND).map_err(|_| fmt::Error)?)
+    }
+}
+
+impl ToSql for IntervalDayTime {
+    fn to_sql(&self) -> MimerDatatype<'_> {
+        MimerDatatype::Interval(self.to_string(), ColumnType::IntervalDayToSecond)
+    }
+}
+impl FromSql for IntervalDayTime {
+    fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
+        Ok(IntervalDayTime {
+            nanoseconds: from_datatype(value, ColumnType::IntervalDayToSecond)?,
+        })
+    }
+}
+
+impl TryFrom<IntervalDayTime> for chrono::Duration {
+    type Error = i32;
+
+    /// Returns -26200 if the interval is outside the range of [chrono::Duration].
+    fn try_from(interval: IntervalDayTime) -> Result<Self, Self::Error> {
+        let seconds = interval.nanoseconds.div_euclid(NANOS_PER_SECOND);
+        let nanos = interval.nanoseconds.rem_euclid(NANOS_PER_SECOND);
+        chrono::Duration::new(seconds.try_into().or(Err(-26200))?, nanos as u32).ok_or(-26200)
+    }
+}
+
+impl From<chrono::Duration> for IntervalDayTime {
+    fn from(duration: chrono::Duration) -> Self {
+        IntervalDayTime {
+            nanoseconds: duration.num_seconds() as i128 * NANOS_PER_SECOND
+                + duration.subsec_nanos() as i128,
+        }
+    }
+}
+
+#[cfg(test)]
+mod interval_tests {
+    use super::*;
+    use crate::common::mimer_options::CursorMode;
+    use crate::testing::*;
+
+    #[test]
+    fn parse_and_format() {
+        let value = parse_interval("02 03:04:05", DAY_TO_SECOND).unwrap();
+        assert_eq!(
+            value,
+            IntervalDayTime::new(2, 3, 4, 5, 0).total_nanoseconds()
+        );
+        assert_eq!(format_interval(value, DAY_TO_SECOND).unwrap(), "2 03:04:05");
+        assert_eq!(
+            parse_interval("-0 00:00:01.25", DAY_TO_SECOND).unwrap(),
+            -1_250_000_000
+        );
+        assert_eq!(parse_interval("02 03:04", DAY_TO_SECOND), Err(-26200));
+
+        assert_eq!(IntervalYearMonth::new(1, 2).to_string(), "1-02");
+        assert_eq!(IntervalYearMonth::from_months(-14).to_string(), "-1-02");
+        assert_eq!(
+            convert(
+                "1-02",
+                ColumnType::IntervalYearToMonth,
+                ColumnType::IntervalMonth
+            )
+            .unwrap(),
+            "14"
+        );
+        assert_eq!(
+            convert(
+                "1-02",
+                ColumnType::IntervalYearToMonth,
+                ColumnType::IntervalYear
+            ),
+            Err(-26024)
+        );
+        assert_eq!(
+            convert(
+                "1-02",
+                ColumnType::IntervalYearToMonth,
+                ColumnType::IntervalDay
+            ),
+            Err(-26203)
+        );
+        assert_eq!(
+            convert(
+                "1 02:00:00",
+                ColumnType::IntervalDayToSecond,
+                ColumnType::IntervalHour
+            )
+            .unwrap(),
+            "26"
+        );
+    }
+
+    #[test]
+    fn chrono_conversions() {
+        let interval = IntervalDayTime::new(0, 0, 0, -1, -500_000_000);
+        let duration = chrono::Duration::try_from(interval).unwrap();
+        assert_eq!(duration, chrono::Duration::milliseconds(-1500));
+        assert_eq!(IntervalDayTime::from(duration), interval);
+
+        assert!(chrono::Months::try_from(IntervalYearMonth::from_months(-1)).is_err());
+        assert_eq!(
+            chrono::Months::try_from(IntervalYearMonth::new(1, 2)).unwrap(),
+            chrono::Months::new(14)
+        );
+    }
+
+    #[test]
+    fn interval_roundtrip() {
+        let conn = establish_connection();
+        drop_create_table(&conn, INTERVAL_TABLE, INTERVAL_TABLE_COLUMNS);
+
+        let year = IntervalYearMonth::new(1, 0);
+        let month = IntervalYearMonth::from_months(1);
+        let year_to_month = IntervalYearMonth::new(1, 2);
+        let day = IntervalDayTime::new(2, 0, 0, 0, 0);
+        let hour = IntervalDayTime::new(0, 3, 0, 0, 0);
+        let minute = IntervalDayTime::new(0, 0, 4, 0, 0);
+        let second = IntervalDayTime::new(0, 0, 0, 5, 0);
+        let day_to_second = IntervalDayTime::new(2, 3, 4, 5, 0);
+        let params: [&dyn ToSql; 13] = [
+            &year,
+            &month,
+            &year_to_month,
+            &day,
+            &hour,
+            &minute,
+            &second,
+            &IntervalDayTime::new(2, 3, 0, 0, 0),
+            &IntervalDayTime::new(2, 3, 4, 0, 0),
+            &day_to_second,
+            &IntervalDayTime::new(0, 3, 4, 0, 0),
+            &IntervalDayTime::new(0, 3, 4, 5, 0),
+            &IntervalDayTime::new(0, 0, 4, 5, 0),
+        ];
+        let stmnt = conn
+            .prepare(
+                &format!(
+                    "INSERT INTO {INTERVAL_TABLE} {INTERVAL_TABLE_COLUMN_NAMES} VALUES(?,?,?,?,?,?,?,?,?,?,?,?,?)"
+                ),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        stmnt.execute_bind(&params).unwrap();
+
+        // A value with minutes doesn't fit an INTERVAL HOUR parameter
+        let stmnt = conn
+            .prepare(
+                &format!("INSERT INTO {INTERVAL_TABLE} (column5) VALUES(?)"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        assert_eq!(stmnt.execute_bind(&[&day_to_second]), Err(-26024));
+
+        let stmnt = conn
+            .prepare(
+                &format!("SELECT * FROM {INTERVAL_TABLE}"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = stmnt.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+
+        assert_eq!(row.get::<IntervalYearMonth>(1).unwrap(), Some(year));
+        assert_eq!(row.get::<IntervalYearMonth>(2).unwrap(), Some(month));
+        assert_eq!(
+            row.get::<IntervalYearMonth>(3).unwrap(),
+            Some(year_to_month)
+        );
+        assert_eq!(row.get::<IntervalDayTime>(4).unwrap(), Some(day));
+        assert_eq!(row.get::<IntervalDayTime>(5).unwrap(), Some(hour));
+        assert_eq!(row.get::<IntervalDayTime>(6).unwrap(), Some(minute));
+        assert_eq!(row.get::<IntervalDayTime>(7).unwrap(), Some(second));
+        assert_eq!(row.get::<IntervalDayTime>(10).unwrap(), Some(day_to_second));
+        assert_eq!(row.get::<IntervalDayTime>(1), Err(-26200));
+        // Fetching as a String still gives the text of the column type
+        assert_eq!(row.get::<String>(3).unwrap().unwrap(), "1-02");
+    }
+}
@@ -0,0 +1,404 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{
+    common::mimer_options::{CursorMode, TransactionMode},
+    connection::Connection,
+    row::Row,
+    transaction::Transaction,
+    types::ToSql,
+};
+
+/// A key made up of one or more `(column, value)` pairs, used by [select_by_key](Connection::select_by_key()),
+/// [update_by_key](Connection::update_by_key()) and [delete_by_key](Connection::delete_by_key()).
+/// The same shape is used for the values of [insert_values](Connection::insert_values()) and [update_by_key](Connection::update_by_key()).
+///
+/// Column names are inserted into the generated SQL as given, while the values are always bound as parameters.
+pub type Key<'a> = [(&'a str, &'a dyn ToSql)];
+
+/// Generates a `WHERE` clause matching every column of `key`, e.g. `WHERE col1 = ? AND col2 = ?`.
+///
+/// Returns -26023 if the key has no columns, so that a statement never silently matches every row of a table.
+pub(crate) fn where_clause(key: &Key) -> Result<String, i32> {
+    if key.is_empty() {
+        return Err(-26023); // Key has no columns
+    }
+    let conditions: Vec<String> = key
+        .iter()
+        .map(|(column, _)| format!("{column} = ?"))
+        .collect();
+    Ok(format!("WHERE {}", conditions.join(" AND ")))
+}
+
+/// `(column, value)` pairs taken from a [Key].
+type Pairs<'a> = Vec<(&'a str, &'a dyn ToSql)>;
+
+/// Splits `values` into the `(column, value)` pairs of the key columns, in the order of `key_columns`, and the other pairs.
+/// Column names are compared case insensitively, as unquoted identifiers are.
+///
+/// Returns -26023 if there are no key columns, and -26041 if a key column isn't among the values.
+fn split_key<'a>(key_columns: &[&str], values: &'a Key<'a>) -> Result<(Pairs<'a>, Pairs<'a>), i32> {
+    if key_columns.is_empty() {
+        return Err(-26023); // Key has no columns
+    }
+    let key = key_columns
+        .iter()
+        .map(|key_column| {
+            values
+                .iter()
+                .find(|(column, _)| column.eq_ignore_ascii_case(key_column))
+                .copied()
+                .ok_or(-26041) // Key column isn't among the values
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let others = values
+        .iter()
+        .filter(|(column, _)| !key_columns.iter().any(|k| k.eq_ignore_ascii_case(column)))
+        .copied()
+        .collect();
+    Ok((key, others))
+}
+
+/// Returns the values of `key`, in the order their markers appear in [where_clause].
+fn key_values<'a>(key: &'a Key<'a>) -> impl Iterator<Item = &'a dyn ToSql> {
+    key.iter().map(|(_, value)| *value)
+}
+
+impl Connection {
+    /// Selects the rows of `table` matching a (possibly composite) key and maps each of them using the closure `f`.
+    /// `columns` is the select list, e.g. `"*"` or `"column_1, column_2"`.
+    ///
+    /// # Errors
+    /// Returns [Err] when the key has no columns (-26023), when the query couldn't be prepared or executed, or when `f` returns an error.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table order_line").ok();
+    /// conn.execute_statement("create table order_line (order_no INT, line_no INT, item VARCHAR(30))").unwrap();
+    /// conn.execute_statement("INSERT INTO order_line VALUES(1, 1, 'apple'), (1, 2, 'pear')").unwrap();
+    ///
+    /// let items = conn
+    ///     .select_by_key("order_line", "item", &[("order_no", &1), ("line_no", &2)], |row| {
+    ///         Ok(row.get::<String>(1)?.unwrap_or_default())
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(items, vec![String::from("pear")]);
+    /// ```
+    pub fn select_by_key<T, F>(
+        &self,
+        table: &str,
+        columns: &str,
+        key: &Key,
+        f: F,
+    ) -> Result<Vec<T>, i32>
+    where
+        F: FnMut(&Row) -> Result<T, i32>,
+    {
+        let sql = format!("SELECT {columns} FROM {table} {}", where_clause(key)?);
+        let params: Vec<&dyn ToSql> = key_values(key).collect();
+        self.query_map(&sql, &params, f)
+    }
+
+    /// Inserts a row into `table`, setting each `(column, value)` pair in `values`.
+    /// Returns the return code of the execution, as [execute](crate::Statement::execute()) does.
+    ///
+    /// # Errors
+    /// Returns [Err] when `values` has no columns (-26023), or when the statement couldn't be prepared or executed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table order_line").ok();
+    /// conn.execute_statement("create table order_line (order_no INT, line_no INT, item VARCHAR(30))").unwrap();
+    ///
+    /// conn.insert_values("order_line", &[("order_no", &1), ("line_no", &1), ("item", &"apple")])
+    ///     .unwrap();
+    /// ```
+    pub fn insert_values(&self, table: &str, values: &Key) -> Result<i32, i32> {
+        if values.is_empty() {
+            return Err(-26023); // Key has no columns
+        }
+        let columns: Vec<&str> = values.iter().map(|(column, _)| *column).collect();
+        let markers = vec!["?"; values.len()];
+        let sql = format!(
+            "INSERT INTO {table} ({}) VALUES({})",
+            columns.join(", "),
+            markers.join(", ")
+        );
+        let params: Vec<&dyn ToSql> = key_values(values).collect();
+        self.prepare(&sql, CursorMode::Forward)?
+            .execute_bind(&params)
+    }
+
+    /// Updates the rows of `table` matching a (possibly composite) key, setting each `(column, value)` pair in `values`.
+    /// Returns the return code of the execution, as [execute](crate::Statement::execute()) does.
+    ///
+    /// # Errors
+    /// Returns [Err] when the key or `values` has no columns (-26023), or when the statement couldn't be prepared or executed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table order_line").ok();
+    /// conn.execute_statement("create table order_line (order_no INT, line_no INT, item VARCHAR(30))").unwrap();
+    /// conn.execute_statement("INSERT INTO order_line VALUES(1, 1, 'apple')").unwrap();
+    ///
+    /// conn.update_by_key("order_line", &[("item", &"banana")], &[("order_no", &1), ("line_no", &1)])
+    ///     .unwrap();
+    /// ```
+    pub fn update_by_key(&self, table: &str, values: &Key, key: &Key) -> Result<i32, i32> {
+        if values.is_empty() {
+            return Err(-26023); // Key has no columns
+        }
+        let assignments: Vec<String> = values
+            .iter()
+            .map(|(column, _)| format!("{column} = ?"))
+            .collect();
+        let sql = format!(
+            "UPDATE {table} SET {} {}",
+            assignments.join(", "),
+            where_clause(key)?
+        );
+        let params: Vec<&dyn ToSql> = key_values(values).chain(key_values(key)).collect();
+        self.prepare(&sql, CursorMode::Forward)?
+            .execute_bind(&params)
+    }
+
+    /// Deletes the rows of `table` matching a (possibly composite) key.
+    /// Returns the return code of the execution, as [execute](crate::Statement::execute()) does.
+    ///
+    /// # Errors
+    /// Returns [Err] when the key has no columns (-26023), or when the statement couldn't be prepared or executed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table order_line").ok();
+    /// conn.execute_statement("create table order_line (order_no INT, line_no INT, item VARCHAR(30))").unwrap();
+    /// conn.execute_statement("INSERT INTO order_line VALUES(1, 1, 'apple')").unwrap();
+    ///
+    /// conn.delete_by_key("order_line", &[("order_no", &1), ("line_no", &1)]).unwrap();
+    /// ```
+    pub fn delete_by_key(&self, table: &str, key: &Key) -> Result<i32, i32> {
+        let sql = format!("DELETE FROM {table} {}", where_clause(key)?);
+        let params: Vec<&dyn ToSql> = key_values(key).collect();
+        self.prepare(&sql, CursorMode::Forward)?
+            .execute_bind(&params)
+    }
+
+    /// Inserts a row into `table`, or updates the row with the same key if there is one.
+    /// `values` holds the `(column, value)` pairs of the row, including the columns named in `key_columns`.
+    /// Returns the number of rows inserted or updated.
+    ///
+    /// The row is first updated by its key, and inserted if no row was updated, in a [ReadWrite](TransactionMode::ReadWrite)
+    /// transaction that is committed before returning. Within a [Transaction], use [upsert](crate::Transaction::upsert()) on the transaction instead.
+    /// If another connection inserts the same key concurrently, either the insert or the commit fails,
+    /// so the call can be repeated with [run_with_retry](crate::Connection::run_with_retry()).
+    ///
+    /// # Errors
+    /// Returns [Err] when there are no key columns (-26023), when a key column isn't among `values` (-26041),
+    /// or when a statement couldn't be prepared or executed or the transaction couldn't be committed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table order_line").ok();
+    /// conn.execute_statement("create table order_line (order_no INT, line_no INT, item VARCHAR(30))").unwrap();
+    ///
+    /// let key = ["order_no", "line_no"];
+    /// conn.upsert("order_line", &key, &[("order_no", &1), ("line_no", &1), ("item", &"apple")])
+    ///     .unwrap();
+    /// conn.upsert("order_line", &key, &[("order_no", &1), ("line_no", &1), ("item", &"pear")])
+    ///     .unwrap();
+    ///
+    /// let items = conn.select_by_key("order_line", "item", &[("order_no", &1)], |row| row.get::<String>(1)).unwrap();
+    /// assert_eq!(items, vec![Some(String::from("pear"))]);
+    /// ```
+    pub fn upsert(&self, table: &str, key_columns: &[&str], values: &Key) -> Result<i32, i32> {
+        let trans = self.begin_transaction(TransactionMode::ReadWrite)?;
+        let rc = trans.upsert(table, key_columns, values)?;
+        trans.commit()?;
+        Ok(rc)
+    }
+
+    /// Updates the row of `table` with the key of `values`, or inserts it if no row was updated.
+    fn upsert_auxillary(
+        &self,
+        table: &str,
+        key_columns: &[&str],
+        values: &Key,
+    ) -> Result<i32, i32> {
+        let (key, others) = split_key(key_columns, values)?;
+        // With only key columns there is nothing to change, but the update still tells whether the row exists
+        let assignments = match others.is_empty() {
+            true => &key,
+            false => &others,
+        };
+        match self.update_by_key(table, assignments, &key)? {
+            0 => self.insert_values(table, values),
+            rc => Ok(rc),
+        }
+    }
+}
+
+impl Transaction<'_> {
+    /// Inserts a row into `table` within the transaction, or updates the row with the same key if there is one,
+    /// see [upsert](crate::Connection::upsert()). The changes are committed with the transaction.
+    ///
+    /// # Errors
+    /// In a [ReadOnly](TransactionMode::ReadOnly) transaction, the call is rejected with the error code -26022.
+    /// Otherwise returns [Err] as [upsert](crate::Connection::upsert()) does.
+    pub fn upsert(&self, table: &str, key_columns: &[&str], values: &Key) -> Result<i32, i32> {
+        if self.mode() == TransactionMode::ReadOnly {
+            return Err(-26022); // Statement modifies data in a read-only transaction
+        }
+        (**self).upsert_auxillary(table, key_columns, values)
+    }
+}
+
+#[cfg(test)]
+mod key_tests {
+    use super::*;
+    use crate::testing::*;
+
+    const KEY_TABLE: &str = "key_table";
+    const KEY_TABLE_COLUMNS: &str = "(order_no INT, line_no INT, item VARCHAR(30))";
+
+    #[test]
+    fn where_clause_generation() {
+        assert_eq!(
+            where_clause(&[("order_no", &1), ("line_no", &2)]).unwrap(),
+            "WHERE order_no = ? AND line_no = ?"
+        );
+        assert_eq!(where_clause(&[]), Err(-26023));
+    }
+
+    #[test]
+    fn composite_key_roundtrip() {
+        let conn = establish_connection();
+        drop_create_table(&conn, KEY_TABLE, KEY_TABLE_COLUMNS);
+        conn.execute_statement(&format!(
+            "INSERT INTO {KEY_TABLE} VALUES(1, 1, 'apple'), (1, 2, 'pear'), (2, 1, 'plum')"
+        ))
+        .unwrap();
+
+        conn.update_by_key(
+            KEY_TABLE,
+            &[("item", &"banana")],
+            &[("order_no", &1), ("line_no", &2)],
+        )
+        .unwrap();
+        conn.delete_by_key(KEY_TABLE, &[("order_no", &2), ("line_no", &1)])
+            .unwrap();
+
+        let items = conn
+            .select_by_key(
+                KEY_TABLE,
+                "item",
+                &[("order_no", &1), ("line_no", &2)],
+                |row| row.get::<String>(1),
+            )
+            .unwrap();
+        assert_eq!(items, vec![Some(String::from("banana"))]);
+
+        let remaining = conn
+            .query_scalar::<i32>(
+                &format!("SELECT CAST(COUNT(*) AS INTEGER) FROM {KEY_TABLE}"),
+                &[],
+            )
+            .unwrap();
+        assert_eq!(remaining, Some(2));
+
+        assert_eq!(conn.delete_by_key(KEY_TABLE, &[]), Err(-26023));
+    }
+    #[test]
+    fn upsert_by_key() {
+        let conn = establish_connection();
+        drop_create_table(&conn, KEY_TABLE, KEY_TABLE_COLUMNS);
+        let key = ["ORDER_NO", "line_no"];
+
+        for item in ["apple", "pear"] {
+            let rc = conn
+                .upsert(
+                    KEY_TABLE,
+                    &key,
+                    &[("item", &item), ("order_no", &1), ("line_no", &1)],
+                )
+                .unwrap();
+            assert_eq!(rc, 1);
+        }
+        let trans = conn.begin_transaction(TransactionMode::ReadWrite).unwrap();
+        trans
+            .upsert(KEY_TABLE, &key, &[("order_no", &1), ("line_no", &2)])
+            .unwrap();
+        trans
+            .upsert(KEY_TABLE, &key, &[("order_no", &1), ("line_no", &2)])
+            .unwrap();
+        trans.commit().unwrap();
+
+        let rows = conn
+            .select_by_key(KEY_TABLE, "line_no, item", &[("order_no", &1)], |row| {
+                Ok((row.get::<i32>(1)?, row.get::<String>(2)?))
+            })
+            .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert!(rows.contains(&(Some(1), Some(String::from("pear")))));
+        assert!(rows.contains(&(Some(2), None)));
+
+        assert_eq!(
+            conn.upsert(KEY_TABLE, &[], &[("order_no", &1)]),
+            Err(-26023)
+        );
+        assert_eq!(
+            conn.upsert(KEY_TABLE, &key, &[("order_no", &1), ("item", &"plum")]),
+            Err(-26041)
+        );
+        let trans = conn.begin_transaction(TransactionMode::ReadOnly).unwrap();
+        assert_eq!(
+            trans.upsert(KEY_TABLE, &key, &[("order_no", &1), ("line_no", &3)]),
+            Err(-26022)
+        );
+    }
+}
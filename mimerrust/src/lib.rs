@@ -35,7 +35,7 @@
 //! 
 //! fn main() {
 //!     print!("Connecting to database\n");
-//!     let mut conn =
+//!     let conn =
 //!         Connection::open("", "RUSTUSER", "RUSTPASSWORD").unwrap_or_else(|ec| panic!("{}", ec));
 //! 
 //!     conn.execute_statement("DROP TABLE test_table").ok();
@@ -85,17 +85,133 @@
 //! The bindings are not re-built automatically, instead a pre-generated binding is used. This is to avoid requirements on having Clang on for example Windows.
 //! To generate new bindings, go into the `mimerrust-bindings` and run `cargo build`.
 //!
+//! # Network configuration
+//! [Connection::open] only takes the name of the database. How a remote database is reached (node, protocol and service/port) is
+//! defined in the `SQLHOSTS` configuration of the Mimer SQL installation (`/etc/sqlhosts` on Linux, where `MIMER_SQLHOSTS` may point
+//! to an alternative file, or the Mimer Administrator on Windows). The Mimer SQL C API does not expose any transport options such as
+//! compression or packet size, so there is nothing to pass through from Rust; tuning has to be done on the server and in `SQLHOSTS`.
+//! The same applies to TLS: [TlsOptions] can be validated from Rust, but [Connection::open_with] refuses to open a connection with
+//! them rather than connecting without the requested transport security.
+//!
+//! # Optional features
+//! - `rayon`: Enables `Cursor::par_process`, which processes result sets in parallel on the [rayon](https://docs.rs/rayon) thread pool.
+//! - `csv`: Enables `Cursor::write_csv` and `Connection::copy_csv_into`, with options in the `csv` module.
+//! - `time`: Implements [ToSql] and [FromSql] for the date and time types of the [time](https://docs.rs/time) crate, as an alternative to [chrono](https://docs.rs/chrono).
+//! - `json`: Implements [ToSql] and [FromSql] for `serde_json::Value`, stored as JSON text in character columns.
+//! - `arrow`: Enables `Cursor::fetch_arrow`, which fetches result sets as [Arrow](https://docs.rs/arrow) record batches.
+//! - `polars`: Enables `Cursor::fetch_dataframe`, which fetches result sets as [Polars](https://docs.rs/polars) data frames.
+//! - `parquet`: Enables `Cursor::write_parquet`, which writes result sets to [Parquet](https://parquet.apache.org) files
+//!   through the Arrow conversion of the `arrow` feature, which it enables.
+//! - `r2d2`: Provides `r2d2::MimerConnectionManager` for pooling connections with [r2d2](https://docs.rs/r2d2).
+//! - `bb8` and `deadpool`: Provide connection managers for the async pools [bb8](https://docs.rs/bb8) and [deadpool](https://docs.rs/deadpool).
+//!   Connections are opened and validated on the tokio blocking thread pool, and `deadpool::interact` runs other work with a pooled connection there.
+//! - `tracing`: Emits [tracing](https://docs.rs/tracing) spans for connecting, preparing, executing, fetching and transaction boundaries,
+//!   with the SQL text, which can be redacted with `Connection::set_trace_redaction`, the parameter count and error codes.
+//! - `metrics`: Publishes counters and histograms of executed statements, fetched rows, execution latency and pool checkouts
+//!   through the [metrics](https://docs.rs/metrics) facade, for exporting to e.g. Prometheus or OpenTelemetry. The metric names are listed in the `metrics` module.
+//! - `diesel`: Provides the `diesel` module with a [Diesel](https://diesel.rs) backend and connection for Mimer SQL,
+//!   so that Diesel's schema DSL and query builder can be used with Mimer databases.
+//! - `sea-query`: Provides `sea_query::MimerQueryBuilder`, which writes [sea-query](https://docs.rs/sea-query) statements in the Mimer SQL dialect,
+//!   and implements [ToSql] for the values of built statements.
+//! - `sea-orm`: Provides `sea_orm::MimerProxy`, which runs the statements of a [sea-orm](https://www.sea-ql.org/SeaORM) database connection
+//!   on a Mimer SQL connection. It enables the `sea-query` feature.
+//! - `test-support`: Publishes the `test_support` module with helpers for integration tests, configured through `MIMER_DATABASE`,
+//!   `MIMER_TEST_IDENT`, `MIMER_TEST_PASSWORD` and `MIMER_TEST_DATABANK`: databank provisioning, tables dropped on scope exit, uniquely named schemas per test,
+//!   and `TestDb`, a databank of its own, optionally for a scratch ident, torn down after the test, which loads SQL, YAML and JSON fixtures.
+//! - `zeroize`: Overwrites the copies of a password made while opening a connection with zeros, using [zeroize](https://docs.rs/zeroize),
+//!   once the session has begun, and the password held by a `ConnectionOptions` or `ConnectionBuilder`, including those of
+//!   reconnecting connections and pool managers, when it is dropped. The caller's own copy isn't affected.
+//!
 
+pub(crate) mod admin;
+#[cfg(feature = "arrow")]
+pub(crate) mod arrow_support;
+/// Async connection pooling with [bb8](https://docs.rs/bb8). Requires the `bb8` feature.
+#[cfg(feature = "bb8")]
+pub mod bb8;
+pub(crate) mod catalog;
+pub(crate) mod columnar;
 pub(crate) mod common;
+/// Reads the local Mimer SQL configuration, such as the databases registered in `SQLHOSTS`.
+pub mod config;
 pub(crate) mod connection;
+pub(crate) mod connection_options;
+pub(crate) mod consistent_reader;
+pub(crate) mod copy;
 pub(crate) mod cursor;
+pub(crate) mod error_sink;
+/// CSV export and import. Requires the `csv` feature.
+#[cfg(feature = "csv")]
+pub mod csv;
+/// Async connection pooling with [deadpool](https://docs.rs/deadpool). Requires the `deadpool` feature.
+#[cfg(feature = "deadpool")]
+pub mod deadpool;
+/// A [Diesel](https://diesel.rs) backend and connection for Mimer SQL. Requires the `diesel` feature.
+#[cfg(feature = "diesel")]
+pub mod diesel;
+pub(crate) mod ffi_buffer;
+#[cfg(feature = "test-support")]
+pub(crate) mod fixture;
+/// Aligned ASCII and Markdown tables of result sets, for debugging and scripts.
+pub mod format;
+pub(crate) mod geometry;
 pub(crate) mod inner_connection;
 pub(crate) mod inner_statement;
+pub(crate) mod interval;
+pub(crate) mod key;
+pub(crate) mod lob;
+pub(crate) mod metadata;
+/// Names of the metrics published through the [metrics](https://docs.rs/metrics) facade. Requires the `metrics` feature.
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub(crate) mod mimer_error;
+/// A mock connection answering statements from a script, for unit testing code written against [Queryable].
+pub mod mock;
+/// Samples the server statistics on an interval on a background thread, for simple health dashboards and alerts.
+pub mod monitor;
+pub(crate) mod observer;
+/// Parquet export. Requires the `parquet` feature.
+#[cfg(feature = "parquet")]
+pub mod parquet;
+#[cfg(feature = "polars")]
+pub(crate) mod polars_support;
+#[cfg(any(feature = "bb8", feature = "deadpool"))]
+pub(crate) mod pool_support;
+pub(crate) mod query_builder;
+pub(crate) mod queryable;
+/// Connection pooling with [r2d2](https://docs.rs/r2d2). Requires the `r2d2` feature.
+#[cfg(feature = "r2d2")]
+pub mod r2d2;
+pub(crate) mod reconnecting;
+pub(crate) mod repository;
+pub(crate) mod retry;
 pub(crate) mod row;
+pub(crate) mod script;
+/// A [sea-orm](https://www.sea-ql.org/SeaORM) proxy database backed by a Mimer SQL connection. Requires the `sea-orm` feature.
+#[cfg(feature = "sea-orm")]
+pub mod sea_orm;
+/// A [sea-query](https://docs.rs/sea-query) query builder for Mimer SQL. Requires the `sea-query` feature.
+#[cfg(feature = "sea-query")]
+pub mod sea_query;
+pub(crate) mod sequence;
+pub(crate) mod server_info;
+pub(crate) mod session_guard;
+/// Quoting of identifiers and literals for SQL built at run time, such as DDL, which can't take parameters.
+/// Values in queries and data changing statements should be bound as parameters instead.
+pub mod sql;
 pub(crate) mod statement;
+pub(crate) mod statistics;
 pub(crate) mod testing;
+/// Helpers for integration tests against a Mimer SQL database. Requires the `test-support` feature.
+#[cfg(feature = "test-support")]
+pub mod test_support;
+#[cfg(not(feature = "test-support"))]
+#[allow(dead_code)]
+pub(crate) mod test_support;
+pub(crate) mod tracing_support;
 pub(crate) mod transaction;
+pub(crate) mod view;
+pub(crate) mod watchdog;
 
 /// Handles datatypes and their conversions between Rust and Mimer SQL.
 ///
@@ -186,7 +302,7 @@ pub(crate) mod transaction;
 /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
 /// # let ident = "RUSTUSER";
 /// # let pass = "RUSTPASSWORD";
-/// let mut conn = Connection::open(db, ident, pass).unwrap();
+/// let conn = Connection::open(db, ident, pass).unwrap();
 /// _ = conn.execute_statement("DROP TABLE my_table");
 /// conn.execute_statement("CREATE TABLE my_table (my_custom_column BINARY(8))").unwrap();
 ///
@@ -207,12 +323,32 @@ pub(crate) mod transaction;
 /// ```
 pub mod types;
 
+pub use admin::{IdentType, Privilege};
+pub use catalog::{DatabankInfo, IndexInfo, TableColumn, TableInfo};
 pub use common::mimer_options::*;
 pub use common::return_codes::*;
 pub use connection::Connection;
-pub use cursor::Cursor;
+pub use connection_options::{ConnectionBuilder, ConnectionOptions, TlsOptions};
+pub use consistent_reader::ConsistentReader;
+pub use copy::{copy_table, CopyOptions};
+pub use cursor::{Cursor, OwnedRows};
+pub use error_sink::{set_error_handler, DriverFailure};
+pub use geometry::{Latitude, Location, Longitude};
+pub use interval::{IntervalDayTime, IntervalYearMonth};
+pub use key::Key;
+pub use lob::{ClobReader, LobAppender};
+pub use metadata::{ColumnInfo, ParameterInfo, ResultSetMetadata};
 pub use mimer_error::MimerError;
-pub use row::Row;
-pub use statement::Statement;
-pub use transaction::Transaction;
+pub use observer::{StatementEvent, StatementEventKind};
+pub use query_builder::{Delete, Insert, Select, Update};
+pub use queryable::{PreparedStatement, Queryable};
+pub use reconnecting::{ReconnectHook, ReconnectingConnection};
+pub use retry::{RetryEvent, StatementRetry, TRANSACTION_CONFLICT};
+pub use row::{FromColumn, FromRow, MappingError, OwnedRow, Row};
+pub use script::ScriptError;
+pub use sequence::Sequence;
+pub use server_info::{Capabilities, Capability, ServerVersion};
+pub use statement::{BatchError, BatchResult, FetchOptions, RowsAffected, Statement, TypeMismatch};
+pub use statistics::{ServerStatistics, StatisticsCounter};
+pub use transaction::{StatementRecord, Transaction, TransactionStatement};
 pub use types::*;
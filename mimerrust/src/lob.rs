@@ -0,0 +1,554 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{
+    common::traits::GetHandle,
+    match_mimer_BLOB, match_mimer_CLOB,
+    mimer_error::MimerError,
+    row::{is_null, Row},
+    statement::Statement,
+    types::LOB_CHUNK_MAXSIZE_SET,
+};
+use mimerrust_sys as ffi;
+
+#[doc(hidden)]
+use std::{io, marker::PhantomData, os::raw::c_char};
+
+/// Gets a handle to the large object in column `idx` of the current row, together with its length,
+/// which is counted in characters for character large objects. Returns [`Ok<None>`] if the value is null.
+pub(crate) fn open_lob(
+    handle: ffi::MimerStatement,
+    idx: i16,
+) -> Result<Option<(ffi::MimerLob, usize)>, i32> {
+    if is_null(handle, idx)? {
+        return Ok(None);
+    }
+    let mut length: usize = 0;
+    let mut lob: ffi::MimerLob = std::ptr::null_mut();
+    let rc = unsafe { ffi::MimerGetLob(handle, idx, &mut length, &mut lob) };
+    if rc < 0 {
+        return Err(rc);
+    }
+    Ok(Some((lob, length)))
+}
+
+/// Fills `buf` with the next bytes of the binary large object `lob`, fetching at most [LOB_CHUNK_MAXSIZE_SET] bytes per call.
+pub(crate) fn read_blob_data(lob: &mut ffi::MimerLob, buf: &mut [u8]) -> Result<(), i32> {
+    for chunk in buf.chunks_mut(LOB_CHUNK_MAXSIZE_SET) {
+        let rc = unsafe {
+            ffi::MimerGetBlobData(
+                lob,
+                chunk.as_mut_ptr() as *mut std::ffi::c_void,
+                chunk.len(),
+            )
+        };
+        if rc < 0 {
+            return Err(rc);
+        }
+    }
+    Ok(())
+}
+
+/// An incremental [Read](std::io::Read)er of the UTF-8 text of a character large object, as returned by [get_clob_reader](crate::Row::get_clob_reader()).
+///
+/// The text is fetched from the server in chunks as it is read, so a large object doesn't have to fit in memory.
+/// Chunks are counted in characters, as the length of a character large object is, and are passed on as bytes,
+/// so a multi-byte character is never lost or broken at a chunk boundary.
+///
+/// The reader borrows the [Row] it was created from, as the large object can only be read while the cursor is on that row.
+pub struct ClobReader<'a> {
+    lob: ffi::MimerLob,
+    remaining: usize, // characters
+    chunk: Vec<u8>,
+    pos: usize,
+    _row: PhantomData<&'a Row>,
+}
+
+impl<'a> ClobReader<'a> {
+    pub(crate) fn new(lob: ffi::MimerLob, length: usize) -> ClobReader<'a> {
+        ClobReader {
+            lob,
+            remaining: length,
+            chunk: Vec::new(),
+            pos: 0,
+            _row: PhantomData,
+        }
+    }
+
+    /// Reads the rest of the large object into a [String].
+    pub(crate) fn read_all(&mut self) -> Result<String, i32> {
+        let mut bytes = self.chunk.split_off(self.pos);
+        loop {
+            self.fetch_chunk()?;
+            if self.chunk.is_empty() {
+                break;
+            }
+            bytes.append(&mut self.chunk);
+        }
+        String::from_utf8(bytes).map_err(|_| -26001) // Not valid UTF-8
+    }
+
+    /// Fetches the next chunk of the large object, replacing the current one.
+    fn fetch_chunk(&mut self) -> Result<(), i32> {
+        self.chunk.clear();
+        self.pos = 0;
+        if self.remaining == 0 {
+            return Ok(());
+        }
+        // A character takes at most 4 bytes in UTF-8, and room is needed for the null terminator
+        let size = self
+            .remaining
+            .saturating_mul(4)
+            .saturating_add(1)
+            .min(LOB_CHUNK_MAXSIZE_SET);
+        self.chunk.resize(size, 0);
+        let rc = unsafe {
+            ffi::MimerGetNclobData8(&mut self.lob, self.chunk.as_mut_ptr() as *mut c_char, size)
+        };
+        if rc < 0 {
+            self.chunk.clear();
+            return Err(rc);
+        }
+        let end = self.chunk.iter().position(|b| *b == 0).unwrap_or(size);
+        self.chunk.truncate(end);
+        if self.chunk.is_empty() {
+            return Err(-26030); // Character large object is shorter than its length
+        }
+        // Count the characters by their first byte, so that a character split between chunks is counted once
+        let characters = self.chunk.iter().filter(|b| *b & 0xC0 != 0x80).count();
+        self.remaining = self.remaining.saturating_sub(characters);
+        Ok(())
+    }
+}
+
+impl io::Read for ClobReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.chunk.len() {
+            self.fetch_chunk()
+                .map_err(|ec| io::Error::other(MimerError::mimer_error_from_code(ec)))?;
+        }
+        let n = buf.len().min(self.chunk.len() - self.pos);
+        buf[..n].copy_from_slice(&self.chunk[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+impl Row {
+    /// Returns a [Read](std::io::Read)er of the text in a character large object column (*CLOB* or *NCLOB*), or [`Ok<None>`] if the value is null.
+    /// The text is fetched incrementally as it is read, see [ClobReader].
+    ///
+    /// # Errors
+    /// Returns [Err] when the column isn't a character large object (-26200), or when the large object couldn't be accessed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// use std::io::Read;
+    ///
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table doc_table").ok();
+    /// conn.execute_statement("create table doc_table (notes NCLOB(1M))").unwrap();
+    /// conn.execute_statement("INSERT INTO doc_table VALUES('åäö €')").unwrap();
+    /// let stmnt = conn.prepare("SELECT notes FROM doc_table", CursorMode::Forward).unwrap();
+    /// let mut cursor = stmnt.open_cursor().unwrap();
+    /// let row = cursor.next_row().unwrap().unwrap();
+    ///
+    /// let mut notes = String::new();
+    /// row.get_clob_reader(1).unwrap().unwrap().read_to_string(&mut notes).unwrap();
+    /// assert_eq!(notes, "åäö €");
+    /// ```
+    pub fn get_clob_reader(&self, idx: i16) -> Result<Option<ClobReader<'_>>, i32> {
+        let strong_inner_statement = self.inner_statement.upgrade().ok_or(-26004)?;
+        let handle = strong_inner_statement.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
+        strong_inner_statement.check_connection()?;
+        let column_type = unsafe { ffi::MimerColumnType(*handle, idx) };
+        if column_type < 0 {
+            return Err(column_type);
+        }
+        match column_type as u32 {
+            match_mimer_CLOB!() => (),
+            _ => return Err(-26200), // Unsupported type conversion
+        }
+        Ok(open_lob(*handle, idx)?.map(|(lob, length)| ClobReader::new(lob, length)))
+    }
+}
+
+impl Row {
+    /// Returns at most `len` bytes of a binary large object column (*BLOB*), starting `offset` bytes into the value,
+    /// or [`Ok<None>`] if the value is null. The range is cut short at the end of the value, and is empty if `offset` is past it.
+    ///
+    /// Only the requested range is kept in memory, which allows random access into, or resumable downloads of, values that are too large to fetch whole.
+    /// Large objects are read sequentially from the server, so the bytes before `offset` are still transferred, in chunks that are discarded.
+    ///
+    /// # Errors
+    /// Returns [Err] when the column isn't a binary large object (-26200), or when the large object couldn't be read.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table file_table").ok();
+    /// conn.execute_statement("create table file_table (content BLOB(1M))").unwrap();
+    /// let insert = conn.prepare("INSERT INTO file_table VALUES(:content)", CursorMode::Forward).unwrap();
+    /// insert.execute_bind(&[&(0..=255u8).collect::<Vec<u8>>()]).unwrap();
+    ///
+    /// let stmnt = conn.prepare("SELECT content FROM file_table", CursorMode::Forward).unwrap();
+    /// let mut cursor = stmnt.open_cursor().unwrap();
+    /// let row = cursor.next_row().unwrap().unwrap();
+    /// assert_eq!(row.get_blob_range(1, 10, 3).unwrap(), Some(vec![10, 11, 12]));
+    /// assert_eq!(row.get_blob_range(1, 254, 10).unwrap(), Some(vec![254, 255]));
+    /// ```
+    pub fn get_blob_range(
+        &self,
+        idx: i16,
+        offset: usize,
+        len: usize,
+    ) -> Result<Option<Vec<u8>>, i32> {
+        let strong_inner_statement = self.inner_statement.upgrade().ok_or(-26004)?;
+        let handle = strong_inner_statement.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
+        strong_inner_statement.check_connection()?;
+        let column_type = unsafe { ffi::MimerColumnType(*handle, idx) };
+        if column_type < 0 {
+            return Err(column_type);
+        }
+        match column_type as u32 {
+            match_mimer_BLOB!() => (),
+            _ => return Err(-26200), // Unsupported type conversion
+        }
+        let (mut lob, length) = match open_lob(*handle, idx)? {
+            Some(lob) => lob,
+            None => return Ok(None),
+        };
+        let start = offset.min(length);
+        let mut skipped = vec![0; start.min(LOB_CHUNK_MAXSIZE_SET)];
+        let mut to_skip = start;
+        while to_skip > 0 {
+            let n = to_skip.min(skipped.len());
+            read_blob_data(&mut lob, &mut skipped[..n])?;
+            to_skip -= n;
+        }
+        let mut bytes = vec![0; len.min(length - start)];
+        read_blob_data(&mut lob, &mut bytes)?;
+        Ok(Some(bytes))
+    }
+}
+
+/// An incremental [Write](std::io::Write)r of a large object parameter, as returned by [bind_lob_appender](crate::Statement::bind_lob_appender()).
+///
+/// The data is sent to the server in chunks as it is written, so it doesn't have to be held in memory at once.
+/// Character large objects are written as UTF-8, which may be split anywhere between writes.
+/// All data must be written before the statement is executed.
+pub struct LobAppender<'a> {
+    lob: ffi::MimerLob,
+    character: bool,
+    remaining: usize, // bytes, or characters for character large objects
+    pending: Vec<u8>, // the start of a multi-byte character that hasn't been sent
+    _statement: PhantomData<&'a Statement>,
+}
+
+impl LobAppender<'_> {
+    /// Gets the amount of data that remains to be written before the statement can be executed,
+    /// counted in characters for character large objects and in bytes otherwise.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    fn append_binary(&mut self, bytes: &[u8]) -> Result<(), i32> {
+        if bytes.len() > self.remaining {
+            return Err(-26034); // Data exceeds the declared length of the large object
+        }
+        for chunk in bytes.chunks(LOB_CHUNK_MAXSIZE_SET) {
+            let rc = unsafe {
+                ffi::MimerSetBlobData(
+                    &mut self.lob,
+                    chunk.as_ptr() as *const std::ffi::c_void,
+                    chunk.len(),
+                )
+            };
+            if rc < 0 {
+                return Err(rc);
+            }
+        }
+        self.remaining -= bytes.len();
+        Ok(())
+    }
+
+    fn append_text(&mut self, bytes: &[u8]) -> Result<(), i32> {
+        self.pending.extend_from_slice(bytes);
+        let complete = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            // An incomplete character at the end is kept until the rest of it is written
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(_) => {
+                self.pending.truncate(self.pending.len() - bytes.len());
+                return Err(-26001); // Not valid UTF-8
+            }
+        };
+        let rest = self.pending.split_off(complete);
+        let text = String::from_utf8(std::mem::replace(&mut self.pending, rest)).unwrap(); // Ok unwrap since the bytes were validated above
+        let characters = text.chars().count();
+        if characters > self.remaining {
+            return Err(-26034); // Data exceeds the declared length of the large object
+        }
+        let mut text = text.as_str();
+        while !text.is_empty() {
+            // Don't split a character between chunks
+            let mut end = text.len().min(LOB_CHUNK_MAXSIZE_SET);
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            let rc = unsafe {
+                ffi::MimerSetNclobData8(&mut self.lob, text.as_ptr() as *const c_char, end)
+            };
+            if rc < 0 {
+                return Err(rc);
+            }
+            text = &text[end..];
+        }
+        self.remaining -= characters;
+        Ok(())
+    }
+}
+
+impl io::Write for LobAppender<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.character {
+            true => self.append_text(buf),
+            false => self.append_binary(buf),
+        }
+        .map_err(|ec| io::Error::other(MimerError::mimer_error_from_code(ec)))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Statement {
+    /// Binds a large object parameter (*BLOB*, *CLOB* or *NCLOB*) whose value is written incrementally through the returned [LobAppender],
+    /// instead of being passed as a single value to [bind](crate::Statement::bind()).
+    ///
+    /// The C API needs the `length` of the value when the parameter is bound, in characters for character large objects and in bytes otherwise,
+    /// and exactly that much must be written before the statement is executed.
+    /// To append to an existing value, e.g. for a log that grows over time, concatenate the parameter to the column in the statement,
+    /// as in `UPDATE log_table SET entries = entries || :more WHERE id = :id`, since the server stores large objects as whole values.
+    ///
+    /// # Errors
+    /// Returns [Err] when the parameter isn't a large object (-26203), or when it couldn't be bound.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// use std::io::Write;
+    ///
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table log_table").ok();
+    /// conn.execute_statement("create table log_table (id INT, entries NCLOB(1M))").unwrap();
+    /// conn.execute_statement("INSERT INTO log_table VALUES(1, 'started;')").unwrap();
+    ///
+    /// let append = conn
+    ///     .prepare("UPDATE log_table SET entries = entries || :more WHERE id = :id", CursorMode::Forward)
+    ///     .unwrap();
+    /// let entry = "stopped;";
+    /// let mut appender = append.bind_lob_appender(1, entry.chars().count()).unwrap();
+    /// appender.write_all(entry.as_bytes()).unwrap();
+    /// append.bind(&1, 2).unwrap();
+    /// append.execute().unwrap();
+    ///
+    /// let entries = conn.query_scalar::<String>("SELECT entries FROM log_table", &[]).unwrap();
+    /// assert_eq!(entries.as_deref(), Some("started;stopped;"));
+    /// ```
+    pub fn bind_lob_appender(&self, idx: i16, length: usize) -> Result<LobAppender<'_>, i32> {
+        let handle = self.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
+        if (*handle).is_null() {
+            return Err(-26005); // Handle is NULL
+        }
+        let parameter_type = unsafe { ffi::MimerParameterType(*handle, idx) };
+        if parameter_type < 0 {
+            return Err(parameter_type);
+        }
+        let character = match parameter_type as u32 {
+            match_mimer_CLOB!() => true,
+            match_mimer_BLOB!() => false,
+            _ => return Err(-26203), // Invalid parameter type for MimerDatatype-variant
+        };
+        let mut lob: ffi::MimerLob = std::ptr::null_mut();
+        let rc = unsafe { ffi::MimerSetLob(*handle, idx, length, &mut lob) };
+        if rc < 0 {
+            return Err(rc);
+        }
+        self.mark_bound(idx);
+        Ok(LobAppender {
+            lob,
+            character,
+            remaining: length,
+            pending: Vec::new(),
+            _statement: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod lob_tests {
+    use crate::common::mimer_options::CursorMode;
+    use crate::testing::*;
+    use std::io::{Read, Write};
+
+    #[test]
+    fn clob_reader_multi_byte() {
+        let conn = establish_connection();
+        drop_create_table(&conn, "lob_table", "(id INT, notes NCLOB(10M))");
+        // Long enough to span several chunks, with characters of 1 to 4 bytes
+        let notes = "aå€😀".repeat(400_000);
+        let insert = conn
+            .prepare(
+                "INSERT INTO lob_table VALUES(:id, :notes)",
+                CursorMode::Forward,
+            )
+            .unwrap();
+        insert.execute_bind(&[&1, &notes]).unwrap();
+        insert.execute_bind(&[&2, &None::<String>]).unwrap();
+
+        let select = conn
+            .prepare(
+                "SELECT notes FROM lob_table ORDER BY id",
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = select.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+        let mut read = String::new();
+        row.get_clob_reader(1)
+            .unwrap()
+            .unwrap()
+            .read_to_string(&mut read)
+            .unwrap();
+        assert_eq!(read.len(), notes.len());
+        assert!(read == notes);
+        assert!(row.get::<String>(1).unwrap().unwrap() == notes);
+
+        let row = cursor.next_row().unwrap().unwrap();
+        assert!(row.get_clob_reader(1).unwrap().is_none());
+        assert_eq!(row.get::<String>(1).unwrap(), None);
+    }
+
+    #[test]
+    fn blob_range() {
+        let conn = establish_connection();
+        drop_create_table(&conn, "blob_table", "(id INT, content BLOB(10M))");
+        // Long enough for the range to start and end in different chunks
+        let content: Vec<u8> = (0..3_000_000u32).map(|i| (i % 251) as u8).collect();
+        let insert = conn
+            .prepare(
+                "INSERT INTO blob_table VALUES(:id, :content)",
+                CursorMode::Forward,
+            )
+            .unwrap();
+        insert.execute_bind(&[&1, &content]).unwrap();
+        insert.execute_bind(&[&2, &None::<Vec<u8>>]).unwrap();
+
+        let select = conn
+            .prepare(
+                "SELECT content, id FROM blob_table ORDER BY id",
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = select.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+        let range = row
+            .get_blob_range(1, 1_000_000, 1_500_000)
+            .unwrap()
+            .unwrap();
+        assert!(range == content[1_000_000..2_500_000]);
+        let tail = row.get_blob_range(1, 2_999_990, 100).unwrap().unwrap();
+        assert_eq!(tail, content[2_999_990..]);
+        assert_eq!(
+            row.get_blob_range(1, 5_000_000, 10).unwrap(),
+            Some(Vec::new())
+        );
+        assert_eq!(row.get_blob_range(2, 0, 10), Err(-26200));
+
+        let row = cursor.next_row().unwrap().unwrap();
+        assert_eq!(row.get_blob_range(1, 0, 10).unwrap(), None);
+    }
+
+    #[test]
+    fn lob_appender() {
+        let conn = establish_connection();
+        drop_create_table(
+            &conn,
+            "lob_table",
+            "(id INT, notes NCLOB(10M), content BLOB(10M))",
+        );
+        conn.execute_statement("INSERT INTO lob_table VALUES(1, 'start;', X'0102')")
+            .unwrap();
+
+        let append = conn
+            .prepare(
+                "UPDATE lob_table SET notes = notes || :notes, content = content || :content WHERE id = 1",
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let notes = "åäö€;".repeat(300_000);
+        let mut appender = append.bind_lob_appender(1, notes.chars().count()).unwrap();
+        // Split the text in the middle of a character
+        let (first, second) = notes.as_bytes().split_at(1_000_001);
+        appender.write_all(first).unwrap();
+        appender.write_all(second).unwrap();
+        assert_eq!(appender.remaining(), 0);
+        assert!(appender.write_all("x".as_bytes()).is_err());
+
+        let content = vec![3u8; 2_000_000];
+        let mut appender = append.bind_lob_appender(2, content.len()).unwrap();
+        appender.write_all(&content[..10]).unwrap();
+        assert_eq!(appender.remaining(), content.len() - 10);
+        appender.write_all(&content[10..]).unwrap();
+        append.execute().unwrap();
+
+        let select = conn
+            .prepare(
+                "SELECT notes, content, id FROM lob_table",
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = select.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+        assert!(row.get::<String>(1).unwrap().unwrap() == format!("start;{notes}"));
+        let fetched = row.get::<Vec<u8>>(2).unwrap().unwrap();
+        assert_eq!(fetched.len(), content.len() + 2);
+        assert_eq!(&fetched[..3], &[1, 2, 3]);
+        assert!(append.bind_lob_appender(3, 1).is_err());
+    }
+}
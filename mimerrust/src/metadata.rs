@@ -0,0 +1,82 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::common::mimer_options::{ColumnType, ParameterMode};
+
+/// Describes a column in the result set of a statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnInfo {
+    /// Name of the column.
+    pub name: String,
+    /// Data type of the column.
+    pub column_type: ColumnType,
+    /// Whether the column may hold null values, if known. See [column_nullable](crate::Statement::column_nullable()).
+    pub nullable: Option<bool>,
+    /// Number of bytes used by a value of the column, if the data type has a fixed size. See [fixed_size](ColumnType::fixed_size()).
+    pub size: Option<usize>,
+}
+
+/// Describes a parameter of a statement, as returned by [parameters](crate::Statement::parameters()).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterInfo {
+    /// Index of the parameter, starting at 1.
+    pub index: i16,
+    /// Name of the parameter, as reported by the server.
+    pub name: String,
+    /// Whether the parameter is an input or output parameter, or both.
+    pub mode: ParameterMode,
+    /// Data type of the parameter.
+    pub param_type: ColumnType,
+}
+
+/// Describes the result set of a statement, as returned by [metadata](crate::Statement::metadata()).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultSetMetadata {
+    pub(crate) columns: Vec<ColumnInfo>,
+}
+
+impl ResultSetMetadata {
+    /// Returns the columns of the result set, in order.
+    pub fn columns(&self) -> &[ColumnInfo] {
+        &self.columns
+    }
+
+    /// Returns a column given its index, starting at 1.
+    pub fn column(&self, idx: i16) -> Option<&ColumnInfo> {
+        match idx {
+            1.. => self.columns.get(idx as usize - 1),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of columns in the result set.
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Returns whether the result set has no columns, e.g. for statements that don't return a result set.
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+}
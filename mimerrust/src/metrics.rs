@@ -0,0 +1,203 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::observer::{StatementEvent, StatementEventKind};
+
+/// Counter of executed statements and opened cursors, labeled with `kind` (`execute` or `open_cursor`) and `outcome` (`ok` or `error`).
+pub const STATEMENTS_EXECUTED: &str = "mimer_statements_executed";
+/// Histogram of the time in seconds spent executing statements and opening cursors, labeled with `kind`.
+pub const STATEMENT_DURATION: &str = "mimer_statement_duration_seconds";
+/// Counter of rows fetched with cursors.
+pub const ROWS_FETCHED: &str = "mimer_rows_fetched";
+/// Histogram of the total time in seconds spent fetching rows with a cursor, recorded when the cursor is closed.
+pub const FETCH_DURATION: &str = "mimer_fetch_duration_seconds";
+/// Counter of connections checked out from a pool, labeled with `pool` (`r2d2`, `bb8` or `deadpool`) and `outcome`.
+/// A checkout is counted when the pool validates or recycles the connection, so r2d2 and bb8 pools must test connections on checkout, which they do by default.
+pub const POOL_CHECKOUTS: &str = "mimer_pool_checkouts";
+/// Counter of connections opened by a pool, labeled with `pool` and `outcome`.
+pub const POOL_CONNECTIONS_OPENED: &str = "mimer_pool_connections_opened";
+
+/// Registers descriptions and units of the metrics published by the driver with the installed recorder.
+/// Call it after installing the recorder, e.g. a Prometheus exporter, to get help texts for the metrics.
+///
+/// # Examples
+/// ```
+/// mimerrust::metrics::describe_metrics();
+/// ```
+pub fn describe_metrics() {
+    ::metrics::describe_counter!(
+        STATEMENTS_EXECUTED,
+        "Statements executed and cursors opened"
+    );
+    ::metrics::describe_histogram!(
+        STATEMENT_DURATION,
+        ::metrics::Unit::Seconds,
+        "Time spent executing statements and opening cursors"
+    );
+    ::metrics::describe_counter!(ROWS_FETCHED, "Rows fetched with cursors");
+    ::metrics::describe_histogram!(
+        FETCH_DURATION,
+        ::metrics::Unit::Seconds,
+        "Time spent fetching rows with a cursor"
+    );
+    ::metrics::describe_counter!(POOL_CHECKOUTS, "Connections checked out from a pool");
+    ::metrics::describe_counter!(POOL_CONNECTIONS_OPENED, "Connections opened by a pool");
+}
+
+/// Publishes a [StatementEvent] to the installed metrics recorder.
+pub(crate) fn record_event(event: &StatementEvent) {
+    let kind = match event.kind {
+        StatementEventKind::Execute => "execute",
+        StatementEventKind::OpenCursor => "open_cursor",
+        StatementEventKind::Fetch => {
+            ::metrics::counter!(ROWS_FETCHED).increment(event.rows.unwrap_or(0));
+            ::metrics::histogram!(FETCH_DURATION).record(event.duration);
+            return;
+        }
+    };
+    ::metrics::counter!(STATEMENTS_EXECUTED, "kind" => kind, "outcome" => outcome(event.result.is_ok()))
+        .increment(1);
+    ::metrics::histogram!(STATEMENT_DURATION, "kind" => kind).record(event.duration);
+}
+
+/// Counts a connection checked out from `pool`.
+#[cfg(any(feature = "r2d2", feature = "bb8", feature = "deadpool"))]
+pub(crate) fn pool_checkout<T, E>(pool: &'static str, result: &Result<T, E>) {
+    ::metrics::counter!(POOL_CHECKOUTS, "pool" => pool, "outcome" => outcome(result.is_ok()))
+        .increment(1);
+}
+
+/// Counts a connection opened by `pool`.
+#[cfg(any(feature = "r2d2", feature = "bb8", feature = "deadpool"))]
+pub(crate) fn pool_connection_opened<T, E>(pool: &'static str, result: &Result<T, E>) {
+    ::metrics::counter!(POOL_CONNECTIONS_OPENED, "pool" => pool, "outcome" => outcome(result.is_ok()))
+        .increment(1);
+}
+
+fn outcome(ok: bool) -> &'static str {
+    match ok {
+        true => "ok",
+        false => "error",
+    }
+}
+
+#[cfg(test)]
+mod metrics_tests {
+    use super::*;
+    use ::metrics::{
+        Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+    };
+    use std::{
+        collections::HashMap,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+        time::Duration,
+    };
+
+    /// Keeps the counters by name and labels, e.g. `mimer_statements_executed{kind=execute,outcome=ok}`.
+    #[derive(Default)]
+    struct CountingRecorder {
+        counters: Mutex<HashMap<String, Arc<AtomicU64>>>,
+    }
+
+    impl CountingRecorder {
+        fn get(&self, key: &str) -> u64 {
+            self.counters
+                .lock()
+                .unwrap()
+                .get(key)
+                .map_or(0, |counter| counter.load(Ordering::Relaxed))
+        }
+    }
+
+    impl Recorder for CountingRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            let labels: Vec<String> = key
+                .labels()
+                .map(|label| format!("{}={}", label.key(), label.value()))
+                .collect();
+            let name = match labels.is_empty() {
+                true => key.name().to_string(),
+                false => format!("{}{{{}}}", key.name(), labels.join(",")),
+            };
+            Counter::from_arc(
+                self.counters
+                    .lock()
+                    .unwrap()
+                    .entry(name)
+                    .or_default()
+                    .clone(),
+            )
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    #[test]
+    fn record_events() {
+        let recorder = CountingRecorder::default();
+        ::metrics::with_local_recorder(&recorder, || {
+            for (kind, rows, result) in [
+                (StatementEventKind::Execute, Some(1), Ok(1)),
+                (StatementEventKind::Execute, None, Err(-12200)),
+                (StatementEventKind::OpenCursor, None, Ok(0)),
+                (StatementEventKind::Fetch, Some(5), Ok(0)),
+                (StatementEventKind::Fetch, Some(2), Ok(0)),
+            ] {
+                record_event(&StatementEvent {
+                    kind,
+                    sql: "SELECT 1 FROM SYSTEM.ONEROW",
+                    duration: Duration::from_millis(1),
+                    rows,
+                    result,
+                });
+            }
+        });
+        assert_eq!(
+            recorder.get("mimer_statements_executed{kind=execute,outcome=ok}"),
+            1
+        );
+        assert_eq!(
+            recorder.get("mimer_statements_executed{kind=execute,outcome=error}"),
+            1
+        );
+        assert_eq!(
+            recorder.get("mimer_statements_executed{kind=open_cursor,outcome=ok}"),
+            1
+        );
+        assert_eq!(recorder.get("mimer_rows_fetched"), 7);
+    }
+}
@@ -23,16 +23,26 @@
 * *********************************************************************/
 
 use crate::common::traits::*;
-use crate::common::{return_codes::MIMER_SUCCESS, traits::GetHandle};
+use crate::ffi_buffer::read_string;
+use crate::retry::TRANSACTION_CONFLICT;
 use mimerrust_sys as ffi;
 
+/// Error codes of integrity constraint violations: a duplicate primary key, and a null value where it isn't allowed.
+const CONSTRAINT_VIOLATIONS: [i32; 2] = [-10101, ffi::MIMER_NULL_VIOLATION];
+
+/// Error codes of syntax errors: "Syntax error, <%> ignored" and "Syntax error, <%> assumed to mean <%>".
+const SYNTAX_ERRORS: [i32; 2] = [-12102, -12103];
+
+/// Error codes of a session whose connection to the server failed, as defined by the C API, and of a dropped connection.
+const CONNECTION_ERRORS: [i32; 4] = [
+    ffi::MIMER_COMMUNICATION_ERROR,
+    ffi::MIMER_UNDEFINED_COMMUNICATION,
+    ffi::MIMER_TLS_ERROR,
+    -26003,
+];
+
 #[doc(hidden)]
-use core::cmp::Ordering;
-#[doc(hidden)]
-use std::{
-    ffi::{c_void, CString},
-    fmt,
-};
+use std::{ffi::c_void, fmt};
 
 /// Represents an error occurring during communication with a MimerSQL database.
 #[derive(Debug)]
@@ -51,56 +61,21 @@ impl MimerError {
             return MimerError::mimer_error_from_code(error_code);
         }
         let mut ec = -1;
-        unsafe {
-            let handle = match handle.get_handle() {
-                Ok(MimerHandle::Session(session)) => *session as *const _ as *mut c_void,
-                Ok(MimerHandle::Statement(statement)) => *statement as *const _ as *mut c_void,
-                Err(ec) => return MimerError::mimer_error_from_code(ec),
-            };
-
-            // let null_ptr: *mut i8 = std::ptr::null_mut();
-            let c_str_dummy = CString::new("").unwrap();
-            let dummy_ptr = c_str_dummy.into_raw();
-
-            let buffer_size: i32 = ffi::MimerGetError8(handle, &mut ec, dummy_ptr, 0);
-
-            // retake pointer to free memory
-            let _ = CString::from_raw(dummy_ptr);
-
-            match buffer_size.cmp(MIMER_SUCCESS) {
-                Ordering::Greater => (),
-                Ordering::Equal => (),
-                Ordering::Less => return MimerError::mimer_error_from_code(buffer_size),
-            }
-
-            let c_buffer_size: usize = (buffer_size + 1) as usize;
-
-            //let mut buffer: Vec<c_char> = Vec::with_capacity(c_buffer_size);
-            let mut buffer = vec![255u8; c_buffer_size];
-            buffer[c_buffer_size - 1] = 0;
-            let c_str = CString::from_vec_unchecked(buffer);
-            let c_str_ptr = c_str.into_raw();
-            //let error_message = buffer.as_mut_ptr();
-
-            let rc = ffi::MimerGetError8(handle, &mut ec, c_str_ptr, c_buffer_size);
-            //assert!(false);
-            match rc.cmp(MIMER_SUCCESS) {
-                Ordering::Greater => (),
-                Ordering::Equal => (),
-                Ordering::Less => return MimerError::mimer_error_from_code(rc),
-            }
-
-            // retake pointer to free memory
-            let maybe_string = CString::from_raw(c_str_ptr).into_string();
-
-            return match maybe_string {
-                Ok(s) => MimerError {
-                    error_code: ec,
-                    error_message: s,
-                },
-                Err(_) => MimerError::mimer_error_from_code(-26001),
-            };
+        let handle = match handle.get_handle() {
+            Ok(MimerHandle::Session(session)) => *session as *const _ as *mut c_void,
+            Ok(MimerHandle::Statement(statement)) => *statement as *const _ as *mut c_void,
+            Err(ec) => return MimerError::mimer_error_from_code(ec),
         };
+
+        let message =
+            read_string(|buf, size| unsafe { ffi::MimerGetError8(handle, &mut ec, buf, size) });
+        match message {
+            Ok(message) => MimerError {
+                error_code: ec,
+                error_message: message.unwrap_or_default(),
+            },
+            Err(rc) => MimerError::mimer_error_from_code(rc),
+        }
     }
 
     /// Returns a [MimerError] given a program dependent error code.
@@ -111,6 +86,7 @@ impl MimerError {
             -14006 => String::from("Login failure"),
             -18500 => String::from("Database name not found in SQLHOSTS file"),
             -24101 => String::from("An illegal sequence of API calls was detected"),
+            -24415 => String::from("TLS error when communicating with the server"),
             -21028 => {
                 String::from("Failed to do a LOCAL connection to the server for database <%>")
             } // TODO: should we bother displaying the database name here? This would mean implementing a way for types that implement the trait GetHandle to also fetch name of database.
@@ -121,6 +97,45 @@ impl MimerError {
             -26005 => String::from("Handle is NULL"),
             -26006 => String::from("Wrong number of parameters"),
             -26007 => String::from("Could not convert UTF-8 string to CString"),
+            -26008 => String::from("Column index out of range"),
+            -26009 => String::from("Query returned no rows"),
+            -26010 => String::from("TLS certificate or key file not found"),
+            -26011 => String::from("TLS certificate or key file is not PEM encoded"),
+            -26012 => String::from(
+                "TLS options can't be applied through the Mimer SQL C API, configure TLS in SQLHOSTS",
+            ),
+            -26013 => String::from("TLS client certificate and client key must be given together"),
+            -26014 => String::from("Could not read the SQLHOSTS file"),
+            -26015 => String::from("Fetch buffer exceeds the memory budget of the connection"),
+            -26016 => String::from("Buffer is too large for the C API"),
+            -26017 => String::from("Background task failed"),
+            -26018 => String::from("No column with the given name"),
+            -26019 => String::from("No value was given for a named parameter"),
+            -26020 => String::from("A value was given for a parameter the statement doesn't have"),
+            -26021 => String::from("Could not read or write CSV data"),
+            -26022 => String::from("Statement modifies data in a read-only transaction"),
+            -26023 => String::from("Key has no columns"),
+            -26024 => String::from("Interval value doesn't fit the fields of the parameter"),
+            -26025 => String::from("Integer value is out of range for the Rust type"),
+            -26026 => String::from("Lease of the consistent reader has expired"),
+            -26027 => String::from("Column value is NULL"),
+            -26028 => String::from("Timed out opening the connection"),
+            -26029 => String::from("Cursor is not scrollable"),
+            -26030 => String::from("Character large object is shorter than its length"),
+            -26031 => String::from("Latitude is out of range, it must be within [-90, 90]"),
+            -26032 => String::from("Longitude is out of range, it must be within [-180, 180]"),
+            -26033 => String::from("Invalid WKT or WKB geometry"),
+            -26034 => String::from("Data exceeds the declared length of the large object"),
+            -26035 => String::from("Not supported by the Mimer SQL server or C API library in use"),
+            -26036 => String::from("Request was cancelled after exceeding the query timeout"),
+            -26037 => String::from("Unexpected positive return code from the C API"),
+            -26038 => String::from("Parameters haven't been bound since the statement was last executed"),
+            -26039 => String::from("Could not write the formatted table"),
+            -26040 => String::from("Could not write the Parquet file"),
+            -26041 => String::from("Key column is not among the values"),
+            -26042 => String::from("No values have been set"),
+            -26043 => String::from("No conditions have been added; refusing to touch every row"),
+            -26044 => String::from("Transaction has already been ended"),
             -26100 => String::from("Failed to get handle, handle is not a connection or statement"),
             -26200 => {
                 String::from("Unsupported type conversion between MimerDatatype and Rust type")
@@ -137,6 +152,13 @@ impl MimerError {
         }
     }
 
+    /// Returns a [MimerError] given a program dependent error code, with `detail` appended to its message.
+    pub(crate) fn mimer_error_with_detail(ec: i32, detail: &str) -> MimerError {
+        let mut error = MimerError::mimer_error_from_code(ec);
+        error.error_message = format!("{}: {detail}", error.error_message);
+        error
+    }
+
     /// Gets the error code from a [MimerError] struct.
     ///
     /// # Examples
@@ -180,6 +202,77 @@ impl MimerError {
     pub fn get_error_message(&self) -> &String {
         &self.error_message
     }
+
+    /// Returns whether the error is an integrity constraint violation.
+    /// Only a duplicate primary key (-10101) and a null value where it isn't allowed ([MIMER_NULL_VIOLATION](ffi::MIMER_NULL_VIOLATION))
+    /// are recognized, other constraint violations can be told apart by their [error code](MimerError::get_error_code()).
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// conn.execute_statement("create table test_table (column_1 VARCHAR(30) PRIMARY KEY, column_2 INT)").unwrap();
+    /// conn.execute_statement("INSERT INTO test_table VALUES('the number one', 1)").unwrap();
+    ///
+    /// let err = match conn.execute_statement("INSERT INTO test_table VALUES('the number one', 2)") {
+    ///     Ok(_) => panic!("Duplicate key was inserted."),
+    ///     Err(ec) => conn.get_error(ec),
+    /// };
+    /// assert!(err.is_constraint_violation());
+    /// assert_eq!(err.sqlstate(), Some("23000"));
+    /// ```
+    pub fn is_constraint_violation(&self) -> bool {
+        CONSTRAINT_VIOLATIONS.contains(&self.error_code)
+    }
+
+    /// Returns whether the transaction was aborted because of a conflict with another transaction, see [TRANSACTION_CONFLICT].
+    /// Mimer SQL uses optimistic concurrency control, so this is what other databases report as a deadlock or serialization failure.
+    /// The transaction can be retried.
+    pub fn is_deadlock(&self) -> bool {
+        self.error_code == TRANSACTION_CONFLICT
+    }
+
+    /// Returns whether the connection to the server is lost or dropped, meaning that a new connection has to be opened.
+    /// This covers the communication errors of the C API ([MIMER_COMMUNICATION_ERROR](ffi::MIMER_COMMUNICATION_ERROR),
+    /// [MIMER_UNDEFINED_COMMUNICATION](ffi::MIMER_UNDEFINED_COMMUNICATION) and [MIMER_TLS_ERROR](ffi::MIMER_TLS_ERROR))
+    /// and connections dropped on the Rust side (-26003).
+    pub fn is_connection_lost(&self) -> bool {
+        CONNECTION_ERRORS.contains(&self.error_code)
+    }
+
+    /// Returns whether the error is a syntax error in an SQL statement, reported by Mimer SQL with the error codes -12102 and -12103.
+    pub fn is_syntax_error(&self) -> bool {
+        SYNTAX_ERRORS.contains(&self.error_code)
+    }
+
+    /// Returns the SQLSTATE class of the error, for the error codes recognized by the `is_*` methods.
+    /// The Mimer SQL C API has no function reporting SQLSTATE values, so they are mapped from those error codes:
+    ///
+    /// | Error | SQLSTATE |
+    /// |---|---|
+    /// | [is_constraint_violation](MimerError::is_constraint_violation()) | 23000 |
+    /// | [is_deadlock](MimerError::is_deadlock()) | 40001 |
+    /// | [is_connection_lost](MimerError::is_connection_lost()) | 08006 |
+    /// | [is_syntax_error](MimerError::is_syntax_error()) | 42000 |
+    ///
+    /// Returns [None] for other errors.
+    pub fn sqlstate(&self) -> Option<&'static str> {
+        if self.is_constraint_violation() {
+            Some("23000")
+        } else if self.is_deadlock() {
+            Some("40001")
+        } else if self.is_connection_lost() {
+            Some("08006")
+        } else if self.is_syntax_error() {
+            Some("42000")
+        } else {
+            None
+        }
+    }
 }
 
 impl fmt::Display for MimerError {
@@ -188,6 +281,33 @@ impl fmt::Display for MimerError {
     }
 }
 
+/// MimerError is [Send], [Sync] and `'static`, so it can be boxed into `Box<dyn Error + Send + Sync>` or error types built on it, e.g. with `?`.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// fn drop_missing_table(conn: &Connection) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+///     conn.execute_statement("DROP TABLE non_existing_table")
+///         .map_err(|ec| conn.get_error(ec))?;
+///     Ok(())
+/// }
+///
+/// let conn = Connection::open(db, ident, pass).unwrap();
+/// let err = drop_missing_table(&conn).unwrap_err();
+/// assert!(err.downcast_ref::<MimerError>().is_some());
+/// ```
+impl std::error::Error for MimerError {}
+
+impl From<MimerError> for i32 {
+    /// Returns the error code, for functions that return errors as codes like the rest of this crate.
+    fn from(err: MimerError) -> i32 {
+        err.error_code
+    }
+}
+
 #[cfg(test)]
 mod error_tests {
     use super::*;
@@ -195,6 +315,22 @@ mod error_tests {
     use crate::statement::*;
     use crate::testing::*;
 
+    #[test]
+    fn error_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync + 'static>() {}
+        assert_send_sync::<MimerError>();
+
+        let conn = establish_connection();
+        let boxed: Box<dyn std::error::Error + Send + Sync> = conn
+            .execute_statement("DROP TABLE non_existing_table")
+            .map_err(|ec| MimerError::new(&conn, ec))
+            .unwrap_err()
+            .into();
+        let err = boxed.downcast::<MimerError>().unwrap();
+        let ec: i32 = (*err).into();
+        assert!(ec == -12501 || ec == -12517);
+    }
+
     #[test]
     fn error_dropping_table() {
         let conn = establish_connection();
@@ -252,9 +388,47 @@ mod error_tests {
         println!("error creating table test: {}", err);
     }
 
+    #[test]
+    fn error_classification() {
+        let conn = establish_connection();
+        drop_create_table(
+            &conn,
+            EXAMPLE_TABLE,
+            "(column_1 VARCHAR(30) PRIMARY KEY, column_2 INT)",
+        );
+        conn.execute_statement(&format!("INSERT INTO {EXAMPLE_TABLE} VALUES('one', 1)"))
+            .unwrap();
+        let err = conn
+            .execute_statement(&format!("INSERT INTO {EXAMPLE_TABLE} VALUES('one', 2)"))
+            .map_err(|ec| MimerError::new(&conn, ec))
+            .unwrap_err();
+        assert_eq!(err.get_error_code(), -10101);
+        assert!(err.is_constraint_violation());
+        assert!(!err.is_syntax_error());
+        assert_eq!(err.sqlstate(), Some("23000"));
+
+        let err = conn
+            .execute_statement("[an invalid statement]")
+            .map_err(|ec| MimerError::new(&conn, ec))
+            .unwrap_err();
+        assert!(SYNTAX_ERRORS.contains(&err.get_error_code()));
+        assert!(err.is_syntax_error());
+        assert!(!err.is_constraint_violation());
+        assert_eq!(err.sqlstate(), Some("42000"));
+
+        assert!(MimerError::mimer_error_from_code(TRANSACTION_CONFLICT).is_deadlock());
+        assert!(MimerError::mimer_error_from_code(-26003).is_connection_lost());
+        assert!(
+            MimerError::mimer_error_from_code(ffi::MIMER_COMMUNICATION_ERROR).is_connection_lost()
+        );
+        // API usage errors in the -18xxx range aren't communication errors
+        assert!(!MimerError::mimer_error_from_code(ffi::MIMER_NOT_SINGLE_ROW).is_connection_lost());
+        assert_eq!(MimerError::mimer_error_from_code(-26008).sqlstate(), None);
+    }
+
     #[test]
     fn error_prepare() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         let stmnt: Result<Statement, i32>;
         let option = CursorMode::Forward;
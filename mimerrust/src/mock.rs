@@ -0,0 +1,301 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{row::OwnedRow, CursorMode, MimerDatatype, PreparedStatement, Queryable, ToSql};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+
+/// A statement that a [MockConnection] expects to be run, together with the result to answer it with.
+///
+/// By default the statement may be bound with any parameters and answers with no rows and no rows affected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expectation {
+    sql: String,
+    params: Option<Vec<MimerDatatype<'static>>>,
+    rows: Vec<OwnedRow>,
+    rows_affected: i32,
+    error: Option<i32>,
+}
+
+impl Expectation {
+    /// Expects the statement `sqlstatement`. Runs of whitespace are treated as a single space when statements are compared.
+    pub fn new(sqlstatement: &str) -> Expectation {
+        Expectation {
+            sql: normalize(sqlstatement),
+            params: None,
+            rows: Vec::new(),
+            rows_affected: 0,
+            error: None,
+        }
+    }
+
+    /// Expects the statement to be bound with `params`, compared by value.
+    pub fn with_params(mut self, params: &[&dyn ToSql]) -> Expectation {
+        self.params = Some(owned_params(params));
+        self
+    }
+
+    /// Answers a query with `rows`, each given as its values ordered by column index.
+    pub fn returning_rows<R: Into<OwnedRow>>(
+        mut self,
+        rows: impl IntoIterator<Item = R>,
+    ) -> Expectation {
+        self.rows = rows.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Answers a data changing statement with the number of rows it affected.
+    pub fn returning_rows_affected(mut self, rows_affected: i32) -> Expectation {
+        self.rows_affected = rows_affected;
+        self
+    }
+
+    /// Answers the statement with the error code `error_code`, as if the server had rejected it.
+    pub fn failing_with(mut self, error_code: i32) -> Expectation {
+        self.error = Some(error_code);
+        self
+    }
+}
+
+/// A stand-in for a [Connection](crate::Connection) in unit tests, which answers statements from a script of [Expectation]s
+/// instead of a Mimer SQL server.
+///
+/// Application code written against the [Queryable] trait can be given a MockConnection in tests.
+/// Every statement must match the next expectation in the script, by SQL and, if the expectation has any, by parameters.
+/// A statement that doesn't match, or that comes after the script has run out, panics and so fails the test.
+/// Expectations left when the MockConnection is dropped panic as well, unless the thread is already panicking.
+///
+/// # Examples
+/// ```
+/// use mimerrust::mock::{Expectation, MockConnection};
+/// use mimerrust::{MimerDatatype, Queryable};
+///
+/// fn user_name(conn: &impl Queryable, id: i32) -> Result<Option<String>, i32> {
+///     conn.query_scalar::<String>("SELECT name FROM users WHERE id = ?", &[&id])
+/// }
+///
+/// let conn = MockConnection::new();
+/// conn.expect(
+///     Expectation::new("SELECT name FROM users WHERE id = ?")
+///         .with_params(&[&7])
+///         .returning_rows([vec![MimerDatatype::String("Ada".to_string())]]),
+/// );
+/// assert_eq!(user_name(&conn, 7), Ok(Some("Ada".to_string())));
+/// ```
+#[derive(Debug, Default)]
+pub struct MockConnection {
+    expectations: Mutex<VecDeque<Expectation>>,
+}
+
+impl MockConnection {
+    /// Creates a MockConnection with no expectations.
+    pub fn new() -> MockConnection {
+        MockConnection::default()
+    }
+
+    /// Adds `expectation` to the end of the script.
+    pub fn expect(&self, expectation: Expectation) {
+        self.expectations.lock().push_back(expectation);
+    }
+
+    /// Returns the number of expectations that haven't been met yet.
+    pub fn remaining(&self) -> usize {
+        self.expectations.lock().len()
+    }
+
+    /// Checks that every expectation has been met.
+    ///
+    /// # Panics
+    /// Panics when expectations remain, listing their statements.
+    pub fn verify(&self) {
+        let expectations = self.expectations.lock();
+        if !expectations.is_empty() {
+            let statements: Vec<&str> = expectations.iter().map(|e| e.sql.as_str()).collect();
+            panic!(
+                "MockConnection: {} expected statement(s) were never run: {statements:?}",
+                statements.len()
+            );
+        }
+    }
+
+    /// Takes the next expectation, panicking if `sqlstatement` and `params` don't match it.
+    fn next(&self, sqlstatement: &str, params: &[&dyn ToSql]) -> Expectation {
+        let sql = normalize(sqlstatement);
+        let expectation = match self.expectations.lock().pop_front() {
+            Some(expectation) => expectation,
+            None => panic!(
+                "MockConnection: unexpected statement {sql:?}, no more statements were expected"
+            ),
+        };
+        if expectation.sql != sql {
+            panic!(
+                "MockConnection: expected statement {:?}, but got {sql:?}",
+                expectation.sql
+            );
+        }
+        if let Some(expected) = &expectation.params {
+            let params = owned_params(params);
+            if *expected != params {
+                panic!(
+                    "MockConnection: statement {sql:?} expected parameters {expected:?}, but got {params:?}"
+                );
+            }
+        }
+        expectation
+    }
+}
+
+impl Drop for MockConnection {
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            self.verify();
+        }
+    }
+}
+
+impl Queryable for MockConnection {
+    type Statement<'s> = MockStatement<'s>;
+
+    fn prepare(&self, sqlstatement: &str, _option: CursorMode) -> Result<MockStatement<'_>, i32> {
+        Ok(MockStatement {
+            connection: self,
+            sql: sqlstatement.to_string(),
+        })
+    }
+}
+
+/// A statement prepared on a [MockConnection].
+///
+/// Preparing a statement doesn't take an expectation from the script; each execution of it does.
+#[derive(Debug)]
+pub struct MockStatement<'a> {
+    connection: &'a MockConnection,
+    sql: String,
+}
+
+impl PreparedStatement for MockStatement<'_> {
+    fn execute_bind(&self, params: &[&dyn ToSql]) -> Result<i32, i32> {
+        let expectation = self.connection.next(&self.sql, params);
+        match expectation.error {
+            Some(error_code) => Err(error_code),
+            None => Ok(expectation.rows_affected),
+        }
+    }
+
+    fn query(&self, params: &[&dyn ToSql]) -> Result<Vec<OwnedRow>, i32> {
+        let expectation = self.connection.next(&self.sql, params);
+        match expectation.error {
+            Some(error_code) => Err(error_code),
+            None => Ok(expectation.rows),
+        }
+    }
+}
+
+/// Collapses runs of whitespace into single spaces, so that statements compare equal regardless of their layout.
+fn normalize(sqlstatement: &str) -> String {
+    sqlstatement
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn owned_params(params: &[&dyn ToSql]) -> Vec<MimerDatatype<'static>> {
+    params.iter().map(|p| p.to_sql().into_owned()).collect()
+}
+
+#[cfg(test)]
+mod mock_tests {
+    use super::*;
+
+    #[test]
+    fn scripted_statements() {
+        let conn = MockConnection::new();
+        conn.expect(
+            Expectation::new("INSERT INTO test_table VALUES(?, ?)")
+                .with_params(&[&"one", &1])
+                .returning_rows_affected(1),
+        );
+        conn.expect(
+            Expectation::new("SELECT column_2 FROM test_table")
+                .returning_rows([vec![MimerDatatype::Int(1)], vec![MimerDatatype::Null]]),
+        );
+        conn.expect(Expectation::new("DELETE FROM test_table").failing_with(-12200));
+
+        assert_eq!(
+            conn.execute("INSERT INTO test_table\n    VALUES(?, ?)", &[&"one", &1]),
+            Ok(1)
+        );
+        let rows = conn.query("SELECT column_2 FROM test_table", &[]).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].get::<i32>(1), Ok(Some(1)));
+        assert_eq!(rows[1].get::<i32>(1), Ok(None));
+        assert_eq!(conn.execute("DELETE FROM test_table", &[]), Err(-12200));
+        assert_eq!(conn.remaining(), 0);
+    }
+
+    #[test]
+    fn query_row_without_rows() {
+        let conn = MockConnection::new();
+        conn.expect(Expectation::new("SELECT column_1 FROM test_table"));
+        assert_eq!(
+            conn.query_scalar::<String>("SELECT column_1 FROM test_table", &[]),
+            Err(-26009)
+        );
+    }
+
+    #[test]
+    fn prepared_statement() {
+        let conn = MockConnection::new();
+        for number in [1, 2] {
+            conn.expect(
+                Expectation::new("INSERT INTO test_table VALUES(?, ?)")
+                    .with_params(&[&"text", &number])
+                    .returning_rows_affected(1),
+            );
+        }
+        let stmnt = conn
+            .prepare("INSERT INTO test_table VALUES(?, ?)", CursorMode::Forward)
+            .unwrap();
+        assert_eq!(conn.remaining(), 2);
+        assert_eq!(stmnt.execute_bind(&[&"text", &1]), Ok(1));
+        assert_eq!(stmnt.execute_bind(&[&"text", &2]), Ok(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected parameters")]
+    fn mismatched_params() {
+        let conn = MockConnection::new();
+        conn.expect(
+            Expectation::new("DELETE FROM test_table WHERE column_2 = ?").with_params(&[&1]),
+        );
+        let _ = conn.execute("DELETE FROM test_table WHERE column_2 = ?", &[&2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "were never run")]
+    fn unmet_expectation() {
+        let conn = MockConnection::new();
+        conn.expect(Expectation::new("SELECT * FROM test_table"));
+    }
+}
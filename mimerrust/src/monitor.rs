@@ -0,0 +1,357 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{
+    connection::Connection,
+    statistics::{ServerStatistics, StatisticsCounter},
+};
+use parking_lot::{Condvar, Mutex};
+
+#[doc(hidden)]
+use std::{
+    sync::Arc,
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// Function called with every [StatisticsDelta] sampled by a [Monitor].
+type SampleCallback = Box<dyn Fn(&StatisticsDelta) + Send>;
+
+/// Function called when a [Threshold] set on a [Monitor] is exceeded.
+type ThresholdCallback = Box<dyn Fn(&ThresholdExceeded) + Send>;
+
+/// The change of the server statistics between two samples taken by a [Monitor].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatisticsDelta {
+    /// The statistics of the previous sample.
+    pub previous: ServerStatistics,
+    /// The statistics of the latest sample.
+    pub current: ServerStatistics,
+    /// Time between the samples.
+    pub elapsed: Duration,
+}
+
+impl StatisticsDelta {
+    /// Gets how much a counter changed between the samples, e.g. the page usage growth for [PagesUsed](StatisticsCounter::PagesUsed).
+    /// Returns [None] when the counter is missing from either sample.
+    pub fn change(&self, counter: StatisticsCounter) -> Option<i64> {
+        Some(self.current.get(counter)? as i64 - self.previous.get(counter)? as i64)
+    }
+
+    /// Gets the change of a counter per second, e.g. the request rate for [Requests](StatisticsCounter::Requests).
+    /// Returns [None] when the counter is missing from either sample, or when no time passed between them.
+    pub fn rate(&self, counter: StatisticsCounter) -> Option<f64> {
+        let seconds = self.elapsed.as_secs_f64();
+        match seconds > 0.0 {
+            true => Some(self.change(counter)? as f64 / seconds),
+            false => None,
+        }
+    }
+}
+
+/// A limit on a counter sampled by a [Monitor], see [MonitorBuilder::threshold].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Threshold {
+    /// The value of the counter is above the limit.
+    Above(u32),
+    /// The counter changed by more than the limit since the previous sample.
+    ChangeAbove(i64),
+    /// The counter changed by more than the limit per second since the previous sample.
+    RateAbove(f64),
+}
+
+impl Threshold {
+    /// Checks whether the threshold is exceeded for `counter` in `delta`.
+    pub fn is_exceeded(&self, counter: StatisticsCounter, delta: &StatisticsDelta) -> bool {
+        match *self {
+            Threshold::Above(limit) => delta
+                .current
+                .get(counter)
+                .is_some_and(|value| value > limit),
+            Threshold::ChangeAbove(limit) => {
+                delta.change(counter).is_some_and(|change| change > limit)
+            }
+            Threshold::RateAbove(limit) => delta.rate(counter).is_some_and(|rate| rate > limit),
+        }
+    }
+}
+
+/// Tells that a [Threshold] was exceeded, as passed to the callback set with [MonitorBuilder::on_threshold_exceeded].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThresholdExceeded<'a> {
+    /// The counter that exceeded the threshold.
+    pub counter: StatisticsCounter,
+    /// The threshold that was exceeded.
+    pub threshold: Threshold,
+    /// The samples in which the threshold was exceeded.
+    pub delta: &'a StatisticsDelta,
+}
+
+/// Sets up a [Monitor], as returned by [Monitor::builder].
+pub struct MonitorBuilder {
+    interval: Duration,
+    counters: Vec<StatisticsCounter>,
+    thresholds: Vec<(StatisticsCounter, Threshold)>,
+    on_sample: Option<SampleCallback>,
+    on_threshold_exceeded: Option<ThresholdCallback>,
+}
+
+impl MonitorBuilder {
+    /// Sets the counters to sample. All [counters](StatisticsCounter::ALL) are sampled by default.
+    pub fn counters(mut self, counters: &[StatisticsCounter]) -> MonitorBuilder {
+        self.counters = counters.to_vec();
+        self
+    }
+
+    /// Adds a threshold for a counter, which calls the callback set with [on_threshold_exceeded](MonitorBuilder::on_threshold_exceeded)
+    /// for every sample in which it is exceeded.
+    pub fn threshold(mut self, counter: StatisticsCounter, threshold: Threshold) -> MonitorBuilder {
+        self.thresholds.push((counter, threshold));
+        self
+    }
+
+    /// Sets a function called with every sample after the first, e.g. to feed a dashboard.
+    pub fn on_sample<F>(mut self, f: F) -> MonitorBuilder
+    where
+        F: Fn(&StatisticsDelta) + Send + 'static,
+    {
+        self.on_sample = Some(Box::new(f));
+        self
+    }
+
+    /// Sets a function called when a [threshold](MonitorBuilder::threshold) is exceeded.
+    pub fn on_threshold_exceeded<F>(mut self, f: F) -> MonitorBuilder
+    where
+        F: Fn(&ThresholdExceeded) + Send + 'static,
+    {
+        self.on_threshold_exceeded = Some(Box::new(f));
+        self
+    }
+
+    /// Starts sampling with `conn` on a background thread, which owns the connection until the monitor is stopped.
+    /// Sampling holds the session while the statistics are read, so the connection should be dedicated to the monitor.
+    pub fn start(self, conn: Connection) -> Monitor {
+        let shared = Arc::new(MonitorState {
+            stopped: Mutex::new(false),
+            wake: Condvar::new(),
+            latest: Mutex::new(None),
+            last_error: Mutex::new(None),
+        });
+        let state = Arc::clone(&shared);
+        let handle = thread::spawn(move || self.run(conn, &state));
+        Monitor {
+            state: shared,
+            handle: Some(handle),
+        }
+    }
+
+    /// Takes a sample every interval until the monitor is stopped.
+    fn run(self, conn: Connection, state: &MonitorState) {
+        let mut previous: Option<(Instant, ServerStatistics)> = None;
+        loop {
+            let taken_at = Instant::now();
+            match conn.get_statistics_for(&self.counters) {
+                Ok(current) => {
+                    *state.last_error.lock() = None;
+                    if let Some((previous_at, previous)) = previous {
+                        let delta = StatisticsDelta {
+                            previous,
+                            current,
+                            elapsed: taken_at - previous_at,
+                        };
+                        self.report(&delta);
+                        *state.latest.lock() = Some(delta);
+                    }
+                    previous = Some((taken_at, current));
+                }
+                Err(ec) => *state.last_error.lock() = Some(ec),
+            }
+
+            let mut stopped = state.stopped.lock();
+            if !*stopped {
+                state.wake.wait_for(&mut stopped, self.interval);
+            }
+            if *stopped {
+                return;
+            }
+        }
+    }
+
+    /// Calls the callbacks for a new sample.
+    fn report(&self, delta: &StatisticsDelta) {
+        if let Some(on_sample) = &self.on_sample {
+            on_sample(delta);
+        }
+        if let Some(on_threshold_exceeded) = &self.on_threshold_exceeded {
+            for (counter, threshold) in &self.thresholds {
+                if threshold.is_exceeded(*counter, delta) {
+                    on_threshold_exceeded(&ThresholdExceeded {
+                        counter: *counter,
+                        threshold: *threshold,
+                        delta,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// State shared between a [Monitor] and its background thread.
+struct MonitorState {
+    stopped: Mutex<bool>,
+    wake: Condvar,
+    latest: Mutex<Option<StatisticsDelta>>,
+    last_error: Mutex<Option<i32>>,
+}
+
+/// Samples the [server statistics](crate::Connection::get_statistics()) on an interval on a background thread,
+/// exposing the change between samples and calling a function when thresholds are exceeded.
+/// The monitor stops when it is dropped.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # use std::time::Duration;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// use mimerrust::monitor::{Monitor, Threshold};
+///
+/// let conn = Connection::open(db, ident, pass).unwrap();
+/// let monitor = Monitor::builder(Duration::from_secs(10))
+///     .threshold(StatisticsCounter::Requests, Threshold::RateAbove(1000.0))
+///     .on_threshold_exceeded(|exceeded| eprintln!("{:?} exceeded {:?}", exceeded.counter, exceeded.threshold))
+///     .start(conn);
+///
+/// if let Some(delta) = monitor.latest() {
+///     println!("page usage grew by {:?}", delta.change(StatisticsCounter::PagesUsed));
+/// }
+/// monitor.stop();
+/// ```
+pub struct Monitor {
+    state: Arc<MonitorState>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Monitor {
+    /// Returns a [MonitorBuilder] for a monitor sampling every `interval`.
+    pub fn builder(interval: Duration) -> MonitorBuilder {
+        MonitorBuilder {
+            interval,
+            counters: StatisticsCounter::ALL.to_vec(),
+            thresholds: Vec::new(),
+            on_sample: None,
+            on_threshold_exceeded: None,
+        }
+    }
+
+    /// Gets the change between the two latest samples, [None] until two samples have been taken.
+    pub fn latest(&self) -> Option<StatisticsDelta> {
+        self.state.latest.lock().clone()
+    }
+
+    /// Gets the error code from the latest sample, [None] if it succeeded.
+    pub fn last_error(&self) -> Option<i32> {
+        *self.state.last_error.lock()
+    }
+
+    /// Stops sampling and waits for the background thread to finish, which closes its connection.
+    pub fn stop(mut self) {
+        self.stop_thread();
+    }
+
+    fn stop_thread(&mut self) {
+        *self.state.stopped.lock() = true;
+        self.state.wake.notify_all();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Monitor {
+    fn drop(&mut self) {
+        self.stop_thread();
+    }
+}
+
+#[cfg(test)]
+mod monitor_tests {
+    use super::*;
+    use crate::testing::*;
+
+    fn delta(previous: u32, current: u32) -> StatisticsDelta {
+        StatisticsDelta {
+            previous: ServerStatistics {
+                requests: Some(previous),
+                ..Default::default()
+            },
+            current: ServerStatistics {
+                requests: Some(current),
+                ..Default::default()
+            },
+            elapsed: Duration::from_secs(2),
+        }
+    }
+
+    #[test]
+    fn deltas_and_thresholds() {
+        let delta = delta(100, 300);
+        assert_eq!(delta.change(StatisticsCounter::Requests), Some(200));
+        assert_eq!(delta.rate(StatisticsCounter::Requests), Some(100.0));
+        assert_eq!(delta.change(StatisticsCounter::PagesUsed), None);
+
+        let requests = StatisticsCounter::Requests;
+        assert!(Threshold::Above(299).is_exceeded(requests, &delta));
+        assert!(!Threshold::Above(300).is_exceeded(requests, &delta));
+        assert!(Threshold::ChangeAbove(199).is_exceeded(requests, &delta));
+        assert!(!Threshold::RateAbove(100.0).is_exceeded(requests, &delta));
+        assert!(!Threshold::Above(0).is_exceeded(StatisticsCounter::PagesUsed, &delta));
+    }
+
+    #[test]
+    fn sample_in_background() {
+        let conn = establish_connection();
+        let exceeded = Arc::new(Mutex::new(Vec::new()));
+        let exceeded_clone = Arc::clone(&exceeded);
+        let monitor = Monitor::builder(Duration::from_millis(50))
+            .counters(&[StatisticsCounter::Requests, StatisticsCounter::PagesUsed])
+            .threshold(StatisticsCounter::Requests, Threshold::ChangeAbove(-1))
+            .on_threshold_exceeded(move |exceeded| exceeded_clone.lock().push(exceeded.counter))
+            .start(conn);
+        thread::sleep(Duration::from_millis(300));
+
+        let delta = monitor.latest().unwrap();
+        assert!(delta.change(StatisticsCounter::Requests).unwrap() >= 0);
+        assert!(delta.current.pages_used.is_some());
+        assert_eq!(delta.current.commits, None);
+        assert_eq!(monitor.last_error(), None);
+        monitor.stop();
+        assert!(exceeded
+            .lock()
+            .iter()
+            .all(|counter| *counter == StatisticsCounter::Requests));
+        assert!(!exceeded.lock().is_empty());
+    }
+}
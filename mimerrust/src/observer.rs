@@ -0,0 +1,212 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::connection::Connection;
+
+#[doc(hidden)]
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Function called with every [StatementEvent] on a connection, see [set_statement_observer](crate::Connection::set_statement_observer()).
+pub(crate) type StatementObserver = Box<dyn Fn(&StatementEvent) + Send + Sync>;
+
+/// A [StatementObserver] as held by the connection, which is cloned out of its lock before it is called.
+pub(crate) type SharedStatementObserver = Arc<dyn Fn(&StatementEvent) + Send + Sync>;
+
+/// What a [StatementEvent] reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatementEventKind {
+    /// A statement was executed, e.g. with [execute_statement](crate::Connection::execute_statement()) or [execute_bind](crate::Statement::execute_bind()).
+    Execute,
+    /// A cursor was opened, which executes the query.
+    OpenCursor,
+    /// A cursor was closed. The event covers all rows fetched with it.
+    Fetch,
+}
+
+/// Describes an execution of, or a fetch from, a statement, as passed to a [statement observer](crate::Connection::set_statement_observer()).
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementEvent<'a> {
+    /// What happened.
+    pub kind: StatementEventKind,
+    /// SQL of the statement.
+    pub sql: &'a str,
+    /// Time spent executing the statement or opening the cursor, including retries, or for [Fetch](StatementEventKind::Fetch) the total time spent fetching rows.
+    pub duration: Duration,
+    /// Number of rows affected by an [Execute](StatementEventKind::Execute) or fetched for a [Fetch](StatementEventKind::Fetch), [None] for a failure or when opening a cursor.
+    pub rows: Option<u64>,
+    /// The return code from the server, or the error code. For a [Fetch](StatementEventKind::Fetch), the last error from fetching if any.
+    pub result: Result<i32, i32>,
+}
+
+/// Rows fetched with a cursor and the time spent fetching them, reported as a [Fetch](StatementEventKind::Fetch) event when the cursor is closed.
+pub(crate) struct FetchObservation {
+    pub(crate) sql: String,
+    rows: u64,
+    duration: Duration,
+    result: Result<i32, i32>,
+}
+
+impl FetchObservation {
+    pub(crate) fn new(sql: &str) -> FetchObservation {
+        FetchObservation {
+            sql: sql.to_string(),
+            rows: 0,
+            duration: Duration::ZERO,
+            result: Ok(0),
+        }
+    }
+
+    /// Adds a fetch that started at `started` and returned `code`.
+    pub(crate) fn record(&mut self, started: Instant, code: i32) {
+        self.duration += started.elapsed();
+        match code {
+            0 => self.rows += 1, // MIMER_SUCCESS
+            code if code < 0 => self.result = Err(code),
+            _ => (),
+        }
+    }
+
+    pub(crate) fn event(&self) -> StatementEvent<'_> {
+        StatementEvent {
+            kind: StatementEventKind::Fetch,
+            sql: &self.sql,
+            duration: self.duration,
+            rows: Some(self.rows),
+            result: self.result,
+        }
+    }
+}
+
+impl Connection {
+    /// Sets a [statement observer](crate::Connection::set_statement_observer()) that calls `log` for every event that took longer than `threshold`,
+    /// e.g. to log slow queries in production.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # use std::time::Duration;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// conn.log_slow_statements(Duration::from_millis(500), |event| {
+    ///     eprintln!("slow statement ({} ms): {}", event.duration.as_millis(), event.sql)
+    /// });
+    /// ```
+    pub fn log_slow_statements<F>(&self, threshold: Duration, log: F)
+    where
+        F: Fn(&StatementEvent) + Send + Sync + 'static,
+    {
+        self.set_statement_observer(Some(Box::new(move |event| {
+            if event.duration > threshold {
+                log(event)
+            }
+        })));
+    }
+}
+
+#[cfg(test)]
+mod observer_tests {
+    use super::*;
+    use crate::common::mimer_options::CursorMode;
+    use crate::testing::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn statement_observer() {
+        let conn = establish_connection();
+        drop_create_table(&conn, "observer_table", "(id INT)");
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_ref = events.clone();
+        conn.set_statement_observer(Some(Box::new(move |event| {
+            events_ref.lock().unwrap().push((
+                event.kind,
+                event.sql.to_string(),
+                event.rows,
+                event.result,
+            ))
+        })));
+
+        let insert = conn
+            .prepare("INSERT INTO observer_table VALUES(?)", CursorMode::Forward)
+            .unwrap();
+        insert.execute_bind(&[&1]).unwrap();
+        insert.execute_bind(&[&2]).unwrap();
+        assert!(conn.execute_statement("DELETE FROM no_such_table").is_err());
+        let select = conn
+            .prepare("SELECT id FROM observer_table", CursorMode::Forward)
+            .unwrap();
+        let mut cursor = select.open_cursor().unwrap();
+        while cursor.next_row().unwrap().is_some() {}
+        drop(cursor);
+        conn.set_statement_observer(None);
+        insert.execute_bind(&[&3]).unwrap();
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 5);
+        assert_eq!(
+            events[0],
+            (
+                StatementEventKind::Execute,
+                "INSERT INTO observer_table VALUES(?)".to_string(),
+                Some(1),
+                Ok(1)
+            )
+        );
+        assert_eq!(events[2].0, StatementEventKind::Execute);
+        assert!(events[2].3.is_err());
+        assert_eq!(events[3].0, StatementEventKind::OpenCursor);
+        assert_eq!(
+            events[4],
+            (
+                StatementEventKind::Fetch,
+                "SELECT id FROM observer_table".to_string(),
+                Some(2),
+                Ok(0)
+            )
+        );
+    }
+
+    #[test]
+    fn log_slow_statements() {
+        let conn = establish_connection();
+        drop_create_table(&conn, "slow_table", "(id INT)");
+        let logged = Arc::new(Mutex::new(0));
+        let logged_ref = logged.clone();
+        conn.log_slow_statements(Duration::from_secs(3600), move |_| {
+            *logged_ref.lock().unwrap() += 1
+        });
+        conn.execute_statement("DELETE FROM slow_table").unwrap();
+        assert_eq!(*logged.lock().unwrap(), 0);
+
+        let logged_ref = logged.clone();
+        conn.log_slow_statements(Duration::ZERO, move |_| *logged_ref.lock().unwrap() += 1);
+        conn.execute_statement("DELETE FROM slow_table").unwrap();
+        assert_eq!(*logged.lock().unwrap(), 1);
+    }
+}
@@ -0,0 +1,209 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{arrow_support::RecordBatchBuilder, cursor::Cursor};
+
+#[doc(hidden)]
+use ::parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties};
+#[doc(hidden)]
+use std::{fs::File, path::Path};
+
+/// Options for [write_parquet](crate::Cursor::write_parquet()).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParquetOptions {
+    batch_size: usize,
+    max_row_group_size: usize,
+    compression: Compression,
+}
+
+impl Default for ParquetOptions {
+    /// Snappy compression, rows fetched in batches of 8192, and row groups of at most 1048576 rows.
+    fn default() -> Self {
+        ParquetOptions {
+            batch_size: 8192,
+            max_row_group_size: 1024 * 1024,
+            compression: Compression::SNAPPY,
+        }
+    }
+}
+
+impl ParquetOptions {
+    /// Creates the [default](ParquetOptions::default()) options.
+    pub fn new() -> ParquetOptions {
+        ParquetOptions::default()
+    }
+
+    /// Sets the number of rows fetched into each Arrow record batch before it is written. A batch size of 0 is treated as 1.
+    pub fn batch_size(mut self, batch_size: usize) -> ParquetOptions {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Sets the maximum number of rows in a row group of the file. A size of 0 is treated as 1.
+    pub fn max_row_group_size(mut self, max_row_group_size: usize) -> ParquetOptions {
+        self.max_row_group_size = max_row_group_size.max(1);
+        self
+    }
+
+    /// Sets the compression of the column data.
+    ///
+    /// Only [UNCOMPRESSED](Compression::UNCOMPRESSED) and [SNAPPY](Compression::SNAPPY) are enabled by this crate.
+    /// Other codecs need the corresponding feature of the `parquet` crate, otherwise writing fails.
+    pub fn compression(mut self, compression: Compression) -> ParquetOptions {
+        self.compression = compression;
+        self
+    }
+}
+
+impl Cursor {
+    /// Writes the remaining rows of the result set to a Parquet file, and returns the number of rows written.
+    ///
+    /// The rows are fetched with [fetch_arrow](crate::Cursor::fetch_arrow()), so columns are mapped to the same Arrow types,
+    /// and only one batch of rows is held in memory at a time. The file is created, or truncated if it exists.
+    /// A result set without rows gives a file holding only the schema.
+    ///
+    /// Only available with the `parquet` feature.
+    ///
+    /// # Errors
+    /// Returns [Err] when a row couldn't be fetched or converted, or with the error code -26040 if the file couldn't be written.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// use mimerrust::parquet::ParquetOptions;
+    ///
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
+    /// let stmnt = conn.prepare("SELECT * FROM test_table", CursorMode::Forward).unwrap();
+    /// stmnt.set_array_size(1000).unwrap();
+    /// let mut cursor = stmnt.open_cursor().unwrap();
+    ///
+    /// let path = std::env::temp_dir().join("test_table.parquet");
+    /// let rows = cursor.write_parquet(&path, &ParquetOptions::new()).unwrap();
+    /// assert_eq!(rows, 1);
+    /// # std::fs::remove_file(path).unwrap();
+    /// ```
+    pub fn write_parquet<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        options: &ParquetOptions,
+    ) -> Result<usize, i32> {
+        let strong_inner_statement = self.inner_statement.upgrade().ok_or(-26004)?;
+        strong_inner_statement.check_connection()?;
+        let schema = RecordBatchBuilder::new(&strong_inner_statement, 0)?.schema();
+
+        let properties = WriterProperties::builder()
+            .set_compression(options.compression)
+            .set_max_row_group_size(options.max_row_group_size)
+            .build();
+        let file = File::create(path).map_err(|_| -26040)?;
+        let mut writer =
+            ArrowWriter::try_new(file, schema, Some(properties)).map_err(|_| -26040)?;
+
+        let mut rows = 0;
+        while let Some(batch) = self.fetch_arrow(options.batch_size)? {
+            writer.write(&batch).map_err(|_| -26040)?;
+            rows += batch.num_rows();
+        }
+        writer.close().map_err(|_| -26040)?;
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod parquet_tests {
+    use super::*;
+    use crate::testing::*;
+    use crate::CursorMode;
+    use ::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    #[test]
+    fn parquet_round_trip() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+        let stmnt = conn
+            .prepare(
+                &format!("INSERT INTO {EXAMPLE_TABLE} {EXAMPLE_TABLE_COLUMN_NAMES} VALUES(?,?)"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        for i in 0..10 {
+            stmnt
+                .execute_bind(&[
+                    &format!("row {i}"),
+                    &(if i % 3 == 0 { None } else { Some(i) }),
+                ])
+                .unwrap();
+        }
+
+        let path = std::env::temp_dir().join("mimerrust_parquet_round_trip.parquet");
+        let stmnt = conn
+            .prepare(
+                &format!("SELECT * FROM {EXAMPLE_TABLE}"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = stmnt.open_cursor().unwrap();
+        let options = ParquetOptions::new()
+            .batch_size(4)
+            .max_row_group_size(5)
+            .compression(Compression::UNCOMPRESSED);
+        assert_eq!(cursor.write_parquet(&path, &options).unwrap(), 10);
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(reader.metadata().num_row_groups(), 2);
+        assert_eq!(reader.schema().fields().len(), 2);
+        let batches = reader
+            .build()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            batches.iter().map(|batch| batch.num_rows()).sum::<usize>(),
+            10
+        );
+        assert_eq!(
+            batches
+                .iter()
+                .map(|batch| batch.column(1).null_count())
+                .sum::<usize>(),
+            4
+        );
+
+        // An empty result set still gives a file with the schema
+        let mut cursor = stmnt.open_cursor().unwrap();
+        while cursor.next_row().unwrap().is_some() {}
+        assert_eq!(cursor.write_parquet(&path, &options).unwrap(), 0);
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 0);
+        assert_eq!(reader.schema().fields().len(), 2);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,111 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::columnar::*;
+use crate::inner_statement::InnerStatement;
+use crate::row::Row;
+
+#[doc(hidden)]
+use polars::prelude::{Column, DataFrame, NamedFrom, Series};
+
+/// The values of one column, collected before the [Series] is created.
+enum ColumnValues {
+    Int32(Vec<Option<i32>>),
+    Int64(Vec<Option<i64>>),
+    Float32(Vec<Option<f32>>),
+    Float64(Vec<Option<f64>>),
+    Boolean(Vec<Option<bool>>),
+    Utf8(Vec<Option<String>>),
+    Binary(Vec<Option<Vec<u8>>>),
+}
+
+/// Collects rows from a cursor into a [DataFrame].
+pub(crate) struct DataFrameBuilder {
+    columns: Vec<(String, ValueKind, ColumnValues)>,
+}
+
+impl DataFrameBuilder {
+    /// Creates a builder for the result set of a statement.
+    pub(crate) fn new(inner_statement: &InnerStatement) -> Result<DataFrameBuilder, i32> {
+        let columns = describe_columns(inner_statement)?
+            .into_iter()
+            .map(|column| {
+                let values = match column.kind {
+                    ValueKind::Int32 => ColumnValues::Int32(Vec::new()),
+                    ValueKind::Int64 => ColumnValues::Int64(Vec::new()),
+                    ValueKind::Float32 => ColumnValues::Float32(Vec::new()),
+                    ValueKind::Float64 => ColumnValues::Float64(Vec::new()),
+                    ValueKind::Boolean => ColumnValues::Boolean(Vec::new()),
+                    ValueKind::Binary => ColumnValues::Binary(Vec::new()),
+                    ValueKind::Utf8
+                    | ValueKind::Clob
+                    | ValueKind::Date
+                    | ValueKind::Time
+                    | ValueKind::Timestamp => ColumnValues::Utf8(Vec::new()),
+                };
+                (column.name, column.kind, values)
+            })
+            .collect();
+        Ok(DataFrameBuilder { columns })
+    }
+
+    /// Appends the current row of a cursor.
+    pub(crate) fn append_row(&mut self, row: &Row) -> Result<(), i32> {
+        for (i, (_, kind, values)) in self.columns.iter_mut().enumerate() {
+            let idx = i as i16 + 1;
+            match values {
+                ColumnValues::Int32(v) => v.push(read_i32(row, idx)?),
+                ColumnValues::Int64(v) => v.push(read_i64(row, idx)?),
+                ColumnValues::Float32(v) => v.push(read_f32(row, idx)?),
+                ColumnValues::Float64(v) => v.push(read_f64(row, idx)?),
+                ColumnValues::Boolean(v) => v.push(read_bool(row, idx)?),
+                ColumnValues::Utf8(v) => v.push(read_str(row, idx, *kind)?),
+                ColumnValues::Binary(v) => v.push(read_binary(row, idx)?),
+            }
+        }
+        Ok(())
+    }
+
+    /// Finishes the data frame.
+    pub(crate) fn finish(self) -> Result<DataFrame, i32> {
+        let columns: Vec<Column> = self
+            .columns
+            .into_iter()
+            .map(|(name, _, values)| {
+                let name = name.as_str().into();
+                let series = match values {
+                    ColumnValues::Int32(v) => Series::new(name, v),
+                    ColumnValues::Int64(v) => Series::new(name, v),
+                    ColumnValues::Float32(v) => Series::new(name, v),
+                    ColumnValues::Float64(v) => Series::new(name, v),
+                    ColumnValues::Boolean(v) => Series::new(name, v),
+                    ColumnValues::Utf8(v) => Series::new(name, v),
+                    ColumnValues::Binary(v) => Series::new(name, v),
+                };
+                series.into()
+            })
+            .collect();
+        DataFrame::new(columns).map_err(|_| -26200)
+    }
+}
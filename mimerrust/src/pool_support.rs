@@ -0,0 +1,62 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{
+    connection::Connection, connection_options::ConnectionOptions, mimer_error::MimerError,
+};
+
+/// Opens a connection on the tokio blocking thread pool, since opening a session blocks until the server has answered.
+pub(crate) async fn connect_blocking(options: ConnectionOptions) -> Result<Connection, MimerError> {
+    tokio::task::spawn_blocking(move || Connection::open_with(&options))
+        .await
+        .unwrap_or_else(|_| Err(MimerError::mimer_error_from_code(-26017))) // Background task failed
+}
+
+/// Runs `f` with the connection on the tokio blocking thread pool, so that waiting for the server doesn't stall the async runtime.
+pub(crate) async fn run_blocking<F, R>(conn: &Connection, f: F) -> Result<R, MimerError>
+where
+    F: FnOnce(&Connection) -> Result<R, i32> + Send + 'static,
+    R: Send + 'static,
+{
+    let conn = conn.clone();
+    tokio::task::spawn_blocking(move || f(&conn).map_err(|ec| conn.get_error(ec)))
+        .await
+        .unwrap_or_else(|_| Err(MimerError::mimer_error_from_code(-26017))) // Background task failed
+}
+
+/// Checks that a connection still works with [ping](Connection::ping()), as the r2d2 adapter does,
+/// or by executing `validation_query` when one is given.
+pub(crate) async fn validate(
+    conn: &Connection,
+    validation_query: Option<&str>,
+) -> Result<(), MimerError> {
+    let validation_query = validation_query.map(str::to_string);
+    run_blocking(conn, move |conn| match validation_query {
+        Some(validation_query) => conn
+            .query_map(&validation_query, &[], |_| Ok(()))
+            .map(|_| ()),
+        None => conn.ping().map(|_| ()),
+    })
+    .await
+}
@@ -0,0 +1,500 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{
+    common::mimer_options::CursorMode, connection::Connection, statement::Statement, types::ToSql,
+};
+
+/// Conditions of a `WHERE` clause, joined with `AND`, and the values of their parameter markers.
+#[derive(Default)]
+struct Filters<'a> {
+    conditions: Vec<String>,
+    params: Vec<&'a dyn ToSql>,
+}
+
+impl<'a> Filters<'a> {
+    fn push(&mut self, condition: &str, params: &[&'a dyn ToSql]) {
+        self.conditions.push(format!("({condition})"));
+        self.params.extend_from_slice(params);
+    }
+
+    /// Returns the `WHERE` clause with a leading space, or an empty string without conditions.
+    fn where_clause(&self) -> String {
+        match self.conditions.is_empty() {
+            true => String::new(),
+            false => format!(" WHERE {}", self.conditions.join(" AND ")),
+        }
+    }
+}
+
+/// Prepares `sql` on `conn` and binds `params` to its parameter markers, in order.
+fn prepare_bound(conn: &Connection, sql: &str, params: &[&dyn ToSql]) -> Result<Statement, i32> {
+    let stmnt = conn.prepare(sql, CursorMode::Forward)?;
+    if !params.is_empty() {
+        stmnt.bind_params(params)?;
+    }
+    Ok(stmnt)
+}
+
+/// Builds a `SELECT` statement on a table, with the values of its conditions bound as parameters.
+///
+/// Table and column names and conditions are inserted into the generated SQL as given, see [quote_ident](crate::sql::quote_ident())
+/// for names that need quoting, while values are always bound as parameters.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// let conn = Connection::open(db, ident, pass).unwrap();
+/// # conn.execute_statement("drop table test_table").ok();
+/// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+/// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
+/// let query = Select::from("test_table")
+///     .columns(&["column_1", "column_2"])
+///     .filter("column_2 BETWEEN ? AND ?", &[&1, &10])
+///     .order_by("column_2 DESC")
+///     .limit(5);
+/// assert_eq!(
+///     query.sql(),
+///     "SELECT column_1, column_2 FROM test_table WHERE (column_2 BETWEEN ? AND ?) \
+///      ORDER BY column_2 DESC FETCH FIRST 5 ROWS ONLY"
+/// );
+///
+/// let stmnt = query.prepare(&conn).unwrap();
+/// let mut cursor = stmnt.open_cursor().unwrap();
+/// while let Some(row) = cursor.next_row().unwrap() {
+///     println!("{:?}", row.get::<String>(1).unwrap());
+/// }
+/// ```
+pub struct Select<'a> {
+    table: String,
+    columns: Vec<String>,
+    filters: Filters<'a>,
+    order_by: Vec<String>,
+    offset: Option<u64>,
+    limit: Option<u64>,
+}
+
+impl<'a> Select<'a> {
+    /// Starts a query on `table`, selecting every column until [columns](Select::columns()) is called.
+    pub fn from(table: &str) -> Select<'a> {
+        Select {
+            table: table.to_string(),
+            columns: Vec::new(),
+            filters: Filters::default(),
+            order_by: Vec::new(),
+            offset: None,
+            limit: None,
+        }
+    }
+
+    /// Adds columns, or other expressions, to the select list.
+    pub fn columns(mut self, columns: &[&str]) -> Select<'a> {
+        self.columns
+            .extend(columns.iter().map(|column| column.to_string()));
+        self
+    }
+
+    /// Adds a condition, e.g. `"column_2 > ?"`, with the values of its parameter markers in order.
+    /// Conditions are joined with `AND`.
+    pub fn filter(mut self, condition: &str, params: &[&'a dyn ToSql]) -> Select<'a> {
+        self.filters.push(condition, params);
+        self
+    }
+
+    /// Adds a sort key, e.g. `"column_2"` or `"column_2 DESC"`.
+    pub fn order_by(mut self, column: &str) -> Select<'a> {
+        self.order_by.push(column.to_string());
+        self
+    }
+
+    /// Skips the first `offset` rows of the result.
+    pub fn offset(mut self, offset: u64) -> Select<'a> {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Returns at most `limit` rows.
+    pub fn limit(mut self, limit: u64) -> Select<'a> {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Generates the SQL of the query.
+    pub fn sql(&self) -> String {
+        let columns = match self.columns.is_empty() {
+            true => String::from("*"),
+            false => self.columns.join(", "),
+        };
+        let mut sql = format!(
+            "SELECT {columns} FROM {}{}",
+            self.table,
+            self.filters.where_clause()
+        );
+        if !self.order_by.is_empty() {
+            sql.push_str(&format!(" ORDER BY {}", self.order_by.join(", ")));
+        }
+        if let Some(offset) = self.offset {
+            sql.push_str(&format!(" OFFSET {offset} ROWS"));
+        }
+        if let Some(limit) = self.limit {
+            sql.push_str(&format!(" FETCH FIRST {limit} ROWS ONLY"));
+        }
+        sql
+    }
+
+    /// Returns the values of the parameter markers, in order.
+    pub fn params(&self) -> &[&'a dyn ToSql] {
+        &self.filters.params
+    }
+
+    /// Prepares the query on `conn` with its parameters bound, ready for [open_cursor](crate::Statement::open_cursor()).
+    ///
+    /// # Errors
+    /// Returns [Err] when the statement couldn't be prepared or the parameters couldn't be bound.
+    pub fn prepare(&self, conn: &Connection) -> Result<Statement, i32> {
+        prepare_bound(conn, &self.sql(), self.params())
+    }
+}
+
+/// Builds an `INSERT` statement of one row, with the values bound as parameters.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// let conn = Connection::open(db, ident, pass).unwrap();
+/// # conn.execute_statement("drop table test_table").ok();
+/// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+/// let insert = Insert::into("test_table")
+///     .value("column_1", &"the number one")
+///     .value("column_2", &1);
+/// assert_eq!(insert.sql(), "INSERT INTO test_table (column_1, column_2) VALUES(?, ?)");
+/// insert.prepare(&conn).unwrap().execute().unwrap();
+/// ```
+pub struct Insert<'a> {
+    table: String,
+    columns: Vec<String>,
+    params: Vec<&'a dyn ToSql>,
+}
+
+impl<'a> Insert<'a> {
+    /// Starts an insert into `table`.
+    pub fn into(table: &str) -> Insert<'a> {
+        Insert {
+            table: table.to_string(),
+            columns: Vec::new(),
+            params: Vec::new(),
+        }
+    }
+
+    /// Sets the value of a column of the inserted row.
+    pub fn value(mut self, column: &str, value: &'a dyn ToSql) -> Insert<'a> {
+        self.columns.push(column.to_string());
+        self.params.push(value);
+        self
+    }
+
+    /// Generates the SQL of the insert.
+    pub fn sql(&self) -> String {
+        format!(
+            "INSERT INTO {} ({}) VALUES({})",
+            self.table,
+            self.columns.join(", "),
+            vec!["?"; self.columns.len()].join(", ")
+        )
+    }
+
+    /// Returns the values of the parameter markers, in order.
+    pub fn params(&self) -> &[&'a dyn ToSql] {
+        &self.params
+    }
+
+    /// Prepares the insert on `conn` with its values bound, ready for [execute](crate::Statement::execute()).
+    ///
+    /// # Errors
+    /// Returns [Err] when no values have been set (-26042), or when the statement couldn't be prepared or the values couldn't be bound.
+    pub fn prepare(&self, conn: &Connection) -> Result<Statement, i32> {
+        if self.columns.is_empty() {
+            return Err(-26042); // No values have been set
+        }
+        prepare_bound(conn, &self.sql(), self.params())
+    }
+}
+
+/// Builds an `UPDATE` statement, with the new values and the values of its conditions bound as parameters.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// let conn = Connection::open(db, ident, pass).unwrap();
+/// # conn.execute_statement("drop table test_table").ok();
+/// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+/// let update = Update::table("test_table")
+///     .set("column_1", &"one")
+///     .filter("column_2 = ?", &[&1]);
+/// assert_eq!(update.sql(), "UPDATE test_table SET column_1 = ? WHERE (column_2 = ?)");
+/// update.prepare(&conn).unwrap().execute().unwrap();
+/// ```
+pub struct Update<'a> {
+    table: String,
+    assignments: Vec<String>,
+    values: Vec<&'a dyn ToSql>,
+    filters: Filters<'a>,
+    params: Vec<&'a dyn ToSql>,
+}
+
+impl<'a> Update<'a> {
+    /// Starts an update of `table`.
+    pub fn table(table: &str) -> Update<'a> {
+        Update {
+            table: table.to_string(),
+            assignments: Vec::new(),
+            values: Vec::new(),
+            filters: Filters::default(),
+            params: Vec::new(),
+        }
+    }
+
+    /// Sets a column to a new value.
+    pub fn set(mut self, column: &str, value: &'a dyn ToSql) -> Update<'a> {
+        self.assignments.push(format!("{column} = ?"));
+        self.values.push(value);
+        self.update_params();
+        self
+    }
+
+    /// Adds a condition selecting the updated rows, with the values of its parameter markers in order.
+    /// Conditions are joined with `AND`.
+    pub fn filter(mut self, condition: &str, params: &[&'a dyn ToSql]) -> Update<'a> {
+        self.filters.push(condition, params);
+        self.update_params();
+        self
+    }
+
+    /// Collects the new values followed by the values of the conditions, the order of their markers in the SQL.
+    fn update_params(&mut self) {
+        self.params.clear();
+        self.params.extend_from_slice(&self.values);
+        self.params.extend_from_slice(&self.filters.params);
+    }
+
+    /// Generates the SQL of the update.
+    pub fn sql(&self) -> String {
+        format!(
+            "UPDATE {} SET {}{}",
+            self.table,
+            self.assignments.join(", "),
+            self.filters.where_clause()
+        )
+    }
+
+    /// Returns the values of the parameter markers, in order.
+    pub fn params(&self) -> &[&'a dyn ToSql] {
+        &self.params
+    }
+
+    /// Prepares the update on `conn` with its values bound, ready for [execute](crate::Statement::execute()).
+    ///
+    /// # Errors
+    /// Returns -26042 when no values have been set, and -26043 when no conditions have been added, so that a statement never silently
+    /// changes every row of a table; use [execute_statement](crate::Connection::execute_statement()) for that.
+    /// Otherwise returns [Err] when the statement couldn't be prepared or the values couldn't be bound.
+    pub fn prepare(&self, conn: &Connection) -> Result<Statement, i32> {
+        if self.assignments.is_empty() {
+            return Err(-26042); // No values have been set
+        }
+        if self.filters.conditions.is_empty() {
+            return Err(-26043); // No conditions have been added
+        }
+        prepare_bound(conn, &self.sql(), self.params())
+    }
+}
+
+/// Builds a `DELETE` statement, with the values of its conditions bound as parameters.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// let conn = Connection::open(db, ident, pass).unwrap();
+/// # conn.execute_statement("drop table test_table").ok();
+/// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+/// let delete = Delete::from("test_table").filter("column_2 < ?", &[&10]);
+/// assert_eq!(delete.sql(), "DELETE FROM test_table WHERE (column_2 < ?)");
+/// delete.prepare(&conn).unwrap().execute().unwrap();
+/// ```
+pub struct Delete<'a> {
+    table: String,
+    filters: Filters<'a>,
+}
+
+impl<'a> Delete<'a> {
+    /// Starts a delete from `table`.
+    pub fn from(table: &str) -> Delete<'a> {
+        Delete {
+            table: table.to_string(),
+            filters: Filters::default(),
+        }
+    }
+
+    /// Adds a condition selecting the deleted rows, with the values of its parameter markers in order.
+    /// Conditions are joined with `AND`.
+    pub fn filter(mut self, condition: &str, params: &[&'a dyn ToSql]) -> Delete<'a> {
+        self.filters.push(condition, params);
+        self
+    }
+
+    /// Generates the SQL of the delete.
+    pub fn sql(&self) -> String {
+        format!("DELETE FROM {}{}", self.table, self.filters.where_clause())
+    }
+
+    /// Returns the values of the parameter markers, in order.
+    pub fn params(&self) -> &[&'a dyn ToSql] {
+        &self.filters.params
+    }
+
+    /// Prepares the delete on `conn` with its values bound, ready for [execute](crate::Statement::execute()).
+    ///
+    /// # Errors
+    /// Returns -26043 when no conditions have been added, so that a statement never silently deletes every row of a table.
+    /// Otherwise returns [Err] when the statement couldn't be prepared or the values couldn't be bound.
+    pub fn prepare(&self, conn: &Connection) -> Result<Statement, i32> {
+        if self.filters.conditions.is_empty() {
+            return Err(-26043); // No conditions have been added
+        }
+        prepare_bound(conn, &self.sql(), self.params())
+    }
+}
+
+#[cfg(test)]
+mod query_builder_tests {
+    use super::*;
+    use crate::testing::*;
+
+    #[test]
+    fn generated_sql() {
+        let select = Select::from("t")
+            .filter("a = ?", &[&1])
+            .filter("b IS NULL", &[])
+            .order_by("a")
+            .order_by("c DESC")
+            .offset(10)
+            .limit(5);
+        assert_eq!(
+            select.sql(),
+            "SELECT * FROM t WHERE (a = ?) AND (b IS NULL) ORDER BY a, c DESC \
+             OFFSET 10 ROWS FETCH FIRST 5 ROWS ONLY"
+        );
+        assert_eq!(select.params().len(), 1);
+
+        // Values are bound in the order of their markers, whatever order the builder was called in
+        let update = Update::table("t")
+            .filter("a = ? OR a = ?", &[&1, &2])
+            .set("b", &"x");
+        assert_eq!(update.sql(), "UPDATE t SET b = ? WHERE (a = ? OR a = ?)");
+        assert_eq!(update.params()[0].to_sql(), "x".to_sql());
+
+        assert_eq!(Delete::from("t").sql(), "DELETE FROM t");
+    }
+
+    #[test]
+    fn crud_with_builders() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+        for i in 1..=5 {
+            let name = format!("value {i}");
+            Insert::into(EXAMPLE_TABLE)
+                .value("column_1", &name)
+                .value("column_2", &i)
+                .prepare(&conn)
+                .unwrap()
+                .execute()
+                .unwrap();
+        }
+
+        let rc = Update::table(EXAMPLE_TABLE)
+            .set("column_1", &"updated")
+            .filter("column_2 >= ?", &[&4])
+            .prepare(&conn)
+            .unwrap()
+            .execute()
+            .unwrap();
+        assert_eq!(rc, 2);
+        let rc = Delete::from(EXAMPLE_TABLE)
+            .filter("column_2 = ?", &[&1])
+            .prepare(&conn)
+            .unwrap()
+            .execute()
+            .unwrap();
+        assert_eq!(rc, 1);
+
+        let stmnt = Select::from(EXAMPLE_TABLE)
+            .columns(&["column_1"])
+            .filter("column_2 > ?", &[&2])
+            .order_by("column_2 DESC")
+            .limit(2)
+            .prepare(&conn)
+            .unwrap();
+        let mut cursor = stmnt.open_cursor().unwrap();
+        let mut values = Vec::new();
+        while let Some(row) = cursor.next_row().unwrap() {
+            values.push(row.get::<String>(1).unwrap().unwrap());
+        }
+        assert_eq!(values, vec!["updated", "updated"]);
+
+        assert_eq!(
+            Delete::from(EXAMPLE_TABLE).prepare(&conn).err(),
+            Some(-26043)
+        );
+        assert_eq!(
+            Update::table(EXAMPLE_TABLE)
+                .set("column_2", &0)
+                .prepare(&conn)
+                .err(),
+            Some(-26043)
+        );
+        assert_eq!(
+            Update::table(EXAMPLE_TABLE)
+                .filter("column_2 = ?", &[&0])
+                .prepare(&conn)
+                .err(),
+            Some(-26042)
+        );
+        assert_eq!(
+            Insert::into(EXAMPLE_TABLE).prepare(&conn).err(),
+            Some(-26042)
+        );
+    }
+}
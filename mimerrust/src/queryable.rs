@@ -0,0 +1,228 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{
+    row::OwnedRow, types::FromSql, Connection, CursorMode, Statement, ToSql, Transaction,
+    TransactionStatement,
+};
+
+/// The statements that can be run on a [Connection], written as a trait so that application code works the same
+/// on a connection, inside a [Transaction], on a pooled connection, and on a [MockConnection](crate::mock::MockConnection) in unit tests.
+///
+/// Rows are returned as [OwnedRow]s, since a [Row](crate::Row) can only be read from an open cursor.
+/// The provided methods mirror [Connection::query_row] and [Connection::query_scalar], so code written against the trait reads the same.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// fn add_and_count(conn: &impl Queryable, text: &str, number: i32) -> Result<Option<i32>, i32> {
+///     conn.execute("INSERT INTO test_table VALUES(?, ?)", &[&text, &number])?;
+///     conn.query_scalar::<i32>("SELECT CAST(COUNT(*) AS INTEGER) FROM test_table", &[])
+/// }
+///
+/// let conn = Connection::open(db, ident, pass).unwrap();
+/// # conn.execute_statement("drop table test_table").ok();
+/// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+/// assert_eq!(add_and_count(&conn, "the number one", 1), Ok(Some(1)));
+///
+/// let trans = conn.begin_transaction(TransactionMode::ReadWrite).unwrap();
+/// assert_eq!(add_and_count(&trans, "the number two", 2), Ok(Some(2)));
+/// trans.commit().unwrap();
+/// ```
+pub trait Queryable {
+    /// The type of the statements returned by [prepare](Queryable::prepare()).
+    type Statement<'s>: PreparedStatement
+    where
+        Self: 's;
+
+    /// Prepares a statement for execution, see [Connection::prepare].
+    ///
+    /// # Errors
+    /// Returns [Err] when the statement couldn't be prepared, or, [in a transaction](Transaction::prepare()), isn't allowed in its mode.
+    fn prepare(&self, sqlstatement: &str, option: CursorMode) -> Result<Self::Statement<'_>, i32>;
+
+    /// Executes a statement that doesn't return a result set, bound with `params`, and returns the number of rows affected.
+    ///
+    /// # Errors
+    /// Returns [Err] when the statement couldn't be prepared or executed.
+    fn execute(&self, sqlstatement: &str, params: &[&dyn ToSql]) -> Result<i32, i32> {
+        self.prepare(sqlstatement, CursorMode::Forward)?
+            .execute_bind(params)
+    }
+
+    /// Executes a query bound with `params` and returns every row of the result set.
+    ///
+    /// # Errors
+    /// Returns [Err] when the query couldn't be prepared or executed, or when a row couldn't be fetched.
+    fn query(&self, sqlstatement: &str, params: &[&dyn ToSql]) -> Result<Vec<OwnedRow>, i32> {
+        self.prepare(sqlstatement, CursorMode::Forward)?
+            .query(params)
+    }
+
+    /// Executes a query and maps the first row of the result set using the closure `f`.
+    /// Any rows after the first one are ignored.
+    ///
+    /// # Errors
+    /// Returns [Err] when the query couldn't be prepared or executed, when it returned no rows (-26009), or when `f` returns an error.
+    fn query_row<T, F>(&self, sqlstatement: &str, params: &[&dyn ToSql], f: F) -> Result<T, i32>
+    where
+        Self: Sized,
+        F: FnOnce(&OwnedRow) -> Result<T, i32>,
+    {
+        match self.query(sqlstatement, params)?.first() {
+            Some(row) => f(row),
+            None => Err(-26009), // Query returned no rows
+        }
+    }
+
+    /// Executes a query and returns the value of the first column of the first row.
+    /// Returns [`Ok<None>`] if the value is null.
+    ///
+    /// # Errors
+    /// Returns [Err] when the query couldn't be prepared or executed, when it returned no rows (-26009), or when the value couldn't be converted to `T`.
+    fn query_scalar<T: FromSql>(
+        &self,
+        sqlstatement: &str,
+        params: &[&dyn ToSql],
+    ) -> Result<Option<T>, i32>
+    where
+        Self: Sized,
+    {
+        Queryable::query_row(self, sqlstatement, params, |row| row.get::<T>(1))
+    }
+}
+
+/// A statement returned by [Queryable::prepare], which can be executed any number of times with different parameters.
+pub trait PreparedStatement {
+    /// Executes the statement bound with `params` and returns the number of rows affected, see [Statement::execute_bind].
+    ///
+    /// # Errors
+    /// Returns [Err] when the parameters couldn't be bound or the statement couldn't be executed.
+    fn execute_bind(&self, params: &[&dyn ToSql]) -> Result<i32, i32>;
+
+    /// Executes the statement as a query bound with `params` and returns every row of the result set.
+    ///
+    /// # Errors
+    /// Returns [Err] when the parameters couldn't be bound, the cursor couldn't be opened, or a row couldn't be fetched.
+    fn query(&self, params: &[&dyn ToSql]) -> Result<Vec<OwnedRow>, i32>;
+}
+
+impl PreparedStatement for Statement {
+    fn execute_bind(&self, params: &[&dyn ToSql]) -> Result<i32, i32> {
+        Statement::execute_bind(self, params)
+    }
+
+    fn query(&self, params: &[&dyn ToSql]) -> Result<Vec<OwnedRow>, i32> {
+        if !params.is_empty() {
+            self.bind_params(params)?;
+        }
+        self.open_cursor()?.into_iter().collect()
+    }
+}
+
+impl Queryable for Connection {
+    type Statement<'s> = Statement;
+
+    fn prepare(&self, sqlstatement: &str, option: CursorMode) -> Result<Statement, i32> {
+        Connection::prepare(self, sqlstatement, option)
+    }
+}
+
+impl PreparedStatement for TransactionStatement<'_> {
+    fn execute_bind(&self, params: &[&dyn ToSql]) -> Result<i32, i32> {
+        PreparedStatement::execute_bind(&**self, params)
+    }
+
+    fn query(&self, params: &[&dyn ToSql]) -> Result<Vec<OwnedRow>, i32> {
+        PreparedStatement::query(&**self, params)
+    }
+}
+
+impl Queryable for Transaction<'_> {
+    type Statement<'s>
+        = TransactionStatement<'s>
+    where
+        Self: 's;
+
+    fn prepare(
+        &self,
+        sqlstatement: &str,
+        option: CursorMode,
+    ) -> Result<TransactionStatement<'_>, i32> {
+        Transaction::prepare(self, sqlstatement, option)
+    }
+}
+
+#[cfg(test)]
+mod queryable_tests {
+    use super::*;
+    use crate::testing::*;
+
+    #[test]
+    fn connection_execute_and_query() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+
+        let inserted = Queryable::execute(
+            &conn,
+            &format!("INSERT INTO {EXAMPLE_TABLE} VALUES(?, ?)"),
+            &[&"one", &1],
+        )
+        .unwrap();
+        assert_eq!(inserted, 1);
+
+        let rows = Queryable::query(
+            &conn,
+            &format!("SELECT * FROM {EXAMPLE_TABLE} WHERE column_2 = ?"),
+            &[&1],
+        )
+        .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].get::<String>(1).unwrap().as_deref(), Some("one"));
+
+        let trans = conn
+            .begin_transaction(crate::TransactionMode::ReadOnly)
+            .unwrap();
+        let denied = Queryable::execute(&trans, &format!("DELETE FROM {EXAMPLE_TABLE}"), &[]);
+        assert_eq!(denied, Err(-26022));
+        let count = Queryable::query_scalar::<i32>(
+            &trans,
+            &format!("SELECT CAST(COUNT(*) AS INTEGER) FROM {EXAMPLE_TABLE}"),
+            &[],
+        );
+        assert_eq!(count, Ok(Some(1)));
+        trans.commit().unwrap();
+
+        let missing = Queryable::query_row(
+            &conn,
+            &format!("SELECT * FROM {EXAMPLE_TABLE} WHERE column_2 = ?"),
+            &[&2],
+            |row| row.get::<i32>(2),
+        );
+        assert_eq!(missing, Err(-26009));
+    }
+}
@@ -0,0 +1,145 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{
+    connection::Connection, connection_options::ConnectionOptions, mimer_error::MimerError,
+    queryable::Queryable, CursorMode, Statement,
+};
+
+/// An [r2d2](https://docs.rs/r2d2) connection manager for Mimer SQL connections.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// use mimerrust::r2d2::MimerConnectionManager;
+///
+/// let manager = MimerConnectionManager::new(db, ident, pass);
+/// let pool = r2d2::Pool::builder().max_size(4).build(manager).unwrap();
+///
+/// let conn = pool.get().unwrap();
+/// let one = conn.query_scalar::<i32>("SELECT 1 FROM SYSTEM.ONEROW", &[]).unwrap();
+/// assert_eq!(one, Some(1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MimerConnectionManager {
+    options: ConnectionOptions,
+}
+
+impl MimerConnectionManager {
+    /// Creates a connection manager opening connections to the given database as `ident`.
+    pub fn new(database: &str, ident: &str, password: &str) -> MimerConnectionManager {
+        MimerConnectionManager {
+            options: ConnectionOptions::new(database, ident, password),
+        }
+    }
+
+    /// Creates a connection manager opening connections with [Connection::open_with].
+    pub fn from_options(options: ConnectionOptions) -> MimerConnectionManager {
+        MimerConnectionManager { options }
+    }
+}
+
+impl ::r2d2::ManageConnection for MimerConnectionManager {
+    type Connection = Connection;
+    type Error = MimerError;
+
+    fn connect(&self) -> Result<Connection, MimerError> {
+        let result = Connection::open_with(&self.options);
+        #[cfg(feature = "metrics")]
+        crate::metrics::pool_connection_opened("r2d2", &result);
+        result
+    }
+
+    fn is_valid(&self, conn: &mut Connection) -> Result<(), MimerError> {
+        let result = conn.ping().map(|_| ()).map_err(|ec| conn.get_error(ec));
+        #[cfg(feature = "metrics")]
+        crate::metrics::pool_checkout("r2d2", &result);
+        result
+    }
+
+    fn has_broken(&self, _conn: &mut Connection) -> bool {
+        // Pinging every connection that is returned would cost a round trip each, is_valid pings when checking out instead.
+        false
+    }
+}
+
+impl Queryable for ::r2d2::PooledConnection<MimerConnectionManager> {
+    type Statement<'s> = Statement;
+
+    fn prepare(&self, sqlstatement: &str, option: CursorMode) -> Result<Statement, i32> {
+        Connection::prepare(self, sqlstatement, option)
+    }
+}
+
+#[cfg(test)]
+mod r2d2_tests {
+    use super::*;
+    use crate::testing::*;
+
+    #[test]
+    fn debug_hides_password() {
+        let debug = format!("{:?}", MimerConnectionManager::new("db", "ident", "s3cret"));
+        assert!(!debug.contains("s3cret"));
+    }
+
+    #[test]
+    fn pool_checkout() {
+        let db = std::env::var("MIMER_DATABASE").unwrap();
+        let manager = MimerConnectionManager::new(&db, IDENT, PASSWORD);
+        let pool = ::r2d2::Pool::builder()
+            .max_size(2)
+            .test_on_check_out(true)
+            .build(manager)
+            .unwrap();
+
+        let conn_1 = pool.get().unwrap();
+        let conn_2 = pool.get().unwrap();
+        assert_eq!(
+            conn_1
+                .query_scalar::<i32>("SELECT 1 FROM SYSTEM.ONEROW", &[])
+                .unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            conn_2
+                .query_scalar::<i32>("SELECT 1 FROM SYSTEM.ONEROW", &[])
+                .unwrap(),
+            Some(1)
+        );
+        drop(conn_1);
+        assert_eq!(pool.state().idle_connections, 1);
+    }
+
+    #[test]
+    fn connect_failure() {
+        let manager = MimerConnectionManager::new("non_existing_database", IDENT, PASSWORD);
+        let pool = ::r2d2::Pool::builder()
+            .connection_timeout(std::time::Duration::from_millis(500))
+            .build_unchecked(manager);
+        assert!(pool.get().is_err());
+    }
+}
@@ -0,0 +1,237 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{
+    common::mimer_options::CursorMode, connection::Connection,
+    connection_options::ConnectionOptions, mimer_error::MimerError, statement::Statement,
+};
+
+#[doc(hidden)]
+use std::ops::{Deref, DerefMut};
+
+/// Function called with the error that made a [ReconnectingConnection] reconnect.
+pub type ReconnectHook = Box<dyn Fn(&MimerError) + Send + Sync>;
+
+/// A connection that opens a new session when the connection to the server is lost, and prepares its cached statements again.
+///
+/// Work is run with [run](ReconnectingConnection::run()), which runs it once more on a new session if it fails because the connection was lost,
+/// see [is_connection_lost](crate::MimerError::is_connection_lost()). Statements prepared with [prepare_cached](ReconnectingConnection::prepare_cached())
+/// are kept across reconnects. Otherwise the ReconnectingConnection is used like a [Connection], which it dereferences to.
+///
+/// Only work that is safe to repeat should be run this way, since the server may have completed it before the connection was lost.
+/// A transaction that was open when the connection was lost is rolled back by the server.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// let mut conn = ReconnectingConnection::open(ConnectionOptions::new(db, ident, pass)).unwrap();
+/// conn.set_reconnect_hook(Some(Box::new(|err| eprintln!("reconnecting after: {err}"))));
+/// # conn.execute_statement("drop table test_table").ok();
+/// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+///
+/// conn.run(|conn| {
+///     let stmnt = conn.prepare_cached("INSERT INTO test_table VALUES(:text, :number)", CursorMode::Forward)?;
+///     stmnt.execute_bind(&[&"the number one", &1])
+/// })
+/// .unwrap();
+/// ```
+pub struct ReconnectingConnection {
+    options: ConnectionOptions,
+    connection: Connection,
+    statements: Vec<(String, CursorMode, Statement)>,
+    reconnect_hook: Option<ReconnectHook>,
+}
+
+impl ReconnectingConnection {
+    /// Opens a connection with [Connection::open_with], keeping the options for reconnecting.
+    ///
+    /// # Errors
+    /// Returns [Err] holding a [MimerError] when the connection failed to open.
+    pub fn open(options: ConnectionOptions) -> Result<ReconnectingConnection, MimerError> {
+        let connection = Connection::open_with(&options)?;
+        Ok(ReconnectingConnection {
+            options,
+            connection,
+            statements: Vec::new(),
+            reconnect_hook: None,
+        })
+    }
+
+    /// Sets a function that is called with the error that made the connection reconnect, or removes it with [None].
+    pub fn set_reconnect_hook(&mut self, hook: Option<ReconnectHook>) {
+        self.reconnect_hook = hook;
+    }
+
+    /// Returns a statement prepared for `sqlstatement` and `option`, preparing it on the first call.
+    /// The statement is kept by the connection, and prepared again on the new session after a reconnect.
+    ///
+    /// # Errors
+    /// Returns [Err] when the statement couldn't be prepared.
+    pub fn prepare_cached(
+        &mut self,
+        sqlstatement: &str,
+        option: CursorMode,
+    ) -> Result<&Statement, i32> {
+        let idx = match self
+            .statements
+            .iter()
+            .position(|(sql, mode, _)| sql == sqlstatement && *mode == option)
+        {
+            Some(idx) => idx,
+            None => {
+                let stmnt = self.connection.prepare(sqlstatement, option)?;
+                self.statements
+                    .push((sqlstatement.to_string(), option, stmnt));
+                self.statements.len() - 1
+            }
+        };
+        Ok(&self.statements[idx].2)
+    }
+
+    /// Runs `f`, and if it fails because the connection was lost, reconnects and runs it once more.
+    ///
+    /// # Errors
+    /// Returns the error from `f`, or the error code from reconnecting if that fails.
+    pub fn run<T, F>(&mut self, mut f: F) -> Result<T, i32>
+    where
+        F: FnMut(&mut ReconnectingConnection) -> Result<T, i32>,
+    {
+        match f(self) {
+            Err(ec) if MimerError::mimer_error_from_code(ec).is_connection_lost() => {
+                let cause = self.connection.get_error(ec);
+                self.reconnect(&cause).map_err(|err| err.get_error_code())?;
+                f(self)
+            }
+            result => result,
+        }
+    }
+
+    /// Opens a new session, replacing the current one, and prepares the cached statements on it.
+    /// `cause` is passed to the [reconnect hook](ReconnectingConnection::set_reconnect_hook()).
+    ///
+    /// # Errors
+    /// Returns [Err] holding a [MimerError] when the connection couldn't be opened or a statement couldn't be prepared again.
+    /// The cached statements are dropped if a statement couldn't be prepared.
+    pub fn reconnect(&mut self, cause: &MimerError) -> Result<(), MimerError> {
+        if let Some(hook) = &self.reconnect_hook {
+            hook(cause);
+        }
+        let cached: Vec<(String, CursorMode)> = self
+            .statements
+            .drain(..)
+            .map(|(sql, mode, _)| (sql, mode))
+            .collect();
+        self.connection = Connection::open_with(&self.options)?;
+        for (sql, mode) in cached {
+            match self.connection.prepare(&sql, mode) {
+                Ok(stmnt) => self.statements.push((sql, mode, stmnt)),
+                Err(ec) => {
+                    let err = self.connection.get_error(ec);
+                    self.statements.clear();
+                    return Err(err);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Deref for ReconnectingConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        &self.connection
+    }
+}
+
+impl DerefMut for ReconnectingConnection {
+    fn deref_mut(&mut self) -> &mut Connection {
+        &mut self.connection
+    }
+}
+
+impl Drop for ReconnectingConnection {
+    fn drop(&mut self) {
+        // The statements are ended before the session they belong to
+        self.statements.clear();
+    }
+}
+
+#[cfg(test)]
+mod reconnecting_tests {
+    use super::*;
+    use crate::testing::*;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[test]
+    fn reconnect_keeps_cached_statements() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+        drop(conn);
+
+        let db = std::env::var("MIMER_DATABASE").unwrap();
+        let mut conn =
+            ReconnectingConnection::open(ConnectionOptions::new(&db, IDENT, PASSWORD)).unwrap();
+        let reconnects = Arc::new(AtomicUsize::new(0));
+        let reconnects_ref = reconnects.clone();
+        conn.set_reconnect_hook(Some(Box::new(move |err| {
+            assert_eq!(err.get_error_code(), -26003);
+            reconnects_ref.fetch_add(1, Ordering::Relaxed);
+        })));
+
+        let insert = format!("INSERT INTO {EXAMPLE_TABLE} VALUES(:text, :number)");
+        let mut attempts = 0;
+        conn.run(|conn| {
+            attempts += 1;
+            let stmnt = conn.prepare_cached(&insert, CursorMode::Forward)?;
+            match attempts {
+                1 => Err(-26003), // Connection is dropped
+                _ => stmnt.execute_bind(&[&"one", &1]),
+            }
+        })
+        .unwrap();
+        assert_eq!(attempts, 2);
+        assert_eq!(reconnects.load(Ordering::Relaxed), 1);
+        assert_eq!(conn.statements.len(), 1);
+
+        let count = conn
+            .query_scalar::<i32>(
+                &format!("SELECT CAST(COUNT(*) AS INTEGER) FROM {EXAMPLE_TABLE}"),
+                &[],
+            )
+            .unwrap();
+        assert_eq!(count, Some(1));
+
+        // Errors that don't mean a lost connection are returned as they are
+        let result: Result<(), i32> = conn.run(|_| Err(-12501));
+        assert_eq!(result, Err(-12501));
+        assert_eq!(reconnects.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,196 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+/// Defines a struct that maps a row of a table, together with `find_by_id`, `insert`, `update`, `delete` and `delete_by_id` functions for it.
+///
+/// The first field is the key of the table and is marked with `#[key]`. Fields are mapped to the columns with the same name,
+/// and a struct has at most 12 fields, as they are fetched through [get_tuple](crate::Row::get_tuple()).
+/// Fields of type [`Option<T>`] map nullable columns. The generated functions return error codes like the rest of the crate:
+///
+/// - `find_by_id(conn, &id) -> Result<Option<Self>, i32>` selects the row with the given key.
+/// - `insert(&self, conn)`, `update(&self, conn)` and `delete(&self, conn)` write the row, using
+///   [insert_values](crate::Connection::insert_values()), [update_by_key](crate::Connection::update_by_key()) and [delete_by_key](crate::Connection::delete_by_key()).
+/// - `delete_by_id(conn, &id)` deletes the row with the given key.
+///
+/// The table name is also available as the associated constant `TABLE`.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// mimer_repository! {
+///     #[derive(Debug, Clone, PartialEq)]
+///     pub struct Customer in "customer" {
+///         #[key]
+///         pub id: i32,
+///         pub name: String,
+///         pub email: Option<String>,
+///     }
+/// }
+///
+/// let conn = Connection::open(db, ident, pass).unwrap();
+/// # conn.execute_statement("drop table customer").ok();
+/// conn.execute_statement("create table customer (id INT PRIMARY KEY, name VARCHAR(30), email VARCHAR(50))").unwrap();
+///
+/// let mut customer = Customer { id: 1, name: String::from("Ada"), email: None };
+/// customer.insert(&conn).unwrap();
+///
+/// customer.email = Some(String::from("ada@example.com"));
+/// customer.update(&conn).unwrap();
+/// assert_eq!(Customer::find_by_id(&conn, &1).unwrap(), Some(customer.clone()));
+///
+/// customer.delete(&conn).unwrap();
+/// assert_eq!(Customer::find_by_id(&conn, &1).unwrap(), None);
+/// ```
+#[macro_export]
+macro_rules! mimer_repository {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident in $table:literal {
+            #[key]
+            $(#[$key_meta:meta])*
+            $key_vis:vis $key:ident : $key_ty:ty
+            $(,
+                $(#[$field_meta:meta])*
+                $field_vis:vis $field:ident : $field_ty:ty
+            )* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $(#[$key_meta])*
+            $key_vis $key: $key_ty,
+            $(
+                $(#[$field_meta])*
+                $field_vis $field: $field_ty,
+            )*
+        }
+
+        impl $name {
+            /// Name of the table the rows are stored in.
+            pub const TABLE: &'static str = $table;
+
+            /// Selects the row with the given key, or returns [None] if there is no such row.
+            $vis fn find_by_id(
+                conn: &$crate::Connection,
+                id: &$key_ty,
+            ) -> ::std::result::Result<::std::option::Option<$name>, i32> {
+                let columns = [stringify!($key) $(, stringify!($field))*].join(", ");
+                let mut rows = conn.select_by_key(
+                    $table,
+                    &columns,
+                    &[(stringify!($key), id as &dyn $crate::ToSql)],
+                    |row| {
+                        let ($key, $($field,)*) = row.get_tuple::<($key_ty, $($field_ty,)*)>()?;
+                        Ok($name { $key, $($field,)* })
+                    },
+                )?;
+                Ok(if rows.is_empty() { None } else { Some(rows.swap_remove(0)) })
+            }
+
+            /// Inserts the row.
+            $vis fn insert(&self, conn: &$crate::Connection) -> ::std::result::Result<i32, i32> {
+                conn.insert_values(
+                    $table,
+                    &[
+                        (stringify!($key), &self.$key as &dyn $crate::ToSql),
+                        $((stringify!($field), &self.$field as &dyn $crate::ToSql),)*
+                    ],
+                )
+            }
+
+            /// Updates every column but the key of the row with the key of `self`.
+            $vis fn update(&self, conn: &$crate::Connection) -> ::std::result::Result<i32, i32> {
+                conn.update_by_key(
+                    $table,
+                    &[$((stringify!($field), &self.$field as &dyn $crate::ToSql),)*],
+                    &[(stringify!($key), &self.$key as &dyn $crate::ToSql)],
+                )
+            }
+
+            /// Deletes the row with the key of `self`.
+            $vis fn delete(&self, conn: &$crate::Connection) -> ::std::result::Result<i32, i32> {
+                Self::delete_by_id(conn, &self.$key)
+            }
+
+            /// Deletes the row with the given key.
+            $vis fn delete_by_id(
+                conn: &$crate::Connection,
+                id: &$key_ty,
+            ) -> ::std::result::Result<i32, i32> {
+                conn.delete_by_key($table, &[(stringify!($key), id as &dyn $crate::ToSql)])
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod repository_tests {
+    use crate::testing::*;
+
+    crate::mimer_repository! {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Measurement in "measurement" {
+            #[key]
+            id: i64,
+            sensor: String,
+            reading: Option<f64>,
+        }
+    }
+
+    #[test]
+    fn repository_crud() {
+        let conn = establish_connection();
+        drop_create_table(
+            &conn,
+            Measurement::TABLE,
+            "(id BIGINT PRIMARY KEY, sensor VARCHAR(30), reading DOUBLE PRECISION)",
+        );
+
+        let mut measurement = Measurement {
+            id: 7,
+            sensor: String::from("north"),
+            reading: None,
+        };
+        measurement.insert(&conn).unwrap();
+        assert_eq!(
+            Measurement::find_by_id(&conn, &7).unwrap(),
+            Some(measurement.clone())
+        );
+        assert!(measurement.insert(&conn).is_err()); // duplicate key
+
+        measurement.reading = Some(21.5);
+        measurement.update(&conn).unwrap();
+        assert_eq!(
+            Measurement::find_by_id(&conn, &7).unwrap(),
+            Some(measurement.clone())
+        );
+
+        measurement.delete(&conn).unwrap();
+        assert_eq!(Measurement::find_by_id(&conn, &7).unwrap(), None);
+        assert_eq!(Measurement::find_by_id(&conn, &8).unwrap(), None);
+    }
+}
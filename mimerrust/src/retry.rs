@@ -0,0 +1,248 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+#[doc(hidden)]
+use std::{fmt, sync::Arc, thread, time::Duration};
+
+/// Error code returned when a transaction is aborted because of a conflict with another transaction.
+///
+/// Mimer SQL uses optimistic concurrency control, so lock conflicts and deadlocks between sessions are reported with this code.
+pub const TRANSACTION_CONFLICT: i32 = -10001;
+
+/// Describes a retry made by a statement with a [StatementRetry] policy, as passed to the [on_retry](StatementRetry::on_retry()) observer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryEvent {
+    /// Number of the retry that is about to be made, starting at 1.
+    pub attempt: u32,
+    /// Error code of the failed execution.
+    pub error_code: i32,
+    /// How long the statement waits before it is executed again.
+    pub delay: Duration,
+}
+
+/// An opt-in policy for re-executing a statement when it fails with a transient error, set with [set_retry](crate::Statement::set_retry()).
+/// The same policy retries a whole unit of work with [run_with_retry](crate::Connection::run_with_retry()).
+///
+/// The delay between executions starts at the initial backoff and is doubled for each retry, up to the maximum backoff.
+/// By default only [TRANSACTION_CONFLICT] is retried.
+///
+/// Retrying a single statement is only meaningful when it runs in its own transaction, i.e. outside a [Transaction](crate::Transaction).
+/// Inside a transaction a conflict aborts the whole transaction, which has to be retried by the caller.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::*;
+/// # use std::time::Duration;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// let conn = Connection::open(db, ident, pass).unwrap();
+/// # conn.execute_statement("drop table test_table").ok();
+/// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+/// let mut stmnt = conn.prepare("UPDATE test_table SET column_2 = column_2 + 1", CursorMode::Forward).unwrap();
+/// stmnt.set_retry(Some(
+///     StatementRetry::new(3)
+///         .backoff(Duration::from_millis(10), Duration::from_millis(200))
+///         .on_retry(|event| eprintln!("retry {} after error {}", event.attempt, event.error_code)),
+/// ));
+/// stmnt.execute().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct StatementRetry {
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    error_codes: Vec<i32>,
+    observer: Option<RetryObserver>,
+}
+
+type RetryObserver = Arc<dyn Fn(&RetryEvent) + Send + Sync>;
+
+impl StatementRetry {
+    /// Creates a policy making at most `max_retries` retries, with a backoff starting at 10 ms and capped at 1 s.
+    pub fn new(max_retries: u32) -> StatementRetry {
+        StatementRetry {
+            max_retries,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+            error_codes: vec![TRANSACTION_CONFLICT],
+            observer: None,
+        }
+    }
+
+    /// Sets the delay before the first retry, and the cap for the doubled delays of later retries.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> StatementRetry {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Sets the error codes that are retried, replacing the default.
+    pub fn retry_on(mut self, error_codes: &[i32]) -> StatementRetry {
+        self.error_codes = error_codes.to_vec();
+        self
+    }
+
+    /// Sets a function that is called before each retry.
+    pub fn on_retry<F>(mut self, observer: F) -> StatementRetry
+    where
+        F: Fn(&RetryEvent) + Send + Sync + 'static,
+    {
+        self.observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Returns whether an error code is retried by this policy.
+    pub fn is_retried(&self, error_code: i32) -> bool {
+        self.error_codes.contains(&error_code)
+    }
+
+    /// Returns the delay before a retry, starting at 1.
+    fn delay(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt - 1).unwrap_or(u32::MAX);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    /// Runs `f`, retrying it according to the policy.
+    pub(crate) fn run<T, F>(&self, mut f: F) -> Result<T, i32>
+    where
+        F: FnMut() -> Result<T, i32>,
+    {
+        let mut attempt = 0;
+        loop {
+            match f() {
+                Err(error_code) if attempt < self.max_retries && self.is_retried(error_code) => {
+                    attempt += 1;
+                    let delay = self.delay(attempt);
+                    if let Some(observer) = &self.observer {
+                        observer(&RetryEvent {
+                            attempt,
+                            error_code,
+                            delay,
+                        });
+                    }
+                    thread::sleep(delay);
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl fmt::Debug for StatementRetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StatementRetry")
+            .field("max_retries", &self.max_retries)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("error_codes", &self.error_codes)
+            .field("observer", &self.observer.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod retry_tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn retry_backoff() {
+        let retry =
+            StatementRetry::new(10).backoff(Duration::from_millis(10), Duration::from_millis(50));
+        assert_eq!(retry.delay(1), Duration::from_millis(10));
+        assert_eq!(retry.delay(2), Duration::from_millis(20));
+        assert_eq!(retry.delay(3), Duration::from_millis(40));
+        assert_eq!(retry.delay(4), Duration::from_millis(50));
+        assert_eq!(retry.delay(40), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn retry_transient_errors() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_ref = events.clone();
+        let retry = StatementRetry::new(2)
+            .backoff(Duration::ZERO, Duration::ZERO)
+            .on_retry(move |event| events_ref.lock().unwrap().push(event.clone()));
+
+        // Succeeds on the second attempt
+        let mut calls = 0;
+        let result = retry.run(|| {
+            calls += 1;
+            match calls {
+                1 => Err(TRANSACTION_CONFLICT),
+                _ => Ok(calls),
+            }
+        });
+        assert_eq!(result, Ok(2));
+        assert_eq!(events.lock().unwrap().len(), 1);
+        assert_eq!(events.lock().unwrap()[0].attempt, 1);
+
+        // Gives up after max_retries
+        let mut calls = 0;
+        let result: Result<(), i32> = retry.run(|| {
+            calls += 1;
+            Err(TRANSACTION_CONFLICT)
+        });
+        assert_eq!(result, Err(TRANSACTION_CONFLICT));
+        assert_eq!(calls, 3);
+
+        // Other errors are returned at once
+        let mut calls = 0;
+        let result: Result<(), i32> = retry.run(|| {
+            calls += 1;
+            Err(-12501)
+        });
+        assert_eq!(result, Err(-12501));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn run_with_retry() {
+        let conn = crate::testing::establish_connection();
+        let retry = StatementRetry::new(3).backoff(Duration::ZERO, Duration::ZERO);
+
+        let mut calls = 0;
+        let result = conn.run_with_retry(&retry, |conn| {
+            calls += 1;
+            let trans = conn.begin_transaction(crate::TransactionMode::ReadWrite)?;
+            match calls {
+                1 => Err(TRANSACTION_CONFLICT), // rolled back when trans is dropped
+                _ => trans.commit(),
+            }
+        });
+        assert!(result.is_ok());
+        assert_eq!(calls, 2);
+
+        let mut calls = 0;
+        let result: Result<(), i32> = conn.run_with_retry(&retry, |_| {
+            calls += 1;
+            Err(-12501)
+        });
+        assert_eq!(result, Err(-12501));
+        assert_eq!(calls, 1);
+    }
+}
@@ -22,7 +22,15 @@
 * See license for more details.
 * *********************************************************************/
 
-use crate::{common::return_codes::MIMER_SUCCESS, common::traits::*, inner_statement::*, types::*};
+use crate::{
+    common::mimer_options::{ColumnNameResolution, ColumnType},
+    common::return_codes::MIMER_SUCCESS,
+    common::traits::*,
+    ffi_buffer::{read_string, read_string_buffered},
+    inner_statement::*,
+    lob::{open_lob, read_blob_data, ClobReader},
+    types::*,
+};
 use crate::{
     match_mimer_BINARY, match_mimer_BLOB, match_mimer_CLOB, match_mimer_big_ints,
     match_mimer_booleans, match_mimer_doubles, match_mimer_real, match_mimer_small_ints,
@@ -31,7 +39,15 @@ use crate::{
 use mimerrust_sys as ffi;
 
 #[doc(hidden)]
-use std::{cmp::Ordering, ffi::CString, ptr::null_mut, sync::Weak};
+use std::{cmp::Ordering, fmt, ptr::null_mut, sync::Weak};
+
+/// Returns whether column `idx` of the current row holds a null value.
+pub(crate) fn is_null(handle: ffi::MimerStatement, idx: i16) -> Result<bool, i32> {
+    match unsafe { ffi::MimerIsNull(handle, idx) } {
+        rc if rc < 0 => Err(rc),
+        rc => Ok(rc > 0),
+    }
+}
 
 #[derive(Clone)]
 /// Represents a row in a result set.
@@ -51,7 +67,7 @@ impl Row {
     /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
     /// # let ident = "RUSTUSER";
     /// # let pass = "RUSTPASSWORD";
-    /// let mut conn = Connection::open(db, ident, pass).unwrap();
+    /// let conn = Connection::open(db, ident, pass).unwrap();
     /// # conn.execute_statement("drop table test_table").ok();
     /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
     /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
@@ -98,33 +114,13 @@ impl Row {
                     }
                 }
             }
-            match_mimer_strings!() => unsafe {
-                let mut size = ffi::MimerGetString8(*handle, idx, std::ptr::null_mut(), 0);
-
-                if size < 0 {
-                    return Err(size);
-                } else {
-                    size += 1;
-                }
-
-                let buffer = vec![0u8; size as usize];
-                let c_str = CString::from_vec_unchecked(buffer);
-                let c_str_ptr = c_str.into_raw();
-
-                let rc = ffi::MimerGetString8(*handle, idx, c_str_ptr, size as usize);
-
-                // retake pointer to free memory
-                let maybe_string = CString::from_raw(c_str_ptr).into_string();
-
-                match maybe_string {
-                    Ok(s) => match rc {
-                        _ if rc + 1 == size as i32 => Ok(MimerDatatype::String(s)),
-                        ffi::MIMER_SQL_NULL_VALUE => Ok(MimerDatatype::Null),
-                        _ => Err(size),
-                    },
-                    Err(_) => Err(-26001),
-                }
-            },
+            match_mimer_strings!() => {
+                let mut buffer = strong_inner_statement.string_buffer.lock();
+                let value = read_string_buffered(&mut buffer, |buf, size| unsafe {
+                    ffi::MimerGetString8(*handle, idx, buf, size)
+                })?;
+                Ok(value.map_or(MimerDatatype::Null, MimerDatatype::String))
+            }
             match_mimer_real!() => {
                 let mut val: f32 = 0.0;
                 unsafe {
@@ -159,7 +155,21 @@ impl Row {
                     }
                 }
             }
+            ffi::MIMER_UUID => {
+                let mut val = [0u8; 16];
+                unsafe {
+                    let err = ffi::MimerGetUUID(*handle, idx, val.as_mut_ptr());
+                    match err {
+                        ffi::MIMER_SQL_NULL_VALUE => Ok(MimerDatatype::Null),
+                        _ if err < 0 => Err(err),
+                        _ => Ok(MimerDatatype::Uuid(val)),
+                    }
+                }
+            }
             match_mimer_BINARY!() | match_mimer_spatial!() => {
+                if is_null(*handle, idx)? {
+                    return Ok(MimerDatatype::Null);
+                }
                 let bytes = unsafe { ffi::MimerGetBinary(*handle, idx, null_mut(), 0) };
                 if bytes < 0 {
                     return Err(bytes);
@@ -178,107 +188,43 @@ impl Row {
             }
 
             match_mimer_BLOB!() => {
-                let mut blob_len: usize = 0;
-                let mut blob_handle: ffi::MimerLob = std::ptr::null_mut();
-                let mut val: Vec<u8> = Vec::new();
-                unsafe {
-                    let err = ffi::MimerGetLob(*handle, idx, &mut blob_len, &mut blob_handle);
-                    if err < 0 {
-                        return Err(err);
-                    }
-                    let mut left_to_return = blob_len;
-                    val.resize(blob_len, 0);
-                    let blob_idx = 0;
-                    while left_to_return > 0 {
-                        let to_recieve = std::cmp::min(left_to_return, LOB_CHUNK_MAXSIZE_SET);
-                        let err = ffi::MimerGetBlobData(
-                            &mut blob_handle,
-                            val.as_mut_ptr().add(blob_idx) as *mut std::ffi::c_void,
-                            to_recieve,
-                        );
-                        if err < 0 {
-                            return Err(err);
-                        }
-                        left_to_return -= to_recieve;
-                    }
-                    Ok(MimerDatatype::BinaryArray(val))
-                }
+                let (mut blob_handle, blob_len) = match open_lob(*handle, idx)? {
+                    Some(lob) => lob,
+                    None => return Ok(MimerDatatype::Null),
+                };
+                let mut val: Vec<u8> = vec![0; blob_len];
+                read_blob_data(&mut blob_handle, &mut val)?;
+                Ok(MimerDatatype::BinaryArray(val))
             }
-            match_mimer_CLOB!() => {
-                let mut clob_len: usize = 0;
-                let mut clob_handle: ffi::MimerLob = std::ptr::null_mut();
-                let mut val: Vec<i8> = Vec::new();
-                unsafe {
-                    let err = ffi::MimerGetLob(*handle, idx, &mut clob_len, &mut clob_handle);
-                    if err < 0 {
-                        return Err(err);
-                    }
-                    let mut left_to_return = clob_len * 4 + 1;
-                    val.resize(clob_len * 4 + 1, 0);
-                    let mut clob_idx = 0;
-                    while left_to_return > 0 {
-                        let to_recieve = std::cmp::min(left_to_return, LOB_CHUNK_MAXSIZE_SET);
-                        let err = ffi::MimerGetNclobData8(
-                            &mut clob_handle,
-                            val.as_mut_ptr().add(clob_idx),
-                            to_recieve,
-                        );
-                        if err < 0 {
-                            return Err(err);
+            match_mimer_CLOB!() => match open_lob(*handle, idx)? {
+                Some((lob, length)) => Ok(MimerDatatype::String(
+                    ClobReader::new(lob, length).read_all()?,
+                )),
+                None => Ok(MimerDatatype::Null),
+            },
+            match_mimer_temporal!() => {
+                let mut buffer = strong_inner_statement.string_buffer.lock();
+                // The buffer is never empty, as getting the size with a null pointer causes a segfault for temporal columns
+                let value = read_string_buffered(&mut buffer, |buf, size| unsafe {
+                    ffi::MimerGetString8(*handle, idx, buf, size)
+                })?;
+                Ok(match value {
+                    None => MimerDatatype::Null,
+                    Some(s) => match ColumnType::from_code(column_type) {
+                        ColumnType::Date | ColumnType::Time | ColumnType::Timestamp => {
+                            MimerDatatype::String(s)
                         }
-                        left_to_return -= to_recieve;
-                        clob_idx += LOB_CHUNK_MAXSIZE_SET;
-                    }
-                    Ok(MimerDatatype::String(
-                        String::from_utf8(
-                            val.iter().filter(|&&c| c != 0).map(|&c| c as u8).collect(),
-                        )
-                        .or_else(|_| Err(-26999))?,
-                    ))
-                }
-            }
-            match_mimer_temporal!() => unsafe {
-                //TODO: when bug is fixed, get size with nullptr instead of dummy buffer
-                let c_str_dummy = CString::new(vec![255u8; 20]).unwrap();
-                let dummy_ptr = c_str_dummy.into_raw();
-
-                // getting the size with a nullpointer here instead of val as ptr causes a segfault. This is only the case for temporal columns, and not for others string columns.
-                let mut size = ffi::MimerGetString8(*handle, idx, dummy_ptr, 0);
-
-                // retake pointer to free memory
-                let _ = CString::from_raw(dummy_ptr);
-
-                if size < 0 {
-                    return Err(size);
-                }
-
-                size += 1;
-
-                let buffer = vec![0u8; size as usize];
-                let c_str = CString::from_vec_unchecked(buffer);
-                let c_str_ptr = c_str.into_raw();
-
-                let rc = ffi::MimerGetString8(*handle, idx, c_str_ptr, size as usize);
-
-                // retake pointer to free memory
-                let maybe_string = CString::from_raw(c_str_ptr).into_string();
-
-                match maybe_string {
-                    Ok(s) => match rc {
-                        _ if rc + 1 == size as i32 => Ok(MimerDatatype::String(s)),
-                        ffi::MIMER_SQL_NULL_VALUE => Ok(MimerDatatype::Null),
-                        _ => Err(size),
+                        interval => MimerDatatype::Interval(s, interval),
                     },
-                    Err(_) => Err(-26001),
-                }
-            },
+                })
+            }
             _ => Err(-26201),
         }
     }
 
     /// Gets the content from a specified index in a row using polymorphism.
     /// Returns a [Result] of either [`Ok<Option<T>>`] or [`Err<i32>`] where T is the type specified and i32 is the error code.
-    /// If a null value is fetched, the return value will be [`Ok<None>`].
+    /// If a null value is fetched, the return value will be [`Ok<None>`], or `Ok(Some(None))` if T is itself an [Option].
     ///
     /// # Errors
     /// Returns [Err] when conversion to the specified type fails.
@@ -289,7 +235,7 @@ impl Row {
     /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
     /// # let ident = "RUSTUSER";
     /// # let pass = "RUSTPASSWORD";
-    /// let mut conn = Connection::open(db, ident, pass).unwrap();
+    /// let conn = Connection::open(db, ident, pass).unwrap();
     /// # conn.execute_statement("drop table test_table").unwrap();
     /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
     /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
@@ -300,19 +246,72 @@ impl Row {
     /// let str:String = row.get(1).unwrap().unwrap();
     /// ```
     pub fn get<T: FromSql>(&self, idx: i16) -> Result<Option<T>, i32> {
-        let val = self.get_type(idx);
-        match val {
-            Ok(val) => match T::from_sql(val) {
-                Ok(val) => Ok(Some(val)),
-                Err(err) => Err(err),
-            },
-            Err(err) => match err.cmp(&ffi::MIMER_SQL_NULL_VALUE) {
-                Ordering::Equal => Ok(None),
-                _ => Err(err),
-            },
+        match self.get_type(idx) {
+            Ok(MimerDatatype::Null) | Err(ffi::MIMER_SQL_NULL_VALUE) => Ok(from_null::<T>()),
+            Ok(val) => T::from_sql(val).map(Some),
+            Err(err) => Err(err),
         }
     }
 
+    /// Gets the content of the column with the given name, see [get](crate::Row::get()).
+    /// The name is resolved with the [column name resolution](crate::Connection::set_column_name_resolution()) of the connection.
+    ///
+    /// # Errors
+    /// Returns [Err] when no column matches the name, or when conversion to the specified type fails.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
+    /// let stmnt = conn.prepare("SELECT * FROM test_table", CursorMode::Forward).unwrap();
+    /// let mut cursor = stmnt.open_cursor().unwrap();
+    ///
+    /// let row = cursor.next_row().unwrap().unwrap();
+    /// let num: i32 = row.get_by_name("column_2").unwrap().unwrap(); // reported by the server as COLUMN_2
+    /// ```
+    pub fn get_by_name<T: FromSql>(&self, name: &str) -> Result<Option<T>, i32> {
+        self.get(self.column_index(name)?)
+    }
+
+    /// Returns the index, starting at 1, of the column with the given name.
+    /// The name is resolved with the [column name resolution](crate::Connection::set_column_name_resolution()) of the connection.
+    /// If several columns match, the first one is returned.
+    ///
+    /// # Errors
+    /// Returns [Err] when no column matches the name.
+    pub fn column_index(&self, name: &str) -> Result<i16, i32> {
+        let strong_inner_statement = self.inner_statement.upgrade().ok_or(-26004)?;
+        let resolution = ColumnNameResolution::from_u8(
+            strong_inner_statement
+                .inner_connection
+                .upgrade()
+                .ok_or(-26003)?
+                .column_name_resolution
+                .load(std::sync::atomic::Ordering::Relaxed),
+        );
+        let handle = strong_inner_statement.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
+
+        let column_count = unsafe { ffi::MimerColumnCount(*handle) };
+        if column_count < 0 {
+            return Err(column_count);
+        }
+        for idx in 1..=column_count as i16 {
+            let column_name =
+                read_string(|buf, size| unsafe { ffi::MimerColumnName8(*handle, idx, buf, size) })?
+                    .unwrap_or_default();
+            if resolution.matches(&column_name, name) {
+                return Ok(idx);
+            }
+        }
+        Err(-26018) // No column with the given name
+    }
+
     /// Checks if the value at the specified index is null.
     ///
     /// # Examples
@@ -321,7 +320,7 @@ impl Row {
     /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
     /// # let ident = "RUSTUSER";
     /// # let pass = "RUSTPASSWORD";
-    /// let mut conn = Connection::open(db, ident, pass).unwrap();
+    /// let conn = Connection::open(db, ident, pass).unwrap();
     /// # conn.execute_statement("drop table test_table").ok();
     /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
     /// let stmnt = conn.prepare("INSERT INTO test_table (column_1) VALUES(?)", CursorMode::Forward).unwrap();
@@ -352,6 +351,323 @@ impl Row {
             }
         }
     }
+
+    /// Gets the leading columns of the row as a tuple, one column per element starting at index 1.
+    /// Elements of type [`Option<T>`] get [None] for null values, while a null value for any other element gives the error code -26027.
+    ///
+    /// # Errors
+    /// Returns [Err] when a column doesn't exist, when a conversion fails, or when a null value is fetched into an element that isn't an [Option] (-26027).
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
+    /// let stmnt = conn.prepare("SELECT column_2, column_1, CAST(NULL AS DOUBLE PRECISION) FROM test_table", CursorMode::Forward).unwrap();
+    /// let mut cursor = stmnt.open_cursor().unwrap();
+    ///
+    /// let row = cursor.next_row().unwrap().unwrap();
+    /// let (number, text, missing) = row.get_tuple::<(i32, String, Option<f64>)>().unwrap();
+    /// assert_eq!((number, text.as_str(), missing), (1, "the number one", None));
+    /// ```
+    pub fn get_tuple<T: FromRow>(&self) -> Result<T, i32> {
+        T::from_row(self)
+    }
+
+    /// Gets the leading columns of the row as a tuple like [get_tuple](Row::get_tuple()), but describes a failing conversion in a [MappingError]
+    /// with the column name and SQL type, the Rust type and the fetched value, instead of a bare error code. Meant for tracking down mapping mismatches during development.
+    ///
+    /// # Errors
+    /// Returns [Err] with the first column that couldn't be fetched.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
+    /// let stmnt = conn.prepare("SELECT column_2, column_1 FROM test_table", CursorMode::Forward).unwrap();
+    /// let mut cursor = stmnt.open_cursor().unwrap();
+    ///
+    /// let row = cursor.next_row().unwrap().unwrap();
+    /// let err = row.get_tuple_diagnosed::<(i32, bool)>().unwrap_err();
+    /// assert_eq!(err.column, 2);
+    /// assert_eq!(err.column_name.as_deref(), Some("COLUMN_1"));
+    /// println!("{err}");
+    /// ```
+    pub fn get_tuple_diagnosed<T: FromRow>(&self) -> Result<T, MappingError> {
+        T::from_row_diagnosed(self)
+    }
+
+    /// Fetches and decodes every column of the row into an [OwnedRow].
+    /// The returned row holds its own copy of the data and stays valid after the cursor has moved on, which makes it possible to hand rows over to other threads.
+    ///
+    /// # Errors
+    /// Returns [Err] when the number of columns couldn't be determined or when a column couldn't be fetched.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
+    /// let stmnt = conn.prepare("SELECT * FROM test_table", CursorMode::Forward).unwrap();
+    /// let mut cursor = stmnt.open_cursor().unwrap();
+    ///
+    /// let owned = cursor.next_row().unwrap().unwrap().to_owned_row().unwrap();
+    /// drop(cursor);
+    /// let id: i32 = owned.get(2).unwrap().unwrap();
+    /// ```
+    pub fn to_owned_row(&self) -> Result<OwnedRow, i32> {
+        let column_count = {
+            let strong_inner_statement = self.inner_statement.upgrade().ok_or(-26004)?;
+            let handle = strong_inner_statement.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
+            strong_inner_statement.check_connection()?;
+            let rc = unsafe { ffi::MimerColumnCount(*handle) };
+            if rc < 0 {
+                return Err(rc);
+            }
+            rc as i16
+        };
+
+        let mut values = Vec::with_capacity(column_count as usize);
+        for idx in 1..=column_count {
+            match self.get_type(idx) {
+                Ok(val) => values.push(val.into_owned()),
+                Err(ffi::MIMER_SQL_NULL_VALUE) => values.push(MimerDatatype::Null),
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(OwnedRow { values })
+    }
+}
+
+/// Converts a null value into `T`, which only succeeds when `T` is an [Option].
+/// Returns [None] for every other type, as [get](Row::get()) does for null values.
+fn from_null<T: FromSql>() -> Option<T> {
+    T::from_sql(MimerDatatype::Null).ok()
+}
+
+/// A value fetched from a single column as part of a tuple with [get_tuple](Row::get_tuple()).
+///
+/// Implemented for every [FromSql] type. [Option] types fetch null values as [None], while other types reject them.
+pub trait FromColumn: Sized {
+    fn from_column(row: &Row, idx: i16) -> Result<Self, i32>;
+}
+
+impl<T: FromSql> FromColumn for T {
+    fn from_column(row: &Row, idx: i16) -> Result<Self, i32> {
+        row.get::<T>(idx)?.ok_or(-26027) // Column value is NULL
+    }
+}
+
+/// A tuple fetched from the leading columns of a row with [get_tuple](Row::get_tuple()).
+/// Implemented for tuples of up to 12 [FromColumn] elements.
+pub trait FromRow: Sized {
+    fn from_row(row: &Row) -> Result<Self, i32>;
+
+    /// Like `from_row`, but describes a failing column, see [get_tuple_diagnosed](Row::get_tuple_diagnosed()).
+    fn from_row_diagnosed(row: &Row) -> Result<Self, MappingError>;
+}
+
+macro_rules! impl_from_row_for_tuples {
+    ($(($($element:ident: $idx:literal),+)),+ $(,)?) => {$(
+        impl<$($element: FromColumn),+> FromRow for ($($element,)+) {
+            fn from_row(row: &Row) -> Result<Self, i32> {
+                Ok(($($element::from_column(row, $idx)?,)+))
+            }
+
+            fn from_row_diagnosed(row: &Row) -> Result<Self, MappingError> {
+                Ok(($(
+                    $element::from_column(row, $idx)
+                        .map_err(|ec| MappingError::new(row, $idx, std::any::type_name::<$element>(), ec))?,
+                )+))
+            }
+        }
+    )+};
+}
+
+/// Describes a column that couldn't be converted to a Rust type, as returned by [get_tuple_diagnosed](Row::get_tuple_diagnosed()).
+/// Parts that can't be read from the row are [None].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MappingError {
+    /// Index of the column, starting at 1.
+    pub column: i16,
+    /// Name of the column as reported by the server.
+    pub column_name: Option<String>,
+    /// SQL type of the column.
+    pub column_type: Option<ColumnType>,
+    /// Name of the Rust type the column was fetched as.
+    pub rust_type: &'static str,
+    /// The fetched value, formatted for display. Binary and large object values are left out.
+    pub value: Option<String>,
+    /// Error code of the failed conversion.
+    pub error_code: i32,
+}
+
+impl MappingError {
+    /// Collects what is known about a column that failed to convert.
+    fn new(row: &Row, idx: i16, rust_type: &'static str, error_code: i32) -> MappingError {
+        let (column_name, column_type) = match row.inner_statement.upgrade() {
+            Some(strong_inner_statement) => match strong_inner_statement.get_statement_handle() {
+                Ok(Some(handle)) => (
+                    read_string(|buf, size| unsafe {
+                        ffi::MimerColumnName8(*handle, idx, buf, size)
+                    })
+                    .ok()
+                    .flatten(),
+                    match unsafe { ffi::MimerColumnType(*handle, idx) } {
+                        code if code < 0 => None,
+                        code => Some(ColumnType::from_code(code)),
+                    },
+                ),
+                _ => (None, None),
+            },
+            None => (None, None),
+        };
+        let value = match column_type {
+            Some(
+                ColumnType::Binary
+                | ColumnType::BinaryVarying
+                | ColumnType::Blob
+                | ColumnType::Clob
+                | ColumnType::Nclob,
+            )
+            | None => None,
+            Some(_) => match row.get_type(idx) {
+                Ok(value) => Some(format!("{value:?}")),
+                Err(ffi::MIMER_SQL_NULL_VALUE) => Some(String::from("NULL")),
+                Err(_) => None,
+            },
+        };
+        MappingError {
+            column: idx,
+            column_name,
+            column_type,
+            rust_type,
+            value,
+            error_code,
+        }
+    }
+}
+
+impl fmt::Display for MappingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}", self.column)?;
+        if let Some(name) = &self.column_name {
+            write!(f, " ({name})")?;
+        }
+        if let Some(column_type) = &self.column_type {
+            write!(f, " of type {column_type}")?;
+        }
+        write!(
+            f,
+            " can't be fetched as {}, error code {}",
+            self.rust_type, self.error_code
+        )?;
+        if let Some(value) = &self.value {
+            write!(f, ", value: {value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for MappingError {}
+
+impl_from_row_for_tuples!(
+    (A: 1),
+    (A: 1, B: 2),
+    (A: 1, B: 2, C: 3),
+    (A: 1, B: 2, C: 3, D: 4),
+    (A: 1, B: 2, C: 3, D: 4, E: 5),
+    (A: 1, B: 2, C: 3, D: 4, E: 5, F: 6),
+    (A: 1, B: 2, C: 3, D: 4, E: 5, F: 6, G: 7),
+    (A: 1, B: 2, C: 3, D: 4, E: 5, F: 6, G: 7, H: 8),
+    (A: 1, B: 2, C: 3, D: 4, E: 5, F: 6, G: 7, H: 8, I: 9),
+    (A: 1, B: 2, C: 3, D: 4, E: 5, F: 6, G: 7, H: 8, I: 9, J: 10),
+    (A: 1, B: 2, C: 3, D: 4, E: 5, F: 6, G: 7, H: 8, I: 9, J: 10, K: 11),
+    (A: 1, B: 2, C: 3, D: 4, E: 5, F: 6, G: 7, H: 8, I: 9, J: 10, K: 11, L: 12),
+);
+
+/// A row from a result set whose values have been fetched from the database.
+///
+/// Unlike [Row], which reads its values from the cursor's current position, an OwnedRow holds its own data.
+/// It can therefore be stored, cloned and sent to other threads independently of the cursor and statement it came from.
+/// OwnedRows are created by [to_owned_row](crate::Row::to_owned_row()), or from a [Vec] of values,
+/// e.g. for the canned result sets of a [MockConnection](crate::mock::MockConnection).
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnedRow {
+    values: Vec<MimerDatatype<'static>>,
+}
+
+impl OwnedRow {
+    /// Gets the content from a specified index in the row using polymorphism, in the same way as [Row::get].
+    /// The index of the first column is 1.
+    /// If a null value is stored at the index, the return value will be [`Ok<None>`].
+    ///
+    /// # Errors
+    /// Returns [Err] when the index is out of range or when conversion to the specified type fails.
+    pub fn get<T: FromSql>(&self, idx: i16) -> Result<Option<T>, i32> {
+        match self.get_type(idx)? {
+            MimerDatatype::Null => Ok(from_null::<T>()),
+            val => T::from_sql(val.clone()).map(Some),
+        }
+    }
+
+    /// Returns a reference to the [MimerDatatype] stored at the specified index.
+    /// The index of the first column is 1.
+    ///
+    /// # Errors
+    /// Returns [Err] when the index is out of range.
+    pub fn get_type(&self, idx: i16) -> Result<&MimerDatatype<'static>, i32> {
+        if idx < 1 {
+            return Err(-26008); // Column index out of range
+        }
+        self.values.get(idx as usize - 1).ok_or(-26008)
+    }
+
+    /// Checks if the value at the specified index is null.
+    ///
+    /// # Errors
+    /// Returns [Err] when the index is out of range.
+    pub fn is_null(&self, idx: i16) -> Result<bool, i32> {
+        Ok(*self.get_type(idx)? == MimerDatatype::Null)
+    }
+
+    /// Returns the number of columns in the row.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if the row has no columns.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Consumes the row and returns its values, ordered by column index.
+    pub fn into_values(self) -> Vec<MimerDatatype<'static>> {
+        self.values
+    }
+}
+
+impl From<Vec<MimerDatatype<'static>>> for OwnedRow {
+    /// Creates a row holding `values`, ordered by column index.
+    fn from(values: Vec<MimerDatatype<'static>>) -> OwnedRow {
+        OwnedRow { values }
+    }
 }
 
 #[cfg(test)]
@@ -362,7 +678,7 @@ mod row_tests {
 
     #[test]
     fn row_get_type() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
         conn.execute_statement(&format!(
             "INSERT INTO {EXAMPLE_TABLE} {EXAMPLE_TABLE_EXAMPLE_VALUES}"
@@ -395,7 +711,7 @@ mod row_tests {
     }
     #[test]
     fn row_get() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
         conn.execute_statement(&format!(
             "INSERT INTO {EXAMPLE_TABLE} {EXAMPLE_TABLE_EXAMPLE_VALUES}"
@@ -420,9 +736,50 @@ mod row_tests {
         assert_eq!(val, EXAMPLE_VALUE_2);
     }
 
+    #[test]
+    fn row_get_null_binary_blob_spatial() {
+        let conn = establish_connection();
+        drop_create_table(
+            &conn,
+            "null_binary_table",
+            "(id INT, column_1 BINARY(4), column_2 VARBINARY(10), column_3 BLOB(10M), column_4 BUILTIN.GIS_LOCATION)",
+        );
+        conn.execute_statement("INSERT INTO null_binary_table VALUES(1, NULL, NULL, NULL, NULL)")
+            .unwrap();
+        // Larger than a chunk, to check that every chunk of the BLOB ends up in its place
+        let blob: Vec<u8> = (0..LOB_CHUNK_MAXSIZE_SET * 2 + 10)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        conn.prepare(
+            "INSERT INTO null_binary_table VALUES(2, x'01020304', x'0506', :blob, NULL)",
+            CursorMode::Forward,
+        )
+        .unwrap()
+        .execute_bind(&[&blob])
+        .unwrap();
+
+        let stmt = conn
+            .prepare(
+                "SELECT * FROM null_binary_table ORDER BY id",
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = stmt.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+        for idx in 2..=5 {
+            assert_eq!(row.get_type(idx).unwrap(), MimerDatatype::Null);
+            assert_eq!(row.get::<Vec<u8>>(idx).unwrap(), None);
+        }
+
+        let row = cursor.next_row().unwrap().unwrap();
+        assert_eq!(row.get::<Vec<u8>>(2).unwrap(), Some(vec![1, 2, 3, 4]));
+        assert_eq!(row.get::<Vec<u8>>(3).unwrap(), Some(vec![5, 6]));
+        assert!(row.get::<Vec<u8>>(4).unwrap() == Some(blob));
+    }
+
     #[test]
     fn row_get_fail() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
         conn.execute_statement(&format!(
             "INSERT INTO {EXAMPLE_TABLE} {EXAMPLE_TABLE_EXAMPLE_VALUES}"
@@ -449,7 +806,7 @@ mod row_tests {
     }
     #[test]
     fn row_get_blob() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, BLOB_TABLE_1024, BLOB_TABLE_1024_COLUMNS);
 
@@ -478,7 +835,7 @@ mod row_tests {
 
     #[test]
     fn row_get_clob_small() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, CLOB_TABLE, CLOB_TABLE_COLUMNS);
 
@@ -504,7 +861,7 @@ mod row_tests {
     }
     #[test]
     fn row_get_clob_big() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, CLOB_TABLE, CLOB_TABLE_COLUMNS);
 
@@ -529,12 +886,44 @@ mod row_tests {
         assert_eq!(val, clob);
     }
 
+    #[test]
+    fn row_to_owned_row() {
+        let conn = establish_connection();
+        drop_create_table(&conn, NULLABLE_TABLE, NULLABLE_TABLE_COLUMNS);
+
+        let int = Some(1);
+        let string1: Option<String> = None;
+        let string2 = String::from("test");
+        let stmnt = conn.prepare(&format!("INSERT INTO {NULLABLE_TABLE} {NULLABLE_TABLE_COLUMN_NAMES} VALUES(:INT,?,:STRING2)"), CursorMode::Forward).unwrap();
+        stmnt.execute_bind(&[&int, &string1, &string2]).unwrap();
+
+        let stmnt = conn
+            .prepare(
+                &format!("SELECT * FROM {NULLABLE_TABLE}"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let owned;
+        {
+            let mut cursor = stmnt.open_cursor().unwrap();
+            owned = cursor.next_row().unwrap().unwrap().to_owned_row().unwrap();
+        }
+
+        assert_eq!(owned.len(), 3);
+        assert_eq!(owned.get::<i32>(1).unwrap(), int);
+        assert!(owned.is_null(2).unwrap());
+        assert!(owned.get::<String>(2).unwrap().is_none());
+        assert_eq!(owned.get::<String>(3).unwrap().unwrap(), string2);
+        assert_eq!(owned.get::<String>(0), Err(-26008));
+        assert_eq!(owned.get::<String>(4), Err(-26008));
+    }
+
     #[test]
     fn check_statement_get() {
         let row;
         let mut cursor;
         {
-            let mut conn = establish_connection();
+            let conn = establish_connection();
 
             drop_create_table(&conn, &EXAMPLE_TABLE, &EXAMPLE_TABLE_COLUMNS);
             conn.execute_statement(&format!(
@@ -561,7 +950,7 @@ mod row_tests {
         let mut cursor;
         let row;
         {
-            let mut conn = establish_connection();
+            let conn = establish_connection();
 
             drop_create_table(&conn, &EXAMPLE_TABLE, &EXAMPLE_TABLE_COLUMNS);
             conn.execute_statement(&format!(
@@ -587,7 +976,7 @@ mod row_tests {
         let mut cursor;
         let mut row;
 
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, BINARY_TABLE, BINARY_TABLE_COLUMNS);
 
         let mut stmnt = conn
@@ -633,7 +1022,7 @@ mod row_tests {
         let mut row;
         let mut vec: Vec<u8>;
 
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, VARBINARY_TABLE, VARBINARY_TABLE_COLUMNS);
         let binary_test: Vec<u8> = vec![b't', b'e', b's', b't'];
         let binary_msg: Vec<u8> = vec![
@@ -677,7 +1066,7 @@ mod row_tests {
     fn test_get_uuid() {
         let mut row: &Row;
 
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, UUID_TABLE, UUID_TABLE_COLUMNS);
 
         let stmnt = conn
@@ -706,9 +1095,64 @@ mod row_tests {
         assert_eq!(u2_fetched.unwrap(), u2);
     }
 
+    #[test]
+    fn test_get_builtin_uuid() {
+        let conn = establish_connection();
+        drop_create_table(&conn, UUID_TABLE, "(column1 BUILTIN.UUID)");
+
+        let stmnt = conn
+            .prepare(
+                &format!("INSERT INTO {UUID_TABLE} VALUES(:b)"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let u1 = uuid::Uuid::new_v4();
+        stmnt.execute_bind(&[&u1]).unwrap();
+        stmnt.execute_bind(&[&None::<uuid::Uuid>]).unwrap();
+
+        let stmnt = conn
+            .prepare(&format!("SELECT * FROM {UUID_TABLE}"), CursorMode::Forward)
+            .unwrap();
+        let mut cursor = stmnt.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+        assert_eq!(
+            row.get_type(1).unwrap(),
+            MimerDatatype::Uuid(*u1.as_bytes())
+        );
+        assert_eq!(row.get::<uuid::Uuid>(1).unwrap(), Some(u1));
+        let row = cursor.next_row().unwrap().unwrap();
+        assert_eq!(row.get_type(1).unwrap(), MimerDatatype::Null);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_get_json() {
+        let conn = establish_connection();
+        drop_create_table(&conn, CLOB_TABLE, CLOB_TABLE_COLUMNS);
+
+        let stmnt = conn
+            .prepare(
+                &format!("INSERT INTO {CLOB_TABLE} VALUES(:c)"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let document = serde_json::json!({"name": "mimer", "tags": ["sql", "rust"], "version": 11});
+        stmnt.execute_bind(&[&document]).unwrap();
+        stmnt.execute_bind(&[&"not json"]).unwrap();
+
+        let stmnt = conn
+            .prepare(&format!("SELECT * FROM {CLOB_TABLE}"), CursorMode::Forward)
+            .unwrap();
+        let mut cursor = stmnt.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+        assert_eq!(row.get::<serde_json::Value>(1).unwrap().unwrap(), document);
+        let row = cursor.next_row().unwrap().unwrap();
+        assert_eq!(row.get::<serde_json::Value>(1).unwrap_err(), -26200);
+    }
+
     #[test]
     fn test_get_null() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, NULLABLE_TABLE, NULLABLE_TABLE_COLUMNS);
 
@@ -743,7 +1187,7 @@ mod row_tests {
 
     #[test]
     fn test_row_singleton() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, NULLABLE_TABLE, NULLABLE_TABLE_COLUMNS);
 
@@ -776,7 +1220,7 @@ mod row_tests {
 
     #[test]
     fn test_row_multibyte_chars() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, EXAMPLE_TABLE_2, EXAMPLE_TABLE_2_COLUMNS);
 
@@ -805,4 +1249,136 @@ mod row_tests {
         let fetched_string = row.get::<String>(1).unwrap().unwrap();
         assert_eq!(fetched_string.trim(), multibyte)
     }
+
+    #[test]
+    fn row_get_tuple() {
+        let conn = establish_connection();
+        drop_create_table(&conn, NULLABLE_TABLE, NULLABLE_TABLE_COLUMNS);
+        conn.execute_statement(&format!(
+            "INSERT INTO {NULLABLE_TABLE} VALUES(1, 'two', NULL)"
+        ))
+        .unwrap();
+
+        let stmnt = conn
+            .prepare(
+                &format!("SELECT * FROM {NULLABLE_TABLE}"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = stmnt.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+
+        let (one, two, three) = row.get_tuple::<(i32, String, Option<String>)>().unwrap();
+        assert_eq!((one, two.as_str(), three), (1, "two", None));
+        assert_eq!(row.get_tuple::<(i32,)>().unwrap(), (1,));
+        assert_eq!(row.get_tuple::<(i32, String, String)>(), Err(-26027));
+        assert_eq!(row.get_tuple::<(String,)>(), Err(-26200));
+        assert!(row
+            .get_tuple::<(i32, String, Option<String>, i32)>()
+            .is_err());
+    }
+
+    #[test]
+    fn row_get_option() {
+        let conn = establish_connection();
+        drop_create_table(&conn, NULLABLE_TABLE, NULLABLE_TABLE_COLUMNS);
+        conn.execute_statement(&format!(
+            "INSERT INTO {NULLABLE_TABLE} VALUES(1, 'two', NULL)"
+        ))
+        .unwrap();
+
+        let stmnt = conn
+            .prepare(
+                &format!("SELECT * FROM {NULLABLE_TABLE}"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = stmnt.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+
+        assert_eq!(row.get::<Option<i32>>(1), Ok(Some(Some(1))));
+        assert_eq!(row.get::<Option<String>>(3), Ok(Some(None)));
+        assert_eq!(row.get::<String>(3), Ok(None));
+        assert_eq!(row.get::<Option<String>>(1), Err(-26200));
+        assert_eq!(Option::<i32>::from_sql(MimerDatatype::Null), Ok(None));
+    }
+
+    #[test]
+    fn row_get_tuple_diagnosed() {
+        let conn = establish_connection();
+        drop_create_table(&conn, NULLABLE_TABLE, NULLABLE_TABLE_COLUMNS);
+        conn.execute_statement(&format!(
+            "INSERT INTO {NULLABLE_TABLE} VALUES(1, 'two', NULL)"
+        ))
+        .unwrap();
+
+        let stmnt = conn
+            .prepare(
+                &format!("SELECT * FROM {NULLABLE_TABLE}"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = stmnt.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+
+        let (one, two) = row.get_tuple_diagnosed::<(i32, String)>().unwrap();
+        assert_eq!((one, two.as_str()), (1, "two"));
+
+        let err = row.get_tuple_diagnosed::<(i32, bool)>().unwrap_err();
+        assert_eq!(err.column, 2);
+        assert_eq!(err.rust_type, "bool");
+        assert_eq!(err.error_code, -26200);
+        assert!(err.column_name.is_some());
+        assert!(err.column_type.is_some());
+        assert!(err.value.as_deref().unwrap().contains("two"));
+        assert!(err.to_string().contains("bool"));
+
+        let err = row
+            .get_tuple_diagnosed::<(i32, String, String)>()
+            .unwrap_err();
+        assert_eq!(err.column, 3);
+        assert_eq!(err.error_code, -26027);
+        assert_eq!(err.value.as_deref(), Some("NULL"));
+    }
+
+    #[test]
+    fn row_get_by_name() {
+        let conn = establish_connection();
+        drop_create_table(
+            &conn,
+            EXAMPLE_TABLE,
+            "(column_1 VARCHAR(30), \"Column_2\" INT)",
+        );
+        conn.execute_statement(&format!("INSERT INTO {EXAMPLE_TABLE} VALUES('one', 1)"))
+            .unwrap();
+
+        let stmnt = conn
+            .prepare(
+                &format!("SELECT * FROM {EXAMPLE_TABLE}"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = stmnt.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+
+        // The default folds unquoted names to uppercase
+        assert_eq!(
+            conn.column_name_resolution(),
+            ColumnNameResolution::UppercaseFold
+        );
+        assert_eq!(
+            row.get_by_name::<String>("column_1").unwrap().unwrap(),
+            "one"
+        );
+        assert_eq!(row.get_by_name::<i32>("Column_2").unwrap().unwrap(), 1);
+        assert_eq!(row.column_index("column_2"), Err(-26018));
+
+        conn.set_column_name_resolution(ColumnNameResolution::Exact);
+        assert_eq!(row.column_index("column_1"), Err(-26018));
+        assert_eq!(row.column_index("COLUMN_1"), Ok(1));
+
+        conn.set_column_name_resolution(ColumnNameResolution::CaseInsensitive);
+        assert_eq!(row.column_index("column_2"), Ok(2));
+        assert_eq!(row.column_index("column_3"), Err(-26018));
+    }
 }
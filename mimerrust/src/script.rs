@@ -0,0 +1,314 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::connection::Connection;
+use std::fmt;
+
+/// Words that follow `END` when it closes a control statement rather than a `BEGIN` block or a `CASE`.
+const END_QUALIFIERS: [&str; 6] = ["IF", "LOOP", "WHILE", "REPEAT", "FOR", "CASE"];
+
+/// Splits an SQL script into its statements, which are separated by semicolons.
+///
+/// Semicolons within string literals, delimited identifiers, comments and compound statements
+/// (`BEGIN` … `END` blocks, e.g. the body of a procedure) do not end a statement.
+/// Comments before a statement and statements that are empty are left out.
+pub(crate) fn split_script(script: &str) -> Vec<&str> {
+    let bytes = script.as_bytes();
+    let mut statements = Vec::new();
+    let mut start = None;
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                i = skip_until(bytes, i + 2, b"\n");
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = skip_until(bytes, i + 2, b"*/");
+                continue;
+            }
+            b';' if depth == 0 => {
+                if let Some(s) = start.take() {
+                    statements.push(script[s..i].trim_end());
+                }
+                i += 1;
+                continue;
+            }
+            c if c.is_ascii_whitespace() || c == b';' => {
+                i += 1;
+                continue;
+            }
+            _ => (),
+        }
+        start.get_or_insert(i);
+        match bytes[i] {
+            quote @ (b'\'' | b'"') => i = skip_quoted(bytes, i + 1, quote),
+            c if c.is_ascii_alphabetic() => {
+                let (word, end) = read_word(bytes, i);
+                i = end;
+                if word.eq_ignore_ascii_case(b"BEGIN") || word.eq_ignore_ascii_case(b"CASE") {
+                    depth += 1;
+                } else if word.eq_ignore_ascii_case(b"END") {
+                    depth = depth.saturating_sub(1);
+                    let next = skip_whitespace(bytes, i);
+                    let (qualifier, end) = read_word(bytes, next);
+                    if END_QUALIFIERS
+                        .iter()
+                        .any(|q| qualifier.eq_ignore_ascii_case(q.as_bytes()))
+                    {
+                        // END IF, END LOOP, ... close statements which didn't increase the depth, except END CASE
+                        if !qualifier.eq_ignore_ascii_case(b"CASE") {
+                            depth += 1;
+                        }
+                        i = end;
+                    }
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    if let Some(s) = start {
+        statements.push(script[s..].trim_end());
+    }
+    statements
+}
+
+/// Returns the keywords and regular identifiers of an SQL statement in order,
+/// leaving out string literals, delimited identifiers and comments.
+pub(crate) fn keywords(sql: &str) -> Vec<&str> {
+    let bytes = sql.as_bytes();
+    let mut words = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'-' if bytes.get(i + 1) == Some(&b'-') => i = skip_until(bytes, i + 2, b"\n"),
+            b'/' if bytes.get(i + 1) == Some(&b'*') => i = skip_until(bytes, i + 2, b"*/"),
+            quote @ (b'\'' | b'"') => i = skip_quoted(bytes, i + 1, quote),
+            c if c.is_ascii_alphabetic() => {
+                let (_, end) = read_word(bytes, i);
+                words.push(&sql[i..end]);
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+    words
+}
+
+/// Returns the index following the next occurence of `terminator`, or the end of `bytes`.
+fn skip_until(bytes: &[u8], from: usize, terminator: &[u8]) -> usize {
+    bytes[from..]
+        .windows(terminator.len())
+        .position(|w| w == terminator)
+        .map_or(bytes.len(), |pos| from + pos + terminator.len())
+}
+
+/// Returns the index following the closing `quote`, where a doubled quote is part of the literal.
+fn skip_quoted(bytes: &[u8], mut i: usize, quote: u8) -> usize {
+    while i < bytes.len() {
+        if bytes[i] == quote {
+            if bytes.get(i + 1) == Some(&quote) {
+                i += 2;
+                continue;
+            }
+            return i + 1;
+        }
+        i += 1;
+    }
+    i
+}
+
+fn skip_whitespace(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    i
+}
+
+/// Reads a regular identifier or keyword starting at `i`, and returns it together with the index following it.
+fn read_word(bytes: &[u8], i: usize) -> (&[u8], usize) {
+    let end = bytes[i..]
+        .iter()
+        .position(|c| !(c.is_ascii_alphanumeric() || matches!(c, b'_' | b'$' | b'#')))
+        .map_or(bytes.len(), |pos| i + pos);
+    (&bytes[i..end], end)
+}
+
+impl Connection {
+    /// Executes an SQL script, e.g. a schema bootstrap file, one statement at a time.
+    /// Statements are separated by semicolons, which may also appear within string literals, comments and
+    /// compound statements such as the `BEGIN` … `END` body of a procedure.
+    /// Each statement is executed as by [execute_statement](crate::Connection::execute_statement()), and can't contain parameter markers.
+    ///
+    /// Returns the number of statements executed. Statements that were executed before a failing one are not undone,
+    /// unless the script is run within a [Transaction](crate::Transaction).
+    ///
+    /// # Errors
+    /// Returns [Err] holding a [ScriptError] with the index and text of the first statement that failed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop procedure add_note").ok();
+    /// # conn.execute_statement("drop table notes").ok();
+    /// let script = "
+    ///     -- Schema for notes; created on start up
+    ///     CREATE TABLE notes (id INT PRIMARY KEY, note VARCHAR(100));
+    ///     INSERT INTO notes VALUES (1, 'first; and only');
+    ///     CREATE PROCEDURE add_note(IN n_id INT, IN n_text VARCHAR(100))
+    ///     MODIFIES SQL DATA
+    ///     BEGIN
+    ///         INSERT INTO notes VALUES (n_id, n_text);
+    ///     END;
+    /// ";
+    /// assert_eq!(conn.execute_script(script).unwrap(), 3);
+    /// ```
+    pub fn execute_script(&self, script: &str) -> Result<usize, ScriptError> {
+        let statements = split_script(script);
+        for (index, statement) in statements.iter().enumerate() {
+            if let Err(error_code) = self.execute_statement(statement) {
+                return Err(ScriptError {
+                    error_code,
+                    index,
+                    statement: statement.to_string(),
+                });
+            }
+        }
+        Ok(statements.len())
+    }
+}
+
+/// Error returned by [execute_script](crate::Connection::execute_script()).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptError {
+    error_code: i32,
+    index: usize,
+    statement: String,
+}
+
+impl ScriptError {
+    /// Gets the error code of the failed statement.
+    pub fn get_error_code(&self) -> i32 {
+        self.error_code
+    }
+
+    /// Gets the index, starting at 0, of the failed statement within the script.
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    /// Gets the text of the failed statement.
+    pub fn get_statement(&self) -> &str {
+        &self.statement
+    }
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Statement {} of the script failed with error {}: {}",
+            self.index, self.error_code, self.statement
+        )
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+#[cfg(test)]
+mod script_tests {
+    use super::*;
+    use crate::testing::*;
+
+    #[test]
+    fn statement_keywords() {
+        assert_eq!(
+            keywords("WITH x AS (SELECT 'it''s' AS \"UPDATE\" FROM t) -- DELETE\n INSERT INTO u SELECT * FROM x"),
+            vec!["WITH", "x", "AS", "SELECT", "AS", "FROM", "t", "INSERT", "INTO", "u", "SELECT", "FROM", "x"]
+        );
+        assert!(keywords("/* only a comment */").is_empty());
+    }
+
+    #[test]
+    fn split_script_statements() {
+        let script = "
+            -- leading comment; with a semicolon
+            CREATE TABLE t (c VARCHAR(10));
+            INSERT INTO t VALUES ('a;b'), ('it''s;');
+            /* block; comment */ ;;
+            SELECT \"odd;name\" FROM t;
+            CREATE PROCEDURE p(IN x INT)
+            MODIFIES SQL DATA
+            BEGIN
+                DECLARE y INT;
+                IF x > 0 THEN
+                    SET y = CASE WHEN x > 1 THEN 2 ELSE 1 END;
+                END IF;
+                WHILE y > 0 DO
+                    SET y = y - 1;
+                END WHILE;
+                BEGIN
+                    INSERT INTO t VALUES ('nested');
+                END;
+            END;
+            DELETE FROM t";
+        let statements = split_script(script);
+        assert_eq!(statements.len(), 5);
+        assert_eq!(statements[0], "CREATE TABLE t (c VARCHAR(10))");
+        assert_eq!(statements[1], "INSERT INTO t VALUES ('a;b'), ('it''s;')");
+        assert_eq!(statements[2], "SELECT \"odd;name\" FROM t");
+        assert!(statements[3].starts_with("CREATE PROCEDURE p"));
+        assert!(statements[3].ends_with("END;\n            END"));
+        assert_eq!(statements[4], "DELETE FROM t");
+        assert!(split_script(" -- nothing here\n ; ").is_empty());
+    }
+
+    #[test]
+    fn execute_script_reports_failing_statement() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+        let script = format!(
+            "INSERT INTO {EXAMPLE_TABLE} VALUES ('one;', 1);
+             INSERT INTO {EXAMPLE_TABLE} VALUES ('two', 2);
+             INSERT INTO no_such_table_in_script VALUES (3);
+             INSERT INTO {EXAMPLE_TABLE} VALUES ('four', 4);"
+        );
+        let err = conn.execute_script(&script).unwrap_err();
+        assert_eq!(err.get_index(), 2);
+        assert!(err.get_statement().contains("no_such_table_in_script"));
+
+        let count = conn
+            .query_scalar::<i32>(
+                &format!("SELECT CAST(COUNT(*) AS INTEGER) FROM {EXAMPLE_TABLE}"),
+                &[],
+            )
+            .unwrap();
+        assert_eq!(count, Some(2));
+    }
+}
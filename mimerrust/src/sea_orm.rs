@@ -0,0 +1,319 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::common::mimer_options::{ColumnType, CursorMode, EndTransactionMode, TransactionMode};
+use crate::connection::Connection;
+use crate::error_sink::report;
+use crate::statement::Statement;
+use crate::transaction::{begin_session_transaction, end_session_transaction};
+use crate::types::{FromSql, MimerDatatype, ToSql};
+use ::sea_orm::sea_query::Value;
+use ::sea_orm::{
+    Database, DatabaseConnection, DbBackend, DbErr, ProxyDatabaseTrait, ProxyExecResult, ProxyRow,
+    RuntimeErr, Statement as SeaStatement,
+};
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+/// A [sea-orm](https://www.sea-ql.org/SeaORM) proxy database that runs the statements of a [DatabaseConnection] on a [Connection].
+///
+/// sea-orm only builds statements for its own backends, so the connection uses the SQLite dialect, which has the same quoting
+/// and `?` parameters as Mimer SQL. The `LIMIT` and `OFFSET` that end a query are rewritten to `OFFSET ... ROWS FETCH FIRST ... ROWS ONLY`.
+/// Names are quoted as they are given, so the tables of the entities are best created with
+/// [MimerQueryBuilder](crate::sea_query::MimerQueryBuilder), which also maps the column types to Mimer SQL types.
+///
+/// Mimer SQL doesn't report the key of an inserted row, so models with auto-incremented keys can be inserted with `insert_many`
+/// or `exec`, but not read back by `insert`. The statements are executed on the calling thread, like the other blocking calls of this crate.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::Connection;
+/// use mimerrust::sea_orm::MimerProxy;
+/// use sea_orm::{ConnectionTrait, Statement};
+///
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let conn = Connection::open(db, "RUSTUSER", "RUSTPASSWORD").unwrap();
+/// let db = MimerProxy::connect(conn).await.unwrap();
+///
+/// let row = db
+///     .query_one(Statement::from_string(db.get_database_backend(), "SELECT 1 AS one FROM SYSTEM.ONEROW"))
+///     .await
+///     .unwrap()
+///     .unwrap();
+/// assert_eq!(row.try_get::<i32>("", "ONE").unwrap(), 1);
+/// # });
+/// ```
+pub struct MimerProxy {
+    connection: Connection,
+}
+
+impl MimerProxy {
+    /// Creates a proxy running statements on `connection`.
+    pub fn new(connection: Connection) -> MimerProxy {
+        MimerProxy { connection }
+    }
+
+    /// Opens a sea-orm [DatabaseConnection] running its statements on `connection`.
+    pub async fn connect(connection: Connection) -> Result<DatabaseConnection, DbErr> {
+        Database::connect_proxy(
+            DbBackend::Sqlite,
+            Arc::new(Box::new(MimerProxy::new(connection))),
+        )
+        .await
+    }
+
+    /// Gets the connection that the statements are run on.
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Prepares a statement and binds its values, after rewriting its `LIMIT` and `OFFSET`.
+    fn prepare(&self, statement: SeaStatement) -> Result<Statement, DbErr> {
+        let mut values = statement.values.map(|values| values.0).unwrap_or_default();
+        let sql = rewrite_limit_offset(&statement.sql, &mut values);
+        let stmt = self
+            .connection
+            .prepare(&sql, CursorMode::Forward)
+            .map_err(|ec| db_err(self.connection.get_error(ec).to_string()))?;
+        let params: Vec<&dyn ToSql> = values.iter().map(|value| value as _).collect();
+        stmt.bind_params(&params)
+            .map_err(|ec| db_err(stmt.get_error(ec).to_string()))?;
+        Ok(stmt)
+    }
+}
+
+impl From<Connection> for MimerProxy {
+    fn from(connection: Connection) -> MimerProxy {
+        MimerProxy::new(connection)
+    }
+}
+
+impl fmt::Debug for MimerProxy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MimerProxy").finish_non_exhaustive()
+    }
+}
+
+#[::async_trait::async_trait]
+impl ProxyDatabaseTrait for MimerProxy {
+    async fn query(&self, statement: SeaStatement) -> Result<Vec<ProxyRow>, DbErr> {
+        let stmt = self.prepare(statement)?;
+        let error = |ec| db_err(stmt.get_error(ec).to_string());
+        let column_count = stmt.column_count().map_err(error)?;
+        let columns = (1..=column_count as i16)
+            .map(|idx| Ok((stmt.get_column_name(idx)?, stmt.column_type(idx)?)))
+            .collect::<Result<Vec<_>, i32>>()
+            .map_err(error)?;
+
+        let mut cursor = stmt.open_cursor().map_err(error)?;
+        let mut rows = Vec::new();
+        while let Some(row) = cursor.next_row().map_err(error)? {
+            let values = row.to_owned_row().map_err(error)?.into_values();
+            let values = columns
+                .iter()
+                .zip(values)
+                .map(|((name, column_type), value)| {
+                    Ok((name.clone(), to_value(value, *column_type)?))
+                })
+                .collect::<Result<BTreeMap<_, _>, i32>>()
+                .map_err(error)?;
+            rows.push(ProxyRow { values });
+        }
+        Ok(rows)
+    }
+
+    async fn execute(&self, statement: SeaStatement) -> Result<ProxyExecResult, DbErr> {
+        let stmt = self.prepare(statement)?;
+        let rows = stmt
+            .execute()
+            .map_err(|ec| db_err(stmt.get_error(ec).to_string()))?;
+        Ok(ProxyExecResult::new(0, rows.max(0) as u64))
+    }
+
+    async fn begin(&self) {
+        if let Err(ec) = begin_session_transaction(&self.connection, TransactionMode::ReadWrite) {
+            report("beginning a sea-orm transaction", ec);
+        }
+    }
+
+    async fn commit(&self) {
+        if let Err(ec) = end_session_transaction(&self.connection, EndTransactionMode::Commit) {
+            report("committing a sea-orm transaction", ec);
+        }
+    }
+
+    async fn rollback(&self) {
+        self.start_rollback();
+    }
+
+    fn start_rollback(&self) {
+        if let Err(ec) = end_session_transaction(&self.connection, EndTransactionMode::Rollback) {
+            report("rolling back a sea-orm transaction", ec);
+        }
+    }
+
+    async fn ping(&self) -> Result<(), DbErr> {
+        self.connection
+            .execute_statement("SELECT 1 FROM SYSTEM.ONEROW")
+            .map(|_| ())
+            .map_err(|ec| {
+                DbErr::Conn(RuntimeErr::Internal(
+                    self.connection.get_error(ec).to_string(),
+                ))
+            })
+    }
+}
+
+/// Wraps the message of an error from the database.
+fn db_err(message: String) -> DbErr {
+    DbErr::Query(RuntimeErr::Internal(message))
+}
+
+/// Rewrites the `LIMIT ?` and `LIMIT ? OFFSET ?` that end a query in the SQLite dialect to the `FETCH FIRST` and `OFFSET` of Mimer SQL,
+/// swapping the values of the limit and the offset, which come in the opposite order.
+fn rewrite_limit_offset(sql: &str, values: &mut [Value]) -> String {
+    if let Some(query) = sql.strip_suffix(" LIMIT ? OFFSET ?") {
+        let len = values.len();
+        if len >= 2 {
+            values.swap(len - 2, len - 1);
+        }
+        format!("{query} OFFSET ? ROWS FETCH FIRST ? ROWS ONLY")
+    } else if let Some(query) = sql.strip_suffix(" LIMIT ?") {
+        format!("{query} FETCH FIRST ? ROWS ONLY")
+    } else {
+        sql.to_string()
+    }
+}
+
+/// Converts a fetched value to the sea-query [Value] of the column type, which is also used for nulls.
+/// Character, decimal and interval values are converted to strings.
+fn to_value(value: MimerDatatype<'static>, column_type: ColumnType) -> Result<Value, i32> {
+    Ok(match column_type {
+        ColumnType::SmallInt => Value::SmallInt(from_sql(value)?),
+        ColumnType::Integer => Value::Int(from_sql(value)?),
+        ColumnType::BigInt => Value::BigInt(from_sql(value)?),
+        ColumnType::Real => Value::Float(from_sql(value)?),
+        ColumnType::Double => Value::Double(from_sql(value)?),
+        ColumnType::Boolean => Value::Bool(from_sql(value)?),
+        ColumnType::Date => Value::ChronoDate(from_sql(value)?.map(Box::new)),
+        ColumnType::Time => Value::ChronoTime(from_sql(value)?.map(Box::new)),
+        ColumnType::Timestamp => Value::ChronoDateTime(from_sql(value)?.map(Box::new)),
+        ColumnType::Uuid => Value::Uuid(from_sql(value)?.map(Box::new)),
+        ColumnType::Binary
+        | ColumnType::BinaryVarying
+        | ColumnType::Blob
+        | ColumnType::GisLocation
+        | ColumnType::GisLatitude
+        | ColumnType::GisLongitude
+        | ColumnType::GisCoordinate => Value::Bytes(from_sql(value)?.map(Box::new)),
+        _ => Value::String(from_sql(value)?.map(Box::new)),
+    })
+}
+
+/// Converts a fetched value, which is [None] when it is null.
+fn from_sql<T: FromSql>(value: MimerDatatype<'static>) -> Result<Option<T>, i32> {
+    match value {
+        MimerDatatype::Null => Ok(None),
+        value => T::from_sql(value).map(Some),
+    }
+}
+
+#[cfg(test)]
+mod sea_orm_tests {
+    use super::*;
+    use crate::testing::*;
+    use ::sea_orm::sea_query::{Alias, Expr, Order, Query};
+    use ::sea_orm::ConnectionTrait;
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn limit_and_offset() {
+        let statement = DbBackend::Sqlite.build(
+            Query::select()
+                .column(Alias::new("COLUMN_1"))
+                .from(Alias::new("TEST_TABLE"))
+                .and_where(Expr::col(Alias::new("COLUMN_2")).gt(1))
+                .limit(2)
+                .offset(5),
+        );
+        let mut values = statement.values.unwrap().0;
+        assert_eq!(
+            rewrite_limit_offset(&statement.sql, &mut values),
+            "SELECT \"COLUMN_1\" FROM \"TEST_TABLE\" WHERE \"COLUMN_2\" > ? OFFSET ? ROWS FETCH FIRST ? ROWS ONLY"
+        );
+        assert_eq!(
+            values,
+            vec![
+                Value::Int(Some(1)),
+                Value::BigUnsigned(Some(5)),
+                Value::BigUnsigned(Some(2))
+            ]
+        );
+
+        let mut values = vec![Value::BigUnsigned(Some(1))];
+        assert_eq!(
+            rewrite_limit_offset("SELECT 1 FROM SYSTEM.ONEROW LIMIT ?", &mut values),
+            "SELECT 1 FROM SYSTEM.ONEROW FETCH FIRST ? ROWS ONLY"
+        );
+    }
+
+    #[test]
+    fn execute_and_query() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+
+        block_on(async {
+            let db = MimerProxy::connect(conn).await.unwrap();
+            let insert = DbBackend::Sqlite.build(
+                Query::insert()
+                    .into_table(Alias::new("TEST_TABLE"))
+                    .columns([Alias::new("COLUMN_1"), Alias::new("COLUMN_2")])
+                    .values_panic(["first".into(), 1.into()])
+                    .values_panic([Option::<String>::None.into(), 2.into()]),
+            );
+            assert_eq!(db.execute(insert).await.unwrap().rows_affected(), 2);
+
+            let select = DbBackend::Sqlite.build(
+                Query::select()
+                    .columns([Alias::new("COLUMN_1"), Alias::new("COLUMN_2")])
+                    .from(Alias::new("TEST_TABLE"))
+                    .order_by(Alias::new("COLUMN_2"), Order::Desc)
+                    .limit(1),
+            );
+            let rows = db.query_all(select).await.unwrap();
+            assert_eq!(rows.len(), 1);
+            assert_eq!(
+                rows[0].try_get::<Option<String>>("", "COLUMN_1").unwrap(),
+                None
+            );
+            assert_eq!(rows[0].try_get::<i32>("", "COLUMN_2").unwrap(), 2);
+        });
+    }
+}
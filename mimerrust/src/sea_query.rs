@@ -0,0 +1,533 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::types::{MimerDatatype, ToSql};
+use ::sea_query::{
+    BinOper, ColumnDef, ColumnSpec, ColumnType, DeleteStatement, EscapeBuilder, ForeignKeyBuilder,
+    ForeignKeyCreateStatement, ForeignKeyDropStatement, GenericBuilder, IndexBuilder,
+    IndexCreateStatement, IndexDropStatement, Mode, Oper, OperLeftAssocDecider, PgInterval,
+    PostgresQueryBuilder, PrecedenceDecider, QueryBuilder, Quote, QuotedBuilder, SchemaBuilder,
+    SelectStatement, SimpleExpr, SqlWriter, StringLen, SubQueryStatement, TableAlterStatement,
+    TableBuilder, TableRef, TableRefBuilder, TableRenameStatement, UpdateStatement, Value,
+};
+use std::fmt::{self, Write};
+
+/// The Mimer SQL query builder for [sea-query](https://docs.rs/sea-query).
+///
+/// Identifiers are quoted with double quotes and written as they are given, so a table created with an unquoted name, which
+/// Mimer SQL folds to uppercase, is referred to with its uppercase name. Values are written as `?` parameters, which can be
+/// bound with the [ToSql] implementation of [Value]. Limits and offsets are written as `OFFSET ? ROWS FETCH FIRST ? ROWS ONLY`,
+/// and column types are mapped to Mimer SQL types, e.g. strings to `NVARCHAR` and UUIDs to `BUILTIN.UUID`.
+/// Column types without a Mimer SQL equivalent, such as `inet` or arrays, are written with their PostgreSQL names,
+/// so executing the statement fails with an error from the server rather than the builder panicking.
+///
+/// `ALTER TABLE` and rename statements, indexes and foreign keys are written as by [PostgresQueryBuilder], which uses
+/// the same standard syntax. Columns added by `ALTER TABLE` therefore get PostgreSQL type names.
+///
+/// # Examples
+/// ```
+/// use mimerrust::sea_query::MimerQueryBuilder;
+/// use mimerrust::{Connection, CursorMode, ToSql};
+/// use ::sea_query::{Alias, Expr, Order, Query};
+///
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// let conn = Connection::open(db, "RUSTUSER", "RUSTPASSWORD").unwrap();
+/// # _ = conn.execute_statement("DROP TABLE sea_table");
+/// conn.execute_statement("CREATE TABLE sea_table (id INT, name NVARCHAR(30))").unwrap();
+///
+/// let (sql, values) = Query::select()
+///     .columns([Alias::new("NAME")])
+///     .from(Alias::new("SEA_TABLE"))
+///     .and_where(Expr::col(Alias::new("ID")).gt(10))
+///     .order_by(Alias::new("ID"), Order::Asc)
+///     .limit(5)
+///     .build(MimerQueryBuilder);
+/// assert_eq!(
+///     sql,
+///     r#"SELECT "NAME" FROM "SEA_TABLE" WHERE "ID" > ? ORDER BY "ID" ASC FETCH FIRST ? ROWS ONLY"#
+/// );
+///
+/// let stmt = conn.prepare(&sql, CursorMode::Forward).unwrap();
+/// let params: Vec<&dyn ToSql> = values.iter().map(|value| value as _).collect();
+/// stmt.execute_bind(&params).unwrap();
+/// ```
+#[derive(Debug, Copy, Clone, Default)]
+pub struct MimerQueryBuilder;
+
+impl GenericBuilder for MimerQueryBuilder {}
+
+impl SchemaBuilder for MimerQueryBuilder {}
+
+impl QuotedBuilder for MimerQueryBuilder {
+    fn quote(&self) -> Quote {
+        Quote::new(b'"')
+    }
+}
+
+impl EscapeBuilder for MimerQueryBuilder {
+    fn escape_string(&self, string: &str) -> String {
+        string.replace('\'', "''")
+    }
+
+    fn unescape_string(&self, string: &str) -> String {
+        string.replace("''", "'")
+    }
+}
+
+impl TableRefBuilder for MimerQueryBuilder {}
+
+impl PrecedenceDecider for MimerQueryBuilder {
+    fn inner_expr_well_known_greater_precedence(
+        &self,
+        inner: &SimpleExpr,
+        outer_oper: &Oper,
+    ) -> bool {
+        PostgresQueryBuilder.inner_expr_well_known_greater_precedence(inner, outer_oper)
+    }
+}
+
+impl OperLeftAssocDecider for MimerQueryBuilder {
+    fn well_known_left_associative(&self, op: &BinOper) -> bool {
+        PostgresQueryBuilder.well_known_left_associative(op)
+    }
+}
+
+impl QueryBuilder for MimerQueryBuilder {
+    fn prepare_query_statement(&self, query: &SubQueryStatement, sql: &mut dyn SqlWriter) {
+        match query {
+            SubQueryStatement::SelectStatement(stmt) => self.prepare_select_statement(stmt, sql),
+            SubQueryStatement::InsertStatement(stmt) => self.prepare_insert_statement(stmt, sql),
+            SubQueryStatement::UpdateStatement(stmt) => self.prepare_update_statement(stmt, sql),
+            SubQueryStatement::DeleteStatement(stmt) => self.prepare_delete_statement(stmt, sql),
+            SubQueryStatement::WithStatement(stmt) => self.prepare_with_query(stmt, sql),
+        }
+    }
+
+    fn prepare_value(&self, value: &Value, sql: &mut dyn SqlWriter) {
+        sql.push_param(value.clone(), self as _);
+    }
+
+    fn prepare_select_limit_offset(&self, select: &SelectStatement, sql: &mut dyn SqlWriter) {
+        // The limit and offset of a statement can't be read outside of sea-query, so they are taken from
+        // the `LIMIT ? OFFSET ?` clause that the default implementation writes
+        let mut clause = LimitOffsetClause::default();
+        PostgresQueryBuilder.prepare_select_limit_offset(select, &mut clause);
+        let mut params = clause.params.iter();
+        let limit = clause
+            .sql
+            .contains(" LIMIT ")
+            .then(|| params.next())
+            .flatten();
+        let offset = clause
+            .sql
+            .contains(" OFFSET ")
+            .then(|| params.next())
+            .flatten();
+
+        if let Some(offset) = offset {
+            write!(sql, " OFFSET ").unwrap();
+            self.prepare_value(offset, sql);
+            write!(sql, " ROWS").unwrap();
+        }
+        if let Some(limit) = limit {
+            write!(sql, " FETCH FIRST ").unwrap();
+            self.prepare_value(limit, sql);
+            write!(sql, " ROWS ONLY").unwrap();
+        }
+    }
+
+    fn prepare_update_limit(&self, update: &UpdateStatement, _sql: &mut dyn SqlWriter) {
+        let mut clause = LimitOffsetClause::default();
+        PostgresQueryBuilder.prepare_update_limit(update, &mut clause);
+        if !clause.params.is_empty() {
+            panic!("Mimer SQL doesn't support LIMIT in UPDATE statements");
+        }
+    }
+
+    fn prepare_delete_limit(&self, delete: &DeleteStatement, _sql: &mut dyn SqlWriter) {
+        let mut clause = LimitOffsetClause::default();
+        PostgresQueryBuilder.prepare_delete_limit(delete, &mut clause);
+        if !clause.params.is_empty() {
+            panic!("Mimer SQL doesn't support LIMIT in DELETE statements");
+        }
+    }
+
+    fn if_null_function(&self) -> &str {
+        "COALESCE"
+    }
+}
+
+/// Collects the text and values of a `LIMIT` and `OFFSET` clause written by another query builder.
+#[derive(Default)]
+struct LimitOffsetClause {
+    sql: String,
+    params: Vec<Value>,
+}
+
+impl Write for LimitOffsetClause {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.sql.write_str(s)
+    }
+}
+
+impl fmt::Display for LimitOffsetClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.sql)
+    }
+}
+
+impl SqlWriter for LimitOffsetClause {
+    fn push_param(&mut self, value: Value, _query_builder: &dyn QueryBuilder) {
+        self.sql.push('?');
+        self.params.push(value);
+    }
+
+    fn as_writer(&mut self) -> &mut dyn Write {
+        self
+    }
+}
+
+impl TableBuilder for MimerQueryBuilder {
+    fn prepare_column_def(&self, column_def: &ColumnDef, sql: &mut dyn SqlWriter) {
+        write!(
+            sql,
+            "{}{}{}",
+            self.quote().left(),
+            column_def.get_column_name(),
+            self.quote().right()
+        )
+        .unwrap();
+
+        if let Some(column_type) = column_def.get_column_type() {
+            write!(sql, " ").unwrap();
+            self.prepare_column_type(column_type, sql);
+        }
+
+        // The identity clause comes before the default value and constraints of a column
+        let specs = column_def.get_column_spec();
+        if specs
+            .iter()
+            .any(|spec| matches!(spec, ColumnSpec::AutoIncrement))
+        {
+            write!(sql, " ").unwrap();
+            self.prepare_column_spec(&ColumnSpec::AutoIncrement, sql);
+        }
+        for spec in specs {
+            if let ColumnSpec::AutoIncrement | ColumnSpec::Comment(_) = spec {
+                continue;
+            }
+            write!(sql, " ").unwrap();
+            self.prepare_column_spec(spec, sql);
+        }
+    }
+
+    fn prepare_column_type(&self, column_type: &ColumnType, sql: &mut dyn SqlWriter) {
+        write!(
+            sql,
+            "{}",
+            match column_type {
+                ColumnType::Char(length) => match length {
+                    Some(length) => format!("NCHAR({length})"),
+                    None => "NCHAR".into(),
+                },
+                ColumnType::String(length) => match length {
+                    StringLen::N(length) => format!("NVARCHAR({length})"),
+                    StringLen::None => "NVARCHAR(255)".into(),
+                    StringLen::Max => "NCLOB".into(),
+                },
+                ColumnType::Text | ColumnType::Json | ColumnType::JsonBinary => "NCLOB".into(),
+                ColumnType::TinyInteger | ColumnType::TinyUnsigned | ColumnType::SmallInteger =>
+                    "SMALLINT".into(),
+                ColumnType::SmallUnsigned | ColumnType::Integer => "INTEGER".into(),
+                ColumnType::Unsigned | ColumnType::BigInteger => "BIGINT".into(),
+                ColumnType::BigUnsigned => "DECIMAL(20)".into(),
+                ColumnType::Float => "REAL".into(),
+                ColumnType::Double => "DOUBLE PRECISION".into(),
+                ColumnType::Decimal(precision) => match precision {
+                    Some((precision, scale)) => format!("DECIMAL({precision}, {scale})"),
+                    None => "DECIMAL".into(),
+                },
+                ColumnType::Money(precision) => match precision {
+                    Some((precision, scale)) => format!("DECIMAL({precision}, {scale})"),
+                    None => "DECIMAL(19, 4)".into(),
+                },
+                ColumnType::DateTime
+                | ColumnType::Timestamp
+                | ColumnType::TimestampWithTimeZone => "TIMESTAMP".into(),
+                ColumnType::Time => "TIME".into(),
+                ColumnType::Date => "DATE".into(),
+                ColumnType::Interval(fields, _) => format!(
+                    "INTERVAL {}",
+                    match fields.as_ref().unwrap_or(&PgInterval::DayToSecond) {
+                        PgInterval::Year => "YEAR",
+                        PgInterval::Month => "MONTH",
+                        PgInterval::Day => "DAY",
+                        PgInterval::Hour => "HOUR",
+                        PgInterval::Minute => "MINUTE",
+                        PgInterval::Second => "SECOND",
+                        PgInterval::YearToMonth => "YEAR TO MONTH",
+                        PgInterval::DayToHour => "DAY TO HOUR",
+                        PgInterval::DayToMinute => "DAY TO MINUTE",
+                        PgInterval::DayToSecond => "DAY TO SECOND",
+                        PgInterval::HourToMinute => "HOUR TO MINUTE",
+                        PgInterval::HourToSecond => "HOUR TO SECOND",
+                        PgInterval::MinuteToSecond => "MINUTE TO SECOND",
+                    }
+                ),
+                ColumnType::Binary(length) => format!("BINARY({length})"),
+                ColumnType::VarBinary(length) => match length {
+                    StringLen::N(length) => format!("VARBINARY({length})"),
+                    StringLen::None => "VARBINARY(255)".into(),
+                    StringLen::Max => "BLOB".into(),
+                },
+                ColumnType::Blob => "BLOB".into(),
+                ColumnType::Boolean => "BOOLEAN".into(),
+                ColumnType::Uuid => "BUILTIN.UUID".into(),
+                ColumnType::Enum { .. } => "NVARCHAR(255)".into(),
+                ColumnType::Custom(iden) => iden.to_string(),
+                column_type => {
+                    // No Mimer SQL equivalent, the server reports the type when the statement is executed
+                    return PostgresQueryBuilder.prepare_column_type(column_type, sql);
+                }
+            }
+        )
+        .unwrap()
+    }
+
+    fn column_spec_auto_increment_keyword(&self) -> &str {
+        "GENERATED BY DEFAULT AS IDENTITY"
+    }
+
+    fn prepare_table_alter_statement(&self, alter: &TableAlterStatement, sql: &mut dyn SqlWriter) {
+        PostgresQueryBuilder.prepare_table_alter_statement(alter, sql)
+    }
+
+    fn prepare_table_rename_statement(
+        &self,
+        rename: &TableRenameStatement,
+        sql: &mut dyn SqlWriter,
+    ) {
+        PostgresQueryBuilder.prepare_table_rename_statement(rename, sql)
+    }
+}
+
+impl IndexBuilder for MimerQueryBuilder {
+    fn prepare_index_create_statement(
+        &self,
+        create: &IndexCreateStatement,
+        sql: &mut dyn SqlWriter,
+    ) {
+        PostgresQueryBuilder.prepare_index_create_statement(create, sql)
+    }
+
+    fn prepare_table_ref_index_stmt(&self, table_ref: &TableRef, sql: &mut dyn SqlWriter) {
+        PostgresQueryBuilder.prepare_table_ref_index_stmt(table_ref, sql)
+    }
+
+    fn prepare_index_drop_statement(&self, drop: &IndexDropStatement, sql: &mut dyn SqlWriter) {
+        PostgresQueryBuilder.prepare_index_drop_statement(drop, sql)
+    }
+
+    fn prepare_index_prefix(&self, create: &IndexCreateStatement, sql: &mut dyn SqlWriter) {
+        PostgresQueryBuilder.prepare_index_prefix(create, sql)
+    }
+}
+
+impl ForeignKeyBuilder for MimerQueryBuilder {
+    fn prepare_table_ref_fk_stmt(&self, table_ref: &TableRef, sql: &mut dyn SqlWriter) {
+        PostgresQueryBuilder.prepare_table_ref_fk_stmt(table_ref, sql)
+    }
+
+    fn prepare_foreign_key_drop_statement_internal(
+        &self,
+        drop: &ForeignKeyDropStatement,
+        sql: &mut dyn SqlWriter,
+        mode: Mode,
+    ) {
+        PostgresQueryBuilder.prepare_foreign_key_drop_statement_internal(drop, sql, mode)
+    }
+
+    fn prepare_foreign_key_create_statement_internal(
+        &self,
+        create: &ForeignKeyCreateStatement,
+        sql: &mut dyn SqlWriter,
+        mode: Mode,
+    ) {
+        PostgresQueryBuilder.prepare_foreign_key_create_statement_internal(create, sql, mode)
+    }
+}
+
+/// Binds the values of statements built by sea-query through the [ToSql] implementations of the corresponding Rust types.
+/// Values of types that this crate doesn't convert, such as decimals, are bound as the text of their SQL literal.
+impl ToSql for Value {
+    fn to_sql(&self) -> MimerDatatype<'_> {
+        match self {
+            Value::Bool(v) => v.to_sql(),
+            Value::TinyInt(v) => v.to_sql(),
+            Value::SmallInt(v) => v.to_sql(),
+            Value::Int(v) => v.to_sql(),
+            Value::BigInt(v) => v.to_sql(),
+            Value::TinyUnsigned(v) => v.to_sql(),
+            Value::SmallUnsigned(v) => v.to_sql(),
+            Value::Unsigned(v) => v.to_sql(),
+            Value::BigUnsigned(v) => v.to_sql(),
+            Value::Float(v) => v.to_sql(),
+            Value::Double(v) => v.to_sql(),
+            Value::String(v) => boxed_to_sql(v),
+            Value::Char(v) => v.to_sql(),
+            Value::Bytes(v) => boxed_to_sql(v),
+            Value::ChronoDate(v) => boxed_to_sql(v),
+            Value::ChronoTime(v) => boxed_to_sql(v),
+            Value::ChronoDateTime(v) => boxed_to_sql(v),
+            Value::ChronoDateTimeUtc(v) => boxed_to_sql(v),
+            Value::ChronoDateTimeLocal(v) => v
+                .as_deref()
+                .map_or(MimerDatatype::Null, |v| v.naive_utc().to_sql().into_owned()),
+            Value::ChronoDateTimeWithTimeZone(v) => boxed_to_sql(v),
+            Value::Uuid(v) => boxed_to_sql(v),
+            #[cfg(feature = "json")]
+            Value::Json(v) => boxed_to_sql(v),
+            #[cfg(feature = "time")]
+            Value::TimeDate(v) => boxed_to_sql(v),
+            #[cfg(feature = "time")]
+            Value::TimeTime(v) => boxed_to_sql(v),
+            #[cfg(feature = "time")]
+            Value::TimeDateTime(v) => boxed_to_sql(v),
+            #[cfg(feature = "time")]
+            Value::TimeDateTimeWithTimeZone(v) => boxed_to_sql(v),
+            #[allow(unreachable_patterns)]
+            value => MimerDatatype::String(value.to_string()),
+        }
+    }
+}
+
+/// Converts the boxed value of a [Value], which is null when it is [None].
+fn boxed_to_sql<T: ToSql>(value: &Option<Box<T>>) -> MimerDatatype<'_> {
+    value.as_deref().map_or(MimerDatatype::Null, T::to_sql)
+}
+
+#[cfg(test)]
+mod sea_query_tests {
+    use super::*;
+    use crate::common::mimer_options::CursorMode;
+    use crate::testing::*;
+    use ::sea_query::{Alias, Expr, Order, Query, Table};
+
+    #[test]
+    fn generated_sql() {
+        let (sql, values) = Query::select()
+            .column(Alias::new("COLUMN_1"))
+            .from(Alias::new("TEST_TABLE"))
+            .and_where(Expr::col(Alias::new("COLUMN_2")).gt(1))
+            .order_by(Alias::new("COLUMN_2"), Order::Asc)
+            .limit(2)
+            .offset(1)
+            .build(MimerQueryBuilder);
+        assert_eq!(
+            sql,
+            "SELECT \"COLUMN_1\" FROM \"TEST_TABLE\" WHERE \"COLUMN_2\" > ? \
+             ORDER BY \"COLUMN_2\" ASC OFFSET ? ROWS FETCH FIRST ? ROWS ONLY"
+        );
+        assert_eq!(
+            values.0,
+            vec![
+                Value::Int(Some(1)),
+                Value::BigUnsigned(Some(1)),
+                Value::BigUnsigned(Some(2))
+            ]
+        );
+
+        let sql = Query::insert()
+            .into_table(Alias::new("TEST_TABLE"))
+            .columns([Alias::new("COLUMN_1"), Alias::new("COLUMN_2")])
+            .values_panic(["it's".into(), 3.into()])
+            .to_string(MimerQueryBuilder);
+        assert_eq!(
+            sql,
+            "INSERT INTO \"TEST_TABLE\" (\"COLUMN_1\", \"COLUMN_2\") VALUES ('it''s', 3)"
+        );
+
+        let sql = Table::create()
+            .table(Alias::new("sea_table"))
+            .col(
+                ColumnDef::new(Alias::new("id"))
+                    .integer()
+                    .not_null()
+                    .auto_increment()
+                    .primary_key(),
+            )
+            .col(ColumnDef::new(Alias::new("name")).string_len(30))
+            .col(ColumnDef::new(Alias::new("created")).date_time())
+            .col(ColumnDef::new(Alias::new("key")).uuid())
+            .to_string(MimerQueryBuilder);
+        assert_eq!(
+            sql,
+            "CREATE TABLE \"sea_table\" ( \
+             \"id\" INTEGER GENERATED BY DEFAULT AS IDENTITY NOT NULL PRIMARY KEY, \
+             \"name\" NVARCHAR(30), \"created\" TIMESTAMP, \"key\" BUILTIN.UUID )"
+        );
+    }
+
+    #[test]
+    fn unsupported_column_type() {
+        let sql = Table::create()
+            .table(Alias::new("sea_table"))
+            .col(ColumnDef::new(Alias::new("address")).inet())
+            .to_string(MimerQueryBuilder);
+        assert_eq!(sql, "CREATE TABLE \"sea_table\" ( \"address\" inet )");
+
+        let conn = establish_connection();
+        assert!(conn.execute_statement(&sql).is_err());
+    }
+
+    #[test]
+    fn insert_and_select() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+
+        let (sql, values) = Query::insert()
+            .into_table(Alias::new("TEST_TABLE"))
+            .columns([Alias::new("COLUMN_1"), Alias::new("COLUMN_2")])
+            .values_panic(["first".into(), 1.into()])
+            .values_panic([Option::<String>::None.into(), 2.into()])
+            .build(MimerQueryBuilder);
+        let stmt = conn.prepare(&sql, CursorMode::Forward).unwrap();
+        let params: Vec<&dyn ToSql> = values.iter().map(|value| value as _).collect();
+        assert_eq!(stmt.execute_bind(&params).unwrap(), 2);
+
+        let (sql, values) = Query::select()
+            .column(Alias::new("COLUMN_1"))
+            .from(Alias::new("TEST_TABLE"))
+            .order_by(Alias::new("COLUMN_2"), Order::Desc)
+            .limit(1)
+            .build(MimerQueryBuilder);
+        let stmt = conn.prepare(&sql, CursorMode::Forward).unwrap();
+        let params: Vec<&dyn ToSql> = values.iter().map(|value| value as _).collect();
+        stmt.bind_params(&params).unwrap();
+        let mut cursor = stmt.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+        assert_eq!(row.get::<String>(1).unwrap(), None);
+        assert!(cursor.next_row().unwrap().is_none());
+    }
+}
@@ -0,0 +1,249 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{
+    common::mimer_options::CursorMode,
+    connection::Connection,
+    statement::Statement,
+    types::{FromSql, ToSql},
+};
+
+/// A sequence in the database, as returned by [sequence](crate::Connection::sequence()).
+///
+/// The queries for the next and current value of the sequence are prepared once, when the handle is created,
+/// which makes it cheaper than [next_sequence_value](crate::Connection::next_sequence_value()) when many values are fetched.
+pub struct Sequence {
+    name: String,
+    next: Statement,
+    current: Statement,
+}
+
+impl Sequence {
+    /// Gets the name of the sequence.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Advances the sequence and returns its new value.
+    ///
+    /// # Errors
+    /// Returns [Err] when the query couldn't be executed, e.g. if the sequence is exhausted, or when the value couldn't be converted to `T`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop sequence order_seq").ok();
+    /// conn.execute_statement("create sequence order_seq start with 100").unwrap();
+    ///
+    /// let sequence = conn.sequence("order_seq").unwrap();
+    /// assert_eq!(sequence.next_value::<i64>().unwrap(), 100);
+    /// assert_eq!(sequence.next_value::<i64>().unwrap(), 101);
+    /// ```
+    pub fn next_value<T: FromSql>(&self) -> Result<T, i32> {
+        fetch_value(&self.next)
+    }
+
+    /// Returns the value most recently returned by [next_value](Sequence::next_value()) in this session, without advancing the sequence.
+    ///
+    /// # Errors
+    /// Returns [Err] when the query couldn't be executed, e.g. if no value has been fetched from the sequence yet,
+    /// or when the value couldn't be converted to `T`.
+    pub fn current_value<T: FromSql>(&self) -> Result<T, i32> {
+        fetch_value(&self.current)
+    }
+}
+
+/// Executes a prepared query for a sequence value and returns the value.
+fn fetch_value<T: FromSql>(stmnt: &Statement) -> Result<T, i32> {
+    let mut cursor = stmnt.open_cursor()?;
+    let row = cursor.next_row()?.ok_or(-26009)?; // Query returned no rows
+    row.get::<T>(1)?.ok_or(-26027) // Column value is NULL
+}
+
+impl Statement {
+    /// Executes a data changing statement, usually an *INSERT*, which takes a value from `sequence`,
+    /// and returns the value the statement took, e.g. the generated key of the inserted row.
+    ///
+    /// Mimer SQL has no `RETURNING` clause, so the value is read as the current value of the sequence in this session.
+    /// It is therefore the value taken by this execution even when other sessions use the sequence at the same time.
+    /// The query for the current value is prepared together with the [Sequence] handle, which can be reused across executions.
+    ///
+    /// # Errors
+    /// Returns [Err] when the statement couldn't be executed, when it didn't take a value from `sequence`,
+    /// or when the value couldn't be converted to `T`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table orders").ok();
+    /// # conn.execute_statement("drop sequence order_seq").ok();
+    /// conn.execute_statement("create sequence order_seq start with 1").unwrap();
+    /// conn.execute_statement("create table orders (id BIGINT PRIMARY KEY, item VARCHAR(30))").unwrap();
+    ///
+    /// let sequence = conn.sequence("order_seq").unwrap();
+    /// let insert = conn
+    ///     .prepare("INSERT INTO orders VALUES(NEXT VALUE FOR order_seq, :item)", CursorMode::Forward)
+    ///     .unwrap();
+    /// assert_eq!(insert.execute_returning::<i64>(&[&"apple"], &sequence).unwrap(), 1);
+    /// assert_eq!(insert.execute_returning::<i64>(&[&"pear"], &sequence).unwrap(), 2);
+    /// ```
+    pub fn execute_returning<T: FromSql>(
+        &self,
+        params: &[&dyn ToSql],
+        sequence: &Sequence,
+    ) -> Result<T, i32> {
+        self.execute_bind(params)?;
+        sequence.current_value()
+    }
+}
+
+impl Connection {
+    /// Creates a handle to the sequence `name`, for fetching its values repeatedly.
+    ///
+    /// # Errors
+    /// Returns [Err] when the queries for the sequence couldn't be prepared, e.g. if the sequence doesn't exist.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop sequence order_seq").ok();
+    /// conn.execute_statement("create sequence order_seq").unwrap();
+    ///
+    /// let sequence = conn.sequence("order_seq").unwrap();
+    /// let id = sequence.next_value::<i64>().unwrap();
+    /// assert_eq!(sequence.current_value::<i64>().unwrap(), id);
+    /// ```
+    pub fn sequence(&self, name: &str) -> Result<Sequence, i32> {
+        Ok(Sequence {
+            name: name.to_string(),
+            next: self.prepare(
+                &format!("SELECT NEXT VALUE FOR {name} FROM SYSTEM.ONEROW"),
+                CursorMode::Forward,
+            )?,
+            current: self.prepare(
+                &format!("SELECT CURRENT VALUE FOR {name} FROM SYSTEM.ONEROW"),
+                CursorMode::Forward,
+            )?,
+        })
+    }
+
+    /// Advances the sequence `name` and returns its new value.
+    /// Use [sequence](crate::Connection::sequence()) instead when fetching many values from the same sequence.
+    ///
+    /// # Errors
+    /// Returns [Err] when the query couldn't be executed, e.g. if the sequence doesn't exist, or when the value couldn't be converted to `T`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop sequence order_seq").ok();
+    /// conn.execute_statement("create sequence order_seq start with 1").unwrap();
+    ///
+    /// assert_eq!(conn.next_sequence_value::<i64>("order_seq").unwrap(), 1);
+    /// ```
+    pub fn next_sequence_value<T: FromSql>(&self, name: &str) -> Result<T, i32> {
+        self.query_scalar::<T>(
+            &format!("SELECT NEXT VALUE FOR {name} FROM SYSTEM.ONEROW"),
+            &[],
+        )?
+        .ok_or(-26027) // Column value is NULL
+    }
+}
+
+#[cfg(test)]
+mod sequence_tests {
+    use crate::testing::*;
+
+    const SEQUENCE: &str = "test_sequence";
+
+    fn drop_create_sequence(conn: &crate::Connection, definition: &str) {
+        conn.execute_statement(&format!("DROP SEQUENCE {SEQUENCE}"))
+            .ok();
+        conn.execute_statement(&format!("CREATE SEQUENCE {SEQUENCE} {definition}"))
+            .unwrap();
+    }
+
+    #[test]
+    fn sequence_values() {
+        let conn = establish_connection();
+        drop_create_sequence(&conn, "START WITH 10 INCREMENT BY 5");
+
+        let sequence = conn.sequence(SEQUENCE).unwrap();
+        assert_eq!(sequence.name(), SEQUENCE);
+        assert_eq!(sequence.next_value::<i64>().unwrap(), 10);
+        assert_eq!(sequence.next_value::<i64>().unwrap(), 15);
+        assert_eq!(sequence.current_value::<i64>().unwrap(), 15);
+        assert_eq!(conn.next_sequence_value::<i64>(SEQUENCE).unwrap(), 20);
+        assert_eq!(sequence.current_value::<i64>().unwrap(), 20);
+
+        assert!(conn.sequence("no_such_sequence").is_err());
+    }
+
+    #[test]
+    fn execute_returning_generated_key() {
+        let conn = establish_connection();
+        drop_create_sequence(&conn, "START WITH 1");
+        drop_create_table(
+            &conn,
+            "sequence_table",
+            "(id BIGINT PRIMARY KEY, item VARCHAR(30))",
+        );
+
+        let sequence = conn.sequence(SEQUENCE).unwrap();
+        let insert = conn
+            .prepare(
+                &format!("INSERT INTO sequence_table VALUES(NEXT VALUE FOR {SEQUENCE}, :item)"),
+                crate::CursorMode::Forward,
+            )
+            .unwrap();
+        let first = insert
+            .execute_returning::<i64>(&[&"apple"], &sequence)
+            .unwrap();
+        let second = insert
+            .execute_returning::<i64>(&[&"pear"], &sequence)
+            .unwrap();
+        assert_eq!((first, second), (1, 2));
+
+        let item = conn
+            .query_scalar::<String>("SELECT item FROM sequence_table WHERE id = ?", &[&second])
+            .unwrap();
+        assert_eq!(item.as_deref(), Some("pear"));
+    }
+}
@@ -0,0 +1,321 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{config, connection::Connection};
+use mimerrust_sys as ffi;
+
+#[doc(hidden)]
+use std::{env, ffi::CStr, fmt, str::FromStr};
+
+/// A Mimer SQL version, e.g. of the server as returned by [server_version](crate::Connection::server_version()).
+///
+/// Versions compare by their numbers, so they can be used to gate features:
+/// ```
+/// # use mimerrust::*;
+/// let version: ServerVersion = "11.0.8".parse().unwrap();
+/// assert!(version >= ServerVersion::new(11, 0, 0));
+/// assert_eq!(version.to_string(), "11.0.8");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ServerVersion {
+    /// The major version.
+    pub major: u32,
+    /// The minor version.
+    pub minor: u32,
+    /// The patch level.
+    pub patch: u32,
+}
+
+impl ServerVersion {
+    /// Creates a version from its numbers.
+    pub fn new(major: u32, minor: u32, patch: u32) -> ServerVersion {
+        ServerVersion {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl FromStr for ServerVersion {
+    type Err = i32;
+
+    /// Parses a version like `11.0.8`, `11.00.0008` or `11.0.8A`. Missing minor and patch numbers are 0, and text after the numbers is ignored.
+    ///
+    /// # Errors
+    /// Returns [Err] with -26200 when `s` doesn't start with a version number.
+    fn from_str(s: &str) -> Result<ServerVersion, i32> {
+        let mut numbers = s.trim().split('.').map(|part| {
+            let digits: String = part.chars().take_while(|c| c.is_ascii_digit()).collect();
+            digits.parse::<u32>()
+        });
+        let major = numbers.next().and_then(|n| n.ok()).ok_or(-26200)?; // Unsupported conversion
+        let minor = numbers.next().and_then(|n| n.ok()).unwrap_or(0);
+        let patch = numbers.next().and_then(|n| n.ok()).unwrap_or(0);
+        Ok(ServerVersion::new(major, minor, patch))
+    }
+}
+
+impl fmt::Display for ServerVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// A feature that depends on the Mimer SQL server or C API library in use, see [Capabilities].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    /// The `BUILTIN.UUID` type, bound with [Uuid](uuid::Uuid). Requires the type on the server and C API 11 or later.
+    Uuid,
+    /// The spatial types `BUILTIN.GIS_LOCATION`, `GIS_LATITUDE` and `GIS_LONGITUDE`. Requires the types on the server and C API 10.1 or later.
+    Spatial,
+    /// Batches of parameters added with [add_batch](crate::Statement::add_batch()). Requires C API 11 or later.
+    Batch,
+}
+
+/// What the server and the linked C API library support, as probed by [capabilities](crate::Connection::capabilities()).
+///
+/// Checking a [Capability] before using it lets an application fall back, or fail with a clear error,
+/// instead of getting a cryptic error code from an older Mimer SQL installation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Version of the server.
+    pub server_version: ServerVersion,
+    /// Version of the linked C API library, [None] if it couldn't be parsed.
+    pub api_version: Option<ServerVersion>,
+    /// Whether the server has the `BUILTIN.UUID` type.
+    pub server_uuid: bool,
+    /// Whether the server has the `BUILTIN.GIS_LOCATION` type and the other spatial types.
+    pub server_spatial: bool,
+}
+
+impl Capabilities {
+    /// Checks whether `capability` is supported by both the server and the C API library.
+    pub fn supports(&self, capability: Capability) -> bool {
+        let api_at_least = |major, minor| {
+            self.api_version
+                .is_some_and(|version| version >= ServerVersion::new(major, minor, 0))
+        };
+        match capability {
+            Capability::Uuid => self.server_uuid && api_at_least(11, 0),
+            Capability::Spatial => self.server_spatial && api_at_least(10, 1),
+            Capability::Batch => api_at_least(11, 0),
+        }
+    }
+
+    /// Checks that `capability` is supported, like [supports](Capabilities::supports).
+    ///
+    /// # Errors
+    /// Returns [Err] with -26035 when the capability isn't supported.
+    pub fn require(&self, capability: Capability) -> Result<(), i32> {
+        match self.supports(capability) {
+            true => Ok(()),
+            false => Err(-26035), // Not supported by the server or C API library
+        }
+    }
+}
+
+impl Connection {
+    /// Probes what the server and the linked C API library support, by reading the [server version](crate::Connection::server_version()),
+    /// the [API version](crate::Connection::api_version()) and the builtin types in the data dictionary.
+    /// The result is cached, so only the first call makes round trips to the server.
+    ///
+    /// # Errors
+    /// Returns [Err] when the server couldn't be queried.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// let id_type = match conn.capabilities().unwrap().supports(Capability::Uuid) {
+    ///     true => "BUILTIN.UUID",
+    ///     false => "BINARY(16)",
+    /// };
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// conn.execute_statement(&format!("CREATE TABLE test_table (id {id_type})")).unwrap();
+    /// ```
+    pub fn capabilities(&self) -> Result<Capabilities, i32> {
+        if let Some(capabilities) = self.cached_capabilities() {
+            return Ok(capabilities);
+        }
+        let has_builtin_type = |name: &str| {
+            self.query_scalar::<i32>(
+                "SELECT CAST(COUNT(*) AS INTEGER) FROM information_schema.user_defined_types \
+                 WHERE user_defined_type_schema = 'BUILTIN' AND user_defined_type_name = ?",
+                &[&name],
+            )
+            .map(|count| count.unwrap_or(0) > 0)
+        };
+        let capabilities = Capabilities {
+            server_version: self.server_version()?,
+            api_version: Connection::api_version().parse().ok(),
+            server_uuid: has_builtin_type("UUID")?,
+            server_spatial: has_builtin_type("GIS_LOCATION")?,
+        };
+        self.cache_capabilities(capabilities);
+        Ok(capabilities)
+    }
+
+    /// Gets the version of the server, as reported by `INFORMATION_SCHEMA.SQL_IMPLEMENTATION_INFO`.
+    ///
+    /// # Errors
+    /// Returns [Err] when the query fails, or with -26200 when the version couldn't be parsed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// if conn.server_version().unwrap() >= ServerVersion::new(11, 0, 0) {
+    ///     println!("connected to Mimer SQL 11 or later");
+    /// }
+    /// ```
+    pub fn server_version(&self) -> Result<ServerVersion, i32> {
+        self.query_scalar::<String>(
+            "SELECT character_value FROM information_schema.sql_implementation_info \
+             WHERE implementation_info_name = 'DBMS VERSION'",
+            &[],
+        )?
+        .ok_or(-26009)? // No rows
+        .parse()
+    }
+
+    /// Gets the version of the linked Mimer SQL C API library, e.g. to log it together with the [server version](crate::Connection::server_version()).
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// println!("Mimer SQL C API {}", Connection::api_version());
+    /// ```
+    pub fn api_version() -> String {
+        let version = unsafe { ffi::MimerAPIVersion() };
+        if version.is_null() {
+            return String::new();
+        }
+        unsafe { CStr::from_ptr(version) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Gets the name of the database the connection is open to.
+    /// When it was opened to the default database, this is `MIMER_DATABASE` if set, and otherwise the `DEFAULT` entry of `SQLHOSTS`,
+    /// or an empty string if neither could be read.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// assert_eq!(conn.database_name(), db.as_str());
+    /// ```
+    pub fn database_name(&self) -> String {
+        match self.database() {
+            "" => env::var("MIMER_DATABASE")
+                .ok()
+                .filter(|database| !database.is_empty())
+                .or_else(|| {
+                    config::list_databases()
+                        .ok()?
+                        .into_iter()
+                        .find(|database| database.is_default)
+                        .map(|database| database.name)
+                })
+                .unwrap_or_default(),
+            database => database.to_string(),
+        }
+    }
+
+    /// Gets the ident the session currently runs as, i.e. `CURRENT_USER`, which differs from the ident logged in as after entering a program ident.
+    ///
+    /// # Errors
+    /// Returns [Err] when the query fails.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// assert_eq!(conn.current_ident().unwrap(), "RUSTUSER");
+    /// ```
+    pub fn current_ident(&self) -> Result<String, i32> {
+        self.query_scalar::<String>("SELECT CURRENT_USER FROM system.onerow", &[])?
+            .ok_or(-26009) // No rows
+    }
+}
+
+#[cfg(test)]
+mod server_info_tests {
+    use super::*;
+    use crate::testing::*;
+
+    #[test]
+    fn parse_versions() {
+        assert_eq!("11.0.8".parse(), Ok(ServerVersion::new(11, 0, 8)));
+        assert_eq!("11.00.0008".parse(), Ok(ServerVersion::new(11, 0, 8)));
+        assert_eq!("10.1.6A".parse(), Ok(ServerVersion::new(10, 1, 6)));
+        assert_eq!("12".parse(), Ok(ServerVersion::new(12, 0, 0)));
+        assert_eq!("beta".parse::<ServerVersion>(), Err(-26200));
+        assert!(ServerVersion::new(11, 0, 8) > ServerVersion::new(10, 1, 12));
+    }
+
+    #[test]
+    fn capability_checks() {
+        let capabilities = Capabilities {
+            server_version: ServerVersion::new(10, 1, 0),
+            api_version: Some(ServerVersion::new(10, 1, 6)),
+            server_uuid: false,
+            server_spatial: true,
+        };
+        assert!(capabilities.supports(Capability::Spatial));
+        assert!(!capabilities.supports(Capability::Batch));
+        assert_eq!(capabilities.require(Capability::Uuid), Err(-26035));
+
+        let conn = establish_connection();
+        let capabilities = conn.capabilities().unwrap();
+        assert_eq!(capabilities.server_version, conn.server_version().unwrap());
+        assert!(capabilities.supports(Capability::Batch));
+        assert_eq!(conn.capabilities().unwrap(), capabilities);
+    }
+
+    #[test]
+    fn session_info() {
+        let conn = establish_connection();
+        assert!(conn.server_version().unwrap().major >= 10);
+        assert!(!Connection::api_version().is_empty());
+        assert_eq!(
+            conn.database_name(),
+            std::env::var("MIMER_DATABASE").unwrap()
+        );
+        assert_eq!(conn.current_ident().unwrap(), IDENT);
+    }
+}
@@ -0,0 +1,72 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use parking_lot::{Mutex, MutexGuard, ReentrantMutex, ReentrantMutexGuard};
+
+#[doc(hidden)]
+use std::{
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+/// Serializes the calls into the C API on a session, shared by the connection and all its statements.
+///
+/// The C API lets a session and its statements be used from any thread, but not from several threads at once,
+/// since they share the connection to the server. The lock is reentrant, so a thread holding the handle of a statement
+/// may take the handle of another statement or of the session of the same connection.
+pub(crate) type SessionLock = Arc<ReentrantMutex<()>>;
+
+/// Access to a C API handle of a session or one of its statements, holding the [SessionLock] of the session
+/// for as long as the handle is borrowed. Handles are only reachable through this guard, so that a call into the C API
+/// can't overlap with another call on the same session.
+pub(crate) struct SessionGuard<'a, T> {
+    // Fields are dropped in order, so the handle is released before the session
+    handle: MutexGuard<'a, T>,
+    _session: ReentrantMutexGuard<'a, ()>,
+}
+
+impl<'a, T> SessionGuard<'a, T> {
+    /// Locks the session, and then the handle.
+    pub(crate) fn lock(session: &'a SessionLock, handle: &'a Mutex<T>) -> SessionGuard<'a, T> {
+        let session = session.lock();
+        SessionGuard {
+            handle: handle.lock(),
+            _session: session,
+        }
+    }
+}
+
+impl<T> Deref for SessionGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.handle
+    }
+}
+
+impl<T> DerefMut for SessionGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.handle
+    }
+}
@@ -0,0 +1,89 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+/// Quotes an identifier as a delimited identifier, surrounded by double quotes and with embedded double quotes doubled.
+///
+/// A delimited identifier is case sensitive, so `quote_ident("my_table")` names another table than the unquoted `my_table`,
+/// which Mimer SQL stores in upper case. Quote the name in upper case to refer to a table created with an unquoted name.
+///
+/// # Examples
+/// ```
+/// use mimerrust::sql::quote_ident;
+///
+/// assert_eq!(quote_ident("My Table"), "\"My Table\"");
+/// assert_eq!(quote_ident("say \"hi\""), "\"say \"\"hi\"\"\"");
+/// ```
+pub fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+/// Quotes a string as a character string literal, surrounded by single quotes and with embedded single quotes doubled.
+///
+/// # Examples
+/// ```
+/// use mimerrust::sql::quote_literal;
+///
+/// assert_eq!(quote_literal("it's"), "'it''s'");
+/// let sql = format!("CREATE TABLE t (c VARCHAR(10) DEFAULT {})", quote_literal("n/a"));
+/// assert_eq!(sql, "CREATE TABLE t (c VARCHAR(10) DEFAULT 'n/a')");
+/// ```
+pub fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod sql_tests {
+    use super::*;
+    use crate::testing::*;
+    use crate::CursorMode;
+
+    #[test]
+    fn quoted_names_and_values() {
+        let conn = establish_connection();
+        let table = quote_ident("Quoted \"Table\"");
+        conn.execute_statement(&format!("DROP TABLE {table}")).ok();
+        conn.execute_statement(&format!(
+            "CREATE TABLE {table} ({} VARCHAR(30) DEFAULT {})",
+            quote_ident("a column"),
+            quote_literal("it's")
+        ))
+        .unwrap();
+        conn.execute_statement(&format!("INSERT INTO {table} DEFAULT VALUES"))
+            .unwrap();
+
+        let stmnt = conn
+            .prepare(
+                &format!("SELECT {} FROM {table}", quote_ident("a column")),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = stmnt.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+        assert_eq!(row.get::<String>(1).unwrap().unwrap(), "it's");
+        drop(cursor);
+        drop(stmnt);
+        conn.execute_statement(&format!("DROP TABLE {table}"))
+            .unwrap();
+    }
+}
@@ -24,33 +24,57 @@
 
 use crate::{
     common::{mimer_options::*, return_codes::*, traits::*},
+    connection::Connection,
     cursor::*,
+    ffi_buffer::read_string,
+    geometry,
     inner_connection::*,
     inner_statement::*,
-    match_mimer_BINARY,
+    interval, match_mimer_BINARY,
+    metadata::*,
     mimer_error::*,
+    observer::{FetchObservation, StatementEvent, StatementEventKind},
+    retry::StatementRetry,
+    session_guard::SessionGuard,
+    tracing_support::traced,
+    transaction::{begin_session_transaction, end_session_transaction},
     types::*,
 };
-use crate::{match_mimer_BLOB, match_mimer_CLOB};
+use crate::{match_mimer_BLOB, match_mimer_CLOB, match_mimer_spatial};
 use mimerrust_sys::{self as ffi, MimerStatement_struct};
 
 #[doc(hidden)]
-use parking_lot::MappedMutexGuard;
+use parking_lot::Mutex;
 #[doc(hidden)]
 use std::{
     cmp::Ordering,
+    collections::HashMap,
     ffi::CString,
-    sync::{Arc, Weak},
+    fmt,
+    sync::{Arc, OnceLock, Weak},
+    time::{Duration, Instant},
 };
 
 /// A prepared statement.
 ///
 /// Each prepared statement is created through [prepare](crate::Connection::prepare()), and can only be executed on the connection that created it.
+///
+/// # Thread safety
+/// A statement is [Send] and [Sync], and may be used from any thread. Each call into the C API on it, e.g. for executing it,
+/// fetching a row or reading a column, holds the lock of the statement and the session lock of its [connection](crate::Connection),
+/// since the C API doesn't allow calls on a session and its statements to overlap. Concurrent use of the statements of one connection is thereby
+/// serialized call by call, while statements of different connections take no shared lock, except that a statement with a
+/// [query timeout](crate::Statement::set_query_timeout()) briefly registers each request with a watchdog shared by all statements.
 pub struct Statement {
     inner_statement: Arc<InnerStatement>,
     num_parameters: usize,
     cursor_mode: CursorMode,
     batch_bool: bool,
+    batch_entries: Mutex<Vec<Vec<MimerDatatype<'static>>>>,
+    sql: String,
+    metadata: OnceLock<ResultSetMetadata>,
+    retry: Option<StatementRetry>,
+    type_mismatch: Mutex<Option<TypeMismatch>>, // From the latest bind, None unless it failed with a mismatch
 }
 
 impl GetHandle for Statement {
@@ -60,7 +84,7 @@ impl GetHandle for Statement {
         handle
     }
 
-    fn get_statement_handle(&self) -> Result<Option<MappedMutexGuard<ffi::MimerStatement>>, i32> {
+    fn get_statement_handle(&self) -> Result<Option<SessionGuard<ffi::MimerStatement>>, i32> {
         let handle = self.inner_statement.get_statement_handle();
         self.inner_statement.check_connection()?;
         handle
@@ -83,15 +107,21 @@ impl Statement {
                 num_parameters,
                 cursor_mode,
                 batch_bool: false, // controls when we run MimerAddBatch. We dont want to run it "the last time" before we run execute.
+                batch_entries: Mutex::new(Vec::new()),
+                sql: sqlstatement.to_string(),
+                metadata: OnceLock::new(),
+                retry: None,
+                type_mismatch: Mutex::new(None),
             },
         ))
     }
 
-    /// Executes this statement.
+    /// Executes this statement, returning the number of rows affected by a data changing statement.
     /// Equivalent to calling [execute_bind](crate::Statement::execute_bind()) with an empty set of parameters, i.e "stmnt.execute_bind(&[]);".
     ///
     /// # Errors
-    /// Returns [Err] when the statement couldn't be executed, e.g. if the statment has unset named parameters.
+    /// Returns [Err] when the statement couldn't be executed, e.g. with -26038 if the statement has parameters that haven't been bound,
+    /// see [unbound_parameters](crate::Statement::unbound_parameters()).
     ///
     /// # Examples
     /// ```
@@ -99,7 +129,7 @@ impl Statement {
     /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
     /// # let ident = "RUSTUSER";
     /// # let pass = "RUSTPASSWORD";
-    /// let mut conn = Connection::open(db, ident, pass).unwrap();
+    /// let conn = Connection::open(db, ident, pass).unwrap();
     ///
     /// # conn.execute_statement("drop table test_table").ok();
     /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
@@ -113,17 +143,40 @@ impl Statement {
     /// stmnt.execute().unwrap();
     /// ```
     pub fn execute(&self) -> Result<i32, i32> {
+        self.record(|| match &self.retry {
+            Some(retry) => retry.run(|| self.execute_auxillary()),
+            None => self.execute_auxillary(),
+        })
+    }
+
+    /// Runs `f`, which executes this statement, and adds the statement to the history of a recording [Transaction](crate::Transaction).
+    fn record<F>(&self, f: F) -> Result<i32, i32>
+    where
+        F: FnOnce() -> Result<i32, i32>,
+    {
+        match self.inner_statement.inner_connection.upgrade() {
+            Some(conn) => conn.record_statement(&self.sql, self.num_parameters, f),
+            None => f(),
+        }
+    }
+
+    /// Executes this statement once.
+    fn execute_auxillary(&self) -> Result<i32, i32> {
         let handle = self.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
         if (*handle).is_null() {
             return Err(-26005); // Handle is NULL
         }
-        unsafe {
-            let rc = ffi::MimerExecute(*handle);
-            match rc.cmp(MIMER_SUCCESS) {
-                Ordering::Equal => Ok(rc),
-                Ordering::Greater => Ok(rc),
-                Ordering::Less => Err(rc),
+        self.check_bound(*handle)?;
+        self.batch_entries.lock().clear();
+        let rc = self
+            .inner_statement
+            .with_query_timeout(|| unsafe { ffi::MimerExecute(*handle) });
+        match rc.cmp(MIMER_SUCCESS) {
+            Ordering::Equal | Ordering::Greater => {
+                self.inner_statement.clear_bound();
+                Ok(rc)
             }
+            Ordering::Less => Err(rc),
         }
     }
 
@@ -142,7 +195,7 @@ impl Statement {
     /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
     /// # let ident = "RUSTUSER";
     /// # let pass = "RUSTPASSWORD";
-    /// let mut conn = Connection::open(db, ident, pass).unwrap();
+    /// let conn = Connection::open(db, ident, pass).unwrap();
     ///
     /// # conn.execute_statement("drop table test_table").ok();
     /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
@@ -154,6 +207,38 @@ impl Statement {
     /// stmnt.execute_bind(&[&s,&i]).unwrap();
     /// ```
     pub fn execute_bind(&self, params: &[&dyn ToSql]) -> Result<i32, i32> {
+        self.record(|| match &self.retry {
+            Some(retry) => retry.run(|| self.execute_bind_auxillary(params)),
+            None => self.execute_bind_auxillary(params),
+        })
+    }
+
+    /// Executes a data changing statement such as *INSERT*, *UPDATE* or *DELETE* like [execute_bind](crate::Statement::execute_bind()),
+    /// and returns the number of rows it affected.
+    ///
+    /// # Errors
+    /// Returns [Err] when the statement couldn't be executed, see [execute_bind](crate::Statement::execute_bind()).
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// conn.execute_statement("INSERT INTO test_table VALUES('one', 1), ('two', 2), ('three', 3)").unwrap();
+    ///
+    /// let stmnt = conn.prepare("UPDATE test_table SET column_2 = column_2 * 10 WHERE column_2 > :min", CursorMode::Forward).unwrap();
+    /// assert_eq!(stmnt.execute_update(&[&1]).unwrap(), RowsAffected(2));
+    /// ```
+    pub fn execute_update(&self, params: &[&dyn ToSql]) -> Result<RowsAffected, i32> {
+        self.execute_bind(params).map(|rc| RowsAffected(rc as u64))
+    }
+
+    /// Binds the parameters and executes this statement once.
+    fn execute_bind_auxillary(&self, params: &[&dyn ToSql]) -> Result<i32, i32> {
         let handle = self.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
         if (*handle).is_null() {
             return Err(-26005); // Handle is NULL
@@ -162,14 +247,143 @@ impl Statement {
         if !params.is_empty() {
             self.set_params(params, *handle)?;
         }
-        unsafe {
-            let rc = ffi::MimerExecute(*handle);
-            match rc.cmp(MIMER_SUCCESS) {
-                Ordering::Equal => Ok(rc),
-                Ordering::Greater => Ok(rc),
-                Ordering::Less => Err(rc),
+        self.check_bound(*handle)?;
+        let rc = self
+            .inner_statement
+            .with_query_timeout(|| unsafe { ffi::MimerExecute(*handle) });
+        match rc.cmp(MIMER_SUCCESS) {
+            Ordering::Equal | Ordering::Greater => {
+                self.inner_statement.clear_bound();
+                Ok(rc)
             }
+            Ordering::Less => Err(rc),
+        }
+    }
+
+    /// Checks that all input parameters have been bound since the statement was last executed,
+    /// as executing with an unset parameter only gives an error from the server that doesn't say which one.
+    fn check_bound(&self, handle: ffi::MimerStatement) -> Result<(), i32> {
+        match self.unbound_indexes(handle).is_empty() {
+            true => Ok(()),
+            false => Err(-26038), // Parameters haven't been bound
+        }
+    }
+
+    /// Returns the indexes of the input parameters that haven't been bound since the statement was last executed.
+    /// Output parameters of a procedure call are never bound, and are left out.
+    fn unbound_indexes(&self, handle: ffi::MimerStatement) -> Vec<i16> {
+        (1..=self.num_parameters as i16)
+            .filter(|idx| !self.inner_statement.is_bound(*idx))
+            .filter(|idx| self.get_parameter_mode_auxillary(handle, *idx) != Ok(ParameterMode::OUT))
+            .collect()
+    }
+
+    /// Returns the indexes, starting at 1, of the input parameters that haven't been bound since the statement was last executed.
+    /// Executing the statement fails with the error code -26038 while this isn't empty, and the message of the [MimerError]
+    /// from [get_error](crate::Statement::get_error()) then lists the names of these parameters.
+    ///
+    /// A parameter is bound by [bind](crate::Statement::bind()) or [execute_bind](crate::Statement::execute_bind()),
+    /// and has to be bound again after the statement has been executed successfully.
+    ///
+    /// # Errors
+    /// Returns [Err] if the statement handle was invalid.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// let stmnt = conn.prepare("INSERT INTO test_table VALUES(:string,:int)", CursorMode::Forward).unwrap();
+    /// stmnt.bind(&"the number one", 1).unwrap();
+    /// assert_eq!(stmnt.unbound_parameters().unwrap(), vec![2]);
+    ///
+    /// let ec = stmnt.execute().unwrap_err();
+    /// assert_eq!(ec, -26038);
+    /// assert!(stmnt.get_error(ec).get_error_message().ends_with(": INT"));
+    /// ```
+    pub fn unbound_parameters(&self) -> Result<Vec<i16>, i32> {
+        let handle = self.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
+        if (*handle).is_null() {
+            return Err(-26005); // Handle is NULL
+        }
+        Ok(self.unbound_indexes(*handle))
+    }
+
+    /// Executes a statement, binding its named parameters from a map.
+    /// Each parameter is looked up by name, either exactly or in uppercase as Mimer SQL reports parameter names,
+    /// so `:name` in the query can be given as `"name"` or `"NAME"`. A name used more than once in the query binds every occurrence.
+    ///
+    /// With `ignore_extra_keys`, keys that don't name a parameter of the statement are ignored, otherwise they are an error.
+    ///
+    /// # Errors
+    /// Returns [Err] if a parameter has no value in the map, if the map has a key that isn't a parameter and `ignore_extra_keys` is false,
+    /// or when the statement couldn't be executed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # use std::collections::HashMap;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    ///
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// let stmnt = conn.prepare("INSERT INTO test_table VALUES(:string,:int)", CursorMode::Forward).unwrap();
+    ///
+    /// let mut params = HashMap::new();
+    /// params.insert(String::from("string"), MimerDatatype::String(String::from("the number one")));
+    /// params.insert(String::from("int"), MimerDatatype::Int(1));
+    /// params.insert(String::from("submit"), MimerDatatype::Null);
+    ///
+    /// stmnt.execute_named_map(&params, true).unwrap();
+    /// assert_eq!(stmnt.execute_named_map(&params, false), Err(-26020));
+    /// ```
+    pub fn execute_named_map<V: ToSql>(
+        &self,
+        params: &HashMap<String, V>,
+        ignore_extra_keys: bool,
+    ) -> Result<i32, i32> {
+        let names = (1..=self.num_parameters as i16)
+            .map(|idx| self.get_parameter_name(idx))
+            .collect::<Result<Vec<String>, i32>>()?;
+        let matches =
+            |name: &str, key: &str| ColumnNameResolution::UppercaseFold.matches(name, key);
+
+        let mut values = Vec::with_capacity(names.len());
+        for name in &names {
+            let value = match params.get(name) {
+                Some(value) => value,
+                None => params
+                    .iter()
+                    .find(|(key, _)| matches(name, key))
+                    .map(|(_, value)| value)
+                    .ok_or(-26019)?, // No value for a named parameter
+            };
+            values.push(value as &dyn ToSql);
+        }
+        if !ignore_extra_keys
+            && params
+                .keys()
+                .any(|key| !names.iter().any(|name| matches(name, key)))
+        {
+            return Err(-26020); // Value for an unknown parameter
         }
+
+        self.execute_bind(&values)
+    }
+
+    /// Sets a policy for retrying [execute](crate::Statement::execute()) and [execute_bind](crate::Statement::execute_bind())
+    /// when they fail with a transient error, or turns retries off with [None]. Retries are off by default.
+    ///
+    /// See [StatementRetry] for an example.
+    pub fn set_retry(&mut self, retry: Option<StatementRetry>) {
+        self.retry = retry;
     }
 
     /// Sets parameters in a Statement, needed before executing it.
@@ -193,6 +407,15 @@ impl Statement {
         Ok(0)
     }
 
+    /// Binds all parameters of the statement in order, without executing it.
+    pub(crate) fn bind_params(&self, params: &[&dyn ToSql]) -> Result<i32, i32> {
+        let handle = self.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
+        if (*handle).is_null() {
+            return Err(-26005); // Handle is NULL
+        }
+        self.set_params(params, *handle)
+    }
+
     /// Binds the value of a parameter in a query.
     /// The parameter is identified by its index, starting at 1.
     ///
@@ -213,6 +436,62 @@ impl Statement {
         value: &dyn ToSql,
         handle: ffi::MimerStatement,
         idx: i16,
+    ) -> Result<i32, i32> {
+        *self.type_mismatch.lock() = None;
+        let value = value.to_sql();
+        let param_type = unsafe { ffi::MimerParameterType(handle, idx) };
+        if param_type >= 0 && !value.can_bind_to(ColumnType::from_code(param_type)) {
+            *self.type_mismatch.lock() = Some(TypeMismatch {
+                param: idx,
+                expected: ColumnType::from_code(param_type),
+                provided: value.type_name(),
+            });
+            return Err(-26203); // Invalid parameter type for MimerDatatype-variant
+        }
+        let result = self.set_param_value(&value, handle, idx);
+        if result.is_ok() {
+            self.inner_statement.mark_bound(idx);
+        }
+        result
+    }
+
+    /// Returns the type mismatch found by the latest bind, if it failed with the error code -26203 because of one.
+    /// Values are checked against the type of the parameter before they are sent, so that e.g. binding a [String] to an *INTEGER*
+    /// parameter tells which parameter and types are involved. The message of the [MimerError] from [get_error](crate::Statement::get_error())
+    /// describes the mismatch as well.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// let stmnt = conn.prepare("INSERT INTO test_table VALUES(:string,:int)", CursorMode::Forward).unwrap();
+    /// assert_eq!(stmnt.bind(&"one", 2), Err(-26203));
+    ///
+    /// let mismatch = stmnt.type_mismatch().unwrap();
+    /// assert_eq!(mismatch.param, 2);
+    /// assert_eq!(mismatch.expected, ColumnType::Integer);
+    /// assert_eq!(mismatch.provided, "String");
+    /// ```
+    pub fn type_mismatch(&self) -> Option<TypeMismatch> {
+        self.type_mismatch.lock().clone()
+    }
+
+    /// Marks a parameter bound outside of [bind](crate::Statement::bind()), e.g. by a LOB appender.
+    pub(crate) fn mark_bound(&self, idx: i16) {
+        self.inner_statement.mark_bound(idx);
+    }
+
+    /// Sets the value of a single parameter with the setter of the C API matching its MimerDatatype variant.
+    fn set_param_value(
+        &self,
+        value: &dyn ToSql,
+        handle: ffi::MimerStatement,
+        idx: i16,
     ) -> Result<i32, i32> {
         let mut rc: i32;
 
@@ -241,6 +520,7 @@ impl Statement {
                 match t as u32 {
                     ffi::MIMER_GIS_LATITUDE | ffi::MIMER_GIS_LONGITUDE => {
                         let arr = value.to_le_bytes();
+                        geometry::check_spatial(t as u32, &arr)?;
                         let ptr = arr.as_ptr() as *const std::ffi::c_void;
                         rc = ffi::MimerSetBinary(handle, idx, ptr, 4);
                     }
@@ -342,6 +622,30 @@ impl Statement {
                     Err(_) => return Err(-26007), // RUST API ERROR: "Could not convert UTF-8 string to CString"
                 }
             },
+            MimerDatatype::Interval(text, column_type) => unsafe {
+                let t = ffi::MimerParameterType(handle, idx);
+                if t < 0 {
+                    return Err(t);
+                }
+                let text = interval::convert(&text, column_type, ColumnType::from_code(t))?;
+                return self.set_param_value(&MimerDatatype::String(text), handle, idx);
+            },
+            MimerDatatype::Uuid(value) => unsafe {
+                let t = ffi::MimerParameterType(handle, idx);
+                if t < 0 {
+                    return Err(t);
+                }
+                match t as u32 {
+                    ffi::MIMER_UUID => rc = ffi::MimerSetUUID(handle, idx, value.as_ptr()),
+                    _ => {
+                        return self.set_param_value(
+                            &MimerDatatype::BinaryArrayRef(&value),
+                            handle,
+                            idx,
+                        )
+                    }
+                }
+            },
             MimerDatatype::BinaryArrayRef(value) => unsafe {
                 let t = ffi::MimerParameterType(handle, idx);
 
@@ -358,6 +662,11 @@ impl Statement {
                         let ptr = value.as_ptr() as *const std::ffi::c_void;
                         rc = ffi::MimerSetBinary(handle, idx, ptr, value.len());
                     }
+                    match_mimer_spatial!() => {
+                        geometry::check_spatial(t as u32, value)?;
+                        let ptr = value.as_ptr() as *const std::ffi::c_void;
+                        rc = ffi::MimerSetBinary(handle, idx, ptr, value.len());
+                    }
                     match_mimer_BLOB!() => {
                         let ptr = value.as_ptr() as *const std::ffi::c_void;
 
@@ -411,13 +720,16 @@ impl Statement {
                 }
             },
 
-            MimerDatatype::BinaryArray(value) => unsafe {
-                let ptr = value.as_ptr() as *const std::ffi::c_void;
-                rc = ffi::MimerSetBinary(handle, idx, ptr, value.len());
-                match rc.cmp(MIMER_SUCCESS) {
-                    Ordering::Equal => (),
-                    _ => return Err(rc),
+            MimerDatatype::BinaryArray(value) => {
+                return self.set_param_value(&MimerDatatype::BinaryArrayRef(&value), handle, idx)
+            }
+            MimerDatatype::Geometry(value) => unsafe {
+                let t = ffi::MimerParameterType(handle, idx);
+                if t < 0 {
+                    return Err(t);
                 }
+                let value = geometry::encode(&value, t as u32)?;
+                return self.set_param_value(&value, handle, idx);
             },
         }
 
@@ -440,7 +752,7 @@ impl Statement {
     /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
     /// # let ident = "RUSTUSER";
     /// # let pass = "RUSTPASSWORD";
-    /// let mut conn = Connection::open(db, ident, pass).unwrap();
+    /// let conn = Connection::open(db, ident, pass).unwrap();
     /// # conn.execute_statement("drop table test_table").ok();
     /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
     /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
@@ -450,7 +762,148 @@ impl Statement {
     /// let mut cursor = stmnt.open_cursor().unwrap();
     /// ```
     pub fn open_cursor(&self) -> Result<Cursor, i32> {
-        Cursor::open(self.inner_statement.clone(), self.cursor_mode)
+        traced!("mimer.open_cursor", { db.statement = self.traced_sql() }, {
+            #[allow(unused_mut)]
+            let mut result = self.open_observed_cursor();
+            #[cfg(feature = "tracing")]
+            if let Ok(cursor) = &mut result {
+                cursor.span = tracing::info_span!(
+                    "mimer.fetch",
+                    db.system = "mimersql",
+                    db.statement = self.traced_sql(),
+                    rows = tracing::field::Empty,
+                    error_code = tracing::field::Empty
+                );
+            }
+            result
+        })
+    }
+
+    /// Returns the SQL of the statement to put in tracing spans, redacted if the connection is set to redact it.
+    #[cfg(feature = "tracing")]
+    fn traced_sql(&self) -> String {
+        crate::tracing_support::statement_text(
+            self.inner_statement.inner_connection.upgrade().as_deref(),
+            &self.sql,
+        )
+    }
+
+    /// Opens the cursor and reports it to the statement observer of the connection, if one is set, and to the metrics recorder with the `metrics` feature.
+    fn open_observed_cursor(&self) -> Result<Cursor, i32> {
+        let observer = self
+            .inner_statement
+            .inner_connection
+            .upgrade()
+            .and_then(|conn| conn.statement_observer());
+        if observer.is_none() && !cfg!(feature = "metrics") {
+            return Cursor::open(self.inner_statement.clone(), self.cursor_mode);
+        }
+        let started = Instant::now();
+        let result = Cursor::open(self.inner_statement.clone(), self.cursor_mode);
+        let event = StatementEvent {
+            kind: StatementEventKind::OpenCursor,
+            sql: &self.sql,
+            duration: started.elapsed(),
+            rows: None,
+            result: result.as_ref().map(|_| 0).map_err(|ec| *ec),
+        };
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_event(&event);
+        if let Some(observer) = observer {
+            observer(&event);
+        }
+        let mut cursor = result?;
+        cursor.observation = Some(FetchObservation::new(&self.sql));
+        Ok(cursor)
+    }
+
+    /// Opens a [Cursor](crate::cursor::Cursor) for the statement like [open_cursor](crate::Statement::open_cursor()),
+    /// after setting the [array size](crate::Statement::set_array_size()) given by `options`.
+    ///
+    /// # Errors
+    /// Returns [Err] when the array size couldn't be set, e.g. if it exceeds the
+    /// [fetch memory budget](crate::Connection::set_fetch_memory_budget()) (-26015), or when the cursor couldn't be opened.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// let stmnt = conn.prepare("SELECT * FROM test_table", CursorMode::Forward).unwrap();
+    ///
+    /// // Fetch as many rows per server request as fit in 4 MB
+    /// let mut cursor = stmnt.open_cursor_with(&FetchOptions::auto()).unwrap();
+    /// ```
+    pub fn open_cursor_with(&self, options: &FetchOptions) -> Result<Cursor, i32> {
+        let size = match (options.array_size, options.memory_target) {
+            (Some(size), _) => Some(size),
+            (None, Some(bytes)) => Some(self.array_size_for(bytes)?),
+            (None, None) => None,
+        };
+        if let Some(size) = size {
+            self.set_array_size(size)?;
+        }
+        self.open_cursor()
+    }
+
+    /// Returns the number of rows that fit in `bytes`, or in the fetch memory budget of the connection if that is smaller.
+    /// At least one row is always fetched.
+    fn array_size_for(&self, bytes: usize) -> Result<i32, i32> {
+        let inner_connection = self
+            .inner_statement
+            .inner_connection
+            .upgrade()
+            .ok_or(-26003)?;
+        let bytes = match inner_connection
+            .fetch_memory_budget
+            .load(std::sync::atomic::Ordering::Relaxed)
+        {
+            0 => bytes,
+            budget => bytes.min(budget),
+        };
+        let row_size = self.row_size()?.max(1) as usize;
+        Ok((bytes / row_size).clamp(1, i32::MAX as usize) as i32)
+    }
+
+    /// Closes `cursor` and opens a new cursor for this statement, executing the query again
+    /// without preparing the statement again.
+    ///
+    /// # Errors
+    /// Returns [Err] when the cursor couldn't be closed or opened again.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// # conn.execute_statement("INSERT INTO test_table VALUES('the number one',1)").unwrap();
+    /// let stmnt = conn.prepare("SELECT * FROM test_table", CursorMode::Forward).unwrap();
+    ///
+    /// let mut cursor = stmnt.open_cursor().unwrap();
+    /// while let Some(_row) = cursor.next_row().unwrap() {
+    ///     // first pass
+    /// }
+    /// let mut cursor = stmnt.reopen_cursor(cursor).unwrap();
+    /// assert!(cursor.next_row().unwrap().is_some());
+    /// ```
+    pub fn reopen_cursor(&self, mut cursor: Cursor) -> Result<Cursor, i32> {
+        if Weak::ptr_eq(
+            &cursor.inner_statement,
+            &Arc::downgrade(&self.inner_statement),
+        ) {
+            cursor.reopen()?;
+            return Ok(cursor);
+        }
+        drop(cursor);
+        self.open_cursor()
     }
 
     /// Returns a MimerError given a [Statement] and a return code.
@@ -465,7 +918,7 @@ impl Statement {
     /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
     /// # let ident = "RUSTUSER";
     /// # let pass = "RUSTPASSWORD";
-    /// let mut conn = Connection::open(db, ident, pass).unwrap();
+    /// let conn = Connection::open(db, ident, pass).unwrap();
     ///
     /// # conn.execute_statement("drop table test_table").ok();
     /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
@@ -478,9 +931,42 @@ impl Statement {
     /// println!("{}",err);
     /// ```
     pub fn get_error(&self, error_code: i32) -> MimerError {
+        if error_code == -26203 {
+            if let Some(mismatch) = self.type_mismatch() {
+                return MimerError::mimer_error_with_detail(error_code, &mismatch.to_string());
+            }
+        }
+        if error_code == -26038 {
+            if let Ok(unbound) = self.unbound_parameters() {
+                let names: Vec<String> = unbound
+                    .into_iter()
+                    .map(|idx| match self.get_parameter_name(idx) {
+                        Ok(name) if !name.is_empty() => name,
+                        _ => format!("parameter {idx}"),
+                    })
+                    .collect();
+                return MimerError::mimer_error_with_detail(error_code, &names.join(", "));
+            }
+        }
         MimerError::new(self, error_code)
     }
 
+    /// Returns the SQL text the statement was prepared from.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// let stmnt = conn.prepare("SELECT 1 FROM system.onerow", CursorMode::Forward).unwrap();
+    /// assert_eq!(stmnt.sql(), "SELECT 1 FROM system.onerow");
+    /// ```
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
     /// Returns the number of parameters in a statement.
     pub fn num_params(&self) -> Result<usize, i32> {
         let _handle = self.get_statement_handle()?;
@@ -512,93 +998,273 @@ impl Statement {
         }
     }
 
-    /// Should this be public? You would need too look in mimerapi.h or similar to make sense of the return codes.
-    fn _get_parameter_type(&self, idx: i16) -> Result<i32, i32> {
+    /// Returns the data type of a parameter in a statement.
+    /// The parameter is identified by its index, starting at 1.
+    ///
+    /// # Errors
+    /// Returns [Err] if the index is out of range or if the statement handle was invalid.
+    pub fn parameter_type(&self, idx: i16) -> Result<ColumnType, i32> {
+        self.parameter_type_code(idx).map(ColumnType::from_code)
+    }
+
+    /// Returns whether a parameter in a statement accepts null values.
+    /// The parameter is identified by its index, starting at 1.
+    ///
+    /// # Errors
+    /// Returns [Err] if the index is out of range or if the statement handle was invalid.
+    pub fn parameter_nullable(&self, idx: i16) -> Result<bool, i32> {
         let handle = self.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
         if (*handle).is_null() {
             return Err(-26005); // Handle is NULL
         }
 
         unsafe {
-            let rc = ffi::MimerParameterType(*handle, idx);
+            let rc = ffi::MimerParameterNullable(*handle, idx);
             match rc.cmp(MIMER_SUCCESS) {
                 Ordering::Less => Err(rc),
-                _ => Ok(rc),
+                Ordering::Equal => Ok(false),
+                Ordering::Greater => Ok(true),
             }
         }
     }
-    /// Returns the name of a parameter in a statement.
-    pub fn get_parameter_name(&self, idx: i16) -> Result<String, i32> {
-        self.get_name_auxillary(idx, true)
-    }
-
-    /// Returns the name of a column in a statement.
-    pub fn get_column_name(&self, idx: i16) -> Result<String, i32> {
-        self.get_name_auxillary(idx, false)
-    }
 
-    /// Helper function for getting parameter and column names.
-    fn get_name_auxillary(&self, idx: i16, is_parameter_name: bool) -> Result<String, i32> {
-        let null_ptr: *mut i8 = std::ptr::null_mut();
+    /// Returns the type code of a parameter from the C API.
+    fn parameter_type_code(&self, idx: i16) -> Result<i32, i32> {
         let handle = self.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
-
-        unsafe {
-            let buffer_size: i32 = match is_parameter_name {
-                true => ffi::MimerParameterName8(*handle, idx, null_ptr, 0),
-                false => ffi::MimerColumnName8(*handle, idx, null_ptr, 0),
-            };
-            match buffer_size.cmp(MIMER_SUCCESS) {
-                Ordering::Less => return Err(buffer_size),
-                _ => (),
-            }
-
-            let c_buffer_size: usize = (buffer_size + 1) as usize;
-
-            let c_str = CString::new(vec![1; c_buffer_size]).unwrap();
-            let c_str_ptr = c_str.into_raw();
-
-            let rc: i32 = match is_parameter_name {
-                true => ffi::MimerParameterName8(*handle, idx, c_str_ptr, c_buffer_size),
-                false => ffi::MimerColumnName8(*handle, idx, c_str_ptr, c_buffer_size),
-            };
-
-            // retake pointer to free memory
-            let retake_cstr = CString::from_raw(c_str_ptr);
-            let maybe_string = retake_cstr.into_string();
-
-            if rc < 0 {
-                return Err(rc);
-            }
-
-            match maybe_string {
-                Ok(s) => Ok(s),
-                Err(_) => return Err(-26001),
-            }
+        if (*handle).is_null() {
+            return Err(-26005); // Handle is NULL
         }
-    }
 
-    /// Returns the number of columns in a statement.
-    pub fn column_count(&self) -> Result<i32, i32> {
-        let handle = self.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
         unsafe {
-            let rc = ffi::MimerColumnCount(*handle);
+            let rc = ffi::MimerParameterType(*handle, idx);
             match rc.cmp(MIMER_SUCCESS) {
                 Ordering::Less => Err(rc),
-                Ordering::Equal => Ok(rc),
-                Ordering::Greater => Ok(rc),
+                _ => Ok(rc),
             }
         }
     }
 
-    /// Sets the array size when fetching data from a statement.
-    /// By default the Mimer API routines MimerFetch and MimerFetchSkip uses an internal fetch buffer equal to the maximum size of one row.
-    /// Depending on the actual size of the data, this buffer may hold more than one row. By increasing the array size, more data is retrieved in each server request.
+    /// Returns the data type of a column in the result set of a statement.
+    /// The column is identified by its index, starting at 1.
+    ///
+    /// # Errors
+    /// Returns [Err] if the index is out of range or if the statement handle was invalid.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// let stmnt = conn.prepare("SELECT * FROM test_table", CursorMode::Forward).unwrap();
+    /// assert_eq!(stmnt.column_type(1).unwrap(), ColumnType::CharacterVarying);
+    /// assert_eq!(stmnt.column_type(2).unwrap(), ColumnType::Integer);
+    /// ```
+    pub fn column_type(&self, idx: i16) -> Result<ColumnType, i32> {
+        self.column_type_code(idx).map(ColumnType::from_code)
+    }
+
+    /// Returns whether a column in the result set of a statement may hold null values, if known.
+    /// The column is identified by its index, starting at 1.
+    ///
+    /// The Mimer SQL C API only reports nullability for the native numeric types (*SMALLINT*, *INTEGER*, *BIGINT*, *REAL* and *DOUBLE PRECISION*),
+    /// which come in a nullable and a not nullable variant. For all other types [None] is returned.
+    ///
+    /// # Errors
+    /// Returns [Err] if the index is out of range or if the statement handle was invalid.
+    pub fn column_nullable(&self, idx: i16) -> Result<Option<bool>, i32> {
+        self.column_type_code(idx)
+            .map(ColumnType::nullable_from_code)
+    }
+
+    /// Returns the metadata of the result set of the statement.
+    /// The metadata is read from the C API on the first call, and kept for the lifetime of the statement.
+    ///
+    /// # Errors
+    /// Returns [Err] if a column couldn't be described or if the statement handle was invalid.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// let stmnt = conn.prepare("SELECT * FROM test_table", CursorMode::Forward).unwrap();
+    /// for column in stmnt.metadata().unwrap().columns() {
+    ///     println!("{}: {:?}", column.name, column.column_type);
+    /// }
+    /// ```
+    pub fn metadata(&self) -> Result<&ResultSetMetadata, i32> {
+        if let Some(metadata) = self.metadata.get() {
+            return Ok(metadata);
+        }
+        let mut columns = Vec::new();
+        for idx in 1..=self.column_count()? as i16 {
+            let code = self.column_type_code(idx)?;
+            let column_type = ColumnType::from_code(code);
+            columns.push(ColumnInfo {
+                name: self.get_column_name(idx)?,
+                column_type,
+                nullable: ColumnType::nullable_from_code(code),
+                size: column_type.fixed_size(),
+            });
+        }
+        Ok(self.metadata.get_or_init(|| ResultSetMetadata { columns }))
+    }
+
+    /// Returns the parameters of the statement in order, with their names, modes and data types read in one call.
+    ///
+    /// # Errors
+    /// Returns [Err] if a parameter couldn't be described or if the statement handle was invalid.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// let stmnt = conn.prepare("INSERT INTO test_table VALUES(:string,:int)", CursorMode::Forward).unwrap();
+    /// for param in stmnt.parameters().unwrap() {
+    ///     println!("{} {}: {:?} {:?}", param.index, param.name, param.mode, param.param_type);
+    /// }
+    /// ```
+    pub fn parameters(&self) -> Result<impl ExactSizeIterator<Item = ParameterInfo>, i32> {
+        let handle = self.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
+        if (*handle).is_null() {
+            return Err(-26005); // Handle is NULL
+        }
+
+        let mut parameters = Vec::with_capacity(self.num_parameters);
+        for index in 1..=self.num_parameters as i16 {
+            let name = read_string(|buf, size| unsafe {
+                ffi::MimerParameterName8(*handle, index, buf, size)
+            })?;
+            let param_type = match unsafe { ffi::MimerParameterType(*handle, index) } {
+                rc if rc < 0 => return Err(rc),
+                code => ColumnType::from_code(code),
+            };
+            parameters.push(ParameterInfo {
+                index,
+                name: name.unwrap_or_default(),
+                mode: self.get_parameter_mode_auxillary(*handle, index)?,
+                param_type,
+            });
+        }
+        Ok(parameters.into_iter())
+    }
+
+    /// Returns the type code of a column from the C API.
+    fn column_type_code(&self, idx: i16) -> Result<i32, i32> {
+        let handle = self.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
+        if (*handle).is_null() {
+            return Err(-26005); // Handle is NULL
+        }
+
+        unsafe {
+            let rc = ffi::MimerColumnType(*handle, idx);
+            match rc.cmp(MIMER_SUCCESS) {
+                Ordering::Less => Err(rc),
+                _ => Ok(rc),
+            }
+        }
+    }
+
+    /// Returns the name of a parameter in a statement.
+    pub fn get_parameter_name(&self, idx: i16) -> Result<String, i32> {
+        self.get_name_auxillary(idx, true)
+    }
+
+    /// Returns the name of a column in a statement.
+    pub fn get_column_name(&self, idx: i16) -> Result<String, i32> {
+        self.get_name_auxillary(idx, false)
+    }
+
+    /// Helper function for getting parameter and column names.
+    fn get_name_auxillary(&self, idx: i16, is_parameter_name: bool) -> Result<String, i32> {
+        let handle = self.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
+
+        let name = read_string(|buf, size| unsafe {
+            match is_parameter_name {
+                true => ffi::MimerParameterName8(*handle, idx, buf, size),
+                false => ffi::MimerColumnName8(*handle, idx, buf, size),
+            }
+        })?;
+        Ok(name.unwrap_or_default())
+    }
+
+    /// Returns the number of columns in a statement.
+    pub fn column_count(&self) -> Result<i32, i32> {
+        let handle = self.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
+        unsafe {
+            let rc = ffi::MimerColumnCount(*handle);
+            match rc.cmp(MIMER_SUCCESS) {
+                Ordering::Less => Err(rc),
+                Ordering::Equal => Ok(rc),
+                Ordering::Greater => Ok(rc),
+            }
+        }
+    }
+
+    /// Returns the maximum number of bytes required to hold one row of the result set.
+    /// Unlike [get_row_size](crate::Cursor::get_row_size()) this is available before a cursor is opened,
+    /// e.g. to choose an array size for [set_array_size](crate::Statement::set_array_size()).
+    ///
+    /// # Errors
+    /// Returns [Err] if the statement handle was invalid or if the connection has been dropped.
+    pub fn row_size(&self) -> Result<i32, i32> {
+        let handle = self.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
+        unsafe {
+            let rc = ffi::MimerRowSize(*handle);
+            match rc.cmp(MIMER_SUCCESS) {
+                Ordering::Less => Err(rc),
+                _ => Ok(rc),
+            }
+        }
+    }
+
+    /// Checks that a fetch buffer of `size` rows fits within the memory budget of the connection.
+    fn check_fetch_memory_budget(&self, size: i32) -> Result<(), i32> {
+        let inner_connection = self
+            .inner_statement
+            .inner_connection
+            .upgrade()
+            .ok_or(-26003)?;
+        let budget = inner_connection
+            .fetch_memory_budget
+            .load(std::sync::atomic::Ordering::Relaxed);
+        if budget == 0 {
+            return Ok(());
+        }
+        let buffer_size = self.row_size()? as usize * size.max(0) as usize;
+        match buffer_size > budget {
+            true => Err(-26015), // Fetch buffer exceeds the memory budget
+            false => Ok(()),
+        }
+    }
+
+    /// Sets the array size when fetching data from a statement.
+    /// By default the Mimer API routines MimerFetch and MimerFetchSkip uses an internal fetch buffer equal to the maximum size of one row.
+    /// Depending on the actual size of the data, this buffer may hold more than one row. By increasing the array size, more data is retrieved in each server request.
     ///
     /// # Parameters
     ///
     /// - `size`: The number of rows to retrieve in each request.
     ///
+    /// # Errors
+    /// Returns [Err] if the array size could not be set, or -26015 if the row size times `size` exceeds the
+    /// [fetch memory budget](crate::Connection::set_fetch_memory_budget()) of the connection.
+    ///
     pub fn set_array_size(&self, size: i32) -> Result<i32, i32> {
+        self.check_fetch_memory_budget(size)?;
         let handle = self.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
         unsafe {
             let rc = ffi::MimerSetArraySize(*handle, size);
@@ -610,12 +1276,46 @@ impl Statement {
         }
     }
 
+    /// Sets the query timeout of the statement. Executing it, opening a cursor for it, or fetching a row, that takes longer than `timeout`
+    /// is cancelled with `MimerCancel` and fails with -26036, so that a hung server can't block the calling thread indefinitely.
+    /// Each request to the server gets the whole timeout. A zero `timeout` removes the timeout, which is the default.
+    ///
+    /// The requests are watched by a background thread, which is shared by all statements.
+    /// A timeout for opening connections is set with [ConnectionOptions::connect_timeout](crate::ConnectionOptions::connect_timeout).
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # use std::time::Duration;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// let stmt = conn.prepare("SELECT CAST(COUNT(*) AS INTEGER) FROM information_schema.columns", CursorMode::Forward).unwrap();
+    /// stmt.set_query_timeout(Duration::from_secs(30));
+    ///
+    /// match stmt.execute() {
+    ///     Err(-26036) => eprintln!("query timed out"),
+    ///     result => { result.unwrap(); }
+    /// }
+    /// ```
+    pub fn set_query_timeout(&self, timeout: Duration) {
+        self.inner_statement.set_query_timeout(Some(timeout));
+    }
+
+    /// Gets the [query timeout](crate::Statement::set_query_timeout()) of the statement, [None] when it has none.
+    pub fn query_timeout(&self) -> Option<Duration> {
+        self.inner_statement.query_timeout()
+    }
+
     /// Set parameters to a prepared statement, and add it to the batch of statments to be executed on the next call to [execute](crate::Statement::execute()).
     /// Note that the statement needs to be declared as mut.
     ///
     /// If the statement query contains named parameters, the parameter values are expected to be given in order in the "params" argument to this method.
     /// If the statement query does not contain named parameters, the "params" argument is expected to be empty.
     /// Can not be used with statements which return result sets, e.g. "SELECT" statements.
+    /// Use [execute_batch_locating](crate::Statement::execute_batch_locating()) instead of [execute](crate::Statement::execute()) to find out which entry made a batch fail,
+    /// or [execute_batch](crate::Statement::execute_batch()) to get the result of every entry.
     ///
     /// # Errors
     /// Returns [Err] when the parameters could not be set or if the statement handle was invalid.
@@ -626,7 +1326,7 @@ impl Statement {
     /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
     /// # let ident = "RUSTUSER";
     /// # let pass = "RUSTPASSWORD";
-    /// let mut conn = Connection::open(db, ident, pass).unwrap();
+    /// let conn = Connection::open(db, ident, pass).unwrap();
     ///
     /// let mut stmnt = conn.prepare("INSERT INTO test_table VALUES(:string,:int)", CursorMode::Forward).unwrap();
     ///
@@ -640,6 +1340,172 @@ impl Statement {
     /// stmnt.execute().unwrap();
     /// ```
     pub fn add_batch(&mut self, params: &[&dyn ToSql]) -> Result<i32, i32> {
+        let rc = self.add_batch_auxillary(params)?;
+        self.batch_entries
+            .lock()
+            .push(params.iter().map(|p| p.to_sql().into_owned()).collect());
+        Ok(rc)
+    }
+
+    /// Executes the batch built with [add_batch](crate::Statement::add_batch()), and locates the entry that made it fail.
+    ///
+    /// A failing batch is not applied, but the error from the server doesn't tell which entry caused the failure.
+    /// This method therefore bisects the batch by re-executing prefixes of it, each within a transaction that is rolled back,
+    /// until the first failing entry is found. This takes about log2(n) extra executions for a batch of n entries.
+    /// The entries are kept by the statement until the batch is executed.
+    ///
+    /// Since the bisection needs its own transactions, the failing entry can't be located when the statement is used
+    /// within a [Transaction](crate::Transaction), in which case the index of the [BatchError] is [None].
+    ///
+    /// # Errors
+    /// Returns [Err] holding a [BatchError] with the error code of the failed execute, and the index (starting at 0) of the
+    /// first batch entry that fails if it could be located.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table batch_table").ok();
+    /// conn.execute_statement("CREATE TABLE batch_table (id INT PRIMARY KEY)").unwrap();
+    /// let mut stmnt = conn.prepare("INSERT INTO batch_table VALUES(:id)", CursorMode::Forward).unwrap();
+    ///
+    /// for id in [1, 2, 3, 2, 4] {
+    ///     stmnt.add_batch(&[&id]).unwrap();
+    /// }
+    /// let err = stmnt.execute_batch_locating().unwrap_err();
+    /// assert_eq!(err.get_index(), Some(3));
+    /// ```
+    pub fn execute_batch_locating(&mut self) -> Result<i32, BatchError> {
+        let entries = std::mem::take(&mut *self.batch_entries.lock());
+        let result = self.execute();
+        self.batch_bool = false;
+        let error_code = match result {
+            Ok(rc) => return Ok(rc),
+            Err(ec) => ec,
+        };
+        let index = match entries.len() {
+            0 => None,
+            1 => Some(0),
+            _ => self.locate_failing_entry(&entries).ok(),
+        };
+        Err(BatchError { error_code, index })
+    }
+
+    /// Executes the batch built with [add_batch](crate::Statement::add_batch()) one entry at a time,
+    /// and returns the number of rows affected, or the error, of every entry.
+    ///
+    /// Unlike [execute](crate::Statement::execute()), a failing entry doesn't stop the batch: the remaining entries are still executed.
+    /// Outside of a [Transaction](crate::Transaction) every successful entry is committed on its own,
+    /// so the failed entries found in the [BatchResult] can be corrected and retried without repeating the others.
+    /// Within a transaction, the caller decides whether to commit the successful entries or to roll back.
+    ///
+    /// Discarding a batch of more than one entry prepares the statement again, keeping its [query timeout](crate::Statement::set_query_timeout()).
+    /// Cursors opened on the statement before then belong to the old statement handle and fail with error code -26004.
+    ///
+    /// # Errors
+    /// Returns [Err] when the statement handle was invalid or the statement couldn't be prepared again to discard the pending batch.
+    /// Errors from the individual entries are reported in the [BatchResult].
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table batch_table").ok();
+    /// conn.execute_statement("CREATE TABLE batch_table (id INT PRIMARY KEY)").unwrap();
+    /// let mut stmnt = conn.prepare("INSERT INTO batch_table VALUES(:id)", CursorMode::Forward).unwrap();
+    /// for id in [1, 2, 1, 3] {
+    ///     stmnt.add_batch(&[&id]).unwrap();
+    /// }
+    /// let result = stmnt.execute_batch().unwrap();
+    /// assert_eq!(result.rows_affected(), RowsAffected(3));
+    /// assert_eq!(result.failures().map(|(index, _)| index).collect::<Vec<_>>(), vec![2]);
+    /// ```
+    pub fn execute_batch(&mut self) -> Result<BatchResult, i32> {
+        let entries = std::mem::take(&mut *self.batch_entries.lock());
+        if entries.len() > 1 {
+            self.discard_pending_batch()?;
+        }
+        self.batch_bool = false;
+        let results = entries
+            .iter()
+            .map(|entry| {
+                let params: Vec<&dyn ToSql> = entry.iter().map(|v| v as &dyn ToSql).collect();
+                self.execute_update(&params)
+            })
+            .collect();
+        Ok(BatchResult { results })
+    }
+
+    /// Replaces the statement handle with a newly prepared one, as the C API can't remove entries added with `MimerAddBatch`.
+    fn discard_pending_batch(&mut self) -> Result<(), i32> {
+        let inner_connection = self
+            .inner_statement
+            .inner_connection
+            .upgrade()
+            .ok_or(-26003)?;
+        let (inner, mut fresh) = Statement::new(
+            Arc::downgrade(&inner_connection),
+            &self.sql,
+            self.cursor_mode,
+        )?;
+        inner_connection.push_statement(inner);
+        fresh
+            .inner_statement
+            .set_query_timeout(self.inner_statement.query_timeout());
+        // The old handle is ended when `fresh` is dropped
+        std::mem::swap(&mut self.inner_statement, &mut fresh.inner_statement);
+        Ok(())
+    }
+
+    /// Bisects the batch entries to find the shortest prefix that fails, and returns the index of its last entry.
+    fn locate_failing_entry(
+        &mut self,
+        entries: &[Vec<MimerDatatype<'static>>],
+    ) -> Result<usize, i32> {
+        let (mut low, mut high) = (1, entries.len());
+        while low < high {
+            let mid = (low + high) / 2;
+            if self.batch_prefix_fails(&entries[..mid])? {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        Ok(low - 1)
+    }
+
+    /// Executes the given batch entries within a transaction which is then rolled back.
+    /// Returns whether the execute failed.
+    fn batch_prefix_fails(&mut self, entries: &[Vec<MimerDatatype<'static>>]) -> Result<bool, i32> {
+        let conn = Connection::from_inner(
+            self.inner_statement
+                .inner_connection
+                .upgrade()
+                .ok_or(-26003)?,
+        );
+        begin_session_transaction(&conn, TransactionMode::ReadWrite)?;
+        let mut failed = false;
+        for entry in entries {
+            let params: Vec<&dyn ToSql> = entry.iter().map(|v| v as &dyn ToSql).collect();
+            if self.add_batch_auxillary(&params).is_err() {
+                failed = true;
+                break;
+            }
+        }
+        failed = failed || self.execute().is_err();
+        self.batch_bool = false;
+        end_session_transaction(&conn, EndTransactionMode::Rollback)?;
+        Ok(failed)
+    }
+
+    /// Sets the parameters of a batch entry, adding the previous entry to the batch.
+    fn add_batch_auxillary(&mut self, params: &[&dyn ToSql]) -> Result<i32, i32> {
         let handle = self.get_statement_handle()?.unwrap(); //Ok unwrap since we know the statement is a statement
         if (*handle).is_null() {
             return Err(-26005); // Handle is NULL
@@ -657,30 +1523,415 @@ impl Statement {
             self.set_params(params, *handle)?;
         }
 
-        drop(handle); // drop is necessary to allow for assignment of self.batchBool
-        self.batch_bool = true;
-        return match rc.cmp(MIMER_SUCCESS) {
-            Ordering::Equal => Ok(rc),
-            Ordering::Greater => Ok(rc),
-            Ordering::Less => Err(rc),
-        };
+        drop(handle); // drop is necessary to allow for assignment of self.batchBool
+        self.batch_bool = true;
+        return match rc.cmp(MIMER_SUCCESS) {
+            Ordering::Equal => Ok(rc),
+            Ordering::Greater => Ok(rc),
+            Ordering::Less => Err(rc),
+        };
+    }
+}
+
+/// Options for opening a cursor with [open_cursor_with](crate::Statement::open_cursor_with()).
+///
+/// The array size is the number of rows retrieved in each server request. It is either given directly with
+/// [array_size](FetchOptions::array_size()), or picked from the [row size](crate::Statement::row_size()) of the statement
+/// to fill a memory target with [auto_tune](FetchOptions::auto_tune()). By default the array size of the statement is left as it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FetchOptions {
+    array_size: Option<i32>,
+    memory_target: Option<usize>,
+}
+
+impl FetchOptions {
+    /// The memory target used by [auto](FetchOptions::auto()), 4 MB per fetch.
+    pub const DEFAULT_MEMORY_TARGET: usize = 4 * 1024 * 1024;
+
+    /// Creates the [default](FetchOptions::default()) options.
+    pub fn new() -> FetchOptions {
+        FetchOptions::default()
+    }
+
+    /// Creates options that auto-tune the array size for a target of [DEFAULT_MEMORY_TARGET](FetchOptions::DEFAULT_MEMORY_TARGET) bytes.
+    pub fn auto() -> FetchOptions {
+        FetchOptions::new().auto_tune(FetchOptions::DEFAULT_MEMORY_TARGET)
+    }
+
+    /// Sets the number of rows to retrieve in each server request.
+    pub fn array_size(mut self, size: i32) -> FetchOptions {
+        self.array_size = Some(size);
+        self.memory_target = None;
+        self
+    }
+
+    /// Picks the array size so that a fetch uses at most `bytes` of memory, given the maximum size of a row.
+    /// The [fetch memory budget](crate::Connection::set_fetch_memory_budget()) of the connection is used instead if it is smaller.
+    pub fn auto_tune(mut self, bytes: usize) -> FetchOptions {
+        self.memory_target = Some(bytes);
+        self.array_size = None;
+        self
+    }
+}
+
+/// The number of rows affected by a statement, as returned by [execute_update](crate::Statement::execute_update()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct RowsAffected(pub u64);
+
+impl fmt::Display for RowsAffected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} rows affected", self.0)
+    }
+}
+
+/// The outcome of every entry of a batch executed with [execute_batch](crate::Statement::execute_batch()), in the order the entries were added.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchResult {
+    results: Vec<Result<RowsAffected, i32>>,
+}
+
+impl BatchResult {
+    /// Gets the number of rows affected, or the error code, of every entry.
+    pub fn results(&self) -> &[Result<RowsAffected, i32>] {
+        &self.results
+    }
+
+    /// Returns whether every entry was executed successfully.
+    pub fn is_success(&self) -> bool {
+        self.results.iter().all(Result::is_ok)
+    }
+
+    /// Gets the index, starting at 0, and the error code of every entry that failed.
+    pub fn failures(&self) -> impl Iterator<Item = (usize, i32)> + '_ {
+        self.results
+            .iter()
+            .enumerate()
+            .filter_map(|(index, result)| result.err().map(|ec| (index, ec)))
+    }
+
+    /// Gets the total number of rows affected by the successful entries.
+    pub fn rows_affected(&self) -> RowsAffected {
+        RowsAffected(self.results.iter().flatten().map(|rows| rows.0).sum())
+    }
+}
+
+/// Error returned by [execute_batch_locating](crate::Statement::execute_batch_locating()).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchError {
+    error_code: i32,
+    index: Option<usize>,
+}
+
+impl BatchError {
+    /// Gets the error code of the failed execute.
+    pub fn get_error_code(&self) -> i32 {
+        self.error_code
+    }
+
+    /// Gets the index, starting at 0, of the first batch entry that made the batch fail, if it could be located.
+    pub fn get_index(&self) -> Option<usize> {
+        self.index
+    }
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.index {
+            Some(index) => write!(
+                f,
+                "Batch failed at entry {index} with error {}",
+                self.error_code
+            ),
+            None => write!(f, "Batch failed with error {}", self.error_code),
+        }
+    }
+}
+/// Describes a value that can't be bound to a parameter because of its type, as returned by [type_mismatch](crate::Statement::type_mismatch()).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeMismatch {
+    /// Index of the parameter, starting at 1.
+    pub param: i16,
+    /// SQL type of the parameter.
+    pub expected: ColumnType,
+    /// Name of the [MimerDatatype] variant the value was converted to, e.g. `"String"`.
+    pub provided: &'static str,
+}
+
+impl fmt::Display for TypeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "parameter {} of type {} can't be bound from a {} value",
+            self.param, self.expected, self.provided
+        )
+    }
+}
+
+impl std::error::Error for TypeMismatch {}
+
+/// Displays the statement as `<SQL> [param1=…, param2=…]`, intended for logging and error reporting.
+///
+/// String literals in the SQL text are rendered as `'…'` and parameter values are never shown,
+/// so that passwords and other sensitive data don't end up in logs. Use [sql](crate::Statement::sql()) to get the exact text.
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", redact_literals(&self.sql))?;
+        if self.num_parameters == 0 {
+            return Ok(());
+        }
+        let names: Vec<String> = (1..=self.num_parameters as i16)
+            .map(|idx| match self.get_parameter_name(idx) {
+                Ok(name) if !name.is_empty() => format!("{name}=…"),
+                _ => format!("{idx}=…"),
+            })
+            .collect();
+        write!(f, " [{}]", names.join(", "))
+    }
+}
+
+/// Replaces the content of string literals in an SQL text with `…`.
+/// Quoted identifiers are left untouched and escaped quotes (`''`) are treated as part of the literal.
+fn redact_literals(sql: &str) -> String {
+    let mut redacted = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                redacted.push_str("'…'");
+                while let Some(c) = chars.next() {
+                    if c == '\'' {
+                        if chars.peek() == Some(&'\'') {
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+            }
+            '"' => {
+                redacted.push(c);
+                for c in chars.by_ref() {
+                    redacted.push(c);
+                    if c == '"' {
+                        break;
+                    }
+                }
+            }
+            _ => redacted.push(c),
+        }
+    }
+    redacted
+}
+
+#[cfg(test)]
+mod statement_tests {
+    use core::panic;
+
+    use chrono::NaiveDate;
+    use chrono::NaiveDateTime;
+    use chrono::NaiveTime;
+
+    use super::*;
+    use crate::testing::*;
+
+    #[test]
+    fn statement_redact_literals() {
+        assert_eq!(
+            redact_literals("CREATE IDENT x AS USER USING 'secret'"),
+            "CREATE IDENT x AS USER USING '…'"
+        );
+        assert_eq!(
+            redact_literals("SELECT 'it''s', \"a'b\" FROM t WHERE c = 'x'"),
+            "SELECT '…', \"a'b\" FROM t WHERE c = '…'"
+        );
+    }
+
+    #[test]
+    fn statement_sql_and_display() {
+        let conn = establish_connection();
+
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+        let sql = format!("SELECT * FROM {EXAMPLE_TABLE} WHERE column_1 = 'x' AND column_2 = :num");
+        let stmt = conn.prepare(&sql, CursorMode::Forward).unwrap();
+        assert_eq!(stmt.sql(), sql);
+        assert_eq!(
+            stmt.to_string().to_lowercase(),
+            format!(
+                "SELECT * FROM {EXAMPLE_TABLE} WHERE column_1 = '…' AND column_2 = :num [num=…]"
+            )
+            .to_lowercase()
+        );
+    }
+
+    #[test]
+    fn column_type_conversion_hint() {
+        let conn = establish_connection();
+
+        drop_create_table(
+            &conn,
+            INTERVAL_TABLE,
+            "(column1 INTERVAL DAY(2) TO SECOND(0))",
+        );
+        conn.execute_statement(&format!(
+            "INSERT INTO {INTERVAL_TABLE} VALUES(INTERVAL '1 02:03:04' DAY TO SECOND)"
+        ))
+        .unwrap();
+        let stmt = conn
+            .prepare(
+                &format!("SELECT * FROM {INTERVAL_TABLE}"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let column_type = stmt.column_type(1).unwrap();
+        assert_eq!(
+            column_type.conversion_hint(),
+            "column is INTERVAL DAY TO SECOND, consider fetching as IntervalDayTime"
+        );
+
+        let mut cursor = stmt.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+        assert_eq!(row.get::<i32>(1), Err(-26200));
+        assert!(row.get::<String>(1).unwrap().is_some());
+    }
+
+    #[test]
+    fn statement_column_and_parameter_types() {
+        let conn = establish_connection();
+
+        drop_create_table(
+            &conn,
+            "types_table",
+            "(c1 VARCHAR(30), c2 INT NOT NULL, c3 BIGINT, c4 DOUBLE PRECISION, c5 BOOLEAN, c6 DATE, c7 BLOB(1K), c8 NCHAR(10))",
+        );
+        let stmt = conn
+            .prepare("SELECT * FROM types_table", CursorMode::Forward)
+            .unwrap();
+        let expected = [
+            ColumnType::CharacterVarying,
+            ColumnType::Integer,
+            ColumnType::BigInt,
+            ColumnType::Double,
+            ColumnType::Boolean,
+            ColumnType::Date,
+            ColumnType::Blob,
+            ColumnType::NationalCharacter,
+        ];
+        for (idx, column_type) in expected.iter().enumerate() {
+            assert_eq!(stmt.column_type(idx as i16 + 1).unwrap(), *column_type);
+        }
+        assert_eq!(stmt.column_nullable(1).unwrap(), None);
+        assert!(stmt.column_type(9).is_err());
+
+        let stmt = conn
+            .prepare(
+                "INSERT INTO types_table (c1, c2) VALUES(:c1, :c2)",
+                CursorMode::Forward,
+            )
+            .unwrap();
+        assert_eq!(
+            stmt.parameter_type(1).unwrap(),
+            ColumnType::CharacterVarying
+        );
+        assert_eq!(stmt.parameter_type(2).unwrap(), ColumnType::Integer);
+        assert!(stmt.parameter_nullable(1).unwrap());
+        assert!(!stmt.parameter_nullable(2).unwrap());
+    }
+
+    #[test]
+    fn statement_row_size_and_memory_budget() {
+        let conn = establish_connection();
+
+        drop_create_table(&conn, BIG_TABLE, BIG_TABLE_COLUMNS);
+        let stmt = conn
+            .prepare(&format!("SELECT * FROM {BIG_TABLE}"), CursorMode::Forward)
+            .unwrap();
+        let row_size = stmt.row_size().unwrap();
+        assert!(row_size > 0);
+        assert_eq!(
+            row_size,
+            stmt.open_cursor().unwrap().get_row_size().unwrap()
+        );
+
+        conn.set_fetch_memory_budget(Some(row_size as usize * 10));
+        assert_eq!(conn.fetch_memory_budget(), Some(row_size as usize * 10));
+        stmt.set_array_size(10).unwrap();
+        assert_eq!(stmt.set_array_size(11), Err(-26015));
+
+        conn.set_fetch_memory_budget(None);
+        stmt.set_array_size(11).unwrap();
     }
-}
 
-#[cfg(test)]
-mod statement_tests {
-    use core::panic;
+    #[test]
+    fn open_cursor_with_fetch_options() {
+        let conn = establish_connection();
 
-    use chrono::NaiveDate;
-    use chrono::NaiveDateTime;
-    use chrono::NaiveTime;
+        drop_create_table(&conn, BIG_TABLE, BIG_TABLE_COLUMNS);
+        let stmt = conn
+            .prepare(&format!("SELECT * FROM {BIG_TABLE}"), CursorMode::Forward)
+            .unwrap();
+        let row_size = stmt.row_size().unwrap() as usize;
 
-    use super::*;
-    use crate::testing::*;
+        assert_eq!(stmt.array_size_for(row_size * 25 + 1).unwrap(), 25);
+        assert_eq!(stmt.array_size_for(0).unwrap(), 1);
+        conn.set_fetch_memory_budget(Some(row_size * 10));
+        assert_eq!(
+            stmt.array_size_for(FetchOptions::DEFAULT_MEMORY_TARGET)
+                .unwrap(),
+            10
+        );
+
+        drop(stmt.open_cursor_with(&FetchOptions::auto()).unwrap());
+        drop(
+            stmt.open_cursor_with(&FetchOptions::new().array_size(10))
+                .unwrap(),
+        );
+        assert_eq!(
+            stmt.open_cursor_with(&FetchOptions::new().array_size(11))
+                .err(),
+            Some(-26015)
+        );
+        conn.set_fetch_memory_budget(None);
+    }
+
+    #[test]
+    fn statement_metadata() {
+        let conn = establish_connection();
+
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+        let stmt = conn
+            .prepare(
+                &format!("SELECT * FROM {EXAMPLE_TABLE}"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let metadata = stmt.metadata().unwrap();
+        assert_eq!(metadata.len(), 2);
+        let column = metadata.column(1).unwrap();
+        assert!(column.name.eq_ignore_ascii_case("column_1"));
+        assert_eq!(column.column_type, ColumnType::CharacterVarying);
+        assert_eq!(column.size, None);
+        let column = metadata.column(2).unwrap();
+        assert!(column.name.eq_ignore_ascii_case("column_2"));
+        assert_eq!(column.column_type, ColumnType::Integer);
+        assert_eq!(column.size, Some(4));
+        assert!(metadata.column(0).is_none());
+        assert!(metadata.column(3).is_none());
+
+        // The metadata is computed once
+        assert!(std::ptr::eq(metadata, stmt.metadata().unwrap()));
+
+        let stmt = conn
+            .prepare(
+                &format!("INSERT INTO {EXAMPLE_TABLE} VALUES('a', 1)"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        assert!(stmt.metadata().unwrap().is_empty());
+    }
 
     #[test]
     fn statement_column_count() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, &EXAMPLE_TABLE, &EXAMPLE_TABLE_COLUMNS);
         let stmt = conn
@@ -691,7 +1942,7 @@ mod statement_tests {
 
     #[test]
     fn statement_get_handle() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
         let option = CursorMode::Forward;
@@ -706,7 +1957,7 @@ mod statement_tests {
     fn check_connection_execute() {
         let stmt;
         {
-            let mut conn = establish_connection();
+            let conn = establish_connection();
 
             drop_create_table(&conn, &EXAMPLE_TABLE, &EXAMPLE_TABLE_COLUMNS);
             stmt = conn
@@ -732,7 +1983,7 @@ mod statement_tests {
     fn check_connection_open_cursor() {
         let stmt;
         {
-            let mut conn = establish_connection();
+            let conn = establish_connection();
 
             drop_create_table(&conn, &EXAMPLE_TABLE, &EXAMPLE_TABLE_COLUMNS);
             stmt = conn
@@ -752,7 +2003,7 @@ mod statement_tests {
     fn check_connection_end_statement() {
         let _stmt;
         {
-            let mut conn = establish_connection();
+            let conn = establish_connection();
 
             drop_create_table(&conn, &EXAMPLE_TABLE, &EXAMPLE_TABLE_COLUMNS);
             _stmt = conn
@@ -765,7 +2016,7 @@ mod statement_tests {
     fn check_connection_column_count() {
         let stmt;
         {
-            let mut conn = establish_connection();
+            let conn = establish_connection();
 
             drop_create_table(&conn, &EXAMPLE_TABLE, &EXAMPLE_TABLE_COLUMNS);
             stmt = conn
@@ -785,7 +2036,7 @@ mod statement_tests {
     fn check_connection_get_column_name() {
         let stmt;
         {
-            let mut conn = establish_connection();
+            let conn = establish_connection();
 
             drop_create_table(&conn, &EXAMPLE_TABLE, &EXAMPLE_TABLE_COLUMNS);
             stmt = conn
@@ -805,7 +2056,7 @@ mod statement_tests {
     fn check_connection_get_parameter_name() {
         let stmt;
         {
-            let mut conn = establish_connection();
+            let conn = establish_connection();
 
             drop_create_table(&conn, &EXAMPLE_TABLE, &EXAMPLE_TABLE_COLUMNS);
             stmt = conn
@@ -828,7 +2079,7 @@ mod statement_tests {
     fn check_connection_num_params() {
         let stmt;
         {
-            let mut conn = establish_connection();
+            let conn = establish_connection();
 
             drop_create_table(&conn, &EXAMPLE_TABLE, &EXAMPLE_TABLE_COLUMNS);
             stmt = conn
@@ -849,7 +2100,7 @@ mod statement_tests {
 
     #[test]
     fn statement_set_params_not_null() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, &BIG_TABLE, &BIG_TABLE_COLUMNS);
         let option = CursorMode::Forward;
 
@@ -875,7 +2126,7 @@ mod statement_tests {
 
     #[test]
     fn test_binary() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, &BIG_TABLE, &BIG_TABLE_COLUMNS);
         let option = CursorMode::Forward;
 
@@ -925,7 +2176,7 @@ mod statement_tests {
 
     #[test]
     fn statement_get_error() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
 
         let stmnt = conn
@@ -947,7 +2198,7 @@ mod statement_tests {
 
     #[test]
     fn execute_option_statement() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, NULLABLE_TABLE, NULLABLE_TABLE_COLUMNS);
 
@@ -967,7 +2218,7 @@ mod statement_tests {
 
     #[test]
     fn test_parameter_names() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, NULLABLE_TABLE, NULLABLE_TABLE_COLUMNS);
 
@@ -990,9 +2241,123 @@ mod statement_tests {
         }
     }
 
+    #[test]
+    fn execute_unbound_parameters() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+
+        let stmnt = conn
+            .prepare(
+                &format!("INSERT INTO {EXAMPLE_TABLE} {EXAMPLE_TABLE_COLUMN_NAMES} VALUES(:text, :number)"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        assert_eq!(stmnt.unbound_parameters().unwrap(), vec![1, 2]);
+        stmnt.bind(&2, 2).unwrap();
+        assert_eq!(stmnt.execute(), Err(-26038));
+        assert_eq!(
+            stmnt.get_error(-26038).get_error_message(),
+            "Parameters haven't been bound since the statement was last executed: TEXT"
+        );
+
+        stmnt.bind(&"two", 1).unwrap();
+        assert!(stmnt.unbound_parameters().unwrap().is_empty());
+        stmnt.execute().unwrap();
+
+        // The values have to be bound again for the next execute
+        assert_eq!(stmnt.unbound_parameters().unwrap(), vec![1, 2]);
+        assert_eq!(stmnt.execute_bind(&[&"three"]), Err(-26038));
+        stmnt.execute_bind(&[&"three", &3]).unwrap();
+    }
+
+    #[test]
+    fn bind_type_mismatch() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+
+        let stmnt = conn
+            .prepare(
+                &format!("INSERT INTO {EXAMPLE_TABLE} {EXAMPLE_TABLE_COLUMN_NAMES} VALUES(:text, :number)"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        assert_eq!(stmnt.type_mismatch(), None);
+        assert_eq!(stmnt.execute_bind(&[&"one", &"two"]), Err(-26203));
+        assert_eq!(
+            stmnt.type_mismatch(),
+            Some(TypeMismatch {
+                param: 2,
+                expected: ColumnType::Integer,
+                provided: "String",
+            })
+        );
+        assert_eq!(
+            stmnt.get_error(-26203).get_error_message(),
+            "Invalid parameter type for MimerDatatype-variant: parameter 2 of type INTEGER can't be bound from a String value"
+        );
+        assert_eq!(stmnt.bind(&true, 2), Err(-26203));
+        assert_eq!(stmnt.type_mismatch().unwrap().provided, "Bool");
+
+        // NULL binds to any type
+        stmnt.execute_bind(&[&"one", &None::<i32>]).unwrap();
+    }
+
+    #[test]
+    fn bind_clears_type_mismatch() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+
+        let stmnt = conn
+            .prepare(
+                &format!("INSERT INTO {EXAMPLE_TABLE} {EXAMPLE_TABLE_COLUMN_NAMES} VALUES(:text, :number)"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        assert_eq!(stmnt.bind(&"two", 2), Err(-26203));
+        assert!(stmnt.type_mismatch().is_some());
+        stmnt.bind(&2, 2).unwrap();
+        assert_eq!(stmnt.type_mismatch(), None);
+        assert_eq!(
+            stmnt.get_error(-26203).get_error_message(),
+            "Invalid parameter type for MimerDatatype-variant"
+        );
+    }
+
+    #[test]
+    fn test_execute_named_map() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+
+        let stmnt = conn
+            .prepare(
+                &format!("INSERT INTO {EXAMPLE_TABLE} VALUES(:text, :num)"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+
+        let mut params: HashMap<String, MimerDatatype> = HashMap::new();
+        params.insert(String::from("TEXT"), MimerDatatype::StringRef("one"));
+        assert_eq!(stmnt.execute_named_map(&params, true), Err(-26019));
+
+        params.insert(String::from("num"), MimerDatatype::Int(1));
+        stmnt.execute_named_map(&params, true).unwrap();
+
+        params.insert(String::from("unused"), MimerDatatype::Null);
+        assert_eq!(stmnt.execute_named_map(&params, false), Err(-26020));
+        stmnt.execute_named_map(&params, true).unwrap();
+
+        let count = conn
+            .query_scalar::<i32>(
+                &format!("SELECT CAST(COUNT(*) AS INTEGER) FROM {EXAMPLE_TABLE}"),
+                &[],
+            )
+            .unwrap();
+        assert_eq!(count, Some(2));
+    }
+
     #[test]
     fn test_column_names() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, BIG_TABLE, BIG_TABLE_COLUMNS);
 
@@ -1010,7 +2375,7 @@ mod statement_tests {
 
     #[test]
     fn test_execute_blob() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, BLOB_TABLE_1024, BLOB_TABLE_1024_COLUMNS);
 
@@ -1031,7 +2396,7 @@ mod statement_tests {
 
     #[test]
     fn test_too_big_blob() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, BLOB_TABLE_1024, BLOB_TABLE_1024_COLUMNS);
 
@@ -1055,7 +2420,7 @@ mod statement_tests {
 
     #[test]
     fn test_gigablob() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, BLOB_TABLE_GIGA, BLOB_TABLE_GIGA_COLUMNS);
 
@@ -1080,7 +2445,7 @@ mod statement_tests {
 
     #[test]
     fn test_small_clob() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, CLOB_TABLE, CLOB_TABLE_COLUMNS);
 
@@ -1101,7 +2466,7 @@ mod statement_tests {
     }
     #[test]
     fn test_bigger_clob() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, CLOB_TABLE, CLOB_TABLE_COLUMNS);
 
@@ -1125,7 +2490,7 @@ mod statement_tests {
     #[ignore = "Takes too long"]
     #[test]
     fn test_giga_clob() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, CLOB_TABLE_GIGA, CLOB_TABLE_GIGA_COLUMNS);
 
@@ -1152,7 +2517,7 @@ mod statement_tests {
 
     #[test]
     fn test_cutting_clob() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, CLOB_TABLE, CLOB_TABLE_COLUMNS);
 
@@ -1179,7 +2544,7 @@ mod statement_tests {
 
     #[test]
     fn test_temporal() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, TEMPORAL_TABLE, TEMPORAL_TABLE_COLUMNS);
 
@@ -1214,9 +2579,209 @@ mod statement_tests {
         assert_eq!(fetched_datetime, date_time);
     }
 
+    #[test]
+    fn test_narrow_and_unsigned_integers() {
+        let conn = establish_connection();
+
+        drop_create_table(
+            &conn,
+            "integer_table",
+            "(c1 SMALLINT, c2 SMALLINT, c3 INTEGER, c4 BIGINT, c5 BIGINT, c6 DECIMAL(20))",
+        );
+        let stmnt = conn
+            .prepare(
+                "INSERT INTO integer_table VALUES(?,?,?,?,?,?)",
+                CursorMode::Forward,
+            )
+            .unwrap();
+        stmnt
+            .execute_bind(&[
+                &-5i8,
+                &300i16,
+                &u16::MAX,
+                &u32::MAX,
+                &(i64::MAX as u64),
+                &u64::MAX,
+            ])
+            .unwrap();
+
+        let stmnt = conn
+            .prepare("SELECT * FROM integer_table", CursorMode::Forward)
+            .unwrap();
+        let mut cursor = stmnt.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+
+        assert_eq!(row.get::<i8>(1).unwrap(), Some(-5));
+        assert_eq!(row.get::<u8>(1), Err(-26025));
+        assert_eq!(row.get::<i16>(2).unwrap(), Some(300));
+        assert_eq!(row.get::<i8>(2), Err(-26025));
+        assert_eq!(row.get::<u16>(3).unwrap(), Some(u16::MAX));
+        assert_eq!(row.get::<u32>(4).unwrap(), Some(u32::MAX));
+        assert_eq!(row.get::<usize>(5).unwrap(), Some(i64::MAX as usize));
+        assert_eq!(row.get::<u64>(6).unwrap(), Some(u64::MAX));
+        assert_eq!(row.get::<u32>(6), Err(-26200));
+
+        // Too large for BIGINT, rejected by the server
+        let stmnt = conn
+            .prepare(
+                "INSERT INTO integer_table (c4) VALUES(?)",
+                CursorMode::Forward,
+            )
+            .unwrap();
+        assert!(stmnt.execute_bind(&[&u64::MAX]).is_err());
+    }
+
+    #[test]
+    fn test_borrowed_and_string_types() {
+        let conn = establish_connection();
+        drop_create_table(
+            &conn,
+            "borrowed_table",
+            "(c1 CHAR(1), c2 VARCHAR(10), c3 VARCHAR(10), c4 VARCHAR(10), c5 VARCHAR(10), c6 VARCHAR(10), c7 VARBINARY(10), c8 VARBINARY(10))",
+        );
+        let stmnt = conn
+            .prepare(
+                "INSERT INTO borrowed_table VALUES(?,?,?,?,?,?,?,?)",
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let string = String::from("string");
+        let bytes = vec![1u8, 2, 3];
+        stmnt
+            .execute_bind(&[
+                &'å',
+                &&string,
+                &std::borrow::Cow::Borrowed("cow"),
+                &Box::<str>::from("box"),
+                &std::rc::Rc::<str>::from("rc"),
+                &std::sync::Arc::<str>::from("arc"),
+                &&bytes,
+                &&bytes[1..],
+            ])
+            .unwrap();
+
+        let stmnt = conn
+            .prepare("SELECT * FROM borrowed_table", CursorMode::Forward)
+            .unwrap();
+        let mut cursor = stmnt.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+        assert_eq!(row.get::<String>(1).unwrap().as_deref(), Some("å"));
+        assert_eq!(row.get::<String>(2).unwrap().as_deref(), Some("string"));
+        assert_eq!(row.get::<String>(3).unwrap().as_deref(), Some("cow"));
+        assert_eq!(row.get::<String>(4).unwrap().as_deref(), Some("box"));
+        assert_eq!(row.get::<String>(5).unwrap().as_deref(), Some("rc"));
+        assert_eq!(row.get::<String>(6).unwrap().as_deref(), Some("arc"));
+        assert_eq!(row.get::<Vec<u8>>(7).unwrap(), Some(vec![1, 2, 3]));
+        assert_eq!(row.get::<Vec<u8>>(8).unwrap(), Some(vec![2, 3]));
+    }
+
+    #[test]
+    fn test_temporal_with_offset() {
+        use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+        let conn = establish_connection();
+
+        drop_create_table(
+            &conn,
+            TEMPORAL_TABLE,
+            "(column1 TIMESTAMP(6), column2 TIMESTAMP(6), column3 VARCHAR(40))",
+        );
+        let stmnt = conn
+            .prepare(
+                &format!("INSERT INTO {TEMPORAL_TABLE} VALUES(:UTC,:OFFSET,:TEXT)"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+
+        let utc = Utc.with_ymd_and_hms(2024, 6, 17, 12, 34, 56).unwrap()
+            + chrono::Duration::microseconds(123456);
+        let offset = FixedOffset::east_opt(2 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2024, 6, 17, 14, 34, 56)
+            .unwrap();
+        stmnt
+            .execute_bind(&[&utc, &offset, &String::from("2024-06-17 14:34:56+02:00")])
+            .unwrap();
+
+        let stmnt = conn
+            .prepare(
+                &format!("SELECT * FROM {TEMPORAL_TABLE}"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = stmnt.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+
+        assert_eq!(row.get::<DateTime<Utc>>(1).unwrap().unwrap(), utc);
+        // Stored as UTC, the offset is not kept
+        let fetched = row.get::<DateTime<FixedOffset>>(2).unwrap().unwrap();
+        assert_eq!(fetched, offset);
+        assert_eq!(fetched.offset().local_minus_utc(), 0);
+        // A string with an offset keeps it
+        let fetched = row.get::<DateTime<FixedOffset>>(3).unwrap().unwrap();
+        assert_eq!(fetched.offset().local_minus_utc(), 2 * 3600);
+        assert_eq!(fetched, offset);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_time_crate_temporal() {
+        use time::macros::{date, datetime, time};
+        let conn = establish_connection();
+
+        drop_create_table(
+            &conn,
+            TEMPORAL_TABLE,
+            "(column1 DATE, column2 TIME(3), column3 TIMESTAMP(6), column4 TIMESTAMP(6), column5 VARCHAR(40))",
+        );
+        let stmnt = conn
+            .prepare(
+                &format!("INSERT INTO {TEMPORAL_TABLE} VALUES(?,?,?,?,?)"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+
+        let date = date!(2024 - 06 - 17);
+        let time = time!(12:34:56.789);
+        let primitive = datetime!(2024-06-17 12:34:56.123456);
+        let offset = datetime!(2024-06-17 14:34:56 +2);
+        stmnt
+            .execute_bind(&[
+                &date,
+                &time,
+                &primitive,
+                &offset,
+                &String::from("2024-06-17 14:34:56+02:00"),
+            ])
+            .unwrap();
+
+        let stmnt = conn
+            .prepare(
+                &format!("SELECT * FROM {TEMPORAL_TABLE}"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        let mut cursor = stmnt.open_cursor().unwrap();
+        let row = cursor.next_row().unwrap().unwrap();
+
+        assert_eq!(row.get::<time::Date>(1).unwrap().unwrap(), date);
+        assert_eq!(row.get::<time::Time>(2).unwrap().unwrap(), time);
+        assert_eq!(
+            row.get::<time::PrimitiveDateTime>(3).unwrap().unwrap(),
+            primitive
+        );
+        // Stored as UTC, the offset is not kept
+        let fetched = row.get::<time::OffsetDateTime>(4).unwrap().unwrap();
+        assert_eq!(fetched, offset);
+        assert!(fetched.offset().is_utc());
+        // A string with an offset keeps it
+        let fetched = row.get::<time::OffsetDateTime>(5).unwrap().unwrap();
+        assert_eq!(fetched.offset().whole_hours(), 2);
+        assert_eq!(fetched, offset);
+    }
+
     #[test]
     fn test_interval() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, INTERVAL_TABLE, INTERVAL_TABLE_COLUMNS);
 
@@ -1298,9 +2863,41 @@ mod statement_tests {
         );
     }
 
+    #[test]
+    fn test_execute_update() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+        let insert = conn
+            .prepare(
+                &format!("INSERT INTO {EXAMPLE_TABLE} VALUES(:text, :number)"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        for number in 1..=3 {
+            assert_eq!(
+                insert.execute_update(&[&"row", &number]).unwrap(),
+                RowsAffected(1)
+            );
+        }
+
+        let update = conn
+            .prepare(
+                &format!("UPDATE {EXAMPLE_TABLE} SET column_1 = 'big' WHERE column_2 >= :min"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        assert_eq!(update.execute_update(&[&2]).unwrap(), RowsAffected(2));
+        assert_eq!(update.execute_update(&[&10]).unwrap(), RowsAffected(0));
+
+        let delete = conn
+            .prepare(&format!("DELETE FROM {EXAMPLE_TABLE}"), CursorMode::Forward)
+            .unwrap();
+        assert_eq!(delete.execute_update(&[]).unwrap(), RowsAffected(3));
+    }
+
     #[test]
     fn test_batch() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
 
@@ -1323,7 +2920,7 @@ mod statement_tests {
     #[test]
     fn test_batch_concurrency() {
         // tests executing a batch, and asserts that another statements execute does not interfere.
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
 
@@ -1348,9 +2945,72 @@ mod statement_tests {
         stmnt_batch.execute().unwrap();
     }
 
+    #[test]
+    fn test_execute_batch() {
+        let conn = establish_connection();
+        drop_create_table(&conn, "batch_table", "(id INT PRIMARY KEY)");
+
+        let mut stmnt = conn
+            .prepare("INSERT INTO batch_table VALUES(:id)", CursorMode::Forward)
+            .unwrap();
+        for id in [1, 2, 1, 3, 2] {
+            stmnt.add_batch(&[&id]).unwrap();
+        }
+        let result = stmnt.execute_batch().unwrap();
+        assert_eq!(result.results().len(), 5);
+        assert!(!result.is_success());
+        assert_eq!(
+            result
+                .failures()
+                .map(|(index, _)| index)
+                .collect::<Vec<_>>(),
+            vec![2, 4]
+        );
+        assert_eq!(result.rows_affected(), RowsAffected(3));
+
+        // The statement can be used as usual afterwards, without the old batch being executed again
+        stmnt.execute_bind(&[&4]).unwrap();
+        let count = conn
+            .query_scalar::<i32>("SELECT CAST(COUNT(*) AS INTEGER) FROM batch_table", &[])
+            .unwrap();
+        assert_eq!(count, Some(4));
+
+        assert!(stmnt.execute_batch().unwrap().results().is_empty());
+    }
+
+    #[test]
+    fn test_execute_batch_locating() {
+        let conn = establish_connection();
+        drop_create_table(&conn, "batch_table", "(id INT PRIMARY KEY)");
+
+        let mut stmnt = conn
+            .prepare("INSERT INTO batch_table VALUES(:id)", CursorMode::Forward)
+            .unwrap();
+        for id in [1, 2, 3, 4, 5, 6, 3, 7] {
+            stmnt.add_batch(&[&id]).unwrap();
+        }
+        let err = stmnt.execute_batch_locating().unwrap_err();
+        assert_eq!(err.get_index(), Some(6));
+
+        // Neither the failed batch nor the bisection may leave rows behind
+        let count = conn
+            .query_scalar::<i32>("SELECT CAST(COUNT(*) AS INTEGER) FROM batch_table", &[])
+            .unwrap();
+        assert_eq!(count, Some(0));
+
+        for id in [1, 2, 3] {
+            stmnt.add_batch(&[&id]).unwrap();
+        }
+        stmnt.execute_batch_locating().unwrap();
+        let count = conn
+            .query_scalar::<i32>("SELECT CAST(COUNT(*) AS INTEGER) FROM batch_table", &[])
+            .unwrap();
+        assert_eq!(count, Some(3));
+    }
+
     #[test]
     fn test_get_parameter_mode() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         // Drop existing procedure if it exists
         if let Err(rc) = conn.execute_statement(&format!("DROP PROCEDURE MATHMAGIC")) {
@@ -1378,6 +3038,49 @@ mod statement_tests {
         assert_ne!(stmnt.get_parameter_mode(3).unwrap(), ParameterMode::OUT);
     }
 
+    #[test]
+    fn statement_parameters() {
+        let conn = establish_connection();
+
+        if let Err(rc) = conn.execute_statement("DROP PROCEDURE MATHMAGIC") {
+            assert_eq!(rc, -12517);
+        } // Object does not exist error
+        conn.execute_statement(PROCEDURE_MATHMAGIC_DEF).unwrap();
+
+        let stmnt = conn
+            .prepare("CALL MATHMAGIC(:x, :y, :z)", CursorMode::Forward)
+            .unwrap();
+        let parameters: Vec<ParameterInfo> = stmnt.parameters().unwrap().collect();
+        assert_eq!(
+            parameters,
+            vec![
+                ParameterInfo {
+                    index: 1,
+                    name: String::from("X"),
+                    mode: ParameterMode::IN,
+                    param_type: ColumnType::Integer,
+                },
+                ParameterInfo {
+                    index: 2,
+                    name: String::from("Y"),
+                    mode: ParameterMode::OUT,
+                    param_type: ColumnType::Integer,
+                },
+                ParameterInfo {
+                    index: 3,
+                    name: String::from("Z"),
+                    mode: ParameterMode::INOUT,
+                    param_type: ColumnType::Integer,
+                },
+            ]
+        );
+
+        let stmnt = conn
+            .prepare("SELECT 1 FROM SYSTEM.ONEROW", CursorMode::Forward)
+            .unwrap();
+        assert_eq!(stmnt.parameters().unwrap().len(), 0);
+    }
+
     // This test covers quite a lot of functionality of this API.
     // This test will run the procedure MATHMAGIC, which takes three parameters, x, y and z.
     // The values of x and z are set to 1 and 3 respectively.
@@ -1386,7 +3089,7 @@ mod statement_tests {
     // Lastly, the values inserted into the table are verified to be the correct ones.
     #[test]
     fn test_procedure() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, RESULT_TABLE, RESULT_TABLE_COLUMNS);
 
@@ -1435,7 +3138,7 @@ mod statement_tests {
     // this test is quite poor, as none of the functionality it tests is desireable for this API (apart from the geo:Point).
     #[test]
     fn test_geo() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         drop_create_table(&conn, SPATIAL_TABLE, SPATIAL_TABLE_COLUMNS);
 
@@ -1474,4 +3177,56 @@ mod statement_tests {
         assert_eq!(row.get::<f32>(3).unwrap().unwrap(), location.1); // check fetched latitude
         assert_eq!(row.get::<(f32, f32)>(4).unwrap().unwrap(), location); // check fetched location
     }
+
+    #[test]
+    fn query_timeout() {
+        let conn = establish_connection();
+        let stmt = conn
+            .prepare(
+                "SELECT COUNT(*) FROM information_schema.columns AS a, information_schema.columns AS b, \
+                 information_schema.columns AS c",
+                CursorMode::Forward,
+            )
+            .unwrap();
+        assert_eq!(stmt.query_timeout(), None);
+        stmt.set_query_timeout(Duration::from_millis(100));
+        assert_eq!(stmt.query_timeout(), Some(Duration::from_millis(100)));
+        let started = Instant::now();
+        assert_eq!(stmt.open_cursor().err(), Some(-26036));
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        // The session is still usable after the cancelled request
+        assert_eq!(
+            conn.query_scalar::<i32>("SELECT 1 FROM system.onerow", &[])
+                .unwrap(),
+            Some(1)
+        );
+        stmt.set_query_timeout(Duration::ZERO);
+        assert_eq!(stmt.query_timeout(), None);
+    }
+
+    #[test]
+    fn query_timeout_after_execute_batch() {
+        let conn = establish_connection();
+        drop_create_table(&conn, "batch_timeout", "(id INT)");
+        let mut stmt = conn
+            .prepare(
+                "INSERT INTO batch_timeout SELECT CAST(COUNT(*) AS INTEGER) + :id \
+                 FROM information_schema.columns AS a, information_schema.columns AS b, \
+                 information_schema.columns AS c",
+                CursorMode::Forward,
+            )
+            .unwrap();
+        stmt.set_query_timeout(Duration::from_millis(100));
+        stmt.add_batch(&[&1]).unwrap();
+        stmt.add_batch(&[&2]).unwrap();
+        let started = Instant::now();
+        let result = stmt.execute_batch().unwrap();
+        assert!(started.elapsed() < Duration::from_secs(20));
+        assert_eq!(stmt.query_timeout(), Some(Duration::from_millis(100)));
+        assert_eq!(
+            result.failures().map(|(_, ec)| ec).collect::<Vec<_>>(),
+            vec![-26036, -26036]
+        );
+    }
 }
@@ -0,0 +1,147 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use mimerrust_sys as ffi;
+
+/// A server statistics counter, as read with [get_statistics_for](crate::Connection::get_statistics_for()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatisticsCounter {
+    /// The number of 4K pages available in the system.
+    Pages4K = ffi::BSI_4K_PAGES as isize,
+    /// The number of 32K pages available in the system.
+    Pages32K = ffi::BSI_32K_PAGES as isize,
+    /// The number of 128K pages available in the system.
+    Pages128K = ffi::BSI_128K_PAGES as isize,
+    /// The total number of pages in use.
+    PagesUsed = ffi::BSI_PAGES_USED as isize,
+    /// The number of 4K pages in use.
+    Pages4KUsed = ffi::BSI_4K_PAGES_USED as isize,
+    /// The number of 32K pages in use.
+    Pages32KUsed = ffi::BSI_32K_PAGES_USED as isize,
+    /// The number of 128K pages in use.
+    Pages128KUsed = ffi::BSI_128K_PAGES_USED as isize,
+    /// The number of requests from clients since the server started.
+    Requests = ffi::BSI_REQUESTS as isize,
+    /// The number of page requests since the server started.
+    PageRequests = ffi::BSI_PAGE_REQUESTS as isize,
+    /// The number of committed transactions since the server started.
+    Commits = ffi::BSI_COMMITS as isize,
+    /// The number of aborted transactions since the server started.
+    Aborts = ffi::BSI_ABORTS as isize,
+}
+
+impl StatisticsCounter {
+    /// All counters, as read by [get_statistics](crate::Connection::get_statistics()).
+    pub const ALL: [StatisticsCounter; 11] = [
+        StatisticsCounter::Pages4K,
+        StatisticsCounter::Pages32K,
+        StatisticsCounter::Pages128K,
+        StatisticsCounter::PagesUsed,
+        StatisticsCounter::Pages4KUsed,
+        StatisticsCounter::Pages32KUsed,
+        StatisticsCounter::Pages128KUsed,
+        StatisticsCounter::Requests,
+        StatisticsCounter::PageRequests,
+        StatisticsCounter::Commits,
+        StatisticsCounter::Aborts,
+    ];
+}
+
+/// Server statistics, as returned by [get_statistics](crate::Connection::get_statistics()).
+///
+/// A counter is [None] when it wasn't requested, or when the server reported it as unavailable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ServerStatistics {
+    /// The number of 4K pages available in the system.
+    pub pages_4k: Option<u32>,
+    /// The number of 32K pages available in the system.
+    pub pages_32k: Option<u32>,
+    /// The number of 128K pages available in the system.
+    pub pages_128k: Option<u32>,
+    /// The total number of pages in use.
+    pub pages_used: Option<u32>,
+    /// The number of 4K pages in use.
+    pub pages_4k_used: Option<u32>,
+    /// The number of 32K pages in use.
+    pub pages_32k_used: Option<u32>,
+    /// The number of 128K pages in use.
+    pub pages_128k_used: Option<u32>,
+    /// The number of requests from clients since the server started.
+    pub requests: Option<u32>,
+    /// The number of page requests since the server started.
+    pub page_requests: Option<u32>,
+    /// The number of committed transactions since the server started.
+    pub commits: Option<u32>,
+    /// The number of aborted transactions since the server started.
+    pub aborts: Option<u32>,
+}
+
+impl ServerStatistics {
+    /// Gets the value of a counter.
+    pub fn get(&self, counter: StatisticsCounter) -> Option<u32> {
+        *self.field(counter)
+    }
+
+    fn field(&self, counter: StatisticsCounter) -> &Option<u32> {
+        match counter {
+            StatisticsCounter::Pages4K => &self.pages_4k,
+            StatisticsCounter::Pages32K => &self.pages_32k,
+            StatisticsCounter::Pages128K => &self.pages_128k,
+            StatisticsCounter::PagesUsed => &self.pages_used,
+            StatisticsCounter::Pages4KUsed => &self.pages_4k_used,
+            StatisticsCounter::Pages32KUsed => &self.pages_32k_used,
+            StatisticsCounter::Pages128KUsed => &self.pages_128k_used,
+            StatisticsCounter::Requests => &self.requests,
+            StatisticsCounter::PageRequests => &self.page_requests,
+            StatisticsCounter::Commits => &self.commits,
+            StatisticsCounter::Aborts => &self.aborts,
+        }
+    }
+
+    fn field_mut(&mut self, counter: StatisticsCounter) -> &mut Option<u32> {
+        match counter {
+            StatisticsCounter::Pages4K => &mut self.pages_4k,
+            StatisticsCounter::Pages32K => &mut self.pages_32k,
+            StatisticsCounter::Pages128K => &mut self.pages_128k,
+            StatisticsCounter::PagesUsed => &mut self.pages_used,
+            StatisticsCounter::Pages4KUsed => &mut self.pages_4k_used,
+            StatisticsCounter::Pages32KUsed => &mut self.pages_32k_used,
+            StatisticsCounter::Pages128KUsed => &mut self.pages_128k_used,
+            StatisticsCounter::Requests => &mut self.requests,
+            StatisticsCounter::PageRequests => &mut self.page_requests,
+            StatisticsCounter::Commits => &mut self.commits,
+            StatisticsCounter::Aborts => &mut self.aborts,
+        }
+    }
+
+    /// Builds the statistics from the values that `MimerGetStatistics` wrote over the requested counters.
+    /// Negative values are the server telling that a counter is unavailable.
+    pub(crate) fn from_values(counters: &[StatisticsCounter], values: &[i32]) -> ServerStatistics {
+        let mut statistics = ServerStatistics::default();
+        for (counter, value) in counters.iter().zip(values) {
+            *statistics.field_mut(*counter) = u32::try_from(*value).ok();
+        }
+        statistics
+    }
+}
@@ -0,0 +1,518 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{
+    admin::{IdentType, Privilege},
+    connection::Connection,
+    error_sink::report,
+    mimer_error::MimerError,
+};
+
+#[cfg(feature = "test-support")]
+pub use crate::fixture::FixtureError;
+
+use std::{
+    env, fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Mimer SQL error code for a table that does not exist.
+const TABLE_NOT_FOUND: i32 = -12501;
+/// Mimer SQL error code for an object that does not exist.
+const OBJECT_NOT_FOUND: i32 = -12517;
+
+/// Describes the database and ident used by integration tests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestConfig {
+    /// Name of the database, an empty name means the default database in `SQLHOSTS`.
+    pub database: String,
+    /// Ident to connect as.
+    pub ident: String,
+    /// Password of the ident.
+    pub password: String,
+    /// Databank created for the ident by [connect](TestConfig::connect()) if it doesn't have one.
+    pub databank: String,
+}
+
+impl TestConfig {
+    /// Creates a test configuration for the given database, ident and password, using the databank `test_databank`.
+    pub fn new(database: &str, ident: &str, password: &str) -> TestConfig {
+        TestConfig {
+            database: database.to_string(),
+            ident: ident.to_string(),
+            password: password.to_string(),
+            databank: String::from("test_databank"),
+        }
+    }
+
+    /// Creates a test configuration from the environment.
+    ///
+    /// | Variable | Default |
+    /// |---------|---------|
+    /// | `MIMER_DATABASE` | The default database in `SQLHOSTS` |
+    /// | `MIMER_TEST_IDENT` | `RUSTUSER` |
+    /// | `MIMER_TEST_PASSWORD` | `RUSTPASSWORD` |
+    /// | `MIMER_TEST_DATABANK` | `test_databank` |
+    pub fn from_env() -> TestConfig {
+        let config = TestConfig::new(
+            &env::var("MIMER_DATABASE").unwrap_or_default(),
+            &env::var("MIMER_TEST_IDENT").unwrap_or(String::from("RUSTUSER")),
+            &env::var("MIMER_TEST_PASSWORD").unwrap_or(String::from("RUSTPASSWORD")),
+        );
+        match env::var("MIMER_TEST_DATABANK") {
+            Ok(databank) => config.databank(&databank),
+            Err(_) => config,
+        }
+    }
+
+    /// Sets the databank created for the ident if it doesn't have one.
+    pub fn databank(mut self, databank: &str) -> TestConfig {
+        self.databank = databank.to_string();
+        self
+    }
+
+    /// Opens a connection and makes sure the ident has a databank to create tables in.
+    ///
+    /// # Errors
+    /// Returns [Err] holding a [MimerError] when the connection failed to open or the databank couldn't be created.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::test_support::*;
+    /// let conn = TestConfig::from_env().connect().unwrap();
+    /// drop_create_table(&conn, "my_test_table", "(id INT, name VARCHAR(30))").unwrap();
+    /// ```
+    pub fn connect(&self) -> Result<Connection, MimerError> {
+        let conn = Connection::open(&self.database, &self.ident, &self.password)?;
+        ensure_databank(&conn, &self.databank).map_err(|ec| conn.get_error(ec))?;
+        Ok(conn)
+    }
+}
+
+/// Creates the databank `name` unless the current ident already owns a databank.
+///
+/// # Errors
+/// Returns [Err] when the databanks couldn't be listed or the databank couldn't be created.
+pub fn ensure_databank(conn: &Connection, name: &str) -> Result<(), i32> {
+    let databanks = conn.query_map(
+        "SELECT * FROM INFORMATION_SCHEMA.EXT_DATABANKS",
+        &[],
+        |_| Ok(()),
+    )?;
+    if databanks.is_empty() {
+        conn.execute_statement(&format!("CREATE DATABANK {name}"))?;
+    }
+    Ok(())
+}
+
+/// Drops a table if it exists.
+///
+/// # Errors
+/// Returns [Err] when the table exists but couldn't be dropped.
+pub fn drop_table_if_exists(conn: &Connection, table: &str) -> Result<(), i32> {
+    match conn.execute_statement(&format!("DROP TABLE {table}")) {
+        Ok(_) | Err(TABLE_NOT_FOUND) | Err(OBJECT_NOT_FOUND) => Ok(()),
+        Err(ec) => Err(ec),
+    }
+}
+
+/// Drops a table if it exists and creates it again with the given column definitions, e.g. `"(id INT, name VARCHAR(30))"`.
+/// Used for starting tests from a clean slate.
+///
+/// # Errors
+/// Returns [Err] when the table couldn't be dropped or created.
+pub fn drop_create_table(conn: &Connection, table: &str, table_columns: &str) -> Result<(), i32> {
+    drop_table_if_exists(conn, table)?;
+    conn.execute_statement(&format!("CREATE TABLE {table} {table_columns}"))?;
+    Ok(())
+}
+
+/// Returns whether a table with the given name is visible to the current ident.
+///
+/// # Errors
+/// Returns [Err] when the table couldn't be looked up.
+pub fn table_exists(conn: &Connection, table: &str) -> Result<bool, i32> {
+    let tables = conn.query_map(
+        "SELECT table_name FROM INFORMATION_SCHEMA.TABLES WHERE table_name = UPPER(CAST(? AS VARCHAR(128)))",
+        &[&table],
+        |_| Ok(()),
+    )?;
+    Ok(!tables.is_empty())
+}
+
+/// Returns a name starting with `prefix` that no other call in any running test process returns,
+/// e.g. `orders_4711_3`, for tables and schemas that tests running in parallel mustn't share.
+pub fn unique_name(prefix: &str) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    format!(
+        "{prefix}_{}_{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// A table created for a test, which is dropped when the TempTable goes out of scope.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::test_support::*;
+/// let conn = TestConfig::from_env().connect().unwrap();
+/// let table = TempTable::create(&conn, &unique_name("orders"), "(id INT, amount INT)").unwrap();
+/// conn.execute_statement(&format!("INSERT INTO {table} VALUES(1, 100)")).unwrap();
+/// // The table is dropped here
+/// ```
+pub struct TempTable<'a> {
+    conn: &'a Connection,
+    name: String,
+}
+
+impl<'a> TempTable<'a> {
+    /// Creates the table `name` with the given column definitions, dropping any table left with that name first.
+    ///
+    /// # Errors
+    /// Returns [Err] when the table couldn't be dropped or created.
+    pub fn create(
+        conn: &'a Connection,
+        name: &str,
+        table_columns: &str,
+    ) -> Result<TempTable<'a>, i32> {
+        drop_create_table(conn, name, table_columns)?;
+        Ok(TempTable {
+            conn,
+            name: name.to_string(),
+        })
+    }
+
+    /// Returns the name of the table.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl fmt::Display for TempTable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl fmt::Debug for TempTable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TempTable")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for TempTable<'_> {
+    fn drop(&mut self) {
+        if let Err(ec) = drop_table_if_exists(self.conn, &self.name) {
+            report("dropping a temporary test table", ec);
+        }
+    }
+}
+
+/// A schema with a [unique name](unique_name) created for a test, which is dropped together with everything in it when the TestSchema goes out of scope.
+/// Tests that create their tables in a schema of their own can run in parallel against the same ident without clashing.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::test_support::*;
+/// let conn = TestConfig::from_env().connect().unwrap();
+/// let schema = TestSchema::create(&conn, "orders_test").unwrap();
+/// let orders = schema.table("orders");
+/// conn.execute_statement(&format!("CREATE TABLE {orders} (id INT)")).unwrap();
+/// // The schema and its tables are dropped here
+/// ```
+pub struct TestSchema<'a> {
+    conn: &'a Connection,
+    name: String,
+}
+
+impl<'a> TestSchema<'a> {
+    /// Creates a schema named from `prefix` with [unique_name].
+    ///
+    /// # Errors
+    /// Returns [Err] when the schema couldn't be created.
+    pub fn create(conn: &'a Connection, prefix: &str) -> Result<TestSchema<'a>, i32> {
+        let name = unique_name(prefix);
+        drop_schema_if_exists(conn, &name)?;
+        conn.execute_statement(&format!("CREATE SCHEMA {name}"))?;
+        Ok(TestSchema { conn, name })
+    }
+
+    /// Returns the name of the schema.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the name of `table` qualified by the schema, e.g. `orders_test_4711_0.orders`.
+    pub fn table(&self, table: &str) -> String {
+        format!("{}.{table}", self.name)
+    }
+}
+
+impl fmt::Debug for TestSchema<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestSchema")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for TestSchema<'_> {
+    fn drop(&mut self) {
+        if let Err(ec) = drop_schema_if_exists(self.conn, &self.name) {
+            report("dropping a test schema", ec);
+        }
+    }
+}
+
+/// Drops a schema and everything in it, if it exists.
+///
+/// # Errors
+/// Returns [Err] when the schema exists but couldn't be dropped.
+pub fn drop_schema_if_exists(conn: &Connection, schema: &str) -> Result<(), i32> {
+    match conn.execute_statement(&format!("DROP SCHEMA {schema} CASCADE")) {
+        Ok(_) | Err(TABLE_NOT_FOUND) | Err(OBJECT_NOT_FOUND) => Ok(()),
+        Err(ec) => Err(ec),
+    }
+}
+
+/// A databank of its own for a test process, created by [create](TestDb::create()) and dropped with its tables when the TestDb is dropped.
+/// Optionally the databank belongs to a scratch ident, which is created for the TestDb and dropped with it.
+///
+/// Setup only uses SQL statements, so it works on every platform against any database the configured ident can connect to,
+/// without creating database definitions or system databanks. Teardown is best-effort: failures are reported through the
+/// [error handler](crate::set_error_handler()) rather than panicking.
+///
+/// Tables should be created `IN` the [databank](TestDb::databank()), since the ident may own others.
+///
+/// # Examples
+/// ```
+/// # use mimerrust::test_support::*;
+/// let db = TestDb::create(&TestConfig::from_env()).unwrap();
+/// db.connection()
+///     .execute_statement(&format!("CREATE TABLE orders (id INT) IN {}", db.databank()))
+///     .unwrap();
+/// // The databank and the table are dropped here
+/// ```
+pub struct TestDb {
+    databank: String,
+    // Declared before `owner` so that the scratch ident's connection is closed before its ident is dropped
+    scratch: Option<(String, Connection)>,
+    owner: Connection,
+}
+
+impl TestDb {
+    /// Connects as the ident of `config` and creates a databank named from [TestConfig::databank] with [unique_name].
+    ///
+    /// # Errors
+    /// Returns [Err] holding a [MimerError] when the connection failed to open or the databank couldn't be created.
+    pub fn create(config: &TestConfig) -> Result<TestDb, MimerError> {
+        let owner = Connection::open(&config.database, &config.ident, &config.password)?;
+        let databank = unique_name(&config.databank);
+        create_databank(&owner, &databank).map_err(|ec| owner.get_error(ec))?;
+        Ok(TestDb {
+            databank,
+            scratch: None,
+            owner,
+        })
+    }
+
+    /// Like [create](TestDb::create()), but creates a scratch ident with a [unique_name] first and creates the databank as that ident.
+    /// The ident of `config` needs the `IDENT` privilege, e.g. granted by SYSADM with `GRANT IDENT TO RUSTUSER`.
+    ///
+    /// # Errors
+    /// Returns [Err] holding a [MimerError] when the connection failed to open, or the ident or the databank couldn't be created.
+    pub fn create_with_scratch_ident(config: &TestConfig) -> Result<TestDb, MimerError> {
+        let owner = Connection::open(&config.database, &config.ident, &config.password)?;
+        let ident = unique_name("TEST_IDENT");
+        let password = unique_name("password");
+        owner
+            .create_ident(&ident, IdentType::User, Some(&password))
+            .and_then(|_| owner.grant(&Privilege::Databank, &ident, false))
+            .map_err(|ec| owner.get_error(ec))?;
+
+        let connection = match Connection::open(&config.database, &ident, &password) {
+            Ok(connection) => connection,
+            Err(err) => {
+                drop_ident(&owner, &ident);
+                return Err(err);
+            }
+        };
+        let databank = unique_name(&config.databank);
+        if let Err(ec) = create_databank(&connection, &databank) {
+            let err = connection.get_error(ec);
+            drop(connection);
+            drop_ident(&owner, &ident);
+            return Err(err);
+        }
+        Ok(TestDb {
+            databank,
+            scratch: Some((ident, connection)),
+            owner,
+        })
+    }
+
+    /// Returns the connection owning the databank, as the scratch ident if there is one.
+    pub fn connection(&self) -> &Connection {
+        match &self.scratch {
+            Some((_, connection)) => connection,
+            None => &self.owner,
+        }
+    }
+
+    /// Returns the name of the databank.
+    pub fn databank(&self) -> &str {
+        &self.databank
+    }
+
+    /// Returns the name of the scratch ident, [None] when the databank belongs to the configured ident.
+    pub fn scratch_ident(&self) -> Option<&str> {
+        self.scratch.as_ref().map(|(ident, _)| ident.as_str())
+    }
+}
+
+impl fmt::Debug for TestDb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestDb")
+            .field("databank", &self.databank)
+            .field("scratch_ident", &self.scratch_ident())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        if let Err(ec) = self
+            .connection()
+            .execute_statement(&format!("DROP DATABANK {} CASCADE", self.databank))
+        {
+            report("dropping a test databank", ec);
+        }
+        if let Some((ident, connection)) = self.scratch.take() {
+            drop(connection);
+            drop_ident(&self.owner, &ident);
+        }
+    }
+}
+
+/// Creates the databank `name`, dropping any databank left with that name by an earlier test run first.
+fn create_databank(conn: &Connection, name: &str) -> Result<(), i32> {
+    match conn.execute_statement(&format!("DROP DATABANK {name} CASCADE")) {
+        Ok(_) | Err(TABLE_NOT_FOUND) | Err(OBJECT_NOT_FOUND) => {}
+        Err(ec) => return Err(ec),
+    }
+    conn.execute_statement(&format!("CREATE DATABANK {name}"))?;
+    Ok(())
+}
+
+/// Drops a scratch ident and everything it owns, reporting a failure.
+fn drop_ident(owner: &Connection, ident: &str) {
+    if let Err(ec) = owner.drop_ident(ident, true) {
+        report("dropping a scratch test ident", ec);
+    }
+}
+
+#[cfg(test)]
+mod test_support_tests {
+    use super::*;
+
+    #[test]
+    fn test_config_new() {
+        let config = TestConfig::new("db", "ident", "password").databank("my_databank");
+        assert_eq!(config.database, "db");
+        assert_eq!(config.ident, "ident");
+        assert_eq!(config.password, "password");
+        assert_eq!(config.databank, "my_databank");
+    }
+
+    #[test]
+    fn drop_create_table_twice() {
+        let conn = crate::testing::establish_connection();
+        drop_table_if_exists(&conn, "test_support_table").unwrap();
+        assert!(!table_exists(&conn, "test_support_table").unwrap());
+        drop_create_table(&conn, "test_support_table", "(id INT)").unwrap();
+        drop_create_table(&conn, "test_support_table", "(id INT)").unwrap();
+        assert!(table_exists(&conn, "test_support_table").unwrap());
+        drop_table_if_exists(&conn, "test_support_table").unwrap();
+    }
+
+    #[test]
+    fn unique_names() {
+        let first = unique_name("test_table");
+        let second = unique_name("test_table");
+        assert!(first.starts_with("test_table_"));
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn temp_table_and_schema_dropped() {
+        let conn = crate::testing::establish_connection();
+        let name = unique_name("temp_table");
+        {
+            let table = TempTable::create(&conn, &name, "(id INT)").unwrap();
+            assert!(table_exists(&conn, table.name()).unwrap());
+        }
+        assert!(!table_exists(&conn, &name).unwrap());
+
+        let qualified;
+        {
+            let schema = TestSchema::create(&conn, "test_schema").unwrap();
+            qualified = schema.table("test_table");
+            conn.execute_statement(&format!("CREATE TABLE {qualified} (id INT)"))
+                .unwrap();
+        }
+        assert_eq!(
+            conn.execute_statement(&format!("SELECT * FROM {qualified}")),
+            Err(TABLE_NOT_FOUND)
+        );
+    }
+
+    #[test]
+    fn test_db_databank() {
+        let config = TestConfig::from_env().databank("test_db");
+        let databank;
+        {
+            let db = TestDb::create(&config).unwrap();
+            databank = db.databank().to_string();
+            assert!(databank.starts_with("test_db_"));
+            assert_eq!(db.scratch_ident(), None);
+            db.connection()
+                .execute_statement(&format!(
+                    "CREATE TABLE test_db_table (id INT) IN {databank}"
+                ))
+                .unwrap();
+        }
+        let conn = crate::testing::establish_connection();
+        let remaining = conn
+            .query_map(
+                "SELECT * FROM INFORMATION_SCHEMA.EXT_DATABANKS WHERE databank_name = UPPER(CAST(? AS VARCHAR(128)))",
+                &[&databank],
+                |_| Ok(()),
+            )
+            .unwrap();
+        assert!(remaining.is_empty());
+    }
+}
@@ -25,14 +25,10 @@
 #![allow(dead_code)]
 
 use crate::connection::*;
+use crate::test_support::{self, TestConfig};
 
 #[doc(hidden)]
-use std::{
-    env,
-    fs::File,
-    io::Read,
-    process::{Command, Output},
-};
+use std::{env, fs::File, io::Read};
 
 pub const PASSWORD: &str = "RUSTPASSWORD";
 pub const IDENT: &str = "RUSTUSER";
@@ -132,18 +128,6 @@ BEGIN
 END;
 ";
 
-///  Creates a local database definition in the sqlhosts file (usually/always /etc/sqlhosts on Unix)
-///  The database definition has name DB and home directory DB_HOMEDIR
-pub fn create_database_definition(db_name: &str, db_homerdir: &str) -> std::io::Result<Output> {
-    // TODO: add support for windows and other OS. Or verify that this function is fine for other OS aswell.
-    if !cfg!(target_os = "linux") {
-        panic!("Support for this function is only implemeted for Linux.")
-    }
-
-    let args = ["mimsqlhosts", "-a", db_name, db_homerdir];
-    Command::new("pkexec").args(args).output()
-}
-
 // Reads from file and writes to buffer. Path should be relative to current directory
 pub fn read_from_file(file_path: &str, mut buffer: String) -> String {
     let file_path = format!(
@@ -168,28 +152,6 @@ pub fn read_from_file(file_path: &str, mut buffer: String) -> String {
     buffer
 }
 
-/// Creates system databanks for db definition DB in directory DB_HOMEDIR
-pub fn create_system_databanks(password: &str, db_name: &str) -> std::io::Result<Output> {
-    if !cfg!(target_os = "linux") {
-        panic!("Support for this function is only implemeted for Linux.")
-    }
-
-    let args = [
-        "-p",
-        password,
-        db_name,
-        "4000",
-        "transaction_databank.dbf",
-        "1000",
-        "log_databank.dbf",
-        "1000",
-        "sql_databank.dbf",
-        "1000",
-    ];
-    let output = Command::new("sdbgen").args(args).output();
-    output
-}
-
 /// Creates a databank for for user RUSTUSER
 /// The databank will be used for performing various queries for testing purposes.
 pub fn create_user_databank(
@@ -209,60 +171,15 @@ pub fn establish_connection() -> Connection {
     let db = env::var("MIMER_DATABASE").expect("Environment variable MIMER_DATABASE not set.");
     let rustuser_pass = env::var("RUSTPASSWORD").unwrap_or(String::from(PASSWORD));
 
-    let mut conn = Connection::open(&db, IDENT, &rustuser_pass)
-        .unwrap_or_else(|ec| panic!("Connection failed: {ec}"));
-
-    // check that the user has a databank, or create one if they dont.
-    let stmnt = conn
-        .prepare(
-            "select * from INFORMATION_SCHEMA.EXT_DATABANKS",
-            crate::CursorMode::Forward,
-        )
-        .unwrap();
-    match stmnt.open_cursor().unwrap().next_row().unwrap() {
-        Some(_) => (),
-        None => {
-            conn.execute_statement("CREATE DATABANK test_databank")
-                .unwrap();
-        }
-    }
-    return conn;
+    TestConfig::new(&db, IDENT, &rustuser_pass)
+        .connect()
+        .unwrap_or_else(|ec| panic!("Connection failed: {ec}"))
 }
 
 /// Drops table and creates it again after with columns specified in function arguments. Used for starting tests from a clean slate.
 pub fn drop_create_table(conn: &Connection, table: &str, table_columns: &str) {
-    if let Err(ec) = conn.execute_statement(&format!("DROP TABLE {}", table)) {
-        assert!(ec == -12501 || ec == -12517); // Mimer SQL Error: Table does not exist or Object does not exist respectively.
-    };
-
-    match conn.execute_statement(&format!("CREATE TABLE {} {}", table, table_columns)) {
-        Ok(_) => (),
-        Err(ec) => {
-            dbg!(conn.get_error(ec));
-            panic!("Execute statement failed, errorcode: {ec}");
-        }
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const TEST_DB: &str = "test_db";
-    const TEST_DB_HOMEDIR: &str = "/usr/local/MimerSQL/test_db";
-
-    #[ignore = "This test is ignored as it requires root access (annoying)."]
-    #[test]
-    fn create_db_def() {
-        create_database_definition(TEST_DB, TEST_DB_HOMEDIR)
-            .unwrap_or_else(|err| panic!("Failed to create database definition: {err}"));
-    }
-
-    #[ignore = "We dont want to run this everytime"]
-    #[test]
-    fn create_sys_databank() {
-        let output = create_system_databanks(PASSWORD, TEST_DB)
-            .unwrap_or_else(|err| panic!("failed to create system databanks: {err}"));
-        dbg!(output);
+    if let Err(ec) = test_support::drop_create_table(conn, table, table_columns) {
+        dbg!(conn.get_error(ec));
+        panic!("Execute statement failed, errorcode: {ec}");
     }
 }
@@ -0,0 +1,141 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+#[cfg(feature = "tracing")]
+use crate::{inner_connection::InnerConnection, mimer_error::MimerError};
+
+/// Runs `$body`, which returns a [Result], in a `tracing` span named `$name` with the given fields,
+/// and records the error code of a failure in the `error_code` field of the span.
+/// Without the `tracing` feature, `$body` is just run and the fields are not evaluated.
+macro_rules! traced {
+    ($name:literal, { $($field:tt)+ }, $body:expr) => {{
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!(
+            $name,
+            db.system = "mimersql",
+            $($field)+,
+            error_code = tracing::field::Empty
+        );
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+        let result = $body;
+        #[cfg(feature = "tracing")]
+        if let Err(err) = &result {
+            span.record("error_code", $crate::tracing_support::ErrorCode::code(err));
+        }
+        result
+    }};
+}
+pub(crate) use traced;
+
+/// The error types that traced calls return.
+#[cfg(feature = "tracing")]
+pub(crate) trait ErrorCode {
+    fn code(&self) -> i32;
+}
+
+#[cfg(feature = "tracing")]
+impl ErrorCode for i32 {
+    fn code(&self) -> i32 {
+        *self
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl ErrorCode for MimerError {
+    fn code(&self) -> i32 {
+        self.get_error_code()
+    }
+}
+
+/// Returns the SQL to put in the `db.statement` field of a span, redacted if the connection is set to redact it.
+#[cfg(feature = "tracing")]
+pub(crate) fn statement_text(conn: Option<&InnerConnection>, sql: &str) -> String {
+    match conn.is_some_and(|conn| {
+        conn.redact_traced_sql
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }) {
+        true => redact(sql),
+        false => sql.to_string(),
+    }
+}
+
+/// Replaces the string and numeric literals in `sql` with `?`, so that values don't end up in traces.
+/// Identifiers, including those with digits, and quoted identifiers are kept.
+#[cfg(feature = "tracing")]
+pub(crate) fn redact(sql: &str) -> String {
+    let mut redacted = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    let mut in_identifier = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                // Skip to the closing quote, where a doubled quote is an escaped quote
+                while let Some(c) = chars.next() {
+                    if c == '\'' && chars.next_if_eq(&'\'').is_none() {
+                        break;
+                    }
+                }
+                redacted.push('?');
+                in_identifier = false;
+            }
+            '"' => {
+                redacted.push(c);
+                for c in chars.by_ref() {
+                    redacted.push(c);
+                    if c == '"' {
+                        break;
+                    }
+                }
+                in_identifier = false;
+            }
+            c if c.is_ascii_digit() && !in_identifier => {
+                while chars.next_if(|c| c.is_ascii_digit() || *c == '.').is_some() {}
+                redacted.push('?');
+            }
+            c => {
+                in_identifier = c.is_alphanumeric() || c == '_';
+                redacted.push(c);
+            }
+        }
+    }
+    redacted
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tracing_tests {
+    use super::*;
+
+    #[test]
+    fn redact_literals() {
+        assert_eq!(
+            redact("INSERT INTO t2 VALUES('it''s', 12.5, -3, \"col 1\")"),
+            "INSERT INTO t2 VALUES(?, ?, -?, \"col 1\")"
+        );
+        assert_eq!(
+            redact("SELECT c1 FROM t WHERE id = ?"),
+            "SELECT c1 FROM t WHERE id = ?"
+        );
+    }
+}
@@ -29,20 +29,44 @@ use crate::{
         traits::{GetHandle, MimerHandle},
     },
     connection::Connection,
+    error_sink::unexpected_return_code,
+    mimer_error::MimerError,
+    script::keywords,
+    session_guard::SessionGuard,
+    statement::Statement,
+    tracing_support::traced,
 };
 use mimerrust_sys as ffi;
 
 #[doc(hidden)]
 use std::{
     cmp::Ordering,
+    marker::PhantomData,
     ops::{Deref, DerefMut},
     result::Result::{Err, Ok},
+    sync::atomic::{AtomicBool, Ordering as AtomicOrdering},
+    time::Duration,
 };
 
+/// A statement executed while a [Transaction] records its history, see [record_history](crate::Transaction::record_history()).
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementRecord {
+    /// SQL of the statement.
+    pub sql: String,
+    /// Result of executing the statement: the return code from the server, or the error code.
+    pub result: Result<i32, i32>,
+    /// Time spent executing the statement, including retries.
+    pub duration: Duration,
+}
+
 /// Represents a transaction on a database connection. A Transaction will roll back by default if the object is dropped.
 /// Use the `commit` method to commit the changes made in the transaction.
+///
+/// Errors from the rollback on drop are passed to the [rollback failure hook](crate::Connection::set_rollback_failure_hook()) of the connection, if any.
 pub struct Transaction<'a> {
-    connection: &'a mut Connection,
+    connection: &'a Connection,
+    mode: TransactionMode,
+    ended: AtomicBool, // Atomic since commit_and_continue ends the transaction through a shared reference
 }
 
 impl GetHandle for Transaction<'_> {
@@ -50,30 +74,20 @@ impl GetHandle for Transaction<'_> {
         self.connection.get_handle()
     }
 
-    fn get_session_handle(
-        &self,
-    ) -> Result<Option<parking_lot::MappedMutexGuard<mimerrust_sys::MimerSession>>, i32> {
+    fn get_session_handle(&self) -> Result<Option<SessionGuard<mimerrust_sys::MimerSession>>, i32> {
         self.connection.get_session_handle()
     }
 }
 
 impl Transaction<'_> {
     /// Creates a Transaction struct
-    pub(crate) fn new(conn: &mut Connection, toption: TransactionMode) -> Result<Transaction, i32> {
-        unsafe {
-            let rc = ffi::MimerBeginTransaction(
-                *conn.get_session_handle()?.unwrap(), //Ok unwrap since we know the connection is a connection
-                toption as i32,
-            );
-            match rc.cmp(MIMER_SUCCESS) {
-                Ordering::Greater => {
-                    // i suppose this is a reasonable panic?
-                    panic!("Return code is positive from C API function which doesn't return a positive value")
-                }
-                Ordering::Equal => Ok(Transaction { connection: conn }),
-                Ordering::Less => Err(rc),
-            }
-        }
+    pub(crate) fn new(conn: &Connection, toption: TransactionMode) -> Result<Transaction, i32> {
+        begin_session_transaction(conn, toption)?;
+        Ok(Transaction {
+            connection: conn,
+            mode: toption,
+            ended: AtomicBool::new(false),
+        })
     }
 
     /// Commits a [Transaction] into the database, returns 0 if successful and a negative number if unsuccessful.
@@ -89,7 +103,7 @@ impl Transaction<'_> {
     /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
     /// # let ident = "RUSTUSER";
     /// # let pass = "RUSTPASSWORD";
-    /// let mut conn = Connection::open(db, ident, pass).unwrap();
+    /// let conn = Connection::open(db, ident, pass).unwrap();
     /// let trans_option = TransactionMode::ReadWrite;
     /// let trans = conn.begin_transaction(trans_option).unwrap();
     ///
@@ -114,7 +128,7 @@ impl Transaction<'_> {
     /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
     /// # let ident = "RUSTUSER";
     /// # let pass = "RUSTPASSWORD";
-    /// let mut conn = Connection::open(db, ident, pass).unwrap();
+    /// let conn = Connection::open(db, ident, pass).unwrap();
     /// let trans_option = TransactionMode::ReadWrite;
     /// let trans = conn.begin_transaction(trans_option).unwrap();
     ///
@@ -126,26 +140,260 @@ impl Transaction<'_> {
         self.end_transaction(EndTransactionMode::Rollback)
     }
 
+    /// Commits the changes made so far and immediately begins a new transaction in the same mode,
+    /// so that a long batch job can commit in chunks while keeping its [prepared statements](crate::Transaction::prepare()).
+    ///
+    /// # Errors
+    /// Returns [Err] when the transaction can't be committed, in which case it remains open and can still be rolled back.
+    /// Returns [Err] as well when the new transaction can't be begun, in which case the Transaction is left ended,
+    /// and further calls return -26044.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// let trans = conn.begin_transaction(TransactionMode::ReadWrite).unwrap();
+    /// let stmnt = trans.prepare("INSERT INTO test_table VALUES('a number', ?)", CursorMode::Forward).unwrap();
+    /// for number in 1..=1000 {
+    ///     stmnt.execute_bind(&[&number]).unwrap();
+    ///     if number % 100 == 0 {
+    ///         trans.commit_and_continue().unwrap();
+    ///     }
+    /// }
+    /// drop(stmnt);
+    /// trans.commit().unwrap();
+    /// ```
+    pub fn commit_and_continue(&self) -> Result<i32, i32> {
+        if self.ended.load(AtomicOrdering::Relaxed) {
+            return Err(-26044); // Transaction has already been ended
+        }
+        let rc = end_session_transaction(self.connection, EndTransactionMode::Commit)?;
+        if let Err(ec) = begin_session_transaction(self.connection, self.mode) {
+            self.ended.store(true, AtomicOrdering::Relaxed);
+            return Err(ec);
+        }
+        Ok(rc)
+    }
+
+    /// Returns the mode the transaction was started with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// let trans = conn.begin_transaction(TransactionMode::ReadOnly).unwrap();
+    /// assert_eq!(trans.mode(), TransactionMode::ReadOnly);
+    /// ```
+    pub fn mode(&self) -> TransactionMode {
+        self.mode
+    }
+
+    /// Starts recording the statements executed on the connection until the transaction ends, clearing any earlier history.
+    /// Statements executed through [execute_statement](crate::Connection::execute_statement()) and the
+    /// [execute](crate::Statement::execute()) and [execute_bind](crate::Statement::execute_bind()) methods of prepared statements are recorded,
+    /// with their result and duration. The history is read with [history](crate::Transaction::history()).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// let trans = conn.begin_transaction(TransactionMode::ReadWrite).unwrap();
+    /// trans.record_history();
+    ///
+    /// trans.execute_statement("INSERT INTO test_table VALUES('the number one', 1)").unwrap();
+    /// assert!(trans.execute_statement("INSERT INTO missing_table VALUES(2)").is_err());
+    ///
+    /// for record in trans.history() {
+    ///     println!("{} -> {:?} in {:?}", record.sql, record.result, record.duration);
+    /// }
+    /// trans.rollback().unwrap();
+    /// ```
+    pub fn record_history(&self) {
+        *self.connection.statement_history() = Some(Vec::new());
+    }
+
+    /// Returns the statements recorded since [record_history](crate::Transaction::record_history()) was called, in the order they were executed.
+    /// Returns an empty history if the transaction isn't recording.
+    pub fn history(&self) -> Vec<StatementRecord> {
+        self.connection
+            .statement_history()
+            .clone()
+            .unwrap_or_default()
+    }
+
+    /// Executes an SQL statement within the transaction, see [execute_statement](crate::Connection::execute_statement()).
+    ///
+    /// # Errors
+    /// In a [ReadOnly](TransactionMode::ReadOnly) transaction, statements that modify data or the schema (e.g. *INSERT*, *UPDATE*, *DELETE* or *CREATE*)
+    /// are rejected with the error code -26022 before they are sent to the server.
+    /// The same check applies to every statement run on the connection while the transaction is open,
+    /// including those run through [Deref] or the [Connection] itself.
+    ///
+    /// The check looks at the keywords of the statement, outside of string literals, delimited identifiers and comments.
+    /// It rejects statements starting with a keyword that modifies data or the schema,
+    /// and *WITH* statements that contain *INSERT*, *UPDATE*, *DELETE* or *MERGE*.
+    /// Since any of those keywords counts, *WITH* … *SELECT* … *FOR UPDATE* is rejected as well, although it only reads.
+    /// *CALL* is left to the server, which runs procedures that only read data and refuses those that modify it,
+    /// as it does for data modified by functions called from a query.
+    /// Otherwise returns [Err] when the statement couldn't be executed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table test_table").ok();
+    /// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+    /// let trans = conn.begin_transaction(TransactionMode::ReadOnly).unwrap();
+    /// let result = trans.execute_statement("INSERT INTO test_table VALUES('the number one', 1)");
+    /// assert_eq!(result, Err(-26022));
+    /// ```
+    pub fn execute_statement(&self, sqlstatement: &str) -> Result<i32, i32> {
+        self.connection.execute_statement(sqlstatement)
+    }
+
+    /// Prepares an SQL statement within the transaction, see [prepare](crate::Connection::prepare()).
+    /// The returned [TransactionStatement] borrows the transaction, so it must be dropped before the transaction is committed or rolled back.
+    ///
+    /// # Errors
+    /// In a [ReadOnly](TransactionMode::ReadOnly) transaction, statements that modify data or the schema are rejected with the error code -26022,
+    /// as for [execute_statement](crate::Transaction::execute_statement()).
+    /// Otherwise returns [Err] when the statement couldn't be prepared.
+    pub fn prepare(
+        &self,
+        sqlstatement: &str,
+        option: CursorMode,
+    ) -> Result<TransactionStatement<'_>, i32> {
+        Ok(TransactionStatement {
+            statement: self.connection.prepare(sqlstatement, option)?,
+            transaction: PhantomData,
+        })
+    }
+
     /// Ends a transaction
     fn end_transaction(&mut self, trans_option: EndTransactionMode) -> Result<i32, i32> {
-        let handle = self.get_session_handle()?.unwrap(); //Ok unwrap since we know the connection is a connection
-        unsafe {
-            let rc = ffi::MimerEndTransaction(*handle, trans_option as i32);
-            match rc.cmp(MIMER_SUCCESS) {
-                Ordering::Greater => {
-                    // i suppose this is a reasonable panic?
-                    panic!("Return code is positive from C API function which doesn't return a positive value")
-                }
-                Ordering::Equal => Ok(rc),
-                Ordering::Less => Err(rc),
+        let rc = end_session_transaction(self.connection, trans_option)?;
+        *self.ended.get_mut() = true;
+        Ok(rc)
+    }
+}
+
+/// Starts a transaction on the session of a connection.
+pub(crate) fn begin_session_transaction(
+    conn: &Connection,
+    trans_option: TransactionMode,
+) -> Result<(), i32> {
+    traced!("mimer.transaction.begin", { mode = ?trans_option }, {
+        let handle = conn.get_session_handle()?.unwrap(); //Ok unwrap since we know the connection is a connection
+        let rc = unsafe { ffi::MimerBeginTransaction(*handle, trans_option as i32) };
+        return_code_result(rc)?;
+        conn.set_read_only_transaction(trans_option == TransactionMode::ReadOnly);
+        Ok(())
+    })
+}
+
+/// Ends the transaction on the session of a connection.
+/// The session handle is released before returning, so that the caller may use the connection again right away.
+pub(crate) fn end_session_transaction(
+    conn: &Connection,
+    trans_option: EndTransactionMode,
+) -> Result<i32, i32> {
+    traced!(
+        "mimer.transaction.end",
+        {
+            outcome = match trans_option {
+                EndTransactionMode::Commit => "commit",
+                EndTransactionMode::Rollback => "rollback",
+            }
+        },
+        {
+            let handle = conn.get_session_handle()?.unwrap(); //Ok unwrap since we know the connection is a connection
+            let rc = unsafe { ffi::MimerEndTransaction(*handle, trans_option as i32) };
+            drop(handle);
+            let result = return_code_result(rc);
+            // A failed commit leaves the transaction open, to be rolled back
+            if result.is_ok() || trans_option == EndTransactionMode::Rollback {
+                conn.set_read_only_transaction(false);
+            }
+            result
+        }
+    )
+}
+
+/// Maps the return code of a C API transaction function to a [Result].
+fn return_code_result(rc: i32) -> Result<i32, i32> {
+    match rc.cmp(MIMER_SUCCESS) {
+        Ordering::Greater => Err(unexpected_return_code(
+            "beginning or ending a transaction",
+            rc,
+        )),
+        Ordering::Equal => Ok(rc),
+        Ordering::Less => Err(rc),
+    }
+}
+
+/// Reports an error from rolling back a transaction that is dropped to the [rollback failure hook](crate::Connection::set_rollback_failure_hook()) of the connection.
+/// Errors telling that the transaction or connection is already gone are ignored.
+pub(crate) fn report_rollback_on_drop(conn: &Connection, result: Result<i32, i32>) {
+    match result {
+        // -24101: the server has already ended the transaction, e.g. after a failed commit. -26003: the connection is dropped.
+        Ok(_) | Err(-24101) | Err(-26003) => (),
+        Err(ec) => {
+            let err = MimerError::new(conn, ec);
+            if let Some(hook) = &*conn.rollback_failure_hook() {
+                hook(&err);
             }
         }
     }
 }
 
+/// Returns whether an SQL statement modifies data or the schema, judging from its keywords.
+/// See [execute_statement](crate::Transaction::execute_statement()) for what it doesn't catch.
+pub(crate) fn is_write_statement(sqlstatement: &str) -> bool {
+    let keywords = keywords(sqlstatement);
+    let Some(first) = keywords.first() else {
+        return false;
+    };
+    match first.to_ascii_uppercase().as_str() {
+        "INSERT" | "UPDATE" | "DELETE" | "MERGE" | "CREATE" | "DROP" | "ALTER" | "GRANT"
+        | "REVOKE" | "COMMENT" => true,
+        // The statement following the common table expressions
+        "WITH" => keywords.iter().any(|keyword| {
+            ["INSERT", "UPDATE", "DELETE", "MERGE"]
+                .iter()
+                .any(|dml| keyword.eq_ignore_ascii_case(dml))
+        }),
+        _ => false,
+    }
+}
+
 impl<'a> Drop for Transaction<'a> {
     fn drop(&mut self) {
-        self.end_transaction(EndTransactionMode::Rollback).ok();
+        *self.connection.statement_history() = None;
+        if *self.ended.get_mut() {
+            return;
+        }
+        let result = self.end_transaction(EndTransactionMode::Rollback);
+        report_rollback_on_drop(self.connection, result);
     }
 }
 
@@ -157,9 +405,27 @@ impl Deref for Transaction<'_> {
     }
 }
 
-impl DerefMut for Transaction<'_> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        self.connection
+/// A statement prepared within a [Transaction] by [prepare](crate::Transaction::prepare()).
+///
+/// It borrows the transaction and therefore can't outlive it, unlike a [Statement] prepared on the connection.
+/// It stays valid across [commit_and_continue](crate::Transaction::commit_and_continue()).
+/// The statement is used through [Deref] to [Statement].
+pub struct TransactionStatement<'t> {
+    statement: Statement,
+    transaction: PhantomData<&'t ()>,
+}
+
+impl Deref for TransactionStatement<'_> {
+    type Target = Statement;
+
+    fn deref(&self) -> &Statement {
+        &self.statement
+    }
+}
+
+impl DerefMut for TransactionStatement<'_> {
+    fn deref_mut(&mut self) -> &mut Statement {
+        &mut self.statement
     }
 }
 
@@ -171,10 +437,10 @@ mod transaction_tests {
 
     #[test]
     fn create_transaction() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
         let trans_option = TransactionMode::ReadWrite;
-        match Transaction::new(&mut conn, trans_option) {
+        match Transaction::new(&conn, trans_option) {
             Ok(_) => (),
             Err(ec) => panic!("Could not create transaction: {}", ec),
         };
@@ -182,13 +448,13 @@ mod transaction_tests {
 
     #[test]
     fn transaction_drop() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         let trans_option = TransactionMode::ReadWrite;
-        match Transaction::new(&mut conn, trans_option) {
+        match Transaction::new(&conn, trans_option) {
             Ok(t) => t,
             Err(ec) => panic!("Could not create transaction: {ec}"),
         };
-        match Transaction::new(&mut conn, trans_option) {
+        match Transaction::new(&conn, trans_option) {
             Ok(_) => (),
             Err(ec) => panic!("Could not create transaction: {ec}"),
         };
@@ -196,9 +462,9 @@ mod transaction_tests {
 
     #[test]
     fn rollback_on_drop() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         let trans_option = TransactionMode::ReadWrite;
-        let mut trans = match Transaction::new(&mut conn, trans_option) {
+        let mut trans = match Transaction::new(&conn, trans_option) {
             Ok(t) => t,
             Err(ec) => panic!("Could not create transaction: {ec}"),
         };
@@ -212,13 +478,41 @@ mod transaction_tests {
         };
     }
 
+    #[test]
+    fn drop_after_end_is_silent() {
+        let conn = establish_connection();
+        let failures = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let failures_ref = failures.clone();
+        conn.set_rollback_failure_hook(Some(Box::new(move |_| {
+            failures_ref.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        })));
+
+        let trans = Transaction::new(&conn, TransactionMode::ReadWrite).unwrap();
+        trans.commit().unwrap();
+        let trans = Transaction::new(&conn, TransactionMode::ReadWrite).unwrap();
+        trans.rollback().unwrap();
+        let trans = Transaction::new(&conn, TransactionMode::ReadWrite).unwrap();
+        // Ended behind the back of the transaction, the rollback on drop finds no transaction
+        unsafe {
+            ffi::MimerEndTransaction(
+                *trans.get_session_handle().unwrap().unwrap(),
+                EndTransactionMode::Rollback as i32,
+            );
+        }
+        drop(trans);
+        let trans = Transaction::new(&conn, TransactionMode::ReadWrite).unwrap();
+        drop(trans);
+
+        assert_eq!(failures.load(std::sync::atomic::Ordering::Relaxed), 0);
+    }
+
     #[test]
     fn execute_while_transaction() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
 
         let trans_option = TransactionMode::ReadWrite;
-        let trans = Transaction::new(&mut conn, trans_option).unwrap();
+        let trans = Transaction::new(&conn, trans_option).unwrap();
 
         trans
             .execute_statement(&format!(
@@ -234,11 +528,11 @@ mod transaction_tests {
 
     #[test]
     fn transaction_rollback() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
 
         let trans_option = TransactionMode::ReadWrite;
-        let trans = Transaction::new(&mut conn, trans_option).unwrap();
+        let trans = Transaction::new(&conn, trans_option).unwrap();
 
         trans
             .execute_statement(&format!(
@@ -266,11 +560,11 @@ mod transaction_tests {
 
     #[test]
     fn transaction_commit() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
 
         let trans_option = TransactionMode::ReadWrite;
-        let trans = Transaction::new(&mut conn, trans_option).unwrap();
+        let trans = Transaction::new(&conn, trans_option).unwrap();
 
         let _ = trans.execute_statement(
             "INSERT INTO test_table (column_1, column_2) VALUES ('some value', 42)",
@@ -296,11 +590,11 @@ mod transaction_tests {
 
     #[test]
     fn transaction_read_only() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
 
         let trans_option = TransactionMode::ReadOnly;
-        let trans = Transaction::new(&mut conn, trans_option).unwrap();
+        let trans = Transaction::new(&conn, trans_option).unwrap();
 
         let _ = trans.execute_statement(
             "INSERT INTO test_table (column_1, column_2) VALUES ('some value', 42)",
@@ -324,13 +618,98 @@ mod transaction_tests {
         }
     }
 
+    #[test]
+    fn transaction_mode_rejects_writes() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+
+        let trans = Transaction::new(&conn, TransactionMode::ReadOnly).unwrap();
+        assert_eq!(trans.mode(), TransactionMode::ReadOnly);
+        assert_eq!(
+            trans.execute_statement(&format!(
+                "  /* comment */ insert INTO {EXAMPLE_TABLE} {EXAMPLE_TABLE_EXAMPLE_VALUES}"
+            )),
+            Err(-26022)
+        );
+        assert_eq!(
+            trans
+                .prepare(&format!("DELETE FROM {EXAMPLE_TABLE}"), CursorMode::Forward)
+                .err(),
+            Some(-26022)
+        );
+        // Statements run through Deref or the connection itself are checked as well
+        assert_eq!(
+            Connection::prepare(
+                &trans,
+                &format!("UPDATE {EXAMPLE_TABLE} SET column_2 = 0"),
+                CursorMode::Forward
+            )
+            .err(),
+            Some(-26022)
+        );
+        assert_eq!(
+            conn.execute_statement(&format!(
+                "WITH x AS (SELECT 1 AS a FROM SYSTEM.ONEROW) \
+                 INSERT INTO {EXAMPLE_TABLE} SELECT 'with', a FROM x"
+            )),
+            Err(-26022)
+        );
+        // Procedures are left to the server, which here finds no such procedure
+        assert_ne!(
+            trans.execute_statement("CALL some_procedure()"),
+            Err(-26022)
+        );
+        assert_eq!(
+            conn.execute_statement(&format!(
+                "WITH x AS (SELECT column_2 FROM {EXAMPLE_TABLE}) SELECT * FROM x FOR UPDATE"
+            )),
+            Err(-26022)
+        );
+        trans
+            .prepare(
+                &format!("SELECT * FROM {EXAMPLE_TABLE}"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        trans.rollback().unwrap();
+
+        let trans = Transaction::new(&conn, TransactionMode::ReadWrite).unwrap();
+        assert_eq!(trans.mode(), TransactionMode::ReadWrite);
+        trans
+            .execute_statement(&format!(
+                "INSERT INTO {EXAMPLE_TABLE} {EXAMPLE_TABLE_EXAMPLE_VALUES}"
+            ))
+            .unwrap();
+    }
+
+    #[test]
+    fn write_statement_classification() {
+        assert!(is_write_statement("update t set a = 1"));
+        assert!(is_write_statement("-- comment\n  CREATE TABLE t (a INT)"));
+        assert!(!is_write_statement("SELECT * FROM t"));
+        assert!(!is_write_statement(
+            "/* DELETE */ SELECT 1 FROM SYSTEM.ONEROW"
+        ));
+        assert!(!is_write_statement("-- DELETE"));
+        assert!(!is_write_statement("call read_totals()"));
+        assert!(is_write_statement(
+            "WITH x AS (SELECT a FROM t) SELECT * FROM x FOR UPDATE"
+        ));
+        assert!(is_write_statement(
+            "WITH x AS (SELECT 1 AS a FROM SYSTEM.ONEROW) INSERT INTO t SELECT a FROM x"
+        ));
+        assert!(!is_write_statement(
+            "WITH x AS (SELECT 'DELETE' AS \"UPDATE\" FROM SYSTEM.ONEROW) SELECT * FROM x"
+        ));
+    }
+
     #[test]
     fn transaction_deref() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
         drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
 
         let trans_option = TransactionMode::ReadWrite;
-        let trans = Transaction::new(&mut conn, trans_option).unwrap();
+        let trans = Transaction::new(&conn, trans_option).unwrap();
 
         let _ = trans.execute_statement(
             "INSERT INTO test_table (column_1, number) VALUES ('some value', 42)",
@@ -344,14 +723,91 @@ mod transaction_tests {
 
     #[test]
     fn transaction_begin_deref() {
-        let mut conn = establish_connection();
+        let conn = establish_connection();
 
         let trans_option = TransactionMode::ReadWrite;
-        let mut trans = Transaction::new(&mut conn, trans_option).unwrap();
+        let trans = Transaction::new(&conn, trans_option).unwrap();
 
         match trans.begin_transaction(trans_option) {
             Ok(_) => panic!("Should not be able to create another transaction"),
             Err(ec) => assert_eq!(-14011, ec),
         };
     }
+
+    #[test]
+    fn statement_history() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+
+        let trans = Transaction::new(&conn, TransactionMode::ReadWrite).unwrap();
+        trans
+            .execute_statement(&format!("INSERT INTO {EXAMPLE_TABLE} VALUES('before', 0)"))
+            .unwrap();
+        assert!(trans.history().is_empty());
+
+        trans.record_history();
+        let insert = format!("INSERT INTO {EXAMPLE_TABLE} VALUES('first', 1)");
+        trans.execute_statement(&insert).unwrap();
+        let update = format!("UPDATE {EXAMPLE_TABLE} SET column_2 = :n WHERE column_2 < :n");
+        let stmnt = trans.prepare(&update, CursorMode::Forward).unwrap();
+        stmnt.execute_bind(&[&5, &5]).unwrap();
+        let failing = "INSERT INTO no_such_table VALUES(1)";
+        assert!(trans.execute_statement(failing).is_err());
+
+        let history = trans.history();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].sql, insert);
+        assert!(history[0].result.is_ok());
+        assert_eq!(history[1].sql, update);
+        assert!(history[1].result.is_ok());
+        assert_eq!(history[2].sql, failing);
+        assert!(history[2].result.is_err());
+        drop(stmnt);
+        trans.rollback().unwrap();
+
+        // Recording ends with the transaction
+        let trans = Transaction::new(&conn, TransactionMode::ReadWrite).unwrap();
+        trans
+            .execute_statement(&format!("INSERT INTO {EXAMPLE_TABLE} VALUES('after', 2)"))
+            .unwrap();
+        assert!(trans.history().is_empty());
+    }
+
+    #[test]
+    fn commit_and_continue() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+
+        let trans = Transaction::new(&conn, TransactionMode::ReadWrite).unwrap();
+        let stmnt = trans
+            .prepare(
+                &format!("INSERT INTO {EXAMPLE_TABLE} VALUES('a number', ?)"),
+                CursorMode::Forward,
+            )
+            .unwrap();
+        stmnt.execute_bind(&[&1]).unwrap();
+        trans.commit_and_continue().unwrap();
+        stmnt.execute_bind(&[&2]).unwrap();
+        drop(stmnt);
+        trans.rollback().unwrap();
+
+        let count = conn
+            .query_scalar::<i32>(
+                &format!("SELECT CAST(COUNT(*) AS INTEGER) FROM {EXAMPLE_TABLE}"),
+                &[],
+            )
+            .unwrap();
+        assert_eq!(count, Some(1));
+    }
+
+    #[test]
+    fn commit_and_continue_after_end() {
+        let conn = establish_connection();
+        let trans = conn.begin_transaction(TransactionMode::ReadWrite).unwrap();
+        // As left by a commit_and_continue that couldn't begin the new transaction
+        trans.ended.store(true, AtomicOrdering::Relaxed);
+        assert_eq!(trans.commit_and_continue(), Err(-26044));
+        trans.ended.store(false, AtomicOrdering::Relaxed);
+        trans.rollback().unwrap();
+    }
 }
@@ -22,6 +22,8 @@
 * See license for more details.
 * *********************************************************************/
 
+use crate::common::mimer_options::ColumnType;
+
 #[doc(hidden)]
 use std::str::FromStr;
 
@@ -29,7 +31,7 @@ pub(crate) const LOB_CHUNK_MAXSIZE_SET: usize = 1048500;
 
 /// Represents Mimer SQL data types.
 /// Can be seen as an "intermediary"-datatype between Rust and Mimer SQL.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum MimerDatatype<'a> {
     Null,
     BigInt(i64),
@@ -41,6 +43,98 @@ pub enum MimerDatatype<'a> {
     Bool(bool),
     BinaryArray(Vec<u8>),
     BinaryArrayRef(&'a [u8]),
+    /// A *BUILTIN.UUID* value, as its 16 bytes.
+    Uuid([u8; 16]),
+    /// An *INTERVAL* value, as text in the format Mimer SQL uses for the interval type, e.g. `02 03:04:05` for [IntervalDayToSecond](ColumnType::IntervalDayToSecond).
+    /// When bound to a parameter of another interval type, the text is converted to the format of that type.
+    Interval(String, ColumnType),
+    /// A geometry, bound as WKT to character columns and as WKB to binary columns.
+    /// A point bound to *BUILTIN.GIS_LOCATION* is bound as a location instead, with its y coordinate as latitude and its x coordinate as longitude.
+    Geometry(geo::Geometry<f64>),
+}
+
+impl MimerDatatype<'_> {
+    /// Converts a borrowed variant into its owned counterpart, detaching the value from the lifetime of the source.
+    /// [StringRef](MimerDatatype::StringRef) becomes [String](MimerDatatype::String) and [BinaryArrayRef](MimerDatatype::BinaryArrayRef) becomes [BinaryArray](MimerDatatype::BinaryArray).
+    pub fn into_owned(self) -> MimerDatatype<'static> {
+        match self {
+            MimerDatatype::Null => MimerDatatype::Null,
+            MimerDatatype::BigInt(v) => MimerDatatype::BigInt(v),
+            MimerDatatype::Int(v) => MimerDatatype::Int(v),
+            MimerDatatype::Double(v) => MimerDatatype::Double(v),
+            MimerDatatype::Real(v) => MimerDatatype::Real(v),
+            MimerDatatype::String(v) => MimerDatatype::String(v),
+            MimerDatatype::StringRef(v) => MimerDatatype::String(v.to_string()),
+            MimerDatatype::Bool(v) => MimerDatatype::Bool(v),
+            MimerDatatype::BinaryArray(v) => MimerDatatype::BinaryArray(v),
+            MimerDatatype::BinaryArrayRef(v) => MimerDatatype::BinaryArray(v.to_vec()),
+            MimerDatatype::Uuid(v) => MimerDatatype::Uuid(v),
+            MimerDatatype::Interval(v, t) => MimerDatatype::Interval(v, t),
+            MimerDatatype::Geometry(v) => MimerDatatype::Geometry(v),
+        }
+    }
+
+    /// Returns the name of the variant, e.g. `"Int"`. Borrowed variants are named after their owned counterparts.
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            MimerDatatype::Null => "Null",
+            MimerDatatype::BigInt(_) => "BigInt",
+            MimerDatatype::Int(_) => "Int",
+            MimerDatatype::Double(_) => "Double",
+            MimerDatatype::Real(_) => "Real",
+            MimerDatatype::String(_) | MimerDatatype::StringRef(_) => "String",
+            MimerDatatype::Bool(_) => "Bool",
+            MimerDatatype::BinaryArray(_) | MimerDatatype::BinaryArrayRef(_) => "BinaryArray",
+            MimerDatatype::Uuid(_) => "Uuid",
+            MimerDatatype::Interval(..) => "Interval",
+            MimerDatatype::Geometry(_) => "Geometry",
+        }
+    }
+
+    /// Returns whether the value can be bound to a parameter of the given type.
+    /// Conversions that the C API does are allowed, such as a number to a character parameter or text to a *DATE*,
+    /// while values that can't be converted, such as text to an *INTEGER*, are not. *NULL* can be bound to any parameter,
+    /// and geometries are checked when they are encoded for the parameter.
+    pub(crate) fn can_bind_to(&self, param: ColumnType) -> bool {
+        use ColumnType::*;
+        let character = matches!(
+            param,
+            Character | CharacterVarying | NationalCharacter | NationalCharacterVarying
+        );
+        let number = matches!(
+            param,
+            Decimal | Numeric | SmallInt | Integer | BigInt | Real | Double | Float
+        );
+        let binary = matches!(param, Binary | BinaryVarying | Blob);
+        let spatial = matches!(
+            param,
+            GisLocation | GisLatitude | GisLongitude | GisCoordinate
+        );
+        match self {
+            _ if matches!(param, Other(_)) => true,
+            MimerDatatype::Null | MimerDatatype::Geometry(_) => true,
+            MimerDatatype::String(_) | MimerDatatype::StringRef(_) => {
+                !binary
+                    && !spatial
+                    && !matches!(
+                        param,
+                        SmallInt | Integer | BigInt | Real | Double | Float | Boolean
+                    )
+            }
+            MimerDatatype::BigInt(_) | MimerDatatype::Int(_) | MimerDatatype::Double(_) => {
+                character || number
+            }
+            MimerDatatype::Real(_) => {
+                character || number || matches!(param, GisLatitude | GisLongitude)
+            }
+            MimerDatatype::Bool(_) => character || param == Boolean,
+            MimerDatatype::BinaryArray(_) | MimerDatatype::BinaryArrayRef(_) => {
+                binary || spatial || param == Uuid
+            }
+            MimerDatatype::Uuid(_) => binary || param == Uuid,
+            MimerDatatype::Interval(..) => character || crate::interval::is_interval(param),
+        }
+    }
 }
 
 /// Defines translation of datatypes from Rust to Mimer SQL.
@@ -53,33 +147,71 @@ pub enum MimerDatatype<'a> {
 /// | [`Option<T>`] where T: [ToSql]    | *NULL* if [None], otherwise the appropriate conversion for the type T and column|
 /// | [i32]     | *INTEGER*, *BIGINT* or *SMALLINT*     |
 /// | [i64]     | *INTEGER*, *BIGINT* or *SMALLINT*     |
-/// | [String]     | String datatypes[^string_datatypes], *CHARACTER LARGE OBJECT* and *NATIONAL CHARACTER LARGE OBJECT*|
+/// | [i8], [i16], [u8], [u16], [u32]     | *INTEGER*, *BIGINT* or *SMALLINT*[^narrow_integers]     |
+/// | [u64], [usize]     | *BIGINT* or *DECIMAL*[^wide_unsigned]     |
+/// | [String], &[str], [`Cow<str>`](std::borrow::Cow), [`Box<str>`], [`Rc<str>`](std::rc::Rc), [`Arc<str>`](std::sync::Arc)     | String datatypes[^string_datatypes], *CHARACTER LARGE OBJECT* and *NATIONAL CHARACTER LARGE OBJECT*|
+/// | [char]     | String datatypes[^string_datatypes] |
 /// | [f32]     | *REAL*, *DOUBLE PRECISION*, BINARY(4)[^f32binary4]|
 /// | ([f32],[f32])     | *BINARY(8)*[^f32f32]  |
+/// | [Latitude](crate::Latitude), [Longitude](crate::Longitude), [Location](crate::Location)     | *BUILTIN.GIS_LATITUDE*, *BUILTIN.GIS_LONGITUDE* and *BUILTIN.GIS_LOCATION* respectively |
 /// | [f64]     | *REAL* and *DOUBLE PRECISION*|
 /// | [bool]     | *BOOLEAN* |
-/// | [`Vec<u8>`]/\[u8; N\]     | *BINARY*, *BINARY VARYING*, *BINARY LARGE OBJECT* |
+/// | [`Vec<u8>`]/&\[u8\]/\[u8; N\]     | *BINARY*, *BINARY VARYING*, *BINARY LARGE OBJECT* |
+/// | [MimerDatatype]     | The conversion for the Rust type held by the variant |
+///
+/// References to the owned types, such as `&String` and `&Vec<u8>`, are bound the same way as the types they refer to.
+/// A value bound to a parameter of a type it can't be converted to, e.g. a [String] to an *INTEGER*, is rejected with the error code -26203
+/// before it is sent to the server, see [type_mismatch](crate::Statement::type_mismatch()).
 ///
 /// The ToSql trait is also implemented for a number of types from external crates, among which are [uuid::Uuid] and various types from the [chrono] crate.
 ///
 ///
 /// | Rust type | Mimer SQL type |
 /// |---------|---------|
-/// | [uuid::Uuid][^uuid]     |  *BINARY*, *BINARY VARYING*, *BINARY LARGE OBJECT*|
+/// | [uuid::Uuid][^uuid]     |  *BUILTIN.UUID*, *BINARY*, *BINARY VARYING*, *BINARY LARGE OBJECT*|
 /// | [chrono::NaiveDate]     | *DATE*|
 /// | [chrono::NaiveTime]     | *TIME*|
 /// | [chrono::NaiveDateTime]     | *TIMESTAMP*|
+/// | [`chrono::DateTime<Utc>`](chrono::DateTime), [`chrono::DateTime<FixedOffset>`](chrono::DateTime)[^datetime]     | *TIMESTAMP*|
+/// | `time::Date`[^time]     | *DATE*|
+/// | `time::Time`[^time]     | *TIME*|
+/// | `time::PrimitiveDateTime`[^time]     | *TIMESTAMP*|
+/// | `time::OffsetDateTime`[^time]     | *TIMESTAMP*|
+/// | [IntervalYearMonth](crate::IntervalYearMonth), [IntervalDayTime](crate::IntervalDayTime)[^interval]     | *INTERVAL*|
 /// | [`geo::Point<i32>`]      | *BINARY*|
+/// | [`geo::Geometry<f64>`](geo::Geometry), [`geo::Point<f64>`](geo::Point), [`geo::LineString<f64>`](geo::LineString), [`geo::Polygon<f64>`](geo::Polygon) and their multi variants[^geometry]     | *BUILTIN.GIS_LOCATION* for points, string datatypes[^string_datatypes] as WKT and binary datatypes as WKB|
+/// | `serde_json::Value`[^json]      | String datatypes[^string_datatypes], *CHARACTER LARGE OBJECT* and *NATIONAL CHARACTER LARGE OBJECT*|
 ///
 /// [^string_datatypes]: String datatypes include *CHARACTER*, *CHARACTER VARYING*, *NATIONAL CHARACTER*, *NATIONAL CHARACTER VARYING*, *DATE*, *TIME*, *TIMESTAMP*, *DECIMAL* and *NUMERIC*.
 ///
+/// [^narrow_integers]: Fetched values are range checked, and values that don't fit the Rust type give the error code -26025.
+///
+/// [^wide_unsigned]: Values above [i64::MAX] are bound as text, so they fit a *DECIMAL(20)* column but are rejected by the server for *BIGINT*.
+/// Fetched values are range checked like for the narrower integers, and can also be read from *DECIMAL* columns.
+///
 /// [^f32binary4]: Converts into an 4 byte binary sequence if column type is *BUILTIN.GIS_LATITUDE* or *BUILTIN.GIS_LONGITUDE*.
 /// Note that values of type *BUILTIN.GIS_LATITUDE* must be within the interval [-90,90], and values of type *BUILTIN.GIS_LONGITUDE* within [-180,180].
 ///
-/// [^f32f32]: Converts into an 8 byte binary sequence, where each f32 makes up 4 bytes. Mainly intended for *BUILTIN.GIS_LOCATION*.
+/// [^f32f32]: Converts into an 8 byte binary sequence, where each f32 makes up 4 bytes. Mainly intended for *BUILTIN.GIS_LOCATION*,
+/// for which [Location](crate::Location) is preferred since its latitude and longitude are labelled.
 /// The location latitude and longitude must be within the interval [-90,90] and [-180,180] respectively.
 ///
-/// [^uuid]: Converts into a 16 byte binary sequence. Mainly intended for *BUILTIN.UUID*.
+/// [^uuid]: Converts into [MimerDatatype::Uuid], which is bound as a *BUILTIN.UUID* or as a 16 byte binary sequence. Mainly intended for *BUILTIN.UUID*.
+///
+/// [^datetime]: Mimer SQL has no *TIMESTAMP WITH TIME ZONE*, so the value is converted to UTC and stored without offset.
+/// When fetched, a *TIMESTAMP* is read as UTC, while strings with an explicit offset (e.g. `2024-01-02 03:04:05+02:00` or RFC 3339) from character columns keep it.
+///
+/// [^interval]: Converts into [MimerDatatype::Interval]. Year-month intervals can be bound to *INTERVAL YEAR*, *INTERVAL MONTH* and *INTERVAL YEAR TO MONTH*,
+/// day-time intervals to the other interval types. A value with a part that is finer than the parameter type holds is rejected.
+///
+/// [^time]: Requires the `time` feature. The conversions mirror the ones for [chrono], so an `OffsetDateTime` is stored as UTC like a [`chrono::DateTime<FixedOffset>`](chrono::DateTime).
+///
+/// [^geometry]: Converts into [MimerDatatype::Geometry]. A point bound to *BUILTIN.GIS_LOCATION* uses its y coordinate as latitude and its x coordinate as longitude.
+/// Values bound to the *BUILTIN.GIS_LATITUDE*, *BUILTIN.GIS_LONGITUDE* and *BUILTIN.GIS_LOCATION* types, including [f32] and ([f32],[f32]),
+/// are range checked and rejected with the error code -26031 for latitudes and -26032 for longitudes.
+/// When fetched, binary values of 8 bytes are read as a location, other binary values as WKB and strings as WKT. Text or bytes that aren't a two dimensional geometry give the error code -26033.
+///
+/// [^json]: Requires the `json` feature. The value is serialized to its JSON text, and parsed from it when fetched.
 ///
 pub trait ToSql {
     fn to_sql(&self) -> MimerDatatype;
@@ -95,7 +227,7 @@ pub trait ToSql {
 /// # let ident = "RUSTUSER";
 /// # let pass = "RUSTPASSWORD";
 /// use chrono::NaiveDate;
-/// let mut conn = Connection::open(db, ident, pass).unwrap();
+/// let conn = Connection::open(db, ident, pass).unwrap();
 /// # _ = conn.execute_statement("DROP TABLE date_table");
 /// conn.execute_statement("CREATE TABLE date_table (column1 DATE)").unwrap();
 ///
@@ -112,10 +244,41 @@ pub trait ToSql {
 /// assert_eq!(fetched_string, fetched_date.to_string());
 /// ```
 ///
+/// [`Option<T>`] where T: [FromSql] converts a null value into [None], mirroring [ToSql] for [`Option<T>`].
+/// Fetching it with [get](crate::Row::get()) gives `Ok(Some(None))` for a null value, which can be flattened into the nullable value:
+/// ```
+///  # use mimerrust::*;
+/// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+/// # let ident = "RUSTUSER";
+/// # let pass = "RUSTPASSWORD";
+/// let conn = Connection::open(db, ident, pass).unwrap();
+/// # conn.execute_statement("drop table test_table").ok();
+/// # conn.execute_statement("create table test_table (column_1 VARCHAR(30), column_2 INT)").unwrap();
+/// conn.execute_statement("INSERT INTO test_table VALUES(NULL, 1)").unwrap();
+/// let stmnt = conn.prepare("SELECT * FROM test_table", CursorMode::Forward).unwrap();
+/// let mut cursor = stmnt.open_cursor().unwrap();
+/// let row = cursor.next_row().unwrap().unwrap();
+///
+/// let text: Option<String> = row.get::<Option<String>>(1).unwrap().flatten();
+/// assert_eq!(text, None);
+/// ```
+///
 pub trait FromSql: Sized {
     fn from_sql(value: MimerDatatype) -> Result<Self, i32>;
 }
 
+impl<T> FromSql for Option<T>
+where
+    T: FromSql,
+{
+    fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
+        match value {
+            MimerDatatype::Null => Ok(None),
+            value => T::from_sql(value).map(Some),
+        }
+    }
+}
+
 impl<T> ToSql for Option<T>
 where
     T: ToSql,
@@ -128,6 +291,16 @@ where
     }
 }
 
+impl ToSql for MimerDatatype<'_> {
+    fn to_sql(&self) -> MimerDatatype<'_> {
+        match self {
+            MimerDatatype::String(val) => MimerDatatype::StringRef(val),
+            MimerDatatype::BinaryArray(val) => MimerDatatype::BinaryArrayRef(val),
+            val => val.clone(),
+        }
+    }
+}
+
 impl ToSql for i32 {
     fn to_sql(&self) -> MimerDatatype {
         MimerDatatype::Int(*self)
@@ -156,6 +329,61 @@ impl FromSql for i64 {
     }
 }
 
+/// Implements [ToSql] and [FromSql] for integer types that are bound through a wider Mimer SQL integer.
+/// Fetched values that don't fit the type are rejected with -26025 instead of being truncated.
+macro_rules! impl_integer_conversions {
+    ($($rust_type:ty => $variant:ident),* $(,)?) => {$(
+        impl ToSql for $rust_type {
+            fn to_sql(&self) -> MimerDatatype<'_> {
+                MimerDatatype::$variant((*self).into())
+            }
+        }
+        impl FromSql for $rust_type {
+            fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
+                match value {
+                    MimerDatatype::Int(val) => val.try_into().or(Err(-26025)),
+                    MimerDatatype::BigInt(val) => val.try_into().or(Err(-26025)),
+                    _ => Err(-26200),
+                }
+            }
+        }
+    )*};
+}
+
+impl_integer_conversions!(i8 => Int, i16 => Int, u8 => Int, u16 => Int, u32 => BigInt);
+
+/// Implements [ToSql] and [FromSql] for unsigned integer types that may exceed [i64].
+/// Values above [i64::MAX] are bound as text, which fits *DECIMAL(20)* and is rejected by the server for *BIGINT*.
+macro_rules! impl_wide_unsigned_conversions {
+    ($($rust_type:ty),*) => {$(
+        impl ToSql for $rust_type {
+            fn to_sql(&self) -> MimerDatatype<'_> {
+                match i64::try_from(*self) {
+                    Ok(val) => MimerDatatype::BigInt(val),
+                    Err(_) => MimerDatatype::String(self.to_string()),
+                }
+            }
+        }
+        impl FromSql for $rust_type {
+            fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
+                match value {
+                    MimerDatatype::Int(val) => val.try_into().or(Err(-26025)),
+                    MimerDatatype::BigInt(val) => val.try_into().or(Err(-26025)),
+                    MimerDatatype::String(val) => match val.trim().parse::<$rust_type>() {
+                        Ok(val) => Ok(val),
+                        // A negative or too large number, as opposed to text that isn't a number
+                        Err(_) if val.trim().parse::<i128>().is_ok() => Err(-26025),
+                        Err(_) => Err(-26200),
+                    },
+                    _ => Err(-26200),
+                }
+            }
+        }
+    )*};
+}
+
+impl_wide_unsigned_conversions!(u64, usize);
+
 impl ToSql for &str {
     fn to_sql(&self) -> MimerDatatype {
         MimerDatatype::StringRef(self)
@@ -166,10 +394,36 @@ impl ToSql for String {
         MimerDatatype::StringRef(self)
     }
 }
+
+/// Implements [ToSql] for types that dereference to [str], which are bound by reference without copying the text.
+macro_rules! impl_str_to_sql {
+    ($($rust_type:ty),* $(,)?) => {$(
+        impl ToSql for $rust_type {
+            fn to_sql(&self) -> MimerDatatype<'_> {
+                MimerDatatype::StringRef(self)
+            }
+        }
+    )*};
+}
+
+impl_str_to_sql!(
+    &String,
+    std::borrow::Cow<'_, str>,
+    Box<str>,
+    std::rc::Rc<str>,
+    std::sync::Arc<str>,
+);
+
+impl ToSql for char {
+    fn to_sql(&self) -> MimerDatatype<'_> {
+        MimerDatatype::String(self.to_string())
+    }
+}
 impl FromSql for String {
     fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
         match value {
             MimerDatatype::String(val) => Ok(val.to_string()),
+            MimerDatatype::Interval(val, _) => Ok(val),
             _ => Err(-26200),
         }
     }
@@ -229,6 +483,16 @@ impl ToSql for Vec<u8> {
         MimerDatatype::BinaryArrayRef(self)
     }
 }
+impl ToSql for &Vec<u8> {
+    fn to_sql(&self) -> MimerDatatype<'_> {
+        MimerDatatype::BinaryArrayRef(self)
+    }
+}
+impl ToSql for &[u8] {
+    fn to_sql(&self) -> MimerDatatype<'_> {
+        MimerDatatype::BinaryArrayRef(self)
+    }
+}
 impl<const N: usize> ToSql for [u8; N] {
     fn to_sql(&self) -> MimerDatatype {
         MimerDatatype::BinaryArrayRef(self)
@@ -238,6 +502,7 @@ impl FromSql for Vec<u8> {
     fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
         match value {
             MimerDatatype::BinaryArray(val) => Ok(val),
+            MimerDatatype::Uuid(val) => Ok(val.to_vec()),
             _ => Err(-26200),
         }
     }
@@ -245,15 +510,15 @@ impl FromSql for Vec<u8> {
 
 impl ToSql for uuid::Uuid {
     fn to_sql(&self) -> MimerDatatype {
-        MimerDatatype::BinaryArrayRef(self.as_bytes())
+        MimerDatatype::Uuid(*self.as_bytes())
     }
 }
 impl FromSql for uuid::Uuid {
     fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
         match value {
+            MimerDatatype::Uuid(val) => Ok(uuid::Uuid::from_bytes(val)),
             MimerDatatype::BinaryArray(val) => {
-                let mut bytes: [u8; 16] = [0; 16];
-                bytes.copy_from_slice(&val[..16]);
+                let bytes: [u8; 16] = val.as_slice().try_into().or(Err(-26200))?;
                 Ok(uuid::Uuid::from_bytes(bytes))
             }
             _ => Err(-26200),
@@ -304,7 +569,8 @@ impl FromSql for chrono::NaiveDateTime {
     fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
         match value {
             MimerDatatype::String(str) => {
-                match chrono::NaiveDateTime::parse_from_str(&str, "%Y-%m-%d %H:%M:%S") {
+                // %.f also accepts timestamps without fractional seconds
+                match chrono::NaiveDateTime::parse_from_str(&str, "%Y-%m-%d %H:%M:%S%.f") {
                     Ok(date_time) => Ok(date_time),
                     Err(_) => Err(-26200),
                 }
@@ -314,6 +580,164 @@ impl FromSql for chrono::NaiveDateTime {
     }
 }
 
+impl ToSql for chrono::DateTime<chrono::Utc> {
+    fn to_sql(&self) -> MimerDatatype<'_> {
+        MimerDatatype::String(self.naive_utc().to_string())
+    }
+}
+impl FromSql for chrono::DateTime<chrono::Utc> {
+    fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
+        chrono::DateTime::<chrono::FixedOffset>::from_sql(value).map(|dt| dt.to_utc())
+    }
+}
+
+impl ToSql for chrono::DateTime<chrono::FixedOffset> {
+    fn to_sql(&self) -> MimerDatatype<'_> {
+        MimerDatatype::String(self.naive_utc().to_string())
+    }
+}
+impl FromSql for chrono::DateTime<chrono::FixedOffset> {
+    fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
+        match value {
+            MimerDatatype::String(str) => {
+                match chrono::DateTime::parse_from_str(&str, "%Y-%m-%d %H:%M:%S%.f%:z")
+                    .or_else(|_| chrono::DateTime::parse_from_rfc3339(&str))
+                {
+                    Ok(date_time) => Ok(date_time),
+                    Err(_) => chrono::NaiveDateTime::from_sql(MimerDatatype::String(str))
+                        .map(|naive| naive.and_utc().fixed_offset()),
+                }
+            }
+            _ => Err(-26200),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_conversions {
+    use super::{FromSql, MimerDatatype, ToSql};
+    use time::{
+        format_description::{well_known::Rfc3339, FormatItem},
+        macros::format_description,
+        Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset,
+    };
+
+    const DATE_FORMAT: &[FormatItem<'_>] = format_description!("[year]-[month]-[day]");
+    const TIME_FORMAT: &[FormatItem<'_>] =
+        format_description!("[hour]:[minute]:[second][optional [.[subsecond]]]");
+    const TIMESTAMP_FORMAT: &[FormatItem<'_>] = format_description!(
+        "[year]-[month]-[day] [hour]:[minute]:[second][optional [.[subsecond]]]"
+    );
+    const OFFSET_TIMESTAMP_FORMAT: &[FormatItem<'_>] = format_description!(
+        "[year]-[month]-[day] [hour]:[minute]:[second][optional [.[subsecond]]][offset_hour sign:mandatory]:[offset_minute]"
+    );
+
+    /// Formats a date the way chrono does, e.g. `2024-06-17`.
+    fn format_date(date: &Date) -> String {
+        format!(
+            "{:04}-{:02}-{:02}",
+            date.year(),
+            u8::from(date.month()),
+            date.day()
+        )
+    }
+
+    /// Formats a time the way chrono does, leaving out the fractional seconds when they are zero.
+    fn format_time(time: &Time) -> String {
+        let mut formatted = format!(
+            "{:02}:{:02}:{:02}",
+            time.hour(),
+            time.minute(),
+            time.second()
+        );
+        match time.nanosecond() {
+            0 => {}
+            nanos if nanos % 1_000_000 == 0 => {
+                formatted.push_str(&format!(".{:03}", nanos / 1_000_000))
+            }
+            nanos if nanos % 1_000 == 0 => formatted.push_str(&format!(".{:06}", nanos / 1_000)),
+            nanos => formatted.push_str(&format!(".{:09}", nanos)),
+        }
+        formatted
+    }
+
+    fn format_timestamp(date_time: &PrimitiveDateTime) -> String {
+        format!(
+            "{} {}",
+            format_date(&date_time.date()),
+            format_time(&date_time.time())
+        )
+    }
+
+    impl ToSql for Date {
+        fn to_sql(&self) -> MimerDatatype<'_> {
+            MimerDatatype::String(format_date(self))
+        }
+    }
+    impl FromSql for Date {
+        fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
+            match value {
+                MimerDatatype::String(str) => Date::parse(&str, DATE_FORMAT).or(Err(-26200)),
+                _ => Err(-26200),
+            }
+        }
+    }
+
+    impl ToSql for Time {
+        fn to_sql(&self) -> MimerDatatype<'_> {
+            MimerDatatype::String(format_time(self))
+        }
+    }
+    impl FromSql for Time {
+        fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
+            match value {
+                MimerDatatype::String(str) => Time::parse(&str, TIME_FORMAT).or(Err(-26200)),
+                _ => Err(-26200),
+            }
+        }
+    }
+
+    impl ToSql for PrimitiveDateTime {
+        fn to_sql(&self) -> MimerDatatype<'_> {
+            MimerDatatype::String(format_timestamp(self))
+        }
+    }
+    impl FromSql for PrimitiveDateTime {
+        fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
+            match value {
+                MimerDatatype::String(str) => {
+                    PrimitiveDateTime::parse(&str, TIMESTAMP_FORMAT).or(Err(-26200))
+                }
+                _ => Err(-26200),
+            }
+        }
+    }
+
+    impl ToSql for OffsetDateTime {
+        fn to_sql(&self) -> MimerDatatype<'_> {
+            let utc = self.to_offset(UtcOffset::UTC);
+            MimerDatatype::String(format_timestamp(&PrimitiveDateTime::new(
+                utc.date(),
+                utc.time(),
+            )))
+        }
+    }
+    impl FromSql for OffsetDateTime {
+        fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
+            match value {
+                MimerDatatype::String(str) => OffsetDateTime::parse(&str, OFFSET_TIMESTAMP_FORMAT)
+                    .or_else(|_| OffsetDateTime::parse(&str, &Rfc3339))
+                    .or_else(|_| {
+                        PrimitiveDateTime::parse(&str, TIMESTAMP_FORMAT)
+                            .map(|naive| naive.assume_utc())
+                    })
+                    .or(Err(-26200)),
+                _ => Err(-26200),
+            }
+        }
+    }
+}
+
 impl ToSql for (f32, f32) {
     fn to_sql(&self) -> MimerDatatype {
         let mut bytes: [u8; 8] = [0; 8];
@@ -365,6 +789,23 @@ impl FromSql for geo::Point<i32> {
     }
 }
 
+#[cfg(feature = "json")]
+impl ToSql for serde_json::Value {
+    fn to_sql(&self) -> MimerDatatype<'_> {
+        MimerDatatype::String(self.to_string())
+    }
+}
+#[cfg(feature = "json")]
+impl FromSql for serde_json::Value {
+    fn from_sql(value: MimerDatatype) -> Result<Self, i32> {
+        match value {
+            MimerDatatype::String(str) => serde_json::from_str(&str).map_err(|_| -26200),
+            MimerDatatype::StringRef(str) => serde_json::from_str(str).map_err(|_| -26200),
+            _ => Err(-26200),
+        }
+    }
+}
+
 #[macro_export]
 #[doc(hidden)]
 macro_rules! match_mimer_temporal {
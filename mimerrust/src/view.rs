@@ -0,0 +1,129 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::{
+    connection::Connection,
+    key::{where_clause, Key},
+    row::FromRow,
+};
+
+/// Error codes telling that the view to drop doesn't exist.
+const VIEW_DOES_NOT_EXIST: [i32; 2] = [-12501, -12517];
+
+impl Connection {
+    /// Defines the view `name` as the query `sql`, replacing any view with that name.
+    /// The old view is dropped before the new one is created, so this is not atomic, and objects that depend on the old view, such as other views, are dropped with it.
+    ///
+    /// # Errors
+    /// Returns [Err] when the old view couldn't be dropped or the new one couldn't be created, e.g. when `sql` is invalid.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mimerrust::*;
+    /// # let db = &std::env::var("MIMER_DATABASE").unwrap();
+    /// # let ident = "RUSTUSER";
+    /// # let pass = "RUSTPASSWORD";
+    /// let conn = Connection::open(db, ident, pass).unwrap();
+    /// # conn.execute_statement("drop table sales cascade").ok();
+    /// conn.execute_statement("create table sales (region VARCHAR(30), amount INT)").unwrap();
+    /// conn.execute_statement("INSERT INTO sales VALUES('north', 10), ('north', 5), ('south', 7)").unwrap();
+    ///
+    /// conn.create_or_replace_view("sales_per_region", "SELECT region, CAST(SUM(amount) AS INT) AS total FROM sales GROUP BY region").unwrap();
+    /// let totals = conn.query_view::<(String, i32)>("sales_per_region", &[("region", &"north")]).unwrap();
+    /// assert_eq!(totals, vec![(String::from("north"), 15)]);
+    /// ```
+    pub fn create_or_replace_view(&self, name: &str, sql: &str) -> Result<i32, i32> {
+        self.drop_view_if_exists(name)?;
+        self.execute_statement(&format!("CREATE VIEW {name} AS {sql}"))
+    }
+
+    /// Drops the view `name` if it exists, together with the objects that depend on it.
+    /// Returns whether a view was dropped.
+    ///
+    /// # Errors
+    /// Returns [Err] when the view exists but couldn't be dropped.
+    pub fn drop_view_if_exists(&self, name: &str) -> Result<bool, i32> {
+        match self.execute_statement(&format!("DROP VIEW {name} CASCADE")) {
+            Ok(_) => Ok(true),
+            Err(ec) if VIEW_DOES_NOT_EXIST.contains(&ec) => Ok(false),
+            Err(ec) => Err(ec),
+        }
+    }
+
+    /// Selects the rows of the view (or table) `name` that match `filter`, fetching the leading columns of each row as a tuple, see [get_tuple](crate::Row::get_tuple()).
+    /// An empty filter selects every row. The filter values are bound as parameters.
+    ///
+    /// # Errors
+    /// Returns [Err] when the query couldn't be executed or a row couldn't be converted to `T`.
+    ///
+    /// See [create_or_replace_view](Connection::create_or_replace_view()) for an example.
+    pub fn query_view<T: FromRow>(&self, name: &str, filter: &Key) -> Result<Vec<T>, i32> {
+        let sql = match filter.is_empty() {
+            true => format!("SELECT * FROM {name}"),
+            false => format!("SELECT * FROM {name} {}", where_clause(filter)?),
+        };
+        let params: Vec<_> = filter.iter().map(|(_, value)| *value).collect();
+        self.query_map(&sql, &params, |row| row.get_tuple::<T>())
+    }
+}
+
+#[cfg(test)]
+mod view_tests {
+    use crate::testing::*;
+
+    #[test]
+    fn create_replace_and_query_view() {
+        let conn = establish_connection();
+        drop_create_table(&conn, EXAMPLE_TABLE, EXAMPLE_TABLE_COLUMNS);
+        conn.execute_statement(&format!(
+            "INSERT INTO {EXAMPLE_TABLE} VALUES('one', 1), ('two', 2), ('three', 3)"
+        ))
+        .unwrap();
+
+        let view = "example_view";
+        conn.drop_view_if_exists(view).unwrap();
+        assert!(!conn.drop_view_if_exists(view).unwrap());
+
+        conn.create_or_replace_view(
+            view,
+            &format!("SELECT column_1 FROM {EXAMPLE_TABLE} WHERE column_2 > 1"),
+        )
+        .unwrap();
+        let mut rows = conn.query_view::<(String,)>(view, &[]).unwrap();
+        rows.sort();
+        assert_eq!(rows, vec![(String::from("three"),), (String::from("two"),)]);
+
+        conn.create_or_replace_view(
+            view,
+            &format!("SELECT column_2, column_1 FROM {EXAMPLE_TABLE}"),
+        )
+        .unwrap();
+        let rows = conn
+            .query_view::<(i32, String)>(view, &[("column_1", &"one")])
+            .unwrap();
+        assert_eq!(rows, vec![(1, String::from("one"))]);
+
+        assert!(conn.drop_view_if_exists(view).unwrap());
+    }
+}
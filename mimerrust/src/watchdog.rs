@@ -0,0 +1,138 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+use crate::inner_connection::InnerConnection;
+use parking_lot::{Condvar, Mutex};
+
+#[doc(hidden)]
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, OnceLock, Weak,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// A request to the server that is cancelled if it hasn't finished by its deadline.
+struct Watch {
+    id: u64,
+    deadline: Instant,
+    connection: Weak<InnerConnection>,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Cancels requests that exceed their query timeout, from a single background thread shared by all connections.
+/// The thread is started on first use, and sleeps until the earliest deadline.
+struct Watchdog {
+    watches: Mutex<Vec<Watch>>,
+    wake: Condvar,
+    next_id: AtomicU64,
+}
+
+fn watchdog() -> &'static Watchdog {
+    static WATCHDOG: OnceLock<&'static Watchdog> = OnceLock::new();
+    WATCHDOG.get_or_init(|| {
+        let watchdog: &'static Watchdog = Box::leak(Box::new(Watchdog {
+            watches: Mutex::new(Vec::new()),
+            wake: Condvar::new(),
+            next_id: AtomicU64::new(0),
+        }));
+        thread::spawn(move || watchdog.run());
+        watchdog
+    })
+}
+
+impl Watchdog {
+    fn run(&self) {
+        let mut watches = self.watches.lock();
+        loop {
+            match watches.iter().map(|watch| watch.deadline).min() {
+                Some(deadline) => {
+                    self.wake.wait_until(&mut watches, deadline);
+                }
+                None => self.wake.wait(&mut watches),
+            }
+            let now = Instant::now();
+            // Cancelling while holding the lock means that a request that finishes meanwhile
+            // sees the cancellation when it is unwatched.
+            watches.retain(|watch| {
+                if watch.deadline > now {
+                    return true;
+                }
+                if let Some(connection) = watch.connection.upgrade() {
+                    connection.cancel();
+                    watch.cancelled.store(true, Ordering::Relaxed);
+                }
+                false
+            });
+        }
+    }
+
+    fn watch(
+        &self,
+        connection: Weak<InnerConnection>,
+        deadline: Instant,
+    ) -> (u64, Arc<AtomicBool>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.watches.lock().push(Watch {
+            id,
+            deadline,
+            connection,
+            cancelled: Arc::clone(&cancelled),
+        });
+        self.wake.notify_one();
+        (id, cancelled)
+    }
+
+    fn unwatch(&self, id: u64) {
+        self.watches.lock().retain(|watch| watch.id != id);
+    }
+}
+
+/// Runs `f`, which makes a request to the server on the session of `connection` and returns its return code,
+/// cancelling the request with `MimerCancel` if it takes longer than `timeout`.
+/// The error from a cancelled request is replaced with -26036.
+pub(crate) fn run_with_timeout<F>(
+    connection: &Weak<InnerConnection>,
+    timeout: Option<Duration>,
+    f: F,
+) -> i32
+where
+    F: FnOnce() -> i32,
+{
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return f(),
+    };
+    let watchdog = watchdog();
+    let (id, cancelled) = watchdog.watch(connection.clone(), Instant::now() + timeout);
+    let rc = f();
+    watchdog.unwatch(id);
+    match rc < 0 && cancelled.load(Ordering::Relaxed) {
+        true => -26036, // Cancelled after exceeding the query timeout
+        false => rc,
+    }
+}
@@ -62,7 +62,7 @@ fn example_main(){
     let db = &std::env::var("MIMER_DATABASE").unwrap();
     let ident = "RUSTUSER";
     let pass = "RUSTPASSWORD";
-    let mut conn = Connection::open(db, ident, pass).unwrap();
+    let conn = Connection::open(db, ident, pass).unwrap();
     _ = conn.execute_statement("DROP TABLE my_table");
     conn.execute_statement("CREATE TABLE my_table (my_custom_column BINARY(8))").unwrap();
 
@@ -0,0 +1,70 @@
+/* *********************************************************************
+* Copyright (c) 2024 Mimer Information Technology
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy
+* of this software and associated documentation files (the "Software"), to deal
+* in the Software without restriction, including without limitation the rights
+* to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+* copies of the Software, and to permit persons to whom the Software is
+* furnished to do so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+* FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+* AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+* LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+* OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+* SOFTWARE.
+*
+* See license for more details.
+* *********************************************************************/
+
+// Runs the programs in `examples/` against the database in MIMER_DATABASE, so that they keep working as references.
+
+#[path = "../examples/batch_loading.rs"]
+mod batch_loading;
+#[path = "../examples/lob_streaming.rs"]
+mod lob_streaming;
+#[path = "../examples/pagination.rs"]
+mod pagination;
+#[cfg(feature = "r2d2")]
+#[path = "../examples/pooling.rs"]
+mod pooling;
+#[path = "../examples/stored_procedures.rs"]
+mod stored_procedures;
+#[path = "../examples/transactions_with_retry.rs"]
+mod transactions_with_retry;
+
+#[test]
+fn example_batch_loading() {
+    batch_loading::main();
+}
+
+#[test]
+fn example_lob_streaming() {
+    lob_streaming::main();
+}
+
+#[test]
+fn example_pagination() {
+    pagination::main();
+}
+
+#[cfg(feature = "r2d2")]
+#[test]
+fn example_pooling() {
+    pooling::main();
+}
+
+#[test]
+fn example_stored_procedures() {
+    stored_procedures::main();
+}
+
+#[test]
+fn example_transactions_with_retry() {
+    transactions_with_retry::main();
+}
@@ -30,7 +30,7 @@ const PASS: &str = "RUSTPASSWORD";
 
 #[test]
 fn test_main(){
-    let mut conn = Connection::open(DB, IDENT, PASS).unwrap();
+    let conn = Connection::open(DB, IDENT, PASS).unwrap();
 
     let rc = conn.execute_statement("DROP TABLE temp_integration");
     match  rc {
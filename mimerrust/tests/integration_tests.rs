@@ -26,7 +26,7 @@ use mimerrust::*;
 
 #[test]
 fn integration_test_1() {
-    let mut conn =
+    let conn =
         Connection::open("", "RUSTUSER", "RUSTPASSWORD").unwrap_or_else(|ec| panic!("{}", ec));
 
     conn.execute_statement("DROP TABLE test_table").ok();
@@ -38,7 +38,7 @@ fn integration_test_1() {
         Err(ec) => panic!("{}", conn.get_error(ec)),
     }
 
-    let mut trans = conn.begin_transaction(TransactionMode::ReadWrite).unwrap();
+    let trans = conn.begin_transaction(TransactionMode::ReadWrite).unwrap();
 
     let rc = trans.execute_statement(
         "INSERT INTO test_table (column_1, column_2) VALUES('the number one',1)",
@@ -83,7 +83,7 @@ fn integration_test_2() {
     };
 
     let rc = Connection::open("", "RUSTUSER", "RUSTPASSWORD");
-    let mut conn = match rc {
+    let conn = match rc {
         Ok(connection) => connection,
         Err(error) => panic!("Error connecting to db {error}"),
     };